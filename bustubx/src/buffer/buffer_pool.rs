@@ -2,15 +2,17 @@ use dashmap::DashMap;
 use std::sync::RwLock;
 use std::{collections::VecDeque, sync::Arc};
 
-use crate::buffer::page::{Page, PageId};
+use crate::buffer::page::{Page, PageId, BUSTUBX_PAGE_SIZE};
 
 use crate::buffer::PageRef;
 use crate::catalog::SchemaRef;
+use crate::recovery::{LogManager, Lsn, PAGE_LSN_SIZE};
 use crate::storage::codec::{
-    BPlusTreeInternalPageCodec, BPlusTreeLeafPageCodec, BPlusTreePageCodec, TablePageCodec,
+    BPlusTreeInternalPageCodec, BPlusTreeLeafPageCodec, BPlusTreePageCodec, CommonCodec,
+    OverflowPageCodec, TablePageCodec,
 };
 use crate::storage::{
-    BPlusTreeInternalPage, BPlusTreeLeafPage, BPlusTreePage, DiskManager, TablePage,
+    BPlusTreeInternalPage, BPlusTreeLeafPage, BPlusTreePage, DiskManager, OverflowPage, TablePage,
 };
 use crate::{BustubxError, BustubxResult};
 
@@ -30,6 +32,8 @@ pub struct BufferPoolManager {
     page_table: Arc<DashMap<PageId, FrameId>>,
     // 缓冲池中空闲的frame
     free_list: Arc<RwLock<VecDeque<FrameId>>>,
+    // page writes are logged before they happen if set
+    log_manager: Option<Arc<LogManager>>,
 }
 impl BufferPoolManager {
     pub fn new(num_pages: usize, disk_manager: Arc<DiskManager>) -> Self {
@@ -46,9 +50,15 @@ impl BufferPoolManager {
             disk_manager,
             page_table: Arc::new(DashMap::new()),
             free_list: Arc::new(RwLock::new(free_list)),
+            log_manager: None,
         }
     }
 
+    pub fn with_log_manager(mut self, log_manager: Arc<LogManager>) -> Self {
+        self.log_manager = Some(log_manager);
+        self
+    }
+
     // 从缓冲池创建一个新页
     pub fn new_page(&self) -> BustubxResult<PageRef> {
         // 缓冲池已满且无可替换的页
@@ -161,10 +171,69 @@ impl BufferPoolManager {
         Ok((page, tree_leaf_page))
     }
 
+    pub fn fetch_overflow_page(&self, page_id: PageId) -> BustubxResult<(PageRef, OverflowPage)> {
+        let page = self.fetch_page(page_id)?;
+        let (overflow_page, _) = OverflowPageCodec::decode(page.read().unwrap().data())?;
+        Ok((page, overflow_page))
+    }
+
+    /// Replaces the data of a table page or index page. The changed bytes are logged
+    /// first and the page is stamped with the lsn of that log record.
+    pub fn write_page(
+        &self,
+        page: &PageRef,
+        mut data: [u8; BUSTUBX_PAGE_SIZE],
+    ) -> BustubxResult<()> {
+        let mut page = page.write().unwrap();
+        if let Some(log_manager) = &self.log_manager {
+            let old_data = page.data();
+            let mut lsn = None;
+            for (start, end) in changed_ranges(old_data, &data) {
+                lsn = Some(log_manager.append_page_update(
+                    page.page_id,
+                    start as u16,
+                    old_data[start..end].to_vec(),
+                    data[start..end].to_vec(),
+                )?);
+            }
+            if let Some(lsn) = lsn {
+                data[0..PAGE_LSN_SIZE].copy_from_slice(&CommonCodec::encode_u64(lsn));
+                page.lsn = lsn;
+            } else {
+                data[0..PAGE_LSN_SIZE].copy_from_slice(&old_data[0..PAGE_LSN_SIZE]);
+            }
+        }
+        page.set_data(data);
+        Ok(())
+    }
+
+    /// Writes bytes which were restored by rolling back a transaction,
+    /// `lsn` is the compensation log record of this change.
+    pub fn restore_page_data(
+        &self,
+        page_id: PageId,
+        offset: u16,
+        bytes: &[u8],
+        lsn: Lsn,
+    ) -> BustubxResult<()> {
+        let page = self.fetch_page(page_id)?;
+        let mut page = page.write().unwrap();
+        let offset = offset as usize;
+        let data = page.data_mut();
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        data[0..PAGE_LSN_SIZE].copy_from_slice(&CommonCodec::encode_u64(lsn));
+        page.lsn = lsn;
+        Ok(())
+    }
+
     // 将缓冲池中指定页写回磁盘
     pub fn flush_page(&self, page_id: PageId) -> BustubxResult<bool> {
         if let Some(frame_id) = self.page_table.get(&page_id) {
             let page = self.pool[*frame_id].clone();
+            // write-ahead logging: log records must be durable before the page
+            if let Some(log_manager) = &self.log_manager {
+                log_manager.flush(page.read().unwrap().lsn)?;
+            }
             self.disk_manager
                 .write_page(page_id, page.read().unwrap().data())?;
             page.write().unwrap().is_dirty = false;
@@ -205,6 +274,7 @@ impl BufferPoolManager {
             self.disk_manager.deallocate_page(page_id)?;
             Ok(true)
         } else {
+            self.disk_manager.deallocate_page(page_id)?;
             Ok(true)
         }
     }
@@ -229,6 +299,23 @@ impl BufferPoolManager {
     }
 }
 
+/// Byte ranges that differ between two versions of a page, the page lsn is excluded.
+/// Ranges separated by only a few equal bytes are merged to avoid tiny log records.
+fn changed_ranges(old_data: &[u8], new_data: &[u8]) -> Vec<(usize, usize)> {
+    const MERGE_GAP: usize = 16;
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for i in PAGE_LSN_SIZE..BUSTUBX_PAGE_SIZE {
+        if old_data[i] == new_data[i] {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some((_, end)) if i - *end <= MERGE_GAP => *end = i + 1,
+            _ => ranges.push((i, i + 1)),
+        }
+    }
+    ranges
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{buffer::BufferPoolManager, storage::DiskManager};
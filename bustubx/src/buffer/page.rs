@@ -1,5 +1,6 @@
 use crate::buffer::buffer_pool::FrameId;
 use crate::buffer::replacer::LRUKReplacer;
+use crate::recovery::{Lsn, INVALID_LSN};
 use dashmap::DashMap;
 use derive_with::With;
 use log::error;
@@ -21,6 +22,8 @@ pub struct Page {
     pub pin_count: u32,
     // 是否被写过
     pub is_dirty: bool,
+    // lsn of the last log record which modified this page
+    pub lsn: Lsn,
 }
 
 impl Page {
@@ -33,6 +36,7 @@ impl Page {
             data: [0; BUSTUBX_PAGE_SIZE],
             pin_count: 0,
             is_dirty: false,
+            lsn: INVALID_LSN,
         }
     }
     pub fn destroy(&mut self) {
@@ -40,6 +44,7 @@ impl Page {
         self.data = [0; BUSTUBX_PAGE_SIZE];
         self.pin_count = 0;
         self.is_dirty = false;
+        self.lsn = INVALID_LSN;
     }
 
     pub fn set_data(&mut self, data: [u8; BUSTUBX_PAGE_SIZE]) {
@@ -51,11 +56,17 @@ impl Page {
         &self.data
     }
 
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.is_dirty = true;
+        &mut self.data
+    }
+
     pub fn replace(&mut self, other: Page) {
         self.page_id = other.page_id;
         self.data = other.data;
         self.pin_count = other.pin_count;
         self.is_dirty = other.is_dirty;
+        self.lsn = other.lsn;
     }
 }
 
@@ -2,13 +2,22 @@ use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+use crate::buffer::{AtomicPageId, PageId};
 use crate::catalog::{
-    key_schema_to_varchar, SchemaRef, COLUMNS_SCHMEA, INDEXES_SCHMEA, INFORMATION_SCHEMA_COLUMNS,
-    INFORMATION_SCHEMA_INDEXES, INFORMATION_SCHEMA_NAME, INFORMATION_SCHEMA_SCHEMAS,
-    INFORMATION_SCHEMA_TABLES, SCHEMAS_SCHMEA, TABLES_SCHMEA,
+    default_to_varchar, histogram_to_varchar, key_schema_to_varchar, Column, Constraint,
+    ConstraintKind, Schema, SchemaRef, TableStatistics, COLUMNS_SCHMEA, CONSTRAINTS_SCHMEA,
+    FOREIGN_KEYS_SCHMEA, INDEXES_SCHMEA, INFORMATION_SCHEMA_COLUMNS,
+    INFORMATION_SCHEMA_CONSTRAINTS, INFORMATION_SCHEMA_FOREIGN_KEYS, INFORMATION_SCHEMA_INDEXES,
+    INFORMATION_SCHEMA_NAME, INFORMATION_SCHEMA_SCHEMAS, INFORMATION_SCHEMA_STATISTICS,
+    INFORMATION_SCHEMA_TABLES, SCHEMAS_SCHMEA, STATISTICS_SCHMEA, TABLES_SCHMEA,
+};
+use crate::common::{ScalarValue, TableReference};
+use crate::expression::ExprTrait;
+use crate::storage::index::TreeIndexIterator;
+use crate::storage::{
+    RecordId, TableIterator, BPLUS_INTERNAL_PAGE_MAX_SIZE, BPLUS_LEAF_PAGE_MAX_SIZE,
+    EMPTY_TUPLE_META,
 };
-use crate::common::TableReference;
-use crate::storage::{BPLUS_INTERNAL_PAGE_MAX_SIZE, BPLUS_LEAF_PAGE_MAX_SIZE, EMPTY_TUPLE_META};
 use crate::{
     buffer::BufferPoolManager,
     storage::{index::BPlusTreeIndex, TableHeap},
@@ -44,6 +53,9 @@ pub struct CatalogTable {
     pub name: String,
     pub table: Arc<TableHeap>,
     pub indexes: HashMap<String, Arc<BPlusTreeIndex>>,
+    /// Collected by the last `ANALYZE` of the table
+    pub statistics: Option<Arc<TableStatistics>>,
+    pub constraints: Vec<Constraint>,
 }
 
 impl CatalogTable {
@@ -52,6 +64,8 @@ impl CatalogTable {
             name: name.into(),
             table,
             indexes: HashMap::new(),
+            statistics: None,
+            constraints: vec![],
         }
     }
 }
@@ -132,65 +146,18 @@ impl Catalog {
             schema.clone(),
             self.buffer_pool.clone(),
         )?);
-        let catalog_table = CatalogTable {
-            name: table_name.clone(),
-            table: table_heap.clone(),
-            indexes: HashMap::new(),
-        };
+        let catalog_table = CatalogTable::new(table_name.clone(), table_heap.clone());
         catalog_schema
             .tables
             .insert(table_name.clone(), catalog_table);
 
-        // update system table
-        let Some(information_schema) = self.schemas.get_mut(INFORMATION_SCHEMA_NAME) else {
-            return Err(BustubxError::Internal(
-                "catalog schema information_schema not created yet".to_string(),
-            ));
-        };
-        let Some(tables_table) = information_schema.tables.get_mut(INFORMATION_SCHEMA_TABLES)
-        else {
-            return Err(BustubxError::Internal(
-                "table information_schema.tables not created yet".to_string(),
-            ));
-        };
-
-        let tuple = Tuple::new(
-            TABLES_SCHMEA.clone(),
-            vec![
-                catalog_name.clone().into(),
-                catalog_schema_name.clone().into(),
-                table_name.clone().into(),
-                (table_heap.first_page_id.load(Ordering::SeqCst)).into(),
-            ],
-        );
-        tables_table.table.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
-
-        let Some(columns_table) = information_schema
-            .tables
-            .get_mut(INFORMATION_SCHEMA_COLUMNS)
-        else {
-            return Err(BustubxError::Internal(
-                "table information_schema.columns not created yet".to_string(),
-            ));
-        };
-        for col in schema.columns.iter() {
-            let sql_type: sqlparser::ast::DataType = (&col.data_type).into();
-            let tuple = Tuple::new(
-                COLUMNS_SCHMEA.clone(),
-                vec![
-                    catalog_name.clone().into(),
-                    catalog_schema_name.clone().into(),
-                    table_name.clone().into(),
-                    col.name.clone().into(),
-                    format!("{sql_type}").into(),
-                    col.nullable.into(),
-                    format!("{}", col.default).into(),
-                ],
-            );
-            columns_table
-                .table
-                .insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
-        }
+        // update system tables
+        self.insert_table_rows(
+            &catalog_name,
+            &catalog_schema_name,
+            &table_name,
+            &table_heap,
+        )?;
 
         Ok(table_heap)
     }
@@ -287,36 +254,13 @@ impl Catalog {
             .insert(index_name.clone(), b_plus_tree_index.clone());
 
         // update system table
-        let Some(information_schema) = self.schemas.get_mut(INFORMATION_SCHEMA_NAME) else {
-            return Err(BustubxError::Internal(
-                "catalog schema information_schema not created yet".to_string(),
-            ));
-        };
-        let Some(indexes_table) = information_schema
-            .tables
-            .get_mut(INFORMATION_SCHEMA_INDEXES)
-        else {
-            return Err(BustubxError::Internal(
-                "table information_schema.indexes not created yet".to_string(),
-            ));
-        };
-
-        let tuple = Tuple::new(
-            INDEXES_SCHMEA.clone(),
-            vec![
-                catalog_name.clone().into(),
-                catalog_schema_name.clone().into(),
-                table_name.clone().into(),
-                index_name.clone().into(),
-                key_schema_to_varchar(&b_plus_tree_index.key_schema).into(),
-                b_plus_tree_index.internal_max_size.into(),
-                b_plus_tree_index.leaf_max_size.into(),
-                b_plus_tree_index.root_page_id.load(Ordering::SeqCst).into(),
-            ],
-        );
-        indexes_table
-            .table
-            .insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+        self.insert_index_row(
+            &catalog_name,
+            &catalog_schema_name,
+            &table_name,
+            &index_name,
+            &b_plus_tree_index,
+        )?;
 
         Ok(b_plus_tree_index)
     }
@@ -347,6 +291,1128 @@ impl Catalog {
         Ok(catalog_table.indexes.get(index_name).cloned())
     }
 
+    /// Adds a constraint to the table, `PRIMARY KEY` and `UNIQUE` constraints create their
+    /// index. A `FOREIGN KEY` without referenced columns references the primary key.
+    /// Existing tuples are not checked, so it's meant for new tables.
+    pub fn add_constraint(
+        &mut self,
+        table_ref: &TableReference,
+        mut constraint: Constraint,
+    ) -> BustubxResult<()> {
+        let catalog_name = table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME);
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+
+        let catalog_table = self.catalog_table(table_ref)?;
+        for existing in catalog_table.constraints.iter() {
+            if existing.name == constraint.name {
+                return Err(BustubxError::Storage(format!(
+                    "constraint {} already exists",
+                    constraint.name
+                )));
+            }
+            if matches!(existing.kind, ConstraintKind::PrimaryKey { .. })
+                && matches!(constraint.kind, ConstraintKind::PrimaryKey { .. })
+            {
+                return Err(BustubxError::Storage(format!(
+                    "table {} already has a primary key",
+                    table_name
+                )));
+            }
+        }
+        let schema = catalog_table.table.schema.clone();
+        for col in constraint.columns() {
+            schema.index_of(None, &col)?;
+        }
+
+        if let ConstraintKind::ForeignKey {
+            columns,
+            referenced_table,
+            referenced_columns,
+            ..
+        } = &mut constraint.kind
+        {
+            *referenced_table = full_table_ref(referenced_table);
+            let Some(referenced) = self.find_table(referenced_table) else {
+                return Err(BustubxError::Storage(format!(
+                    "table {} not created yet",
+                    referenced_table.table()
+                )));
+            };
+            if referenced_columns.is_empty() {
+                let Some(primary_key) = referenced.constraints.iter().find_map(|c| match &c.kind {
+                    ConstraintKind::PrimaryKey { columns } => Some(columns),
+                    _ => None,
+                }) else {
+                    return Err(BustubxError::Storage(format!(
+                        "table {} has no primary key",
+                        referenced_table.table()
+                    )));
+                };
+                *referenced_columns = primary_key.clone();
+            }
+            if columns.len() != referenced_columns.len() {
+                return Err(BustubxError::Storage(format!(
+                    "foreign key {} has {} columns but references {} columns",
+                    constraint.name,
+                    columns.len(),
+                    referenced_columns.len()
+                )));
+            }
+            // referenced rows are looked up with the index of the key
+            if !referenced
+                .constraints
+                .iter()
+                .any(|c| c.key_columns() == Some(referenced_columns.as_slice()))
+            {
+                return Err(BustubxError::Storage(format!(
+                    "there is no unique constraint matching given keys for referenced table {}",
+                    referenced_table.table()
+                )));
+            }
+        }
+
+        if let Some(key_columns) = constraint.key_columns() {
+            let key_indices = key_columns
+                .iter()
+                .map(|col| schema.index_of(None, col))
+                .collect::<BustubxResult<Vec<_>>>()?;
+            self.create_index(
+                constraint.name.clone(),
+                table_ref,
+                Arc::new(schema.project(&key_indices)?),
+            )?;
+        }
+
+        // update system table
+        self.insert_constraint_row(catalog_name, catalog_schema_name, table_name, &constraint)?;
+        self.load_constraint(table_ref, constraint)
+    }
+
+    /// Checks a tuple which is about to be written against the `NOT NULL` columns and the
+    /// constraints of the table. `rid` is the tuple's own record id when it's updated.
+    pub fn check_constraints(
+        &self,
+        table_ref: &TableReference,
+        tuple: &Tuple,
+        rid: Option<RecordId>,
+    ) -> BustubxResult<()> {
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+        let Some(catalog_table) = self
+            .schemas
+            .get(catalog_schema_name)
+            .and_then(|schema| schema.tables.get(table_name))
+        else {
+            return Err(BustubxError::Storage(format!(
+                "table {} not created yet",
+                table_name
+            )));
+        };
+
+        for (col, value) in tuple.schema.columns.iter().zip(tuple.data.iter()) {
+            if !col.nullable && value.is_null() {
+                return Err(BustubxError::ConstraintViolation(format!(
+                    "null value in column {} violates not-null constraint",
+                    col.name
+                )));
+            }
+        }
+
+        for constraint in catalog_table.constraints.iter() {
+            if let ConstraintKind::Check { expr, .. } = &constraint.kind {
+                let has_null = constraint.columns().iter().any(|col| {
+                    tuple
+                        .schema
+                        .index_of(None, col)
+                        .is_ok_and(|idx| tuple.data[idx].is_null())
+                });
+                if !has_null && expr.evaluate(tuple)?.as_boolean()? == Some(false) {
+                    return Err(BustubxError::ConstraintViolation(format!(
+                        "row violates check constraint {}",
+                        constraint.name
+                    )));
+                }
+                continue;
+            }
+            if let ConstraintKind::ForeignKey {
+                columns,
+                referenced_table,
+                ..
+            } = &constraint.kind
+            {
+                let values = columns
+                    .iter()
+                    .map(|col| Ok(tuple.data[tuple.schema.index_of(None, col)?].clone()))
+                    .collect::<BustubxResult<Vec<_>>>()?;
+                // rows with a null in the foreign key reference nothing
+                if values.iter().any(|value| value.is_null()) {
+                    continue;
+                }
+                let (referenced, index) = self.referenced_key(constraint)?;
+                let key = key_tuple(index, values)?;
+                // a row may reference itself
+                if referenced_table == &full_table_ref(table_ref)
+                    && tuple.project_with_schema(index.key_schema.clone())? == key
+                {
+                    continue;
+                }
+                if find_live_tuple(referenced, index, &key, None)?.is_none() {
+                    return Err(BustubxError::ConstraintViolation(format!(
+                        "key ({}) is not present in table {}, violates foreign key constraint {}",
+                        format_key(&key),
+                        referenced_table.table(),
+                        constraint.name
+                    )));
+                }
+                continue;
+            }
+            let Some(index) = catalog_table.indexes.get(&constraint.name) else {
+                return Err(BustubxError::Internal(format!(
+                    "index of constraint {} not found",
+                    constraint.name
+                )));
+            };
+            let key = tuple.project_with_schema(index.key_schema.clone())?;
+            // null never equals another key
+            if key.data.iter().any(|value| value.is_null()) {
+                continue;
+            }
+            if find_live_tuple(catalog_table, index, &key, rid)?.is_some() {
+                return Err(BustubxError::ConstraintViolation(format!(
+                    "duplicate key ({}) violates unique constraint {}",
+                    format_key(&key),
+                    constraint.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Foreign keys of all tables which reference the table, with their tables.
+    pub fn referencing_foreign_keys(
+        &self,
+        table_ref: &TableReference,
+    ) -> Vec<(TableReference, Constraint)> {
+        let table_ref = full_table_ref(table_ref);
+        let mut foreign_keys = vec![];
+        for (catalog_schema_name, catalog_schema) in self.schemas.iter() {
+            for (table_name, catalog_table) in catalog_schema.tables.iter() {
+                for constraint in catalog_table.constraints.iter() {
+                    let ConstraintKind::ForeignKey {
+                        referenced_table, ..
+                    } = &constraint.kind
+                    else {
+                        continue;
+                    };
+                    if referenced_table == &table_ref {
+                        foreign_keys.push((
+                            TableReference::full(
+                                DEFAULT_CATALOG_NAME,
+                                catalog_schema_name,
+                                table_name,
+                            ),
+                            constraint.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+        foreign_keys
+    }
+
+    /// Live tuples of the table whose foreign key references the key of the referenced
+    /// tuple.
+    pub fn referencing_tuples(
+        &self,
+        table_ref: &TableReference,
+        foreign_key: &Constraint,
+        referenced_tuple: &Tuple,
+    ) -> BustubxResult<Vec<(RecordId, Tuple)>> {
+        let ConstraintKind::ForeignKey {
+            columns,
+            referenced_columns,
+            ..
+        } = &foreign_key.kind
+        else {
+            return Err(BustubxError::Internal(format!(
+                "constraint {} is not a foreign key",
+                foreign_key.name
+            )));
+        };
+        let key = referenced_columns
+            .iter()
+            .map(|col| {
+                Ok(referenced_tuple.data[referenced_tuple.schema.index_of(None, col)?].clone())
+            })
+            .collect::<BustubxResult<Vec<_>>>()?;
+        if key.iter().any(|value| value.is_null()) {
+            return Ok(vec![]);
+        }
+
+        let table_heap = self.table_heap(table_ref)?;
+        let column_indices = columns
+            .iter()
+            .map(|col| table_heap.schema.index_of(None, col))
+            .collect::<BustubxResult<Vec<_>>>()?;
+        let mut tuples = vec![];
+        let mut iterator = TableIterator::new(table_heap.clone(), ..);
+        while let Some((rid, tuple)) = iterator.next()? {
+            let mut matches = true;
+            for (idx, value) in column_indices.iter().zip(key.iter()) {
+                let column_value = &tuple.data[*idx];
+                if column_value.is_null() || column_value.cast_to(&value.data_type())? != *value {
+                    matches = false;
+                    break;
+                }
+            }
+            if matches {
+                tuples.push((rid, tuple));
+            }
+        }
+        Ok(tuples)
+    }
+
+    /// Writes root page ids of the table's indexes which changed since they were
+    /// recorded in information_schema.indexes.
+    pub fn sync_index_roots(&self, table_ref: &TableReference) -> BustubxResult<()> {
+        let catalog_name = table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME);
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+
+        let Some(catalog_table) = self
+            .schemas
+            .get(catalog_schema_name)
+            .and_then(|schema| schema.tables.get(table_name))
+        else {
+            return Err(BustubxError::Storage(format!(
+                "table {} not created yet",
+                table_name
+            )));
+        };
+        if catalog_table.indexes.is_empty() {
+            return Ok(());
+        }
+
+        let indexes_table = self.table_heap(&TableReference::partial(
+            INFORMATION_SCHEMA_NAME,
+            INFORMATION_SCHEMA_INDEXES,
+        ))?;
+        let mut iterator = TableIterator::new(indexes_table.clone(), ..);
+        while let Some((rid, mut tuple)) = iterator.next()? {
+            let (
+                ScalarValue::Varchar(Some(tuple_catalog_name)),
+                ScalarValue::Varchar(Some(tuple_schema_name)),
+                ScalarValue::Varchar(Some(tuple_table_name)),
+                ScalarValue::Varchar(Some(index_name)),
+            ) = (
+                tuple.value(0)?,
+                tuple.value(1)?,
+                tuple.value(2)?,
+                tuple.value(3)?,
+            )
+            else {
+                return Err(BustubxError::Internal(format!(
+                    "Failed to decode index tuple: {:?}",
+                    tuple
+                )));
+            };
+            if tuple_catalog_name != catalog_name
+                || tuple_schema_name != catalog_schema_name
+                || tuple_table_name != table_name
+            {
+                continue;
+            }
+            let Some(index) = catalog_table.indexes.get(index_name) else {
+                continue;
+            };
+            let root_page_id: ScalarValue = index.root_page_id.load(Ordering::SeqCst).into();
+            if tuple.value(7)? != &root_page_id {
+                tuple.data[7] = root_page_id;
+                indexes_table.update_tuple(rid, tuple)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn table_statistics(
+        &self,
+        table_ref: &TableReference,
+    ) -> BustubxResult<Option<Arc<TableStatistics>>> {
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+        let Some(catalog_table) = self
+            .schemas
+            .get(catalog_schema_name)
+            .and_then(|schema| schema.tables.get(table_name))
+        else {
+            return Err(BustubxError::Storage(format!(
+                "table {} not created yet",
+                table_name
+            )));
+        };
+        Ok(catalog_table.statistics.clone())
+    }
+
+    /// Replaces the statistics of the table, including its rows in
+    /// information_schema.statistics.
+    pub fn update_statistics(
+        &mut self,
+        table_ref: &TableReference,
+        statistics: TableStatistics,
+    ) -> BustubxResult<()> {
+        let catalog_name = table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME);
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+
+        let table_key = [
+            catalog_name.to_string().into(),
+            catalog_schema_name.to_string().into(),
+            table_name.to_string().into(),
+        ];
+        self.delete_information_rows(INFORMATION_SCHEMA_STATISTICS, &table_key)?;
+        let statistics_table = self.table_heap(&TableReference::partial(
+            INFORMATION_SCHEMA_NAME,
+            INFORMATION_SCHEMA_STATISTICS,
+        ))?;
+
+        let table_schema = self.table_heap(table_ref)?.schema.clone();
+        for col in table_schema.columns.iter() {
+            let Some(column_statistics) = statistics.column(&col.name) else {
+                continue;
+            };
+            let bound_to_varchar = |value: &ScalarValue| {
+                ScalarValue::Varchar((!value.is_null()).then(|| value.to_string()))
+            };
+            let tuple = Tuple::new(
+                STATISTICS_SCHMEA.clone(),
+                vec![
+                    catalog_name.to_string().into(),
+                    catalog_schema_name.to_string().into(),
+                    table_name.to_string().into(),
+                    col.name.clone().into(),
+                    statistics.row_count.into(),
+                    column_statistics.null_count.into(),
+                    column_statistics.distinct_count.into(),
+                    bound_to_varchar(&column_statistics.min),
+                    bound_to_varchar(&column_statistics.max),
+                    histogram_to_varchar(&column_statistics.histogram).into(),
+                ],
+            );
+            statistics_table.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+        }
+
+        self.load_statistics(table_ref, Arc::new(statistics))
+    }
+
+    /// Removes the table and its indexes, returns the pages they occupied. The pages
+    /// must not be freed before the removal is committed.
+    pub fn drop_table(&mut self, table_ref: &TableReference) -> BustubxResult<Vec<PageId>> {
+        let catalog_name = table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME);
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+        if catalog_schema_name == INFORMATION_SCHEMA_NAME {
+            return Err(BustubxError::Storage(
+                "Cannot drop system table".to_string(),
+            ));
+        }
+
+        let referencing = self
+            .referencing_foreign_keys(table_ref)
+            .into_iter()
+            .filter(|(child_ref, _)| child_ref != &full_table_ref(table_ref))
+            .map(|(child_ref, constraint)| format!("{} of {}", constraint.name, child_ref.table()))
+            .collect::<Vec<_>>();
+        if !referencing.is_empty() {
+            return Err(BustubxError::Storage(format!(
+                "Cannot drop table {}, it is referenced by foreign key {}",
+                table_name,
+                referencing.join(", ")
+            )));
+        }
+
+        let Some(catalog_table) = self
+            .schemas
+            .get_mut(catalog_schema_name)
+            .and_then(|schema| schema.tables.remove(table_name))
+        else {
+            return Err(BustubxError::Storage(format!(
+                "table {} not created yet",
+                table_name
+            )));
+        };
+        let mut page_ids = catalog_table.table.page_ids()?;
+        for index in catalog_table.indexes.values() {
+            page_ids.extend(index.page_ids()?);
+        }
+
+        // update system tables
+        let table_key = [
+            catalog_name.to_string().into(),
+            catalog_schema_name.to_string().into(),
+            table_name.to_string().into(),
+        ];
+        for information_table in [
+            INFORMATION_SCHEMA_TABLES,
+            INFORMATION_SCHEMA_COLUMNS,
+            INFORMATION_SCHEMA_INDEXES,
+            INFORMATION_SCHEMA_STATISTICS,
+            INFORMATION_SCHEMA_CONSTRAINTS,
+            INFORMATION_SCHEMA_FOREIGN_KEYS,
+        ] {
+            self.delete_information_rows(information_table, &table_key)?;
+        }
+        Ok(page_ids)
+    }
+
+    /// Finds the table of an index by the index name, index names only need to be unique
+    /// within a table.
+    pub fn index_table(
+        &self,
+        catalog_schema_name: Option<&str>,
+        index_name: &str,
+    ) -> BustubxResult<Option<TableReference>> {
+        let catalog_schema_name = catalog_schema_name.unwrap_or(DEFAULT_SCHEMA_NAME);
+        let Some(catalog_schema) = self.schemas.get(catalog_schema_name) else {
+            return Err(BustubxError::Storage(format!(
+                "catalog schema {} not created yet",
+                catalog_schema_name
+            )));
+        };
+        let mut tables = catalog_schema
+            .tables
+            .iter()
+            .filter(|(_, table)| table.indexes.contains_key(index_name))
+            .map(|(table_name, _)| table_name.clone())
+            .collect::<Vec<_>>();
+        match tables.len() {
+            0 => Ok(None),
+            1 => Ok(Some(TableReference::partial(
+                catalog_schema_name,
+                tables.remove(0),
+            ))),
+            _ => Err(BustubxError::Storage(format!(
+                "index name {} is ambiguous, it exists on tables {}",
+                index_name,
+                tables.join(", ")
+            ))),
+        }
+    }
+
+    /// Removes the index, returns the pages it occupied. The pages must not be freed
+    /// before the removal is committed.
+    pub fn drop_index(
+        &mut self,
+        table_ref: &TableReference,
+        index_name: &str,
+    ) -> BustubxResult<Vec<PageId>> {
+        let catalog_name = table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME);
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+
+        let Some(catalog_table) = self
+            .schemas
+            .get_mut(catalog_schema_name)
+            .and_then(|schema| schema.tables.get_mut(table_name))
+        else {
+            return Err(BustubxError::Storage(format!(
+                "table {} not created yet",
+                table_name
+            )));
+        };
+        if let Some(constraint) = catalog_table
+            .constraints
+            .iter()
+            .find(|constraint| constraint.name == index_name)
+        {
+            return Err(BustubxError::Storage(format!(
+                "Cannot drop index {}, it enforces {} constraint {}",
+                index_name,
+                constraint.constraint_type(),
+                constraint.name
+            )));
+        }
+        let Some(index) = catalog_table.indexes.remove(index_name) else {
+            return Err(BustubxError::Storage(format!(
+                "index {} not created yet",
+                index_name
+            )));
+        };
+        let page_ids = index.page_ids()?;
+
+        // update system table
+        self.delete_information_rows(
+            INFORMATION_SCHEMA_INDEXES,
+            &[
+                catalog_name.to_string().into(),
+                catalog_schema_name.to_string().into(),
+                table_name.to_string().into(),
+                index_name.to_string().into(),
+            ],
+        )?;
+        Ok(page_ids)
+    }
+
+    /// Adds a column at the end of the table. The table is rewritten with the default of
+    /// the column in existing tuples, returns the pages of the old table and indexes which
+    /// must not be freed before the change is committed.
+    pub fn add_column(
+        &mut self,
+        table_ref: &TableReference,
+        column: Column,
+    ) -> BustubxResult<Vec<PageId>> {
+        let catalog_table = self.catalog_table(table_ref)?;
+        let schema = catalog_table.table.schema.clone();
+        if schema.index_of(None, &column.name).is_ok() {
+            return Err(BustubxError::Storage(format!(
+                "column {} already exists",
+                column.name
+            )));
+        }
+
+        let default = column.default.clone();
+        let mut columns = schema.columns.clone();
+        columns.push(Arc::new(column));
+        let (new_table, page_ids) =
+            self.rewrite_table(catalog_table, Arc::new(Schema { columns }), |mut data| {
+                data.push(default.clone());
+                data
+            })?;
+        self.replace_table(table_ref, table_ref.table(), new_table)?;
+        Ok(page_ids)
+    }
+
+    /// Drops a column and the indexes containing it. The table is rewritten, returns the
+    /// pages of the old table and indexes which must not be freed before the change is
+    /// committed.
+    pub fn drop_column(
+        &mut self,
+        table_ref: &TableReference,
+        column_name: &str,
+    ) -> BustubxResult<Vec<PageId>> {
+        let catalog_table = self.catalog_table(table_ref)?;
+        let schema = catalog_table.table.schema.clone();
+        let column_idx = schema.index_of(None, column_name)?;
+        self.check_not_referenced(table_ref, &[column_name])?;
+        if schema.column_count() == 1 {
+            return Err(BustubxError::Storage(format!(
+                "Cannot drop {column_name}, the only column of the table"
+            )));
+        }
+
+        let mut columns = schema.columns.clone();
+        columns.remove(column_idx);
+        let (new_table, page_ids) =
+            self.rewrite_table(catalog_table, Arc::new(Schema { columns }), |mut data| {
+                data.remove(column_idx);
+                data
+            })?;
+        self.replace_table(table_ref, table_ref.table(), new_table)?;
+        Ok(page_ids)
+    }
+
+    pub fn rename_column(
+        &mut self,
+        table_ref: &TableReference,
+        old_name: &str,
+        new_name: &str,
+    ) -> BustubxResult<()> {
+        let catalog_table = self.catalog_table(table_ref)?;
+        let schema = catalog_table.table.schema.clone();
+        let column_idx = schema.index_of(None, old_name)?;
+        self.check_not_referenced(table_ref, &[old_name])?;
+        if schema.index_of(None, new_name).is_ok() {
+            return Err(BustubxError::Storage(format!(
+                "column {new_name} already exists"
+            )));
+        }
+
+        let mut columns = schema.columns.clone();
+        columns[column_idx] = Arc::new(columns[column_idx].as_ref().clone().with_name(new_name));
+        let renamed_indexes = catalog_table.indexes.iter().map(|(index_name, index)| {
+            let key_columns = index.key_schema.columns.iter().map(|col| {
+                if col.name == old_name {
+                    new_name
+                } else {
+                    &col.name
+                }
+            });
+            (index_name.clone(), index, key_columns.collect::<Vec<_>>())
+        });
+        let mut new_table =
+            self.reuse_table(catalog_table, Arc::new(Schema { columns }), renamed_indexes)?;
+        for constraint in catalog_table.constraints.iter() {
+            let mut constraint = constraint.clone();
+            match &mut constraint.kind {
+                ConstraintKind::PrimaryKey { columns }
+                | ConstraintKind::Unique { columns }
+                | ConstraintKind::ForeignKey { columns, .. } => {
+                    for col in columns.iter_mut().filter(|col| *col == old_name) {
+                        *col = new_name.to_string();
+                    }
+                }
+                // the stored expression can't be rewritten
+                ConstraintKind::Check { .. } => {
+                    if constraint.columns().iter().any(|col| col == old_name) {
+                        return Err(BustubxError::Storage(format!(
+                            "Cannot rename column {}, it is used by check constraint {}",
+                            old_name, constraint.name
+                        )));
+                    }
+                }
+            }
+            new_table.constraints.push(constraint);
+        }
+        self.replace_table(table_ref, table_ref.table(), new_table)
+    }
+
+    pub fn rename_table(
+        &mut self,
+        table_ref: &TableReference,
+        new_name: &str,
+    ) -> BustubxResult<()> {
+        let catalog_table = self.catalog_table(table_ref)?;
+        self.check_not_referenced(table_ref, &[])?;
+        let new_table_ref = TableReference::full(
+            table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME),
+            table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME),
+            new_name,
+        );
+        if self.table_heap(&new_table_ref).is_ok() {
+            return Err(BustubxError::Storage(format!(
+                "table {new_name} already exists"
+            )));
+        }
+
+        let columns = catalog_table
+            .table
+            .schema
+            .columns
+            .iter()
+            .map(|col| {
+                let relation = col.relation.as_ref().map(|_| new_table_ref.clone());
+                Arc::new(col.as_ref().clone().with_relation(relation))
+            })
+            .collect();
+        let indexes = catalog_table.indexes.iter().map(|(index_name, index)| {
+            let key_columns = index.key_schema.columns.iter().map(|col| col.name.as_str());
+            (index_name.clone(), index, key_columns.collect::<Vec<_>>())
+        });
+        let mut new_table =
+            self.reuse_table(catalog_table, Arc::new(Schema { columns }), indexes)?;
+        new_table.constraints = catalog_table.constraints.clone();
+        self.replace_table(table_ref, new_name, new_table)
+    }
+
+    fn catalog_table(&self, table_ref: &TableReference) -> BustubxResult<&CatalogTable> {
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+        if catalog_schema_name == INFORMATION_SCHEMA_NAME {
+            return Err(BustubxError::Storage(
+                "Cannot alter system table".to_string(),
+            ));
+        }
+        self.schemas
+            .get(catalog_schema_name)
+            .and_then(|schema| schema.tables.get(table_name))
+            .ok_or_else(|| BustubxError::Storage(format!("table {} not created yet", table_name)))
+    }
+
+    fn find_table(&self, table_ref: &TableReference) -> Option<&CatalogTable> {
+        self.schemas
+            .get(table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME))
+            .and_then(|schema| schema.tables.get(table_ref.table()))
+    }
+
+    /// The referenced table of a foreign key and the index of the referenced key.
+    fn referenced_key(
+        &self,
+        foreign_key: &Constraint,
+    ) -> BustubxResult<(&CatalogTable, &Arc<BPlusTreeIndex>)> {
+        let ConstraintKind::ForeignKey {
+            referenced_table,
+            referenced_columns,
+            ..
+        } = &foreign_key.kind
+        else {
+            return Err(BustubxError::Internal(format!(
+                "constraint {} is not a foreign key",
+                foreign_key.name
+            )));
+        };
+        let Some(referenced) = self.find_table(referenced_table) else {
+            return Err(BustubxError::Storage(format!(
+                "table {} not created yet",
+                referenced_table.table()
+            )));
+        };
+        referenced
+            .constraints
+            .iter()
+            .find(|c| c.key_columns() == Some(referenced_columns.as_slice()))
+            .and_then(|c| referenced.indexes.get(&c.name))
+            .map(|index| (referenced, index))
+            .ok_or_else(|| {
+                BustubxError::Internal(format!(
+                    "index of key referenced by {} not found",
+                    foreign_key.name
+                ))
+            })
+    }
+
+    /// Rejects changing columns of the table which are referenced by foreign keys of
+    /// other tables, or the table itself if no columns are given.
+    fn check_not_referenced(
+        &self,
+        table_ref: &TableReference,
+        column_names: &[&str],
+    ) -> BustubxResult<()> {
+        for (_, constraint) in self.referencing_foreign_keys(table_ref) {
+            let ConstraintKind::ForeignKey {
+                referenced_columns, ..
+            } = &constraint.kind
+            else {
+                continue;
+            };
+            if column_names.is_empty()
+                || referenced_columns
+                    .iter()
+                    .any(|col| column_names.contains(&col.as_str()))
+            {
+                return Err(BustubxError::Storage(format!(
+                    "Cannot alter table {}, it is referenced by foreign key {}",
+                    table_ref.table(),
+                    constraint.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies all tuples of the table into a new table with the new schema and rebuilds
+    /// the indexes whose columns still exist. Returns the new table and the pages of the
+    /// old table and indexes.
+    fn rewrite_table(
+        &self,
+        catalog_table: &CatalogTable,
+        schema: SchemaRef,
+        rewrite: impl Fn(Vec<ScalarValue>) -> Vec<ScalarValue>,
+    ) -> BustubxResult<(CatalogTable, Vec<PageId>)> {
+        let table_heap = Arc::new(TableHeap::try_new(
+            schema.clone(),
+            self.buffer_pool.clone(),
+        )?);
+        let mut new_table = CatalogTable::new(catalog_table.name.clone(), table_heap.clone());
+        let mut page_ids = catalog_table.table.page_ids()?;
+        for (index_name, index) in catalog_table.indexes.iter() {
+            page_ids.extend(index.page_ids()?);
+            let key_indices = index
+                .key_schema
+                .columns
+                .iter()
+                .map(|col| schema.index_of(None, &col.name))
+                .collect::<BustubxResult<Vec<_>>>();
+            // indexes on dropped columns are dropped as well
+            let Ok(key_indices) = key_indices else {
+                continue;
+            };
+            new_table.indexes.insert(
+                index_name.clone(),
+                Arc::new(BPlusTreeIndex::new(
+                    Arc::new(schema.project(&key_indices)?),
+                    self.buffer_pool.clone(),
+                    index.internal_max_size,
+                    index.leaf_max_size,
+                )),
+            );
+        }
+        // constraints on dropped columns are dropped as well
+        new_table.constraints = catalog_table
+            .constraints
+            .iter()
+            .filter(|constraint| {
+                constraint
+                    .columns()
+                    .iter()
+                    .all(|col| schema.index_of(None, col).is_ok())
+            })
+            .cloned()
+            .collect();
+
+        let mut iterator = TableIterator::new(catalog_table.table.clone(), ..);
+        while let Some((_, tuple)) = iterator.next()? {
+            let tuple = Tuple::new(schema.clone(), rewrite(tuple.data));
+            for (col, value) in schema.columns.iter().zip(tuple.data.iter()) {
+                if !col.nullable && value.is_null() {
+                    return Err(BustubxError::Storage(format!(
+                        "column {} contains null values",
+                        col.name
+                    )));
+                }
+            }
+            let rid = table_heap.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+            for index in new_table.indexes.values() {
+                index.insert(&tuple.project_with_schema(index.key_schema.clone())?, rid)?;
+            }
+        }
+        Ok((new_table, page_ids))
+    }
+
+    /// Makes a table with a new schema, but the same layout of tuples, which keeps the pages
+    /// of the table and its indexes.
+    fn reuse_table<'a>(
+        &self,
+        catalog_table: &CatalogTable,
+        schema: SchemaRef,
+        indexes: impl Iterator<Item = (String, &'a Arc<BPlusTreeIndex>, Vec<&'a str>)>,
+    ) -> BustubxResult<CatalogTable> {
+        let table_heap = TableHeap {
+            schema: schema.clone(),
+            buffer_pool: self.buffer_pool.clone(),
+            first_page_id: AtomicPageId::new(
+                catalog_table.table.first_page_id.load(Ordering::SeqCst),
+            ),
+            last_page_id: AtomicPageId::new(
+                catalog_table.table.last_page_id.load(Ordering::SeqCst),
+            ),
+        };
+        let mut new_table = CatalogTable::new(catalog_table.name.clone(), Arc::new(table_heap));
+        for (index_name, index, key_columns) in indexes {
+            let key_indices = key_columns
+                .into_iter()
+                .map(|name| schema.index_of(None, name))
+                .collect::<BustubxResult<Vec<_>>>()?;
+            let new_index = BPlusTreeIndex {
+                key_schema: Arc::new(schema.project(&key_indices)?),
+                buffer_pool: self.buffer_pool.clone(),
+                internal_max_size: index.internal_max_size,
+                leaf_max_size: index.leaf_max_size,
+                root_page_id: AtomicPageId::new(index.root_page_id.load(Ordering::SeqCst)),
+            };
+            new_table.indexes.insert(index_name, Arc::new(new_index));
+        }
+        Ok(new_table)
+    }
+
+    /// Replaces the table by an altered one, which may have a new name, and rewrites its
+    /// rows in the system tables. Statistics of the table are dropped.
+    fn replace_table(
+        &mut self,
+        table_ref: &TableReference,
+        new_table_name: &str,
+        mut new_table: CatalogTable,
+    ) -> BustubxResult<()> {
+        let catalog_name = table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME);
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_key = [
+            catalog_name.to_string().into(),
+            catalog_schema_name.to_string().into(),
+            table_ref.table().to_string().into(),
+        ];
+        for information_table in [
+            INFORMATION_SCHEMA_TABLES,
+            INFORMATION_SCHEMA_COLUMNS,
+            INFORMATION_SCHEMA_INDEXES,
+            INFORMATION_SCHEMA_STATISTICS,
+            INFORMATION_SCHEMA_CONSTRAINTS,
+            INFORMATION_SCHEMA_FOREIGN_KEYS,
+        ] {
+            self.delete_information_rows(information_table, &table_key)?;
+        }
+
+        self.insert_table_rows(
+            catalog_name,
+            catalog_schema_name,
+            new_table_name,
+            &new_table.table,
+        )?;
+        let mut index_names = new_table.indexes.keys().collect::<Vec<_>>();
+        index_names.sort();
+        for index_name in index_names {
+            self.insert_index_row(
+                catalog_name,
+                catalog_schema_name,
+                new_table_name,
+                index_name,
+                &new_table.indexes[index_name],
+            )?;
+        }
+        for constraint in new_table.constraints.iter() {
+            self.insert_constraint_row(
+                catalog_name,
+                catalog_schema_name,
+                new_table_name,
+                constraint,
+            )?;
+        }
+
+        new_table.name = new_table_name.to_string();
+        let Some(catalog_schema) = self.schemas.get_mut(catalog_schema_name) else {
+            return Err(BustubxError::Storage(format!(
+                "catalog schema {} not created yet",
+                catalog_schema_name
+            )));
+        };
+        catalog_schema.tables.remove(table_ref.table());
+        catalog_schema
+            .tables
+            .insert(new_table_name.to_string(), new_table);
+        Ok(())
+    }
+
+    fn insert_table_rows(
+        &self,
+        catalog_name: &str,
+        catalog_schema_name: &str,
+        table_name: &str,
+        table_heap: &TableHeap,
+    ) -> BustubxResult<()> {
+        let tables_table = self.table_heap(&TableReference::partial(
+            INFORMATION_SCHEMA_NAME,
+            INFORMATION_SCHEMA_TABLES,
+        ))?;
+        let tuple = Tuple::new(
+            TABLES_SCHMEA.clone(),
+            vec![
+                catalog_name.to_string().into(),
+                catalog_schema_name.to_string().into(),
+                table_name.to_string().into(),
+                (table_heap.first_page_id.load(Ordering::SeqCst)).into(),
+            ],
+        );
+        tables_table.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+
+        let columns_table = self.table_heap(&TableReference::partial(
+            INFORMATION_SCHEMA_NAME,
+            INFORMATION_SCHEMA_COLUMNS,
+        ))?;
+        for col in table_heap.schema.columns.iter() {
+            let sql_type: sqlparser::ast::DataType = (&col.data_type).into();
+            let tuple = Tuple::new(
+                COLUMNS_SCHMEA.clone(),
+                vec![
+                    catalog_name.to_string().into(),
+                    catalog_schema_name.to_string().into(),
+                    table_name.to_string().into(),
+                    col.name.clone().into(),
+                    format!("{sql_type}").into(),
+                    col.nullable.into(),
+                    default_to_varchar(&col.default).into(),
+                ],
+            );
+            columns_table.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+        }
+        Ok(())
+    }
+
+    fn insert_index_row(
+        &self,
+        catalog_name: &str,
+        catalog_schema_name: &str,
+        table_name: &str,
+        index_name: &str,
+        index: &BPlusTreeIndex,
+    ) -> BustubxResult<()> {
+        let indexes_table = self.table_heap(&TableReference::partial(
+            INFORMATION_SCHEMA_NAME,
+            INFORMATION_SCHEMA_INDEXES,
+        ))?;
+        let tuple = Tuple::new(
+            INDEXES_SCHMEA.clone(),
+            vec![
+                catalog_name.to_string().into(),
+                catalog_schema_name.to_string().into(),
+                table_name.to_string().into(),
+                index_name.to_string().into(),
+                key_schema_to_varchar(&index.key_schema).into(),
+                index.internal_max_size.into(),
+                index.leaf_max_size.into(),
+                index.root_page_id.load(Ordering::SeqCst).into(),
+            ],
+        );
+        indexes_table.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+        Ok(())
+    }
+
+    fn insert_constraint_row(
+        &self,
+        catalog_name: &str,
+        catalog_schema_name: &str,
+        table_name: &str,
+        constraint: &Constraint,
+    ) -> BustubxResult<()> {
+        if let ConstraintKind::ForeignKey {
+            columns,
+            referenced_table,
+            referenced_columns,
+            on_delete,
+        } = &constraint.kind
+        {
+            let foreign_keys_table = self.table_heap(&TableReference::partial(
+                INFORMATION_SCHEMA_NAME,
+                INFORMATION_SCHEMA_FOREIGN_KEYS,
+            ))?;
+            let tuple = Tuple::new(
+                FOREIGN_KEYS_SCHMEA.clone(),
+                vec![
+                    catalog_name.to_string().into(),
+                    catalog_schema_name.to_string().into(),
+                    table_name.to_string().into(),
+                    constraint.name.clone().into(),
+                    columns.join(", ").into(),
+                    referenced_table
+                        .schema()
+                        .unwrap_or(DEFAULT_SCHEMA_NAME)
+                        .to_string()
+                        .into(),
+                    referenced_table.table().to_string().into(),
+                    referenced_columns.join(", ").into(),
+                    on_delete.to_string().into(),
+                ],
+            );
+            foreign_keys_table.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+            return Ok(());
+        }
+        let constraints_table = self.table_heap(&TableReference::partial(
+            INFORMATION_SCHEMA_NAME,
+            INFORMATION_SCHEMA_CONSTRAINTS,
+        ))?;
+        let tuple = Tuple::new(
+            CONSTRAINTS_SCHMEA.clone(),
+            vec![
+                catalog_name.to_string().into(),
+                catalog_schema_name.to_string().into(),
+                table_name.to_string().into(),
+                constraint.name.clone().into(),
+                constraint.constraint_type().to_string().into(),
+                constraint.definition().into(),
+            ],
+        );
+        constraints_table.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+        Ok(())
+    }
+
+    /// Deletes the rows of an information_schema table which start with the key values.
+    fn delete_information_rows(
+        &self,
+        information_table: &str,
+        key: &[ScalarValue],
+    ) -> BustubxResult<()> {
+        let table_heap = self.table_heap(&TableReference::partial(
+            INFORMATION_SCHEMA_NAME,
+            information_table,
+        ))?;
+        let mut iterator = TableIterator::new(table_heap.clone(), ..);
+        while let Some((rid, tuple)) = iterator.next()? {
+            if tuple.data.starts_with(key) {
+                let mut meta = table_heap.tuple_meta(rid)?;
+                meta.is_deleted = true;
+                table_heap.update_tuple_meta(meta, rid)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn load_schema(&mut self, name: impl Into<String>, schema: CatalogSchema) {
         self.schemas.insert(name.into(), schema);
     }
@@ -391,16 +1457,108 @@ impl Catalog {
         catalog_table.indexes.insert(index_name.into(), index);
         Ok(())
     }
+    pub fn load_statistics(
+        &mut self,
+        table_ref: &TableReference,
+        statistics: Arc<TableStatistics>,
+    ) -> BustubxResult<()> {
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+        let Some(catalog_table) = self
+            .schemas
+            .get_mut(catalog_schema_name)
+            .and_then(|schema| schema.tables.get_mut(table_name))
+        else {
+            return Err(BustubxError::Storage(format!(
+                "catalog table {} not created yet",
+                table_name
+            )));
+        };
+        catalog_table.statistics = Some(statistics);
+        Ok(())
+    }
+
+    pub fn load_constraint(
+        &mut self,
+        table_ref: &TableReference,
+        constraint: Constraint,
+    ) -> BustubxResult<()> {
+        let catalog_schema_name = table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME);
+        let table_name = table_ref.table();
+        let Some(catalog_table) = self
+            .schemas
+            .get_mut(catalog_schema_name)
+            .and_then(|schema| schema.tables.get_mut(table_name))
+        else {
+            return Err(BustubxError::Storage(format!(
+                "catalog table {} not created yet",
+                table_name
+            )));
+        };
+        catalog_table.constraints.push(constraint);
+        Ok(())
+    }
+}
+
+fn full_table_ref(table_ref: &TableReference) -> TableReference {
+    TableReference::full(
+        table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME),
+        table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME),
+        table_ref.table(),
+    )
+}
+
+/// Builds a key of the index from values which are cast to the types of the key.
+fn key_tuple(index: &BPlusTreeIndex, values: Vec<ScalarValue>) -> BustubxResult<Tuple> {
+    let data = values
+        .iter()
+        .zip(index.key_schema.columns.iter())
+        .map(|(value, col)| value.cast_to(&col.data_type))
+        .collect::<BustubxResult<Vec<_>>>()?;
+    Ok(Tuple::new(index.key_schema.clone(), data))
+}
+
+/// Finds a tuple which isn't deleted and has the key, except the tuple `skip`.
+fn find_live_tuple(
+    catalog_table: &CatalogTable,
+    index: &Arc<BPlusTreeIndex>,
+    key: &Tuple,
+    skip: Option<RecordId>,
+) -> BustubxResult<Option<RecordId>> {
+    // entries of deleted tuples and old versions are kept in the index
+    let mut iterator = TreeIndexIterator::new(index.clone(), key.clone()..=key.clone());
+    while let Some(rid) = iterator.next()? {
+        if Some(rid) == skip {
+            continue;
+        }
+        if catalog_table.table.tuple_meta(rid)?.is_deleted {
+            continue;
+        }
+        let tuple = catalog_table.table.tuple(rid)?;
+        if tuple.project_with_schema(index.key_schema.clone())? == *key {
+            return Ok(Some(rid));
+        }
+    }
+    Ok(None)
+}
+
+fn format_key(key: &Tuple) -> String {
+    key.data
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
+    use tempfile::TempDir;
 
     use crate::common::TableReference;
     use crate::{
         catalog::{Column, DataType, Schema},
-        Database,
+        BustubxError, Database,
     };
 
     #[test]
@@ -473,4 +1631,238 @@ mod tests {
             .unwrap();
         assert_eq!(index3.key_schema, key_schema1);
     }
+
+    #[test]
+    pub fn test_catalog_load_statistics() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+        let table_ref = TableReference::bare("t1");
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("create table t1 (a int, b varchar)").unwrap();
+        db.run("insert into t1 values (1, 'a,b'), (2, null), (2, 'c')")
+            .unwrap();
+        assert!(db.catalog.table_statistics(&table_ref).unwrap().is_none());
+        db.run("analyze table t1").unwrap();
+        let statistics = db.catalog.table_statistics(&table_ref).unwrap().unwrap();
+        assert_eq!(statistics.row_count, 3);
+        assert_eq!(statistics.distinct_count("a"), Some(2));
+        assert_eq!(statistics.column("b").unwrap().null_count, 1);
+        drop(db);
+
+        let db = Database::new_on_disk(db_path).unwrap();
+        assert_eq!(
+            db.catalog.table_statistics(&table_ref).unwrap(),
+            Some(statistics)
+        );
+    }
+
+    #[test]
+    pub fn test_catalog_drop_table() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("create table t1 (a int, b varchar)").unwrap();
+        db.run("create index idx1 on t1 (a)").unwrap();
+        for i in 0..500 {
+            db.run(&format!("insert into t1 values ({i}, 'value {i}')"))
+                .unwrap();
+        }
+        db.run("analyze table t1").unwrap();
+        let table_ref = TableReference::bare("t1");
+        let table_pages = db
+            .catalog
+            .table_heap(&table_ref)
+            .unwrap()
+            .page_ids()
+            .unwrap();
+        let index = db.catalog.index(&table_ref, "idx1").unwrap().unwrap();
+        assert!(table_pages.len() > 1);
+        assert!(index.page_ids().unwrap().len() > 1);
+        let file_len = db.buffer_pool.disk_manager.db_file_len().unwrap();
+
+        db.run("drop table t1").unwrap();
+        assert!(db.catalog.table_heap(&table_ref).is_err());
+        for information_table in ["tables", "columns", "indexes", "statistics"] {
+            let rows = db
+                .run(&format!(
+                    "select * from information_schema.{information_table} where table_name = 't1'"
+                ))
+                .unwrap();
+            assert!(rows.is_empty(), "{information_table}");
+        }
+        drop(db);
+
+        // the pages of the table and index are reused after reopening
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        assert!(db.run("select * from t1").is_err());
+        db.run("create table t2 (a int, b varchar)").unwrap();
+        db.run("create index idx2 on t2 (a)").unwrap();
+        for i in 0..500 {
+            db.run(&format!("insert into t2 values ({i}, 'value {i}')"))
+                .unwrap();
+        }
+        assert_eq!(db.buffer_pool.disk_manager.db_file_len().unwrap(), file_len);
+        assert_eq!(db.run("select * from t2").unwrap().len(), 500);
+    }
+
+    #[test]
+    pub fn test_catalog_alter_table() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("create table t1 (a int, b varchar)").unwrap();
+        db.run("create index idx1 on t1 (a)").unwrap();
+        for i in 0..200 {
+            db.run(&format!("insert into t1 values ({i}, 'value {i}')"))
+                .unwrap();
+        }
+        db.run("alter table t1 add column c int default 5").unwrap();
+        db.run("alter table t1 drop column b").unwrap();
+        db.run("alter table t1 rename column a to id").unwrap();
+        db.run("alter table t1 rename to t2").unwrap();
+        drop(db);
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        assert!(db.run("select * from t1").is_err());
+        let table_ref = TableReference::bare("t2");
+        let schema = db.catalog.table_heap(&table_ref).unwrap().schema.clone();
+        let names = schema
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["id", "c"]);
+        let index = db.catalog.index(&table_ref, "idx1").unwrap().unwrap();
+        assert_eq!(index.key_schema.columns[0].name, "id");
+
+        let rows = db.run("select * from t2 where id = 42").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].data, vec![42i32.into(), 5i32.into()]);
+        assert_eq!(db.run("select * from t2").unwrap().len(), 200);
+    }
+
+    #[test]
+    pub fn test_catalog_column_defaults() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("create table t1 (a int default -1, b varchar default 'it''s', c varchar default 'null')")
+            .unwrap();
+        drop(db);
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("insert into t1 default values").unwrap();
+        let rows = db.run("select * from t1").unwrap();
+        assert_eq!(
+            rows[0].data,
+            vec![
+                (-1i32).into(),
+                "it's".to_string().into(),
+                "null".to_string().into()
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_catalog_constraints() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("create table t1 (a int primary key, b int unique, c int check (c > 0))")
+            .unwrap();
+        db.run("insert into t1 values (1, 10, 100)").unwrap();
+        drop(db);
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        let table_ref = TableReference::bare("t1");
+        assert!(!db.catalog.table_heap(&table_ref).unwrap().schema.columns[0].nullable);
+        for sql in [
+            "insert into t1 values (1, 20, 200)",
+            "insert into t1 values (null, 20, 200)",
+            "insert into t1 values (2, 10, 200)",
+            "insert into t1 values (2, 20, 0)",
+        ] {
+            assert!(
+                matches!(db.run(sql), Err(BustubxError::ConstraintViolation(_))),
+                "{sql}"
+            );
+        }
+
+        // constraints follow renamed and dropped columns
+        db.run("alter table t1 rename column a to id").unwrap();
+        assert!(db.run("alter table t1 rename column c to d").is_err());
+        db.run("alter table t1 drop column c").unwrap();
+        let rows = db
+            .run("select constraint_name, definition from information_schema.constraints")
+            .unwrap();
+        let rows = rows
+            .iter()
+            .map(|row| format!("{} {}", row.data[0], row.data[1]))
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec!["t1_pkey id", "t1_b_key b"]);
+        assert!(matches!(
+            db.run("insert into t1 values (1, 20)"),
+            Err(BustubxError::ConstraintViolation(_))
+        ));
+        db.run("insert into t1 values (2, 20)").unwrap();
+    }
+
+    #[test]
+    pub fn test_catalog_foreign_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("create table t1 (a int primary key, b int)")
+            .unwrap();
+        db.run("create table t2 (c int references t1 on delete cascade, d int)")
+            .unwrap();
+        db.run("insert into t1 values (1, 10), (2, 20)").unwrap();
+        db.run("insert into t2 values (1, 100), (2, 200)").unwrap();
+        drop(db);
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        assert!(matches!(
+            db.run("insert into t2 values (3, 300)"),
+            Err(BustubxError::ConstraintViolation(_))
+        ));
+        assert!(db.run("drop table t1").is_err());
+
+        // foreign keys follow renamed columns and tables of the referencing table
+        db.run("alter table t2 rename column c to e").unwrap();
+        db.run("alter table t2 rename to t3").unwrap();
+        let rows = db
+            .run("select * from information_schema.foreign_keys")
+            .unwrap();
+        let rows = rows
+            .iter()
+            .map(|row| {
+                row.data
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec!["bustubx public t3 t2_c_fkey e public t1 a CASCADE"]
+        );
+
+        db.run("delete from t1 where a = 1").unwrap();
+        let rows = db.run("select d from t3").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].data[0], 200.into());
+    }
 }
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::common::TableReference;
+use crate::expression::{collect_columns, Expr};
+use crate::{BustubxError, BustubxResult};
+
+/// A rule the rows of a table must follow. `PRIMARY KEY` and `UNIQUE` constraints are
+/// enforced with an index which has the name of the constraint, `FOREIGN KEY` constraints
+/// look up the referenced rows with the index of the referenced key.
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    pub name: String,
+    pub kind: ConstraintKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum ConstraintKind {
+    PrimaryKey {
+        columns: Vec<String>,
+    },
+    Unique {
+        columns: Vec<String>,
+    },
+    /// Rows for which `expr` is false violate the constraint. Comparisons with null are
+    /// false rather than unknown, so rows with a null in a referenced column pass.
+    /// `sql` is the text of the expression stored in information_schema.constraints.
+    Check {
+        expr: Expr,
+        sql: String,
+    },
+    /// Non-null values of `columns` must exist in `referenced_columns` of the referenced
+    /// table, which are its primary key or unique.
+    ForeignKey {
+        columns: Vec<String>,
+        referenced_table: TableReference,
+        referenced_columns: Vec<String>,
+        on_delete: ReferentialAction,
+    },
+}
+
+/// What happens to referencing rows when the referenced row is deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString)]
+pub enum ReferentialAction {
+    #[strum(serialize = "RESTRICT")]
+    Restrict,
+    #[strum(serialize = "CASCADE")]
+    Cascade,
+    #[strum(serialize = "SET NULL")]
+    SetNull,
+}
+
+impl Constraint {
+    pub fn new(name: impl Into<String>, kind: ConstraintKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+        }
+    }
+
+    /// Columns of the key of a `PRIMARY KEY` or `UNIQUE` constraint.
+    pub fn key_columns(&self) -> Option<&[String]> {
+        match &self.kind {
+            ConstraintKind::PrimaryKey { columns } | ConstraintKind::Unique { columns } => {
+                Some(columns)
+            }
+            ConstraintKind::Check { .. } | ConstraintKind::ForeignKey { .. } => None,
+        }
+    }
+
+    /// Names of the columns the constraint refers to.
+    pub fn columns(&self) -> Vec<String> {
+        match &self.kind {
+            ConstraintKind::PrimaryKey { columns }
+            | ConstraintKind::Unique { columns }
+            | ConstraintKind::ForeignKey { columns, .. } => columns.clone(),
+            ConstraintKind::Check { expr, .. } => {
+                let mut columns = HashSet::new();
+                collect_columns(expr, &mut columns);
+                columns.into_iter().map(|col| col.name).collect()
+            }
+        }
+    }
+
+    pub fn constraint_type(&self) -> &'static str {
+        match &self.kind {
+            ConstraintKind::PrimaryKey { .. } => "PRIMARY KEY",
+            ConstraintKind::Unique { .. } => "UNIQUE",
+            ConstraintKind::Check { .. } => "CHECK",
+            ConstraintKind::ForeignKey { .. } => "FOREIGN KEY",
+        }
+    }
+
+    /// Text stored in information_schema.constraints, key columns separated by commas or
+    /// the expression of a check.
+    pub fn definition(&self) -> String {
+        match &self.kind {
+            ConstraintKind::PrimaryKey { columns } | ConstraintKind::Unique { columns } => {
+                columns.join(", ")
+            }
+            ConstraintKind::Check { sql, .. } => sql.clone(),
+            ConstraintKind::ForeignKey {
+                columns,
+                referenced_table,
+                referenced_columns,
+                on_delete,
+            } => format!(
+                "({}) REFERENCES {} ({}) ON DELETE {}",
+                columns.join(", "),
+                referenced_table,
+                referenced_columns.join(", "),
+                on_delete
+            ),
+        }
+    }
+
+    /// Builds a key constraint from the stored text, check constraints need their
+    /// expression bound by the planner.
+    pub fn try_new_key(
+        name: impl Into<String>,
+        constraint_type: &str,
+        definition: &str,
+    ) -> BustubxResult<Self> {
+        let columns = definition
+            .split(',')
+            .map(|col| col.trim().to_string())
+            .collect();
+        let kind = match constraint_type {
+            "PRIMARY KEY" => ConstraintKind::PrimaryKey { columns },
+            "UNIQUE" => ConstraintKind::Unique { columns },
+            _ => {
+                return Err(BustubxError::Internal(format!(
+                    "{constraint_type} is not a key constraint"
+                )))
+            }
+        };
+        Ok(Self::new(name, kind))
+    }
+}
@@ -1,3 +1,6 @@
+use crate::common::decimal::{
+    DEFAULT_DECIMAL_PRECISION, DEFAULT_DECIMAL_SCALE, MAX_DECIMAL_PRECISION,
+};
 use crate::error::BustubxError;
 use crate::BustubxResult;
 use sqlparser::dialect::PostgreSqlDialect;
@@ -16,9 +19,93 @@ pub enum DataType {
     Float32,
     Float64,
     Varchar(Option<usize>),
+    /// Variable length binary string.
+    Blob,
+    /// Fixed-point number with precision and scale.
+    Decimal(u8, u8),
+    /// Days since 1970-01-01.
+    Date,
+    /// Microseconds since midnight.
+    Time,
+    /// Microseconds since 1970-01-01 00:00:00 UTC.
+    Timestamp,
+    Interval,
 }
 
 impl DataType {
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
+        )
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer()
+            || matches!(
+                self,
+                DataType::Float32 | DataType::Float64 | DataType::Decimal(_, _)
+            )
+    }
+
+    /// Precision and scale of a decimal that holds every value of this type, for
+    /// integers and decimals.
+    pub fn decimal_precision_scale(&self) -> Option<(u8, u8)> {
+        match self {
+            DataType::Int8 | DataType::UInt8 => Some((3, 0)),
+            DataType::Int16 | DataType::UInt16 => Some((5, 0)),
+            DataType::Int32 | DataType::UInt32 => Some((10, 0)),
+            DataType::Int64 => Some((19, 0)),
+            DataType::UInt64 => Some((20, 0)),
+            DataType::Decimal(precision, scale) => Some((*precision, *scale)),
+            _ => None,
+        }
+    }
+
+    pub fn is_temporal(&self) -> bool {
+        matches!(
+            self,
+            DataType::Date | DataType::Time | DataType::Timestamp | DataType::Interval
+        )
+    }
+
+    /// Coerce `lhs_type` and `rhs_type` to a common type for the purposes of a comparison operation.
+    /// A string is compared to a temporal value or a blob as that type and a date to a timestamp
+    /// as timestamp, other types are coerced as numbers.
+    pub fn comparison_coercion(l: &DataType, r: &DataType) -> BustubxResult<DataType> {
+        use super::DataType::*;
+        match (l, r) {
+            (Date, Timestamp) | (Timestamp, Date) => Ok(Timestamp),
+            (Varchar(_), t) | (t, Varchar(_)) if t.is_temporal() => Ok(*t),
+            (Varchar(_), Blob) | (Blob, Varchar(_)) => Ok(Blob),
+            _ => Self::comparison_numeric_coercion(l, r),
+        }
+    }
+
+    /// Type the values of several expressions are coerced to when they form one column, e.g.
+    /// the arguments of `coalesce` or the columns of a `UNION`.
+    pub fn common_type(types: &[DataType]) -> BustubxResult<DataType> {
+        let (first, rest) = types.split_first().ok_or_else(|| {
+            BustubxError::Internal("Cannot find common type of no types".to_string())
+        })?;
+        let mut result = *first;
+        for data_type in rest {
+            result = match (result, data_type) {
+                (l, r) if l == *r => l,
+                (DataType::Varchar(_), DataType::Varchar(_)) => DataType::Varchar(None),
+                (l, r) => Self::comparison_coercion(&l, r)?,
+            };
+        }
+        Ok(result)
+    }
+
     /// Coerce `lhs_type` and `rhs_type` to a common type for the purposes of a comparison operation
     /// where one both are numeric
     pub fn comparison_numeric_coercion(l: &DataType, r: &DataType) -> BustubxResult<DataType> {
@@ -28,7 +115,18 @@ impl DataType {
         }
         match (l, r) {
             (Float64, _) | (_, Float64) => Ok(Float64),
+            (Decimal(_, _), Float32) | (Float32, Decimal(_, _)) => Ok(Float64),
             (_, Float32) | (Float32, _) => Ok(Float32),
+            (Decimal(_, _), _) | (_, Decimal(_, _)) => {
+                let error = || {
+                    BustubxError::Internal(format!("Cannot coerce {} and {} for comparison", l, r))
+                };
+                let (p1, s1) = l.decimal_precision_scale().ok_or_else(error)?;
+                let (p2, s2) = r.decimal_precision_scale().ok_or_else(error)?;
+                let scale = s1.max(s2);
+                let precision = ((p1 - s1).max(p2 - s2) + scale).min(MAX_DECIMAL_PRECISION);
+                Ok(Decimal(precision, scale.min(precision)))
+            }
             // The following match arms encode the following logic: Given the two
             // integral types, we choose the narrowest possible integral type that
             // accommodates all values of both types. Note that some information
@@ -96,6 +194,38 @@ impl TryFrom<&sqlparser::ast::DataType> for DataType {
             sqlparser::ast::DataType::CharacterVarying(len) => {
                 Ok(DataType::Varchar(len.map(|l| l.length as usize)))
             }
+            sqlparser::ast::DataType::Bytea | sqlparser::ast::DataType::Blob(_) => {
+                Ok(DataType::Blob)
+            }
+            sqlparser::ast::DataType::Decimal(info)
+            | sqlparser::ast::DataType::Numeric(info)
+            | sqlparser::ast::DataType::Dec(info) => {
+                let (precision, scale) = match info {
+                    sqlparser::ast::ExactNumberInfo::None => (
+                        DEFAULT_DECIMAL_PRECISION as u64,
+                        DEFAULT_DECIMAL_SCALE as u64,
+                    ),
+                    sqlparser::ast::ExactNumberInfo::Precision(precision) => (*precision, 0),
+                    sqlparser::ast::ExactNumberInfo::PrecisionAndScale(precision, scale) => {
+                        (*precision, *scale)
+                    }
+                };
+                if precision == 0 || precision > MAX_DECIMAL_PRECISION as u64 || scale > precision {
+                    return Err(BustubxError::NotSupport(format!(
+                        "Decimal precision must be between 1 and {} and scale at most precision: {}",
+                        MAX_DECIMAL_PRECISION, value
+                    )));
+                }
+                Ok(DataType::Decimal(precision as u8, scale as u8))
+            }
+            sqlparser::ast::DataType::Date => Ok(DataType::Date),
+            sqlparser::ast::DataType::Time(
+                _,
+                sqlparser::ast::TimezoneInfo::None | sqlparser::ast::TimezoneInfo::WithoutTimeZone,
+            ) => Ok(DataType::Time),
+            // timestamps with time zone are stored as UTC
+            sqlparser::ast::DataType::Timestamp(_, _) => Ok(DataType::Timestamp),
+            sqlparser::ast::DataType::Interval => Ok(DataType::Interval),
             _ => Err(BustubxError::NotSupport(format!(
                 "Not support datatype {}",
                 value
@@ -124,6 +254,21 @@ impl From<&DataType> for sqlparser::ast::DataType {
                     unit: None,
                 }))
             }
+            DataType::Blob => sqlparser::ast::DataType::Bytea,
+            DataType::Decimal(precision, scale) => sqlparser::ast::DataType::Decimal(
+                sqlparser::ast::ExactNumberInfo::PrecisionAndScale(
+                    *precision as u64,
+                    *scale as u64,
+                ),
+            ),
+            DataType::Date => sqlparser::ast::DataType::Date,
+            DataType::Time => {
+                sqlparser::ast::DataType::Time(None, sqlparser::ast::TimezoneInfo::None)
+            }
+            DataType::Timestamp => {
+                sqlparser::ast::DataType::Timestamp(None, sqlparser::ast::TimezoneInfo::None)
+            }
+            DataType::Interval => sqlparser::ast::DataType::Interval,
         }
     }
 }
@@ -141,7 +286,13 @@ impl std::fmt::Display for DataType {
             | DataType::UInt32
             | DataType::UInt64
             | DataType::Float32
-            | DataType::Float64 => write!(f, "{self:?}"),
+            | DataType::Float64
+            | DataType::Blob
+            | DataType::Date
+            | DataType::Time
+            | DataType::Timestamp
+            | DataType::Interval => write!(f, "{self:?}"),
+            DataType::Decimal(precision, scale) => write!(f, "Decimal({precision}, {scale})"),
             DataType::Varchar(len_opt) => {
                 if let Some(len) = len_opt {
                     write!(f, "Varchar({len})")
@@ -167,6 +318,7 @@ impl TryFrom<&str> for DataType {
 #[cfg(test)]
 mod tests {
     use crate::catalog::DataType;
+    use crate::common::decimal::{DEFAULT_DECIMAL_PRECISION, DEFAULT_DECIMAL_SCALE};
 
     #[test]
     fn parse_data_type() {
@@ -181,5 +333,41 @@ mod tests {
             DataType::try_from(format!("{sql_type}").as_str()).unwrap(),
             DataType::Varchar(Some(100))
         );
+
+        for data_type in [
+            DataType::Blob,
+            DataType::Date,
+            DataType::Time,
+            DataType::Timestamp,
+            DataType::Interval,
+        ] {
+            let sql_type: sqlparser::ast::DataType = (&data_type).into();
+            assert_eq!(
+                DataType::try_from(format!("{sql_type}").as_str()).unwrap(),
+                data_type
+            );
+        }
+        let sql_type: sqlparser::ast::DataType = (&DataType::Decimal(10, 2)).into();
+        assert_eq!(
+            DataType::try_from(format!("{sql_type}").as_str()).unwrap(),
+            DataType::Decimal(10, 2)
+        );
+        assert_eq!(
+            DataType::try_from("numeric").unwrap(),
+            DataType::Decimal(DEFAULT_DECIMAL_PRECISION, DEFAULT_DECIMAL_SCALE)
+        );
+        assert_eq!(
+            DataType::try_from("numeric(5)").unwrap(),
+            DataType::Decimal(5, 0)
+        );
+        assert!(DataType::try_from("decimal(39, 2)").is_err());
+        assert!(DataType::try_from("decimal(2, 3)").is_err());
+        assert_eq!(DataType::try_from("blob").unwrap(), DataType::Blob);
+
+        assert_eq!(
+            DataType::try_from("timestamp with time zone").unwrap(),
+            DataType::Timestamp
+        );
+        assert!(DataType::try_from("time with time zone").is_err());
     }
 }
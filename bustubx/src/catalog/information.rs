@@ -1,11 +1,17 @@
 use crate::buffer::{AtomicPageId, PageId, INVALID_PAGE_ID};
 use crate::catalog::catalog::{CatalogSchema, CatalogTable};
-use crate::catalog::{Catalog, Column, DataType, Schema, SchemaRef, DEFAULT_SCHEMA_NAME};
+use crate::catalog::{
+    parse_histogram_from_varchar, Catalog, Column, ColumnStatistics, Constraint, ConstraintKind,
+    DataType, ReferentialAction, Schema, SchemaRef, TableStatistics, DEFAULT_SCHEMA_NAME,
+};
 use crate::common::{ScalarValue, TableReference};
+use crate::parser::parse_expr;
+use crate::planner::{LogicalPlanner, PlannerContext};
 use crate::storage::TableHeap;
 use crate::{BustubxError, BustubxResult, Database};
 
 use crate::storage::index::BPlusTreeIndex;
+use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
 pub static INFORMATION_SCHEMA_NAME: &str = "information_schema";
@@ -13,6 +19,9 @@ pub static INFORMATION_SCHEMA_SCHEMAS: &str = "schemas";
 pub static INFORMATION_SCHEMA_TABLES: &str = "tables";
 pub static INFORMATION_SCHEMA_COLUMNS: &str = "columns";
 pub static INFORMATION_SCHEMA_INDEXES: &str = "indexes";
+pub static INFORMATION_SCHEMA_STATISTICS: &str = "statistics";
+pub static INFORMATION_SCHEMA_CONSTRAINTS: &str = "constraints";
+pub static INFORMATION_SCHEMA_FOREIGN_KEYS: &str = "foreign_keys";
 
 pub static SCHEMAS_SCHMEA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(Schema::new(vec![
@@ -55,12 +64,55 @@ pub static INDEXES_SCHMEA: LazyLock<SchemaRef> = LazyLock::new(|| {
     ]))
 });
 
+pub static STATISTICS_SCHMEA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Column::new("table_catalog", DataType::Varchar(None), false),
+        Column::new("table_schema", DataType::Varchar(None), false),
+        Column::new("table_name", DataType::Varchar(None), false),
+        Column::new("column_name", DataType::Varchar(None), false),
+        Column::new("row_count", DataType::UInt64, false),
+        Column::new("null_count", DataType::UInt64, false),
+        Column::new("distinct_count", DataType::UInt64, false),
+        Column::new("min", DataType::Varchar(None), true),
+        Column::new("max", DataType::Varchar(None), true),
+        Column::new("histogram", DataType::Varchar(None), false),
+    ]))
+});
+
+pub static CONSTRAINTS_SCHMEA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Column::new("table_catalog", DataType::Varchar(None), false),
+        Column::new("table_schema", DataType::Varchar(None), false),
+        Column::new("table_name", DataType::Varchar(None), false),
+        Column::new("constraint_name", DataType::Varchar(None), false),
+        Column::new("constraint_type", DataType::Varchar(None), false),
+        Column::new("definition", DataType::Varchar(None), false),
+    ]))
+});
+
+pub static FOREIGN_KEYS_SCHMEA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Column::new("table_catalog", DataType::Varchar(None), false),
+        Column::new("table_schema", DataType::Varchar(None), false),
+        Column::new("table_name", DataType::Varchar(None), false),
+        Column::new("constraint_name", DataType::Varchar(None), false),
+        Column::new("columns", DataType::Varchar(None), false),
+        Column::new("referenced_table_schema", DataType::Varchar(None), false),
+        Column::new("referenced_table_name", DataType::Varchar(None), false),
+        Column::new("referenced_columns", DataType::Varchar(None), false),
+        Column::new("on_delete", DataType::Varchar(None), false),
+    ]))
+});
+
 pub fn load_catalog_data(db: &mut Database) -> BustubxResult<()> {
     load_information_schema(&mut db.catalog)?;
     load_schemas(db)?;
     create_default_schema_if_not_exists(&mut db.catalog)?;
     load_user_tables(db)?;
     load_user_indexes(db)?;
+    load_statistics(db)?;
+    load_constraints(db)?;
+    load_foreign_keys(db)?;
     Ok(())
 }
 
@@ -77,6 +129,12 @@ fn load_information_schema(catalog: &mut Catalog) -> BustubxResult<()> {
     let information_schema_tables_first_page_id = meta.information_schema_tables_first_page_id;
     let information_schema_columns_first_page_id = meta.information_schema_columns_first_page_id;
     let information_schema_indexes_first_page_id = meta.information_schema_indexes_first_page_id;
+    let information_schema_statistics_first_page_id =
+        meta.information_schema_statistics_first_page_id;
+    let information_schema_constraints_first_page_id =
+        meta.information_schema_constraints_first_page_id;
+    let information_schema_foreign_keys_first_page_id =
+        meta.information_schema_foreign_keys_first_page_id;
     drop(meta);
 
     // load last page id
@@ -100,6 +158,21 @@ fn load_information_schema(catalog: &mut Catalog) -> BustubxResult<()> {
         information_schema_indexes_first_page_id,
         INDEXES_SCHMEA.clone(),
     )?;
+    let information_schema_statistics_last_page_id = load_table_last_page_id(
+        catalog,
+        information_schema_statistics_first_page_id,
+        STATISTICS_SCHMEA.clone(),
+    )?;
+    let information_schema_constraints_last_page_id = load_table_last_page_id(
+        catalog,
+        information_schema_constraints_first_page_id,
+        CONSTRAINTS_SCHMEA.clone(),
+    )?;
+    let information_schema_foreign_keys_last_page_id = load_table_last_page_id(
+        catalog,
+        information_schema_foreign_keys_first_page_id,
+        FOREIGN_KEYS_SCHMEA.clone(),
+    )?;
 
     let mut information_schema = CatalogSchema::new(INFORMATION_SCHEMA_NAME);
 
@@ -147,6 +220,42 @@ fn load_information_schema(catalog: &mut Catalog) -> BustubxResult<()> {
         CatalogTable::new(INFORMATION_SCHEMA_INDEXES, Arc::new(indexes_table)),
     );
 
+    let statistics_table = TableHeap {
+        schema: STATISTICS_SCHMEA.clone(),
+        buffer_pool: catalog.buffer_pool.clone(),
+        first_page_id: AtomicPageId::new(information_schema_statistics_first_page_id),
+        last_page_id: AtomicPageId::new(information_schema_statistics_last_page_id),
+    };
+    information_schema.tables.insert(
+        INFORMATION_SCHEMA_STATISTICS.to_string(),
+        CatalogTable::new(INFORMATION_SCHEMA_STATISTICS, Arc::new(statistics_table)),
+    );
+
+    let constraints_table = TableHeap {
+        schema: CONSTRAINTS_SCHMEA.clone(),
+        buffer_pool: catalog.buffer_pool.clone(),
+        first_page_id: AtomicPageId::new(information_schema_constraints_first_page_id),
+        last_page_id: AtomicPageId::new(information_schema_constraints_last_page_id),
+    };
+    information_schema.tables.insert(
+        INFORMATION_SCHEMA_CONSTRAINTS.to_string(),
+        CatalogTable::new(INFORMATION_SCHEMA_CONSTRAINTS, Arc::new(constraints_table)),
+    );
+
+    let foreign_keys_table = TableHeap {
+        schema: FOREIGN_KEYS_SCHMEA.clone(),
+        buffer_pool: catalog.buffer_pool.clone(),
+        first_page_id: AtomicPageId::new(information_schema_foreign_keys_first_page_id),
+        last_page_id: AtomicPageId::new(information_schema_foreign_keys_last_page_id),
+    };
+    information_schema.tables.insert(
+        INFORMATION_SCHEMA_FOREIGN_KEYS.to_string(),
+        CatalogTable::new(
+            INFORMATION_SCHEMA_FOREIGN_KEYS,
+            Arc::new(foreign_keys_table),
+        ),
+    );
+
     catalog.load_schema(INFORMATION_SCHEMA_NAME, information_schema);
     Ok(())
 }
@@ -196,6 +305,7 @@ fn load_user_tables(db: &mut Database) -> BustubxResult<()> {
             return error;
         };
 
+        let table_ref = TableReference::full(catalog, table_schema, table_name);
         let column_tuples = db.run(&format!("select * from {}.{} where table_catalog = '{}' and table_schema = '{}' and table_name = '{}'",
                                             INFORMATION_SCHEMA_NAME, INFORMATION_SCHEMA_COLUMNS, catalog, table_schema, table_name))?;
         let mut columns = vec![];
@@ -217,9 +327,12 @@ fn load_user_tables(db: &mut Database) -> BustubxResult<()> {
                 return error;
             };
             let data_type: DataType = data_type_str.as_str().try_into()?;
-            let default = ScalarValue::from_string(default, data_type)?;
-            columns
-                .push(Column::new(column_name.clone(), data_type, *nullable).with_default(default));
+            let default = parse_default_from_varchar(default, data_type)?;
+            columns.push(
+                Column::new(column_name.clone(), data_type, *nullable)
+                    .with_relation(Some(table_ref.clone()))
+                    .with_default(default),
+            );
         }
         let schema = Arc::new(Schema::new(columns));
 
@@ -233,7 +346,7 @@ fn load_user_tables(db: &mut Database) -> BustubxResult<()> {
             last_page_id: AtomicPageId::new(last_page_id),
         };
         db.catalog.load_table(
-            TableReference::full(catalog, table_schema, table_name),
+            table_ref,
             CatalogTable::new(table_name, Arc::new(table_heap)),
         )?;
     }
@@ -295,6 +408,194 @@ fn load_user_indexes(db: &mut Database) -> BustubxResult<()> {
     Ok(())
 }
 
+fn load_statistics(db: &mut Database) -> BustubxResult<()> {
+    let statistics_tuples = db.run(&format!(
+        "select * from {}.{}",
+        INFORMATION_SCHEMA_NAME, INFORMATION_SCHEMA_STATISTICS
+    ))?;
+    let mut tables: HashMap<TableReference, TableStatistics> = HashMap::new();
+    for statistics_tuple in statistics_tuples.into_iter() {
+        let error = Err(BustubxError::Internal(format!(
+            "Failed to decode statistics tuple: {:?}",
+            statistics_tuple
+        )));
+        let ScalarValue::Varchar(Some(catalog_name)) = statistics_tuple.value(0)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(table_schema_name)) = statistics_tuple.value(1)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(table_name)) = statistics_tuple.value(2)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(column_name)) = statistics_tuple.value(3)? else {
+            return error;
+        };
+        let ScalarValue::UInt64(Some(row_count)) = statistics_tuple.value(4)? else {
+            return error;
+        };
+        let ScalarValue::UInt64(Some(null_count)) = statistics_tuple.value(5)? else {
+            return error;
+        };
+        let ScalarValue::UInt64(Some(distinct_count)) = statistics_tuple.value(6)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(min) = statistics_tuple.value(7)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(max) = statistics_tuple.value(8)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(histogram)) = statistics_tuple.value(9)? else {
+            return error;
+        };
+
+        let table_ref = TableReference::full(catalog_name, table_schema_name, table_name);
+        let table_schema = db.catalog.table_heap(&table_ref)?.schema.clone();
+        let data_type = table_schema.column_with_name(None, column_name)?.data_type;
+        let parse_value = |value: &Option<String>| match value {
+            Some(value) => ScalarValue::from_string(value, data_type),
+            None => Ok(ScalarValue::new_empty(data_type)),
+        };
+        let column_statistics = ColumnStatistics {
+            null_count: *null_count,
+            distinct_count: *distinct_count,
+            min: parse_value(min)?,
+            max: parse_value(max)?,
+            histogram: parse_histogram_from_varchar(histogram, data_type)?,
+        };
+
+        let table_statistics = tables.entry(table_ref).or_default();
+        table_statistics.row_count = *row_count;
+        table_statistics
+            .columns
+            .insert(column_name.clone(), column_statistics);
+    }
+    for (table_ref, table_statistics) in tables {
+        db.catalog
+            .load_statistics(&table_ref, Arc::new(table_statistics))?;
+    }
+    Ok(())
+}
+
+fn load_constraints(db: &mut Database) -> BustubxResult<()> {
+    let constraint_tuples = db.run(&format!(
+        "select * from {}.{}",
+        INFORMATION_SCHEMA_NAME, INFORMATION_SCHEMA_CONSTRAINTS
+    ))?;
+    for constraint_tuple in constraint_tuples.into_iter() {
+        let error = Err(BustubxError::Internal(format!(
+            "Failed to decode constraint tuple: {:?}",
+            constraint_tuple
+        )));
+        let ScalarValue::Varchar(Some(catalog_name)) = constraint_tuple.value(0)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(table_schema_name)) = constraint_tuple.value(1)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(table_name)) = constraint_tuple.value(2)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(constraint_name)) = constraint_tuple.value(3)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(constraint_type)) = constraint_tuple.value(4)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(definition)) = constraint_tuple.value(5)? else {
+            return error;
+        };
+
+        let constraint = if constraint_type == "CHECK" {
+            let planner = LogicalPlanner {
+                context: PlannerContext {
+                    catalog: &db.catalog,
+                },
+            };
+            let expr = planner.bind_expr(&parse_expr(definition)?)?;
+            Constraint::new(
+                constraint_name,
+                ConstraintKind::Check {
+                    expr,
+                    sql: definition.clone(),
+                },
+            )
+        } else {
+            Constraint::try_new_key(constraint_name, constraint_type, definition)?
+        };
+        let table_ref = TableReference::full(catalog_name, table_schema_name, table_name);
+        db.catalog.load_constraint(&table_ref, constraint)?;
+    }
+    Ok(())
+}
+
+fn load_foreign_keys(db: &mut Database) -> BustubxResult<()> {
+    let foreign_key_tuples = db.run(&format!(
+        "select * from {}.{}",
+        INFORMATION_SCHEMA_NAME, INFORMATION_SCHEMA_FOREIGN_KEYS
+    ))?;
+    for foreign_key_tuple in foreign_key_tuples.into_iter() {
+        let error = Err(BustubxError::Internal(format!(
+            "Failed to decode foreign key tuple: {:?}",
+            foreign_key_tuple
+        )));
+        let ScalarValue::Varchar(Some(catalog_name)) = foreign_key_tuple.value(0)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(table_schema_name)) = foreign_key_tuple.value(1)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(table_name)) = foreign_key_tuple.value(2)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(constraint_name)) = foreign_key_tuple.value(3)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(columns)) = foreign_key_tuple.value(4)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(referenced_schema_name)) = foreign_key_tuple.value(5)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(referenced_table_name)) = foreign_key_tuple.value(6)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(referenced_columns)) = foreign_key_tuple.value(7)? else {
+            return error;
+        };
+        let ScalarValue::Varchar(Some(on_delete)) = foreign_key_tuple.value(8)? else {
+            return error;
+        };
+
+        let split_columns = |columns: &str| {
+            columns
+                .split(',')
+                .map(|col| col.trim().to_string())
+                .collect::<Vec<_>>()
+        };
+        let Ok(on_delete) = on_delete.parse::<ReferentialAction>() else {
+            return error;
+        };
+        let constraint = Constraint::new(
+            constraint_name,
+            ConstraintKind::ForeignKey {
+                columns: split_columns(columns),
+                referenced_table: TableReference::full(
+                    catalog_name.clone(),
+                    referenced_schema_name,
+                    referenced_table_name,
+                ),
+                referenced_columns: split_columns(referenced_columns),
+                on_delete,
+            },
+        );
+        let table_ref = TableReference::full(catalog_name, table_schema_name, table_name);
+        db.catalog.load_constraint(&table_ref, constraint)?;
+    }
+    Ok(())
+}
+
 fn load_table_last_page_id(
     catalog: &mut Catalog,
     first_page_id: PageId,
@@ -314,6 +615,24 @@ fn load_table_last_page_id(
     }
 }
 
+/// Strings are quoted like SQL literals, so they can't be confused with null.
+pub fn default_to_varchar(default: &ScalarValue) -> String {
+    match default {
+        ScalarValue::Varchar(Some(v)) => format!("'{}'", v.replace('\'', "''")),
+        _ => default.to_string(),
+    }
+}
+
+fn parse_default_from_varchar(varchar: &str, data_type: DataType) -> BustubxResult<ScalarValue> {
+    match varchar
+        .strip_prefix('\'')
+        .and_then(|quoted| quoted.strip_suffix('\''))
+    {
+        Some(quoted) => ScalarValue::Varchar(Some(quoted.replace("''", "'"))).cast_to(&data_type),
+        None => ScalarValue::from_string(&varchar.to_string(), data_type),
+    }
+}
+
 pub fn key_schema_to_varchar(key_schema: &Schema) -> String {
     key_schema
         .columns
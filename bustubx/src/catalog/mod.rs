@@ -1,11 +1,15 @@
 mod catalog;
 mod column;
+mod constraint;
 mod data_type;
 mod information;
 mod schema;
+mod statistics;
 
 pub use catalog::*;
 pub use column::{Column, ColumnRef};
+pub use constraint::{Constraint, ConstraintKind, ReferentialAction};
 pub use data_type::DataType;
 pub use information::*;
 pub use schema::*;
+pub use statistics::*;
@@ -0,0 +1,318 @@
+use crate::catalog::DataType;
+use crate::common::ScalarValue;
+use crate::BustubxResult;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Bound;
+
+/// Number of buckets of the equi-depth histograms built by `ANALYZE`.
+pub const HISTOGRAM_BUCKETS: usize = 16;
+
+/// Selectivity of predicates the statistics can't tell anything about.
+pub const DEFAULT_SELECTIVITY: f64 = 0.1;
+
+/// Statistics of a table collected by `ANALYZE`, stored in information_schema.statistics.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TableStatistics {
+    pub row_count: u64,
+    pub columns: HashMap<String, ColumnStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStatistics {
+    pub null_count: u64,
+    pub distinct_count: u64,
+    /// Smallest and largest non-null values, null if the column only has null values
+    pub min: ScalarValue,
+    pub max: ScalarValue,
+    /// Upper bounds of equi-depth buckets, each bucket holds the same number of non-null
+    /// values. The first bucket starts at `min`.
+    pub histogram: Vec<ScalarValue>,
+}
+
+impl TableStatistics {
+    pub fn column(&self, name: &str) -> Option<&ColumnStatistics> {
+        self.columns.get(name)
+    }
+
+    /// Estimated fraction of rows whose column equals `value`.
+    pub fn eq_selectivity(&self, column: &str, value: &ScalarValue) -> Option<f64> {
+        let stats = self.column(column)?;
+        if self.row_count == 0 {
+            return Some(0.0);
+        }
+        if value.is_null() {
+            return Some(stats.null_count as f64 / self.row_count as f64);
+        }
+        Some(stats.eq_fraction(value) * self.non_null_fraction(stats))
+    }
+
+    /// Estimated fraction of rows whose column is within the bounds.
+    pub fn range_selectivity(
+        &self,
+        column: &str,
+        lower: Bound<&ScalarValue>,
+        upper: Bound<&ScalarValue>,
+    ) -> Option<f64> {
+        let stats = self.column(column)?;
+        if self.row_count == 0 {
+            return Some(0.0);
+        }
+        let lower = match lower {
+            Bound::Included(value) => stats.fraction_below(value),
+            Bound::Excluded(value) => stats.fraction_below(value) + stats.eq_fraction(value),
+            Bound::Unbounded => 0.0,
+        };
+        let upper = match upper {
+            Bound::Included(value) => stats.fraction_below(value) + stats.eq_fraction(value),
+            Bound::Excluded(value) => stats.fraction_below(value),
+            Bound::Unbounded => 1.0,
+        };
+        Some((upper - lower).clamp(0.0, 1.0) * self.non_null_fraction(stats))
+    }
+
+    pub fn distinct_count(&self, column: &str) -> Option<u64> {
+        self.column(column).map(|stats| stats.distinct_count)
+    }
+
+    fn non_null_fraction(&self, stats: &ColumnStatistics) -> f64 {
+        (self.row_count - stats.null_count.min(self.row_count)) as f64 / self.row_count as f64
+    }
+}
+
+impl ColumnStatistics {
+    /// Computes the statistics of all values of a column.
+    pub fn compute(data_type: DataType, values: Vec<ScalarValue>) -> Self {
+        let total = values.len();
+        let mut values = values
+            .into_iter()
+            .filter(|value| !value.is_null())
+            .collect::<Vec<_>>();
+        let null_count = (total - values.len()) as u64;
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let mut distinct_count = 0;
+        for (idx, value) in values.iter().enumerate() {
+            if idx == 0 || &values[idx - 1] != value {
+                distinct_count += 1;
+            }
+        }
+
+        let buckets = HISTOGRAM_BUCKETS.min(values.len());
+        let histogram = (1..=buckets)
+            .map(|bucket| values[(bucket * values.len()).div_ceil(buckets) - 1].clone())
+            .collect();
+
+        Self {
+            null_count,
+            distinct_count,
+            min: values
+                .first()
+                .cloned()
+                .unwrap_or(ScalarValue::new_empty(data_type)),
+            max: values
+                .last()
+                .cloned()
+                .unwrap_or(ScalarValue::new_empty(data_type)),
+            histogram,
+        }
+    }
+
+    /// Estimated fraction of non-null values equal to `value`. Values which are the upper
+    /// bound of several buckets are frequent ones and take the rows of those buckets.
+    fn eq_fraction(&self, value: &ScalarValue) -> f64 {
+        if self.distinct_count == 0 || value < &self.min || value > &self.max {
+            return 0.0;
+        }
+        let bounds = self
+            .histogram
+            .iter()
+            .filter(|bound| *bound == value)
+            .count();
+        let frequent = if bounds > 1 {
+            (bounds - 1) as f64 / self.histogram.len() as f64
+        } else {
+            0.0
+        };
+        frequent.max(1.0 / self.distinct_count as f64)
+    }
+
+    /// Estimated fraction of non-null values less than `value`.
+    fn fraction_below(&self, value: &ScalarValue) -> f64 {
+        let buckets = self.histogram.len();
+        let mut lower = &self.min;
+        for (idx, upper) in self.histogram.iter().enumerate() {
+            if value <= lower {
+                return idx as f64 / buckets as f64;
+            }
+            if value <= upper {
+                return (idx as f64 + interpolate(lower, upper, value)) / buckets as f64;
+            }
+            lower = upper;
+        }
+        1.0
+    }
+}
+
+/// Position of `value` between `lower` and `upper` as a fraction, assumes values are
+/// evenly distributed in between.
+fn interpolate(lower: &ScalarValue, upper: &ScalarValue, value: &ScalarValue) -> f64 {
+    match (to_f64(lower), to_f64(upper), to_f64(value)) {
+        (Some(lower), Some(upper), Some(value)) if upper > lower => {
+            ((value - lower) / (upper - lower)).clamp(0.0, 1.0)
+        }
+        _ => 0.5,
+    }
+}
+
+fn to_f64(value: &ScalarValue) -> Option<f64> {
+    match value {
+        ScalarValue::Date(v) => v.map(|v| v as f64),
+        ScalarValue::Time(v) | ScalarValue::Timestamp(v) => v.map(|v| v as f64),
+        _ => match value.cast_to(&DataType::Float64) {
+            Ok(ScalarValue::Float64(v)) => v,
+            _ => None,
+        },
+    }
+}
+
+/// Encodes histogram bounds as a comma separated list, commas and backslashes in the
+/// values are escaped by a backslash.
+pub fn histogram_to_varchar(histogram: &[ScalarValue]) -> String {
+    histogram
+        .iter()
+        .map(|value| value.to_string().replace('\\', "\\\\").replace(',', "\\,"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn parse_histogram_from_varchar(
+    varchar: &str,
+    data_type: DataType,
+) -> BustubxResult<Vec<ScalarValue>> {
+    if varchar.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut bounds = vec![];
+    let mut bound = String::new();
+    let mut chars = varchar.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => bound.extend(chars.next()),
+            ',' => bounds.push(std::mem::take(&mut bound)),
+            c => bound.push(c),
+        }
+    }
+    bounds.push(bound);
+    bounds
+        .iter()
+        .map(|bound| ScalarValue::from_string(bound, data_type))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalog::{
+        histogram_to_varchar, parse_histogram_from_varchar, ColumnStatistics, DataType,
+        TableStatistics,
+    };
+    use crate::common::ScalarValue;
+    use std::collections::HashMap;
+    use std::ops::Bound;
+
+    fn table_statistics(values: Vec<ScalarValue>) -> TableStatistics {
+        let row_count = values.len() as u64;
+        let stats = ColumnStatistics::compute(DataType::Int32, values);
+        TableStatistics {
+            row_count,
+            columns: HashMap::from([("a".to_string(), stats)]),
+        }
+    }
+
+    #[test]
+    pub fn test_column_statistics() {
+        let mut values = (0..100)
+            .map(|i| ScalarValue::Int32(Some(i % 50)))
+            .collect::<Vec<_>>();
+        values.extend((0..10).map(|_| ScalarValue::Int32(None)));
+        let stats = ColumnStatistics::compute(DataType::Int32, values);
+        assert_eq!(stats.null_count, 10);
+        assert_eq!(stats.distinct_count, 50);
+        assert_eq!(stats.min, ScalarValue::Int32(Some(0)));
+        assert_eq!(stats.max, ScalarValue::Int32(Some(49)));
+        assert_eq!(stats.histogram.len(), 16);
+        assert_eq!(stats.histogram.last(), Some(&ScalarValue::Int32(Some(49))));
+
+        let stats = ColumnStatistics::compute(DataType::Int32, vec![ScalarValue::Int32(None)]);
+        assert_eq!(stats.distinct_count, 0);
+        assert_eq!(stats.min, ScalarValue::Int32(None));
+        assert!(stats.histogram.is_empty());
+    }
+
+    #[test]
+    pub fn test_selectivity() {
+        let stats = table_statistics((0..1000).map(|i| ScalarValue::Int32(Some(i))).collect());
+        let eq = stats
+            .eq_selectivity("a", &ScalarValue::Int32(Some(10)))
+            .unwrap();
+        assert!((eq - 0.001).abs() < 1e-9);
+        assert_eq!(
+            stats.eq_selectivity("a", &ScalarValue::Int32(Some(1000))),
+            Some(0.0)
+        );
+        assert_eq!(
+            stats.eq_selectivity("b", &ScalarValue::Int32(Some(1))),
+            None
+        );
+
+        let range = stats
+            .range_selectivity(
+                "a",
+                Bound::Excluded(&ScalarValue::Int32(Some(899))),
+                Bound::Unbounded,
+            )
+            .unwrap();
+        assert!((range - 0.1).abs() < 0.01);
+        let range = stats
+            .range_selectivity(
+                "a",
+                Bound::Included(&ScalarValue::Int32(Some(250))),
+                Bound::Excluded(&ScalarValue::Int32(Some(750))),
+            )
+            .unwrap();
+        assert!((range - 0.5).abs() < 0.01);
+
+        // skewed values take the rows of all buckets they bound
+        let mut values = (0..900)
+            .map(|_| ScalarValue::Int32(Some(7)))
+            .collect::<Vec<_>>();
+        values.extend((0..100).map(|i| ScalarValue::Int32(Some(i + 100))));
+        let stats = table_statistics(values);
+        let eq = stats
+            .eq_selectivity("a", &ScalarValue::Int32(Some(7)))
+            .unwrap();
+        assert!(eq > 0.8);
+        let eq = stats
+            .eq_selectivity("a", &ScalarValue::Int32(Some(150)))
+            .unwrap();
+        assert!(eq < 0.02);
+    }
+
+    #[test]
+    pub fn test_histogram_varchar() {
+        let histogram = vec![
+            ScalarValue::Varchar(Some("a,b".to_string())),
+            ScalarValue::Varchar(Some("c\\".to_string())),
+            ScalarValue::Varchar(Some("".to_string())),
+        ];
+        let varchar = histogram_to_varchar(&histogram);
+        assert_eq!(
+            parse_histogram_from_varchar(&varchar, DataType::Varchar(None)).unwrap(),
+            histogram
+        );
+        assert_eq!(
+            parse_histogram_from_varchar("", DataType::Int32).unwrap(),
+            vec![]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+/// Decimals are stored as an `i128` scaled by `10^scale`, e.g. `123.45` with scale 2 is
+/// `12345`, which fits 38 decimal digits.
+pub const MAX_DECIMAL_PRECISION: u8 = 38;
+/// Precision and scale of `DECIMAL` and `NUMERIC` without a precision.
+pub const DEFAULT_DECIMAL_PRECISION: u8 = 38;
+pub const DEFAULT_DECIMAL_SCALE: u8 = 10;
+/// Scale an arithmetic result keeps at least when its precision exceeds the maximum.
+const MIN_ADJUSTED_SCALE: u8 = 6;
+
+pub fn pow10(exp: u8) -> Option<i128> {
+    10i128.checked_pow(exp as u32)
+}
+
+/// Changes the scale of `value`, rounding half away from zero if the scale decreases.
+pub fn rescale(value: i128, from: u8, to: u8) -> Option<i128> {
+    if to >= from {
+        return value.checked_mul(pow10(to - from)?);
+    }
+    match pow10(from - to) {
+        Some(divisor) => Some(div_round(value, divisor)),
+        // the divisor exceeds any value
+        None => Some(0),
+    }
+}
+
+/// Divides rounding half away from zero.
+pub fn div_round(dividend: i128, divisor: i128) -> i128 {
+    let quotient = dividend / divisor;
+    let remainder = dividend % divisor;
+    if remainder.unsigned_abs() >= divisor.unsigned_abs() - remainder.unsigned_abs() {
+        quotient + dividend.signum() * divisor.signum()
+    } else {
+        quotient
+    }
+}
+
+pub fn is_within_precision(value: i128, precision: u8) -> bool {
+    pow10(precision).is_none_or(|max| value.unsigned_abs() < max as u128)
+}
+
+pub fn to_f64(value: i128, scale: u8) -> f64 {
+    value as f64 / 10f64.powi(scale as i32)
+}
+
+/// Conversion of a decimal to a primitive number, integers are rounded half away from zero.
+pub trait FromDecimal: Sized {
+    fn from_decimal(value: i128, scale: u8) -> Option<Self>;
+}
+
+macro_rules! impl_from_decimal_for_integer {
+    ($($ty:ty),+) => {
+        $(impl FromDecimal for $ty {
+            fn from_decimal(value: i128, scale: u8) -> Option<Self> {
+                <$ty>::try_from(rescale(value, scale, 0)?).ok()
+            }
+        })+
+    };
+}
+
+impl_from_decimal_for_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl FromDecimal for f32 {
+    fn from_decimal(value: i128, scale: u8) -> Option<Self> {
+        Some(to_f64(value, scale) as f32)
+    }
+}
+
+impl FromDecimal for f64 {
+    fn from_decimal(value: i128, scale: u8) -> Option<Self> {
+        Some(to_f64(value, scale))
+    }
+}
+
+/// Key which orders decimals of any scale, the integer part and the fraction scaled to
+/// the maximum precision.
+pub fn sort_key(value: i128, scale: u8) -> (i128, i128) {
+    let unit = pow10(scale).unwrap_or(i128::MAX);
+    let fraction = value.rem_euclid(unit);
+    (
+        value.div_euclid(unit),
+        fraction.saturating_mul(pow10(MAX_DECIMAL_PRECISION.saturating_sub(scale)).unwrap_or(1)),
+    )
+}
+
+/// Parses `[+-]digits[.digits][e[+-]digits]` to a value with `scale`, extra fraction
+/// digits are rounded half away from zero.
+pub fn parse_decimal(s: &str, scale: u8) -> Option<i128> {
+    let s = s.trim();
+    let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().ok()?),
+        None => (s, 0),
+    };
+    let (negative, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => (true, mantissa),
+        None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    // the value is `digits * 10^shift` with the scale applied
+    let digits = format!("{integer}{fraction}");
+    let digits = digits.trim_start_matches('0');
+    let shift = exponent
+        .checked_sub(fraction.len() as i32)?
+        .checked_add(scale as i32)?;
+    let value = if shift >= 0 {
+        if digits.is_empty() {
+            0
+        } else {
+            digits
+                .parse::<i128>()
+                .ok()?
+                .checked_mul(10i128.checked_pow(shift as u32)?)?
+        }
+    } else {
+        let dropped = shift.unsigned_abs() as usize;
+        if dropped > digits.len() {
+            0
+        } else {
+            let (kept, rest) = digits.split_at(digits.len() - dropped);
+            let kept = if kept.is_empty() {
+                0
+            } else {
+                kept.parse::<i128>().ok()?
+            };
+            if rest.starts_with(['5', '6', '7', '8', '9']) {
+                kept.checked_add(1)?
+            } else {
+                kept
+            }
+        }
+    };
+    Some(if negative { -value } else { value })
+}
+
+pub fn format_decimal(value: i128, scale: u8) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let digits = value.unsigned_abs().to_string();
+    if scale == 0 {
+        return format!("{sign}{digits}");
+    }
+    let digits = format!("{digits:0>width$}", width = scale as usize + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale as usize);
+    format!("{sign}{integer}.{fraction}")
+}
+
+/// Caps the precision of an arithmetic result at the maximum, the scale is reduced to keep
+/// the integer digits but not below 6 digits.
+pub fn adjust_precision_scale(precision: u8, scale: u8) -> (u8, u8) {
+    if precision <= MAX_DECIMAL_PRECISION {
+        return (precision, scale);
+    }
+    let integer_digits = precision - scale;
+    let min_scale = scale.min(MIN_ADJUSTED_SCALE);
+    let scale = MAX_DECIMAL_PRECISION
+        .saturating_sub(integer_digits)
+        .max(min_scale)
+        .min(scale);
+    (MAX_DECIMAL_PRECISION, scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_decimal() {
+        let cases = [
+            ("123.45", 2, "123.45"),
+            ("-0.5", 2, "-0.50"),
+            ("1.005", 2, "1.01"),
+            ("-1.005", 2, "-1.01"),
+            ("1.004", 2, "1.00"),
+            ("1e3", 0, "1000"),
+            ("1.5E-1", 3, "0.150"),
+            (".5", 0, "1"),
+            ("0.0000000000000000000000000000000000000000001", 2, "0.00"),
+            (
+                "99999999999999999999999999999999999999",
+                0,
+                "99999999999999999999999999999999999999",
+            ),
+        ];
+        for (input, scale, expected) in cases {
+            let value = parse_decimal(input, scale).unwrap();
+            assert_eq!(format_decimal(value, scale), expected);
+        }
+        assert!(parse_decimal("1.2.3", 2).is_none());
+        assert!(parse_decimal("abc", 2).is_none());
+        assert!(parse_decimal("-", 2).is_none());
+        assert!(parse_decimal("1e40", 0).is_none());
+    }
+
+    #[test]
+    fn test_rescale_and_sort_key() {
+        assert_eq!(rescale(12345, 2, 4), Some(1234500));
+        assert_eq!(rescale(12345, 2, 1), Some(1235));
+        assert_eq!(rescale(-12345, 2, 1), Some(-1235));
+        assert_eq!(rescale(-12344, 2, 0), Some(-123));
+        assert_eq!(rescale(1, 0, 39), None);
+        assert_eq!(div_round(7, -2), -4);
+        assert!(is_within_precision(99999, 5));
+        assert!(!is_within_precision(-100000, 5));
+
+        assert_eq!(sort_key(150, 2), sort_key(15, 1));
+        assert!(sort_key(-150, 2) < sort_key(-149, 2));
+        assert!(sort_key(-1, 0) < sort_key(-5, 1));
+        assert!(sort_key(10, 0) > sort_key(99, 1));
+
+        assert_eq!(adjust_precision_scale(20, 4), (20, 4));
+        assert_eq!(adjust_precision_scale(77, 20), (38, 6));
+        assert_eq!(adjust_precision_scale(40, 10), (38, 8));
+        assert_eq!(adjust_precision_scale(50, 3), (38, 3));
+    }
+}
@@ -1,6 +1,8 @@
 mod bitmap;
+pub mod decimal;
 mod scalar;
 mod table_ref;
+pub mod temporal;
 pub mod util;
 
 pub use bitmap::DynamicBitmap;
@@ -1,4 +1,6 @@
 use crate::catalog::DataType;
+use crate::common::decimal;
+use crate::common::temporal::{self, Interval};
 use crate::{BustubxError, BustubxResult};
 use std::cmp::Ordering;
 
@@ -16,6 +18,158 @@ pub enum ScalarValue {
     Float32(Option<f32>),
     Float64(Option<f64>),
     Varchar(Option<String>),
+    Blob(Option<Vec<u8>>),
+    /// Value scaled by `10^scale`, precision and scale.
+    Decimal(Option<i128>, u8, u8),
+    Date(Option<i32>),
+    Time(Option<i64>),
+    Timestamp(Option<i64>),
+    Interval(Option<Interval>),
+}
+
+/// Checked conversion of integer and float values to a numeric primitive type.
+trait FromNumeric: Sized {
+    fn from_integer(value: i128) -> Option<Self>;
+    /// Rounds half away from zero like decimals, fails for NaN, infinity and values out of
+    /// range after rounding.
+    fn from_float(value: f64) -> Option<Self>;
+}
+
+macro_rules! impl_from_numeric_for_integer {
+    ($($ty:ty),+) => {
+        $(impl FromNumeric for $ty {
+            fn from_integer(value: i128) -> Option<Self> {
+                <$ty>::try_from(value).ok()
+            }
+
+            fn from_float(value: f64) -> Option<Self> {
+                let value = value.round();
+                // `MAX as f64 + 1.0` rounds to `MAX as f64` for 64 and 128 bit types,
+                // which are powers of two minus one
+                (value >= <$ty>::MIN as f64 && value < <$ty>::MAX as f64 + 1.0)
+                    .then_some(value as $ty)
+            }
+        })+
+    };
+}
+
+impl_from_numeric_for_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl FromNumeric for f32 {
+    fn from_integer(value: i128) -> Option<Self> {
+        Some(value as f32)
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        let result = value as f32;
+        (result.is_finite() || !value.is_finite()).then_some(result)
+    }
+}
+
+impl FromNumeric for f64 {
+    fn from_integer(value: i128) -> Option<Self> {
+        Some(value as f64)
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+/// Casts any numeric scalar value to the primitive type `$ty` of `$data_type`, values
+/// out of its range are an error.
+macro_rules! cast_numeric {
+    ($value:expr, $ty:ty, $data_type:expr, $error:expr) => {{
+        let out_of_range = || {
+            BustubxError::Execution(format!(
+                "{} is out of range for type {}",
+                $value, $data_type
+            ))
+        };
+        match $value {
+            ScalarValue::Int8(v) => v.map(|v| <$ty>::from_integer(v as i128)),
+            ScalarValue::Int16(v) => v.map(|v| <$ty>::from_integer(v as i128)),
+            ScalarValue::Int32(v) => v.map(|v| <$ty>::from_integer(v as i128)),
+            ScalarValue::Int64(v) => v.map(|v| <$ty>::from_integer(v as i128)),
+            ScalarValue::UInt8(v) => v.map(|v| <$ty>::from_integer(v as i128)),
+            ScalarValue::UInt16(v) => v.map(|v| <$ty>::from_integer(v as i128)),
+            ScalarValue::UInt32(v) => v.map(|v| <$ty>::from_integer(v as i128)),
+            ScalarValue::UInt64(v) => v.map(|v| <$ty>::from_integer(v as i128)),
+            ScalarValue::Float32(v) => v.map(|v| <$ty>::from_float(v as f64)),
+            ScalarValue::Float64(v) => v.map(|v| <$ty>::from_float(v)),
+            ScalarValue::Decimal(v, _, scale) => {
+                v.map(|v| <$ty as decimal::FromDecimal>::from_decimal(v, *scale))
+            }
+            _ => return Err($error),
+        }
+        .map(|v| v.ok_or_else(out_of_range))
+        .transpose()
+    }};
+}
+
+/// Applies the checked integer method `$op` to two optional values.
+macro_rules! checked_int_op {
+    ($l:expr, $r:expr, $op:ident, $overflow:expr) => {
+        $l.zip(*$r)
+            .map(|(l, r)| l.$op(r).ok_or_else($overflow))
+            .transpose()
+    };
+}
+
+/// Applies the float operator `$op` to two optional values, a non-finite result
+/// of finite operands is an overflow.
+macro_rules! checked_float_op {
+    ($l:expr, $r:expr, $op:tt, $overflow:expr) => {
+        $l.zip(*$r)
+            .map(|(l, r)| {
+                let v = l $op r;
+                if v.is_finite() || !l.is_finite() || !r.is_finite() {
+                    Ok(v)
+                } else {
+                    Err($overflow())
+                }
+            })
+            .transpose()
+    };
+}
+
+/// Generates a checked arithmetic method for two scalar values of the same type.
+/// A NULL operand yields NULL, integer overflow and division by zero yield an error.
+macro_rules! impl_checked_arithmetic {
+    ($name:ident, $int_op:ident, $float_op:tt, $symbol:literal, $is_division:expr) => {
+        pub fn $name(&self, other: &Self) -> BustubxResult<Self> {
+            use ScalarValue::*;
+            if $is_division && !self.is_null() && other.is_zero() {
+                return Err(BustubxError::Execution("Division by zero".to_string()));
+            }
+            let overflow = || {
+                BustubxError::Execution(format!(
+                    "Arithmetic overflow: {} {} {}",
+                    self, $symbol, other
+                ))
+            };
+            match (self, other) {
+                (Int8(l), Int8(r)) => checked_int_op!(l, r, $int_op, overflow).map(Int8),
+                (Int16(l), Int16(r)) => checked_int_op!(l, r, $int_op, overflow).map(Int16),
+                (Int32(l), Int32(r)) => checked_int_op!(l, r, $int_op, overflow).map(Int32),
+                (Int64(l), Int64(r)) => checked_int_op!(l, r, $int_op, overflow).map(Int64),
+                (UInt8(l), UInt8(r)) => checked_int_op!(l, r, $int_op, overflow).map(UInt8),
+                (UInt16(l), UInt16(r)) => checked_int_op!(l, r, $int_op, overflow).map(UInt16),
+                (UInt32(l), UInt32(r)) => checked_int_op!(l, r, $int_op, overflow).map(UInt32),
+                (UInt64(l), UInt64(r)) => checked_int_op!(l, r, $int_op, overflow).map(UInt64),
+                (Float32(l), Float32(r)) => {
+                    checked_float_op!(l, r, $float_op, overflow).map(Float32)
+                }
+                (Float64(l), Float64(r)) => {
+                    checked_float_op!(l, r, $float_op, overflow).map(Float64)
+                }
+                _ => Err(BustubxError::Execution(format!(
+                    "Can not apply {} to {:?} and {:?}",
+                    $symbol, self, other
+                ))),
+            }
+        }
+    };
 }
 
 impl ScalarValue {
@@ -33,6 +187,12 @@ impl ScalarValue {
             DataType::Float32 => Self::Float32(None),
             DataType::Float64 => Self::Float64(None),
             DataType::Varchar(_) => Self::Varchar(None),
+            DataType::Blob => Self::Blob(None),
+            DataType::Decimal(precision, scale) => Self::Decimal(None, precision, scale),
+            DataType::Date => Self::Date(None),
+            DataType::Time => Self::Time(None),
+            DataType::Timestamp => Self::Timestamp(None),
+            DataType::Interval => Self::Interval(None),
         }
     }
 
@@ -50,6 +210,12 @@ impl ScalarValue {
             ScalarValue::Float32(_) => DataType::Float32,
             ScalarValue::Float64(_) => DataType::Float64,
             ScalarValue::Varchar(_) => DataType::Varchar(None),
+            ScalarValue::Blob(_) => DataType::Blob,
+            ScalarValue::Decimal(_, precision, scale) => DataType::Decimal(*precision, *scale),
+            ScalarValue::Date(_) => DataType::Date,
+            ScalarValue::Time(_) => DataType::Time,
+            ScalarValue::Timestamp(_) => DataType::Timestamp,
+            ScalarValue::Interval(_) => DataType::Interval,
         }
     }
 
@@ -67,6 +233,12 @@ impl ScalarValue {
             ScalarValue::Float32(v) => v.is_none(),
             ScalarValue::Float64(v) => v.is_none(),
             ScalarValue::Varchar(v) => v.is_none(),
+            ScalarValue::Blob(v) => v.is_none(),
+            ScalarValue::Decimal(v, _, _) => v.is_none(),
+            ScalarValue::Date(v) => v.is_none(),
+            ScalarValue::Time(v) => v.is_none(),
+            ScalarValue::Timestamp(v) => v.is_none(),
+            ScalarValue::Interval(v) => v.is_none(),
         }
     }
 
@@ -78,96 +250,115 @@ impl ScalarValue {
         if &self.data_type() == data_type {
             return Ok(self.clone());
         }
+        if self.is_null() {
+            return Ok(ScalarValue::new_empty(*data_type));
+        }
 
         match data_type {
-            DataType::Int8 => {
-                let data = match self {
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as i8)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::Int8)
-            }
-            DataType::Int16 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as i16)),
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as i16)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::Int16)
-            }
-            DataType::Int32 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as i32)),
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as i32)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::Int32)
-            }
-            DataType::Int64 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as i64)),
-                    ScalarValue::Int32(v) => Ok(v.map(|v| v as i64)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::Int64)
-            }
-            DataType::UInt8 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as u8)),
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as u8)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::UInt8)
-            }
-            DataType::UInt16 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as u16)),
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as u16)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::UInt16)
-            }
-            DataType::UInt32 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as u32)),
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as u32)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::UInt32)
-            }
-            DataType::UInt64 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as u64)),
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as u64)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::UInt64)
-            }
+            DataType::Int8 => cast_numeric!(self, i8, data_type, error).map(ScalarValue::Int8),
+            DataType::Int16 => cast_numeric!(self, i16, data_type, error).map(ScalarValue::Int16),
+            DataType::Int32 => cast_numeric!(self, i32, data_type, error).map(ScalarValue::Int32),
+            DataType::Int64 => cast_numeric!(self, i64, data_type, error).map(ScalarValue::Int64),
+            DataType::UInt8 => cast_numeric!(self, u8, data_type, error).map(ScalarValue::UInt8),
+            DataType::UInt16 => cast_numeric!(self, u16, data_type, error).map(ScalarValue::UInt16),
+            DataType::UInt32 => cast_numeric!(self, u32, data_type, error).map(ScalarValue::UInt32),
+            DataType::UInt64 => cast_numeric!(self, u64, data_type, error).map(ScalarValue::UInt64),
             DataType::Float32 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as f32)),
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as f32)),
-                    ScalarValue::Float64(v) => Ok(v.map(|v| v as f32)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::Float32)
+                cast_numeric!(self, f32, data_type, error).map(ScalarValue::Float32)
             }
             DataType::Float64 => {
-                let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v as f64)),
-                    ScalarValue::Int32(v) => Ok(v.map(|v| v as f64)),
-                    ScalarValue::Int64(v) => Ok(v.map(|v| v as f64)),
-                    _ => Err(error),
-                };
-                data.map(ScalarValue::Float64)
+                cast_numeric!(self, f64, data_type, error).map(ScalarValue::Float64)
             }
             DataType::Varchar(_) => {
                 let data = match self {
-                    ScalarValue::Int8(v) => Ok(v.map(|v| v.to_string())),
-                    _ => Err(error),
+                    ScalarValue::Boolean(_) => Err(error),
+                    _ => Ok((!self.is_null()).then(|| self.to_string())),
                 };
                 data.map(ScalarValue::Varchar)
             }
+            DataType::Blob => match self {
+                ScalarValue::Varchar(v) => {
+                    Ok(ScalarValue::Blob(v.as_deref().map(parse_blob).transpose()?))
+                }
+                _ => Err(error),
+            },
+            DataType::Decimal(precision, scale) => {
+                let value = match self {
+                    ScalarValue::Decimal(v, _, from) => {
+                        v.and_then(|v| decimal::rescale(v, *from, *scale))
+                    }
+                    // parse the shortest representation so that e.g. 0.1 stays exact
+                    ScalarValue::Float32(_) | ScalarValue::Float64(_) | ScalarValue::Varchar(_) => {
+                        let string = match self {
+                            ScalarValue::Varchar(Some(v)) => v.clone(),
+                            _ => self.to_string(),
+                        };
+                        let value = decimal::parse_decimal(&string, *scale);
+                        if value.is_none() && matches!(self, ScalarValue::Varchar(_)) {
+                            return Err(BustubxError::Execution(format!(
+                                "Invalid input for type {}: '{}'",
+                                data_type, string
+                            )));
+                        }
+                        value
+                    }
+                    _ => cast_numeric!(self, i128, data_type, error)?
+                        .and_then(|v| decimal::rescale(v, 0, *scale)),
+                };
+                match value {
+                    Some(v) if decimal::is_within_precision(v, *precision) => {
+                        Ok(ScalarValue::Decimal(Some(v), *precision, *scale))
+                    }
+                    _ => Err(BustubxError::Execution(format!(
+                        "{} is out of range for type {}",
+                        self, data_type
+                    ))),
+                }
+            }
+            DataType::Date => match self {
+                ScalarValue::Varchar(v) => Ok(ScalarValue::Date(
+                    v.as_deref()
+                        .map(|s| parse_input(s, data_type, temporal::parse_date))
+                        .transpose()?,
+                )),
+                ScalarValue::Timestamp(v) => Ok(ScalarValue::Date(
+                    v.map(|v| v.div_euclid(temporal::MICROS_PER_DAY) as i32),
+                )),
+                _ => Err(error),
+            },
+            DataType::Time => match self {
+                ScalarValue::Varchar(v) => Ok(ScalarValue::Time(
+                    v.as_deref()
+                        .map(|s| parse_input(s, data_type, temporal::parse_time))
+                        .transpose()?,
+                )),
+                ScalarValue::Timestamp(v) => Ok(ScalarValue::Time(
+                    v.map(|v| v.rem_euclid(temporal::MICROS_PER_DAY)),
+                )),
+                _ => Err(error),
+            },
+            DataType::Timestamp => match self {
+                ScalarValue::Varchar(v) => Ok(ScalarValue::Timestamp(
+                    v.as_deref()
+                        .map(|s| parse_input(s, data_type, temporal::parse_timestamp))
+                        .transpose()?,
+                )),
+                ScalarValue::Date(v) => Ok(ScalarValue::Timestamp(
+                    v.map(|v| v as i64 * temporal::MICROS_PER_DAY),
+                )),
+                _ => Err(error),
+            },
+            DataType::Interval => match self {
+                ScalarValue::Varchar(v) => Ok(ScalarValue::Interval(
+                    v.as_deref()
+                        .map(|s| parse_input(s, data_type, str::parse))
+                        .transpose()?,
+                )),
+                ScalarValue::Time(v) => {
+                    Ok(ScalarValue::Interval(v.map(|v| Interval::new(0, 0, v))))
+                }
+                _ => Err(error),
+            },
             _ => Err(error),
         }
     }
@@ -182,13 +373,34 @@ impl ScalarValue {
         }
     }
 
-    pub fn wrapping_add(&self, _other: Self) -> BustubxResult<Self> {
-        todo!()
+    pub fn is_zero(&self) -> bool {
+        match self {
+            ScalarValue::Int8(v) => *v == Some(0),
+            ScalarValue::Int16(v) => *v == Some(0),
+            ScalarValue::Int32(v) => *v == Some(0),
+            ScalarValue::Int64(v) => *v == Some(0),
+            ScalarValue::UInt8(v) => *v == Some(0),
+            ScalarValue::UInt16(v) => *v == Some(0),
+            ScalarValue::UInt32(v) => *v == Some(0),
+            ScalarValue::UInt64(v) => *v == Some(0),
+            ScalarValue::Float32(v) => *v == Some(0.0),
+            ScalarValue::Float64(v) => *v == Some(0.0),
+            ScalarValue::Decimal(v, _, _) => *v == Some(0),
+            ScalarValue::Boolean(_)
+            | ScalarValue::Varchar(_)
+            | ScalarValue::Blob(_)
+            | ScalarValue::Date(_)
+            | ScalarValue::Time(_)
+            | ScalarValue::Timestamp(_)
+            | ScalarValue::Interval(_) => false,
+        }
     }
 
-    pub fn wrapping_sub(&self, _other: Self) -> BustubxResult<Self> {
-        todo!()
-    }
+    impl_checked_arithmetic!(checked_add, checked_add, +, "+", false);
+    impl_checked_arithmetic!(checked_sub, checked_sub, -, "-", false);
+    impl_checked_arithmetic!(checked_mul, checked_mul, *, "*", false);
+    impl_checked_arithmetic!(checked_div, checked_div, /, "/", true);
+    impl_checked_arithmetic!(checked_rem, checked_rem, %, "%", true);
 
     pub fn from_string(string: &String, data_type: DataType) -> BustubxResult<Self> {
         let is_null = string.eq_ignore_ascii_case("null");
@@ -318,6 +530,18 @@ impl ScalarValue {
                 let v = if is_null { None } else { Some(string.clone()) };
                 Ok(ScalarValue::Varchar(v))
             }
+            DataType::Blob
+            | DataType::Decimal(_, _)
+            | DataType::Date
+            | DataType::Time
+            | DataType::Timestamp
+            | DataType::Interval => {
+                if is_null {
+                    Ok(ScalarValue::new_empty(data_type))
+                } else {
+                    ScalarValue::Varchar(Some(string.clone())).cast_to(&data_type)
+                }
+            }
         }
     }
 }
@@ -356,6 +580,20 @@ impl PartialEq for ScalarValue {
             (Float64(_), _) => false,
             (Varchar(v1), Varchar(v2)) => v1.eq(v2),
             (Varchar(_), _) => false,
+            (Blob(v1), Blob(v2)) => v1.eq(v2),
+            (Blob(_), _) => false,
+            (Decimal(v1, _, s1), Decimal(v2, _, s2)) => {
+                v1.map(|v| decimal::sort_key(v, *s1)) == v2.map(|v| decimal::sort_key(v, *s2))
+            }
+            (Decimal(_, _, _), _) => false,
+            (Date(v1), Date(v2)) => v1.eq(v2),
+            (Date(_), _) => false,
+            (Time(v1), Time(v2)) => v1.eq(v2),
+            (Time(_), _) => false,
+            (Timestamp(v1), Timestamp(v2)) => v1.eq(v2),
+            (Timestamp(_), _) => false,
+            (Interval(v1), Interval(v2)) => v1.eq(v2),
+            (Interval(_), _) => false,
         }
     }
 }
@@ -396,6 +634,20 @@ impl PartialOrd for ScalarValue {
             (Float64(_), _) => None,
             (Varchar(v1), Varchar(v2)) => v1.partial_cmp(v2),
             (Varchar(_), _) => None,
+            (Blob(v1), Blob(v2)) => v1.partial_cmp(v2),
+            (Blob(_), _) => None,
+            (Decimal(v1, _, s1), Decimal(v2, _, s2)) => v1
+                .map(|v| decimal::sort_key(v, *s1))
+                .partial_cmp(&v2.map(|v| decimal::sort_key(v, *s2))),
+            (Decimal(_, _, _), _) => None,
+            (Date(v1), Date(v2)) => v1.partial_cmp(v2),
+            (Date(_), _) => None,
+            (Time(v1), Time(v2)) => v1.partial_cmp(v2),
+            (Time(_), _) => None,
+            (Timestamp(v1), Timestamp(v2)) => v1.partial_cmp(v2),
+            (Timestamp(_), _) => None,
+            (Interval(v1), Interval(v2)) => v1.partial_cmp(v2),
+            (Interval(_), _) => None,
         }
     }
 }
@@ -416,6 +668,12 @@ impl std::hash::Hash for ScalarValue {
             UInt32(v) => v.hash(state),
             UInt64(v) => v.hash(state),
             Varchar(v) => v.hash(state),
+            Blob(v) => v.hash(state),
+            Decimal(v, _, scale) => v.map(|v| decimal::sort_key(v, *scale)).hash(state),
+            Date(v) => v.hash(state),
+            Time(v) => v.hash(state),
+            Timestamp(v) => v.hash(state),
+            Interval(v) => v.hash(state),
         }
     }
 }
@@ -463,6 +721,20 @@ impl std::fmt::Display for ScalarValue {
             ScalarValue::Float64(Some(v)) => write!(f, "{v}"),
             ScalarValue::Varchar(None) => write!(f, "NULL"),
             ScalarValue::Varchar(Some(v)) => write!(f, "{v}"),
+            ScalarValue::Blob(None) => write!(f, "NULL"),
+            ScalarValue::Blob(Some(v)) => write!(f, "{}", format_blob(v)),
+            ScalarValue::Decimal(None, _, _) => write!(f, "NULL"),
+            ScalarValue::Decimal(Some(v), _, scale) => {
+                write!(f, "{}", decimal::format_decimal(*v, *scale))
+            }
+            ScalarValue::Date(None) => write!(f, "NULL"),
+            ScalarValue::Date(Some(v)) => write!(f, "{}", temporal::format_date(*v)),
+            ScalarValue::Time(None) => write!(f, "NULL"),
+            ScalarValue::Time(Some(v)) => write!(f, "{}", temporal::format_time(*v)),
+            ScalarValue::Timestamp(None) => write!(f, "NULL"),
+            ScalarValue::Timestamp(Some(v)) => write!(f, "{}", temporal::format_timestamp(*v)),
+            ScalarValue::Interval(None) => write!(f, "NULL"),
+            ScalarValue::Interval(Some(v)) => write!(f, "{v}"),
         }
     }
 }
@@ -495,3 +767,69 @@ impl_from_for_scalar!(u64, UInt64);
 impl_from_for_scalar!(f32, Float32);
 impl_from_for_scalar!(f64, Float64);
 impl_from_for_scalar!(String, Varchar);
+impl_from_for_scalar!(Vec<u8>, Blob);
+impl_from_for_scalar!(Interval, Interval);
+
+/// Parses the text of a cast to `data_type`, a failure names the text and the type.
+fn parse_input<T>(
+    s: &str,
+    data_type: &DataType,
+    parse: impl Fn(&str) -> BustubxResult<T>,
+) -> BustubxResult<T> {
+    parse(s).map_err(|_| {
+        BustubxError::Execution(format!("Invalid input for type {}: '{}'", data_type, s))
+    })
+}
+
+/// Parses the hex format `\x0a1b` like postgres, otherwise the bytes of the string where
+/// `\\` is a backslash and `\` followed by three octal digits is a byte.
+fn parse_blob(s: &str) -> BustubxResult<Vec<u8>> {
+    let error = || {
+        BustubxError::Execution(format!(
+            "Invalid input for type {}: '{}'",
+            DataType::Blob,
+            s
+        ))
+    };
+    if let Some(hex) = s.strip_prefix("\\x").or_else(|| s.strip_prefix("\\X")) {
+        let digits = hex
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .map(|c| c.to_digit(16).map(|d| d as u8).ok_or_else(error))
+            .collect::<BustubxResult<Vec<u8>>>()?;
+        if digits.len() % 2 != 0 {
+            return Err(error());
+        }
+        return Ok(digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect());
+    }
+    let bytes = s.as_bytes();
+    let mut blob = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] != b'\\' {
+            blob.push(bytes[idx]);
+            idx += 1;
+        } else if bytes.get(idx + 1) == Some(&b'\\') {
+            blob.push(b'\\');
+            idx += 2;
+        } else {
+            let octal = s.get(idx + 1..idx + 4).ok_or_else(error)?;
+            blob.push(u8::from_str_radix(octal, 8).map_err(|_| error())?);
+            idx += 4;
+        }
+    }
+    Ok(blob)
+}
+
+/// Formats a blob in the hex format of postgres.
+fn format_blob(blob: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + blob.len() * 2);
+    s.push_str("\\x");
+    for byte in blob {
+        s.push_str(&format!("{byte:02x}"));
+    }
+    s
+}
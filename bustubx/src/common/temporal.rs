@@ -0,0 +1,446 @@
+use crate::{BustubxError, BustubxResult};
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+pub const MICROS_PER_SECOND: i64 = 1_000_000;
+pub const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+pub const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+pub const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+/// Days per month when an interval is compared or its month part is converted to days.
+pub const DAYS_PER_MONTH: i64 = 30;
+/// `num_days_from_ce` of 1970-01-01.
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// A span of time with separate month, day and microsecond parts like postgres does,
+/// as the length of a month or a day depends on the date it's added to.
+#[derive(Debug, Clone, Copy, Default, derive_new::new)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub micros: i64,
+}
+
+impl Interval {
+    /// Length of the interval in microseconds assuming 30-day months, used for ordering.
+    fn approximate_micros(&self) -> i128 {
+        (self.months as i128 * DAYS_PER_MONTH as i128 + self.days as i128) * MICROS_PER_DAY as i128
+            + self.micros as i128
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            months: self.months.checked_add(other.months)?,
+            days: self.days.checked_add(other.days)?,
+            micros: self.micros.checked_add(other.micros)?,
+        })
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Self {
+            months: self.months.checked_neg()?,
+            days: self.days.checked_neg()?,
+            micros: self.micros.checked_neg()?,
+        })
+    }
+
+    /// Multiplies every part by `factor`, fractional months and days cascade down to days
+    /// and microseconds. Likewise for `checked_div_f64`.
+    pub fn checked_mul_f64(&self, factor: f64) -> Option<Self> {
+        Self::from_fractional(
+            self.months as f64 * factor,
+            self.days as f64 * factor,
+            self.micros as f64 * factor,
+        )
+    }
+
+    pub fn checked_div_f64(&self, divisor: f64) -> Option<Self> {
+        Self::from_fractional(
+            self.months as f64 / divisor,
+            self.days as f64 / divisor,
+            self.micros as f64 / divisor,
+        )
+    }
+
+    fn from_fractional(months: f64, days: f64, micros: f64) -> Option<Self> {
+        let days = days + months.fract() * DAYS_PER_MONTH as f64;
+        let micros = micros + days.fract() * MICROS_PER_DAY as f64;
+        let (months, days, micros) = (months.trunc(), days.trunc(), micros.round());
+        if !(months.is_finite() && days.is_finite() && micros.is_finite())
+            || months.abs() > i32::MAX as f64
+            || days.abs() > i32::MAX as f64
+            || micros.abs() >= i64::MAX as f64
+        {
+            return None;
+        }
+        Some(Self::new(months as i32, days as i32, micros as i64))
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.approximate_micros() == other.approximate_micros()
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.approximate_micros().cmp(&other.approximate_micros())
+    }
+}
+
+impl std::hash::Hash for Interval {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.approximate_micros().hash(state)
+    }
+}
+
+/// Parses postgres style intervals such as `1 year 2 mons 3 days 04:05:06`,
+/// `1.5 hours`, `-2 days ago` or `10` (seconds).
+impl FromStr for Interval {
+    type Err = BustubxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || BustubxError::Internal(format!("Parse interval '{}' failed", s));
+        let (mut months, mut days, mut micros) = (0f64, 0f64, 0f64);
+        let mut tokens = s.split_whitespace().peekable();
+        if tokens.peek() == Some(&"@") {
+            tokens.next();
+        }
+        let mut ago = false;
+        while let Some(token) = tokens.next() {
+            if token.eq_ignore_ascii_case("ago") && tokens.peek().is_none() {
+                ago = true;
+            } else if token.contains(':') {
+                micros += parse_time_of_day(token).ok_or_else(error)? as f64;
+            } else {
+                let value = token.parse::<f64>().map_err(|_| error())?;
+                let (unit_months, unit_days, unit_micros) = match tokens.next() {
+                    Some(unit) => interval_unit(unit).ok_or_else(error)?,
+                    None => (0, 0, MICROS_PER_SECOND),
+                };
+                months += value * unit_months as f64;
+                days += value * unit_days as f64;
+                micros += value * unit_micros as f64;
+            }
+        }
+        let interval = Self::from_fractional(months, days, micros).ok_or_else(error)?;
+        if ago {
+            interval.checked_neg().ok_or_else(error)
+        } else {
+            Ok(interval)
+        }
+    }
+}
+
+/// Formats like postgres, e.g. `1 year 2 mons 3 days 04:05:06.5` or `00:00:00`.
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        let plural = |value: i32, unit: &str| {
+            if value == 1 {
+                format!("{value} {unit}")
+            } else {
+                format!("{value} {unit}s")
+            }
+        };
+        if self.months / 12 != 0 {
+            parts.push(plural(self.months / 12, "year"));
+        }
+        if self.months % 12 != 0 {
+            parts.push(plural(self.months % 12, "mon"));
+        }
+        if self.days != 0 {
+            parts.push(plural(self.days, "day"));
+        }
+        if self.micros != 0 || parts.is_empty() {
+            let sign = if self.micros < 0 { "-" } else { "" };
+            parts.push(format!(
+                "{sign}{}",
+                format_micros(self.micros.unsigned_abs())
+            ));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Months, days and microseconds of one unit of an interval field.
+pub fn interval_unit(unit: &str) -> Option<(i64, i64, i64)> {
+    match unit.to_ascii_lowercase().as_str() {
+        "microsecond" | "microseconds" | "us" | "usec" | "usecs" => Some((0, 0, 1)),
+        "millisecond" | "milliseconds" | "ms" | "msec" | "msecs" => Some((0, 0, 1000)),
+        "second" | "seconds" | "s" | "sec" | "secs" => Some((0, 0, MICROS_PER_SECOND)),
+        "minute" | "minutes" | "m" | "min" | "mins" => Some((0, 0, MICROS_PER_MINUTE)),
+        "hour" | "hours" | "h" | "hr" | "hrs" => Some((0, 0, MICROS_PER_HOUR)),
+        "day" | "days" | "d" => Some((0, 1, 0)),
+        "week" | "weeks" | "w" => Some((0, 7, 0)),
+        "month" | "months" | "mon" | "mons" => Some((1, 0, 0)),
+        "year" | "years" | "y" | "yr" | "yrs" => Some((12, 0, 0)),
+        "decade" | "decades" => Some((120, 0, 0)),
+        "century" | "centuries" => Some((1200, 0, 0)),
+        "millennium" | "millennia" | "millenniums" => Some((12000, 0, 0)),
+        _ => None,
+    }
+}
+
+/// Parses `[-]HH:MM[:SS[.ffffff]]` to microseconds, hours may exceed 24.
+fn parse_time_of_day(s: &str) -> Option<i64> {
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(s) => (-1, s),
+        None => (1, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let mut fields = s.split(':');
+    let hours = fields.next()?.parse::<i64>().ok()?;
+    let minutes = fields.next()?.parse::<i64>().ok()?;
+    let seconds = fields.next().map_or(Some(0.0), |s| s.parse::<f64>().ok())?;
+    if fields.next().is_some() || !(0..60).contains(&minutes) || !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    let micros = hours
+        .checked_mul(MICROS_PER_HOUR)?
+        .checked_add(minutes * MICROS_PER_MINUTE + (seconds * 1e6).round() as i64)?;
+    Some(sign * micros)
+}
+
+/// Formats non-negative microseconds as `HH:MM:SS`, with trailing-zero-trimmed fractional
+/// seconds if there are any.
+fn format_micros(micros: u64) -> String {
+    let seconds = micros / MICROS_PER_SECOND as u64;
+    let fraction = micros % MICROS_PER_SECOND as u64;
+    let mut s = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    if fraction != 0 {
+        s.push_str(format!(".{fraction:06}").trim_end_matches('0'));
+    }
+    s
+}
+
+pub fn date_to_naive(days: i32) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(days.checked_add(UNIX_EPOCH_DAYS_FROM_CE)?)
+}
+
+pub fn naive_to_date(date: NaiveDate) -> i32 {
+    date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE
+}
+
+pub fn timestamp_to_naive(micros: i64) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp_micros(micros).map(|datetime| datetime.naive_utc())
+}
+
+pub fn naive_to_timestamp(datetime: NaiveDateTime) -> i64 {
+    datetime.and_utc().timestamp_micros()
+}
+
+pub fn time_to_naive(micros: i64) -> Option<NaiveTime> {
+    let seconds = u32::try_from(micros.div_euclid(MICROS_PER_SECOND)).ok()?;
+    let nanos = micros.rem_euclid(MICROS_PER_SECOND) as u32 * 1000;
+    NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos)
+}
+
+pub fn naive_to_time(time: NaiveTime) -> i64 {
+    time.num_seconds_from_midnight() as i64 * MICROS_PER_SECOND + time.nanosecond() as i64 / 1000
+}
+
+/// Parses `YYYY-MM-DD` to days since 1970-01-01, a time part is ignored.
+pub fn parse_date(s: &str) -> BustubxResult<i32> {
+    let error = || BustubxError::Internal(format!("Parse date '{}' failed", s));
+    let s = s.trim();
+    let date = match NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(_) => timestamp_to_naive(parse_timestamp(s)?)
+            .ok_or_else(error)?
+            .date(),
+    };
+    Ok(naive_to_date(date))
+}
+
+/// Parses `HH:MM[:SS[.ffffff]]` to microseconds since midnight.
+pub fn parse_time(s: &str) -> BustubxResult<i64> {
+    let s = s.trim();
+    NaiveTime::parse_from_str(s, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+        .map(naive_to_time)
+        .map_err(|_| BustubxError::Internal(format!("Parse time '{}' failed", s)))
+}
+
+/// Parses `YYYY-MM-DD[( |T)HH:MM[:SS[.ffffff]]][Z|UTC|(+|-)HH[[:]MM]]` to microseconds
+/// since the unix epoch, timestamps with an offset are converted to UTC.
+pub fn parse_timestamp(s: &str) -> BustubxResult<i64> {
+    let error = || BustubxError::Internal(format!("Parse timestamp '{}' failed", s));
+    let (datetime, offset_micros) = split_utc_offset(s.trim()).ok_or_else(error)?;
+    let (date, time) = match datetime.split_once([' ', 'T']) {
+        Some((date, time)) => (date, parse_time(time).map_err(|_| error())?),
+        None => (datetime, 0),
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| error())?;
+    let date_micros = naive_to_timestamp(date.and_time(NaiveTime::MIN));
+    date_micros
+        .checked_add(time)
+        .and_then(|micros| micros.checked_sub(offset_micros))
+        .ok_or_else(error)
+}
+
+/// Splits a trailing UTC offset off a timestamp literal, returns the offset in microseconds.
+fn split_utc_offset(s: &str) -> Option<(&str, i64)> {
+    if let Some(datetime) = s.strip_suffix(['Z', 'z']) {
+        return Some((datetime.trim_end(), 0));
+    }
+    if let Some(datetime) = s
+        .len()
+        .checked_sub(3)
+        .filter(|index| {
+            s.get(*index..)
+                .is_some_and(|utc| utc.eq_ignore_ascii_case("utc"))
+        })
+        .map(|index| &s[..index])
+    {
+        return Some((datetime.trim_end(), 0));
+    }
+    // the dashes of the date aren't an offset, an offset follows the time
+    let Some(index) = s
+        .rfind(['+', '-'])
+        .filter(|index| s[..*index].contains(':'))
+    else {
+        return Some((s, 0));
+    };
+    let (sign, offset) = (
+        if &s[index..=index] == "-" { -1 } else { 1 },
+        &s[index + 1..],
+    );
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if offset.len() > 2 => offset.split_at(2),
+        None => (offset, "0"),
+    };
+    let hours = hours.parse::<i64>().ok().filter(|hours| *hours <= 15)?;
+    let minutes = minutes
+        .parse::<i64>()
+        .ok()
+        .filter(|minutes| *minutes < 60)?;
+    Some((
+        s[..index].trim_end(),
+        sign * (hours * MICROS_PER_HOUR + minutes * MICROS_PER_MINUTE),
+    ))
+}
+
+pub fn format_date(days: i32) -> String {
+    match date_to_naive(days) {
+        Some(date) => date.to_string(),
+        None => format!("<invalid date {days}>"),
+    }
+}
+
+pub fn format_time(micros: i64) -> String {
+    format_micros(micros.rem_euclid(MICROS_PER_DAY) as u64)
+}
+
+pub fn format_timestamp(micros: i64) -> String {
+    let date = micros.div_euclid(MICROS_PER_DAY) as i32;
+    format!("{} {}", format_date(date), format_time(micros))
+}
+
+/// Adds `interval` to a timestamp, months first and then days and microseconds.
+pub fn timestamp_add_interval(timestamp: i64, interval: &Interval) -> Option<i64> {
+    let datetime = timestamp_to_naive(timestamp)?;
+    let months = Months::new(interval.months.unsigned_abs());
+    let datetime = if interval.months >= 0 {
+        datetime.checked_add_months(months)?
+    } else {
+        datetime.checked_sub_months(months)?
+    };
+    naive_to_timestamp(datetime)
+        .checked_add((interval.days as i64).checked_mul(MICROS_PER_DAY)?)?
+        .checked_add(interval.micros)
+}
+
+/// The interval between two timestamps, whole days are in the day part.
+pub fn timestamp_sub_timestamp(left: i64, right: i64) -> Option<Interval> {
+    let micros = left.checked_sub(right)?;
+    let days = i32::try_from(micros / MICROS_PER_DAY).ok()?;
+    Some(Interval::new(0, days, micros % MICROS_PER_DAY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_interval() {
+        let cases = [
+            ("1 day", "1 day"),
+            ("2 hours 30 minutes", "02:30:00"),
+            (
+                "1 year 2 mons 3 days 04:05:06",
+                "1 year 2 mons 3 days 04:05:06",
+            ),
+            ("1.5 years", "1 year 6 mons"),
+            ("1.5 days", "1 day 12:00:00"),
+            ("-1 days -00:00:01.25", "-1 days -00:00:01.25"),
+            ("3 days ago", "-3 days"),
+            ("0", "00:00:00"),
+            ("10", "00:00:10"),
+        ];
+        for (input, expected) in cases {
+            let interval = input.parse::<Interval>().unwrap();
+            assert_eq!(interval.to_string(), expected);
+            assert_eq!(expected.parse::<Interval>().unwrap(), interval);
+        }
+        assert!("1 fortnight".parse::<Interval>().is_err());
+        assert!("1:99".parse::<Interval>().is_err());
+        assert_eq!(
+            "1 mon".parse::<Interval>().unwrap(),
+            "30 days".parse::<Interval>().unwrap()
+        );
+        assert!("1 day".parse::<Interval>().unwrap() < "25 hours".parse::<Interval>().unwrap());
+    }
+
+    #[test]
+    fn test_parse_and_format_timestamp() {
+        assert_eq!(parse_date("1970-01-02").unwrap(), 1);
+        assert_eq!(parse_date("1969-12-31 23:00:00").unwrap(), -1);
+        assert_eq!(format_date(-1), "1969-12-31");
+        assert_eq!(parse_time("01:02:03.5").unwrap(), 3_723_500_000);
+        assert_eq!(format_time(3_723_500_000), "01:02:03.5");
+        assert!(parse_time("25:00:00").is_err());
+
+        let ts = parse_timestamp("2024-02-29 10:30:00").unwrap();
+        assert_eq!(format_timestamp(ts), "2024-02-29 10:30:00");
+        assert_eq!(parse_timestamp("2024-02-29T10:30:00Z").unwrap(), ts);
+        assert_eq!(parse_timestamp("2024-02-29 12:30:00+02").unwrap(), ts);
+        assert_eq!(parse_timestamp("2024-02-29 05:00:00-05:30").unwrap(), ts);
+        assert_eq!(
+            format_timestamp(parse_timestamp("1969-12-31 23:59:59.999").unwrap()),
+            "1969-12-31 23:59:59.999"
+        );
+        assert!(parse_timestamp("2024-02-30").is_err());
+
+        let month = "1 mon".parse::<Interval>().unwrap();
+        let ts = timestamp_add_interval(parse_timestamp("2024-01-31").unwrap(), &month).unwrap();
+        assert_eq!(format_timestamp(ts), "2024-02-29 00:00:00");
+        assert_eq!(
+            timestamp_sub_timestamp(
+                parse_timestamp("2024-03-01 02:00").unwrap(),
+                parse_timestamp("2024-02-29").unwrap()
+            )
+            .unwrap()
+            .to_string(),
+            "1 day 02:00:00"
+        );
+    }
+}
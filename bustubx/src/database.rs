@@ -1,4 +1,8 @@
 use log::debug;
+use sqlparser::ast::{
+    Expr, ObjectName, Statement, TransactionIsolationLevel, TransactionMode, Value,
+};
+use std::path::Path;
 use std::sync::Arc;
 use tempfile::TempDir;
 
@@ -6,69 +10,232 @@ use crate::buffer::BUFFER_POOL_SIZE;
 use crate::catalog::load_catalog_data;
 use crate::common::util::{pretty_format_logical_plan, pretty_format_physical_plan};
 use crate::error::{BustubxError, BustubxResult};
+use crate::execution::physical_plan::HASH_JOIN_MEMORY_BUDGET;
 use crate::optimizer::LogicalOptimizer;
 use crate::planner::logical_plan::LogicalPlan;
 use crate::planner::PhysicalPlanner;
+use crate::recovery::{LogManager, LogRecovery};
+use crate::transaction::{
+    DeadVersion, IsolationLevel, LockManager, Transaction, TransactionManager,
+    DEADLOCK_DETECTION_INTERVAL,
+};
 use crate::{
     buffer::BufferPoolManager,
     catalog::Catalog,
     execution::{ExecutionContext, ExecutionEngine},
     planner::{LogicalPlanner, PlannerContext},
-    storage::{DiskManager, Tuple},
+    storage::{DiskManager, Tuple, SPILL_BUFFER_POOL_SIZE},
 };
 
 pub struct Database {
     disk_manager: Arc<DiskManager>,
     pub(crate) buffer_pool: Arc<BufferPoolManager>,
     pub(crate) catalog: Catalog,
+    log_manager: Arc<LogManager>,
+    pub(crate) lock_manager: Arc<LockManager>,
+    pub(crate) transaction_manager: TransactionManager,
+    pub(crate) spill_buffer_pool: Arc<BufferPoolManager>,
+    /// Set by `SET hash_join_memory_budget = <bytes>`
+    hash_join_memory_budget: usize,
+    /// Transaction started by `BEGIN`
+    txn: Option<Transaction>,
     temp_dir: Option<TempDir>,
 }
 impl Database {
     pub fn new_on_disk(db_path: &str) -> BustubxResult<Self> {
-        let disk_manager = Arc::new(DiskManager::try_new(db_path)?);
-        let buffer_pool = Arc::new(BufferPoolManager::new(
-            BUFFER_POOL_SIZE,
-            disk_manager.clone(),
-        ));
-
-        let catalog = Catalog::new(buffer_pool.clone());
-
-        let mut db = Self {
-            disk_manager,
-            buffer_pool,
-            catalog,
-            temp_dir: None,
-        };
-        load_catalog_data(&mut db)?;
-        Ok(db)
+        Self::open(Path::new(db_path), None)
     }
 
     pub fn new_temp() -> BustubxResult<Self> {
         let temp_dir = TempDir::new()?;
         let temp_path = temp_dir.path().join("test.db");
-        let disk_manager =
-            Arc::new(DiskManager::try_new(temp_path.to_str().ok_or(
-                BustubxError::Internal("Invalid temp path".to_string()),
-            )?)?);
-        let buffer_pool = Arc::new(BufferPoolManager::new(
-            BUFFER_POOL_SIZE,
-            disk_manager.clone(),
-        ));
+        Self::open(&temp_path, Some(temp_dir))
+    }
+
+    fn open(db_path: &Path, temp_dir: Option<TempDir>) -> BustubxResult<Self> {
+        let mut log_path = db_path.as_os_str().to_owned();
+        log_path.push("-wal");
+        if !db_path.exists() && Path::new(&log_path).exists() {
+            // log of a removed database
+            std::fs::remove_file(&log_path)?;
+        }
+
+        let disk_manager = Arc::new(DiskManager::try_new(db_path)?);
+        let log_manager = Arc::new(LogManager::try_new(&log_path)?);
+        LogRecovery::new(disk_manager.clone(), log_manager.clone()).recover()?;
+
+        let buffer_pool = Arc::new(
+            BufferPoolManager::new(BUFFER_POOL_SIZE, disk_manager.clone())
+                .with_log_manager(log_manager.clone()),
+        );
+        let lock_manager = Arc::new(LockManager::new());
+        lock_manager.start_deadlock_detection(DEADLOCK_DETECTION_INTERVAL);
+        let transaction_manager = TransactionManager::new(
+            log_manager.clone(),
+            buffer_pool.clone(),
+            lock_manager.clone(),
+        );
 
         let catalog = Catalog::new(buffer_pool.clone());
+        let spill_buffer_pool = Arc::new(BufferPoolManager::new(
+            SPILL_BUFFER_POOL_SIZE,
+            Arc::new(DiskManager::try_new_temp()?),
+        ));
 
         let mut db = Self {
             disk_manager,
             buffer_pool,
             catalog,
-            temp_dir: Some(temp_dir),
+            log_manager,
+            lock_manager,
+            transaction_manager,
+            spill_buffer_pool,
+            hash_join_memory_budget: HASH_JOIN_MEMORY_BUDGET,
+            txn: None,
+            temp_dir,
         };
         load_catalog_data(&mut db)?;
         Ok(db)
     }
 
+    /// Runs a single statement. Outside of a transaction started by `BEGIN`, the statement
+    /// runs in its own transaction. A failed statement never leaves partial changes behind,
+    /// inside a transaction only the changes of the failed statement are rolled back.
     pub fn run(&mut self, sql: &str) -> BustubxResult<Vec<Tuple>> {
-        let logical_plan = self.create_logical_plan(sql)?;
+        let stmt = Self::parse_statement(sql)?;
+        let result = self.run_statement(stmt);
+        // dead versions may only be removed outside of a transaction started by `BEGIN`,
+        // its rollback would restore them otherwise
+        if self.txn.is_none() {
+            self.remove_dead_versions()?;
+        }
+        result
+    }
+
+    fn run_statement(&mut self, stmt: Statement) -> BustubxResult<Vec<Tuple>> {
+        match stmt {
+            Statement::StartTransaction { modes } => {
+                if self.txn.is_some() {
+                    return Err(BustubxError::Execution(
+                        "There is already a transaction in progress".to_string(),
+                    ));
+                }
+                let mut isolation_level = IsolationLevel::ReadUncommitted;
+                for mode in modes.iter() {
+                    if let TransactionMode::IsolationLevel(level) = mode {
+                        isolation_level = match level {
+                            TransactionIsolationLevel::ReadUncommitted => {
+                                IsolationLevel::ReadUncommitted
+                            }
+                            TransactionIsolationLevel::RepeatableRead => {
+                                IsolationLevel::SnapshotIsolation
+                            }
+                            TransactionIsolationLevel::Serializable => IsolationLevel::Serializable,
+                            TransactionIsolationLevel::ReadCommitted => {
+                                return Err(BustubxError::NotSupport(format!(
+                                    "isolation level {} not supported",
+                                    level
+                                )))
+                            }
+                        };
+                    }
+                }
+                self.txn = Some(self.transaction_manager.begin(isolation_level));
+                Ok(vec![])
+            }
+            Statement::Commit { .. } => {
+                let Some(txn) = self.txn.take() else {
+                    return Err(BustubxError::Execution(
+                        "There is no transaction in progress".to_string(),
+                    ));
+                };
+                self.transaction_manager.commit(txn)?;
+                Ok(vec![])
+            }
+            Statement::SetVariable {
+                variable, value, ..
+            } => {
+                self.set_variable(&variable, &value)?;
+                Ok(vec![])
+            }
+            Statement::Rollback { .. } => {
+                let Some(txn) = self.txn.take() else {
+                    return Err(BustubxError::Execution(
+                        "There is no transaction in progress".to_string(),
+                    ));
+                };
+                if self.transaction_manager.abort(txn)? {
+                    self.reload_catalog()?;
+                }
+                Ok(vec![])
+            }
+            _ => match self.txn.take() {
+                Some(txn) => {
+                    let savepoint = self.transaction_manager.savepoint(&txn);
+                    let result = self.execute(&stmt, &txn);
+                    let rolled_back = match result {
+                        // the transaction was aborted by the lock manager
+                        Err(BustubxError::Transaction(_)) => self.transaction_manager.abort(txn)?,
+                        Err(_) => {
+                            let rolled_back = self
+                                .transaction_manager
+                                .rollback_to_savepoint(&txn, savepoint)?;
+                            self.txn = Some(txn);
+                            rolled_back
+                        }
+                        Ok(_) => {
+                            self.txn = Some(txn);
+                            false
+                        }
+                    };
+                    if rolled_back {
+                        self.reload_catalog()?;
+                    }
+                    result
+                }
+                None => {
+                    let txn = self
+                        .transaction_manager
+                        .begin(IsolationLevel::ReadUncommitted);
+                    match self.execute(&stmt, &txn) {
+                        Ok(tuples) => {
+                            self.transaction_manager.commit(txn)?;
+                            Ok(tuples)
+                        }
+                        Err(e) => {
+                            if self.transaction_manager.abort(txn)? {
+                                self.reload_catalog()?;
+                            }
+                            Err(e)
+                        }
+                    }
+                }
+            },
+        }
+    }
+
+    fn set_variable(&mut self, variable: &ObjectName, value: &[Expr]) -> BustubxResult<()> {
+        let name = variable.to_string().to_lowercase();
+        match (name.as_str(), value) {
+            ("hash_join_memory_budget", [Expr::Value(Value::Number(number, _))]) => {
+                self.hash_join_memory_budget = number.parse().map_err(|_| {
+                    BustubxError::Execution(format!("invalid value {} for {}", number, name))
+                })?;
+                Ok(())
+            }
+            ("hash_join_memory_budget", _) => Err(BustubxError::Execution(format!(
+                "{} must be a number of bytes",
+                name
+            ))),
+            _ => Err(BustubxError::NotSupport(format!(
+                "variable {} not supported",
+                name
+            ))),
+        }
+    }
+
+    fn execute(&mut self, stmt: &Statement, txn: &Transaction) -> BustubxResult<Vec<Tuple>> {
+        let logical_plan = self.plan_statement(stmt)?;
         debug!(
             "Logical Plan: \n{}",
             pretty_format_logical_plan(&logical_plan)
@@ -77,12 +244,13 @@ impl Database {
         let optimized_logical_plan = LogicalOptimizer::new().optimize(&logical_plan)?;
         debug!(
             "Optimized Logical Plan: \n{}",
-            pretty_format_logical_plan(&logical_plan)
+            pretty_format_logical_plan(&optimized_logical_plan)
         );
 
         // logical plan -> physical plan
         let physical_planner = PhysicalPlanner {
             catalog: &self.catalog,
+            hash_join_memory_budget: self.hash_join_memory_budget,
         };
         let physical_plan = physical_planner.create_physical_plan(optimized_logical_plan);
         debug!(
@@ -90,7 +258,15 @@ impl Database {
             pretty_format_physical_plan(&physical_plan)
         );
 
-        let execution_ctx = ExecutionContext::new(&mut self.catalog);
+        // page writes from now on belong to this transaction
+        self.log_manager.begin(txn.id);
+        let execution_ctx = ExecutionContext::new(
+            &mut self.catalog,
+            txn,
+            &self.lock_manager,
+            &self.transaction_manager,
+            &self.spill_buffer_pool,
+        );
         let mut execution_engine = ExecutionEngine {
             context: execution_ctx,
         };
@@ -98,15 +274,107 @@ impl Database {
         Ok(tuples)
     }
 
-    pub fn create_logical_plan(&mut self, sql: &str) -> BustubxResult<LogicalPlan> {
+    /// Removes the index entries of versions no transaction can see anymore, and frees
+    /// the overflow pages of such deleted tuples.
+    fn remove_dead_versions(&mut self) -> BustubxResult<()> {
+        let dead_versions = self.transaction_manager.take_unreachable_versions();
+        if dead_versions.is_empty() {
+            return Ok(());
+        }
+        let txn = self
+            .transaction_manager
+            .begin(IsolationLevel::ReadUncommitted);
+        self.log_manager.begin(txn.id);
+        match self.remove_versions(&txn, dead_versions) {
+            Ok(()) => self.transaction_manager.commit(txn),
+            Err(e) => {
+                if self.transaction_manager.abort(txn)? {
+                    self.reload_catalog()?;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn remove_versions(
+        &mut self,
+        txn: &Transaction,
+        dead_versions: Vec<DeadVersion>,
+    ) -> BustubxResult<()> {
+        let mut changed_tables = vec![];
+        for dead_version in dead_versions {
+            // the table was dropped or rewritten since
+            let Ok(table_heap) = self.catalog.table_heap(&dead_version.table_ref) else {
+                continue;
+            };
+            if Arc::as_ptr(&table_heap) != dead_version.table_heap.as_ptr() {
+                continue;
+            }
+            let meta = table_heap.tuple_meta(dead_version.rid)?;
+            if meta.is_deleted && !self.transaction_manager.visible_to_all(meta.delete_txn_id) {
+                // deleted again after the version died, wait for that deletion
+                self.transaction_manager
+                    .record_dead_version(meta.delete_txn_id, dead_version);
+                continue;
+            }
+            let tuple = if meta.is_deleted {
+                // no transaction reads the values of the tuple anymore
+                let page_ids = table_heap.clear_deleted_tuple(dead_version.rid)?;
+                self.transaction_manager.drop_pages_on_commit(txn, page_ids);
+                None
+            } else {
+                Some(table_heap.tuple(dead_version.rid)?)
+            };
+
+            let indexes = self.catalog.table_indexes(&dead_version.table_ref)?;
+            for (weak_index, key) in dead_version.index_entries.iter() {
+                let Some(index) = indexes
+                    .iter()
+                    .find(|index| Arc::as_ptr(index) == weak_index.as_ptr())
+                else {
+                    continue;
+                };
+                // the key was changed back to the one of the dead version
+                if let Some(tuple) = &tuple {
+                    if &tuple.project_with_schema(index.key_schema.clone())? == key {
+                        continue;
+                    }
+                }
+                index.delete(key, dead_version.rid)?;
+                if !changed_tables.contains(&dead_version.table_ref) {
+                    changed_tables.push(dead_version.table_ref.clone());
+                }
+            }
+        }
+        for table_ref in changed_tables.iter() {
+            self.catalog.sync_index_roots(table_ref)?;
+        }
+        Ok(())
+    }
+
+    /// In-memory catalog may refer to rolled back pages, so it's rebuilt from system tables.
+    fn reload_catalog(&mut self) -> BustubxResult<()> {
+        self.catalog = Catalog::new(self.buffer_pool.clone());
+        load_catalog_data(self)
+    }
+
+    fn parse_statement(sql: &str) -> BustubxResult<Statement> {
         // sql -> ast
-        let stmts = crate::parser::parse_sql(sql)?;
+        let mut stmts = crate::parser::parse_sql(sql)?;
         if stmts.len() != 1 {
             return Err(BustubxError::NotSupport(
                 "only support one sql statement".to_string(),
             ));
         }
-        let stmt = &stmts[0];
+        Ok(stmts.remove(0))
+    }
+
+    pub fn create_logical_plan(&mut self, sql: &str) -> BustubxResult<LogicalPlan> {
+        let stmt = Self::parse_statement(sql)?;
+        self.plan_statement(&stmt)
+    }
+
+    fn plan_statement(&self, stmt: &Statement) -> BustubxResult<LogicalPlan> {
         let mut planner = LogicalPlanner {
             context: PlannerContext {
                 catalog: &self.catalog,
@@ -117,6 +385,260 @@ impl Database {
     }
 
     pub fn flush(&self) -> BustubxResult<()> {
-        self.buffer_pool.flush_all_pages()
+        self.buffer_pool.flush_all_pages()?;
+        // all changes are on disk now, log records are not needed by recovery anymore
+        if !self.log_manager.has_running_txns() {
+            self.buffer_pool.disk_manager.sync()?;
+            self.log_manager.truncate()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::TableReference;
+    use crate::storage::index::TreeIndexIterator;
+    use crate::transaction::{IsolationLevel, Transaction};
+    use crate::{BustubxError, Database, Tuple};
+    use tempfile::TempDir;
+
+    fn query_rows(db: &mut Database, sql: &str) -> Vec<String> {
+        db.run(sql)
+            .unwrap()
+            .iter()
+            .map(|tuple| format!("{:?}", tuple.data))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_recover_committed_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("create table t1 (a int, b varchar)").unwrap();
+        db.run("create index idx1 on t1 (a)").unwrap();
+        db.run("insert into t1 values (1, 'a'), (2, 'b'), (3, 'c')")
+            .unwrap();
+        db.run("delete from t1 where a = 2").unwrap();
+        let expected = query_rows(&mut db, "select * from t1");
+        // crash without writing back dirty pages
+        std::mem::forget(db);
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        assert_eq!(query_rows(&mut db, "select * from t1"), expected);
+        db.run("insert into t1 values (4, 'd')").unwrap();
+        assert_eq!(query_rows(&mut db, "select * from t1").len(), 3);
+    }
+
+    #[test]
+    pub fn test_recover_uncommitted_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_path = db_path.to_str().unwrap();
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        db.run("insert into t1 values (1, 1)").unwrap();
+        let expected = query_rows(&mut db, "select * from t1");
+
+        // crash in the middle of a transaction whose changes were written to disk
+        db.run("begin").unwrap();
+        db.run("insert into t1 values (2, 2), (3, 3)").unwrap();
+        db.run("update t1 set b = 10").unwrap();
+        db.buffer_pool.flush_all_pages().unwrap();
+        std::mem::forget(db);
+
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        assert_eq!(query_rows(&mut db, "select * from t1"), expected);
+
+        // recovery itself can crash and run again
+        std::mem::forget(db);
+        let mut db = Database::new_on_disk(db_path).unwrap();
+        assert_eq!(query_rows(&mut db, "select * from t1"), expected);
+    }
+
+    #[test]
+    pub fn test_rollback_transaction() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        db.run("create index idx1 on t1 (a)").unwrap();
+        db.run("insert into t1 values (1, 1)").unwrap();
+        let expected = query_rows(&mut db, "select * from t1");
+
+        db.run("begin").unwrap();
+        db.run("insert into t1 values (2, 2), (3, 3)").unwrap();
+        db.run("delete from t1 where a = 1").unwrap();
+        db.run("create table t2 (a int)").unwrap();
+        assert_eq!(query_rows(&mut db, "select * from t1").len(), 2);
+        db.run("rollback").unwrap();
+
+        assert_eq!(query_rows(&mut db, "select * from t1"), expected);
+        assert!(db.run("select * from t2").is_err());
+
+        let index = db
+            .catalog
+            .index(&TableReference::bare("t1"), "idx1")
+            .unwrap()
+            .unwrap();
+        let key = |a: i32| Tuple::new(index.key_schema.clone(), vec![a.into()]);
+        assert!(index.get(&key(1)).unwrap().is_some());
+        assert_eq!(index.get(&key(2)).unwrap(), None);
+    }
+
+    #[test]
+    pub fn test_failed_statement_in_transaction() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+
+        db.run("begin").unwrap();
+        db.run("insert into t1 values (1, 1), (2, 2)").unwrap();
+        assert!(db.run("update t1 set b = 10 / (a - 2)").is_err());
+        db.run("insert into t1 values (3, 3)").unwrap();
+        db.run("commit").unwrap();
+
+        assert_eq!(
+            query_rows(&mut db, "select * from t1"),
+            vec![
+                "[Int32(Some(1)), Int32(Some(1))]",
+                "[Int32(Some(2)), Int32(Some(2))]",
+                "[Int32(Some(3)), Int32(Some(3))]",
+            ]
+        );
+        assert!(db.run("commit").is_err());
+    }
+
+    #[test]
+    pub fn test_snapshot_isolation() {
+        snapshot_isolation(false);
+        snapshot_isolation(true);
+    }
+
+    #[test]
+    pub fn test_snapshot_reads_large_deleted_values() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b varchar)").unwrap();
+        db.run(&format!(
+            "insert into t1 values (1, '{}')",
+            "a".repeat(10000)
+        ))
+        .unwrap();
+        let table_heap = db.catalog.table_heap(&TableReference::bare("t1")).unwrap();
+        let page_count = table_heap.page_ids().unwrap().len();
+
+        let reader = db
+            .transaction_manager
+            .begin(IsolationLevel::SnapshotIsolation);
+        db.run("delete from t1").unwrap();
+        // would reuse the overflow pages if they were freed by the deletion
+        db.run(&format!(
+            "insert into t1 values (2, '{}')",
+            "b".repeat(10000)
+        ))
+        .unwrap();
+        let stmt = Database::parse_statement("select b from t1").unwrap();
+        let tuples = db.execute(&stmt, &reader).unwrap();
+        assert_eq!(tuples.len(), 1);
+        assert_eq!(tuples[0].data, vec!["a".repeat(10000).into()]);
+        db.transaction_manager.abort(reader).unwrap();
+
+        // no transaction can see the deleted tuple anymore
+        assert_eq!(
+            query_rows(&mut db, "select a from t1"),
+            vec!["[Int32(Some(2))]"]
+        );
+        assert_eq!(table_heap.page_ids().unwrap().len(), page_count);
+    }
+
+    fn index_entry_count(db: &Database) -> usize {
+        let indexes = db
+            .catalog
+            .table_indexes(&TableReference::bare("t1"))
+            .unwrap();
+        let mut iterator = TreeIndexIterator::new(indexes[0].clone(), ..);
+        let mut count = 0;
+        while iterator.next().unwrap().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    fn snapshot_isolation(with_index: bool) {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        if with_index {
+            db.run("create index idx1 on t1 (a)").unwrap();
+        }
+        db.run("insert into t1 values (1, 1), (2, 2)").unwrap();
+
+        let execute = |db: &mut Database, txn: &Transaction, sql: &str| {
+            let stmt = Database::parse_statement(sql).unwrap();
+            db.execute(&stmt, txn).map(|tuples| {
+                let mut rows = tuples
+                    .iter()
+                    .map(|tuple| format!("{:?}", tuple.data))
+                    .collect::<Vec<String>>();
+                rows.sort();
+                rows
+            })
+        };
+
+        let reader = db
+            .transaction_manager
+            .begin(IsolationLevel::SnapshotIsolation);
+        let expected = execute(&mut db, &reader, "select * from t1").unwrap();
+        assert_eq!(expected.len(), 2);
+
+        // readers don't block writers
+        let writer = db
+            .transaction_manager
+            .begin(IsolationLevel::ReadUncommitted);
+        execute(&mut db, &writer, "update t1 set a = 10, b = 10 where a = 1").unwrap();
+        execute(&mut db, &writer, "delete from t1 where a = 2").unwrap();
+        execute(&mut db, &writer, "insert into t1 values (3, 3)").unwrap();
+        if with_index {
+            assert_eq!(index_entry_count(&db), 4);
+        }
+        assert_eq!(
+            execute(&mut db, &reader, "select * from t1").unwrap(),
+            expected
+        );
+        db.transaction_manager.commit(writer).unwrap();
+        assert_eq!(
+            execute(&mut db, &reader, "select * from t1").unwrap(),
+            expected
+        );
+        // old versions are rebuilt from all columns before reading some of them
+        assert_eq!(
+            execute(&mut db, &reader, "select b from t1").unwrap(),
+            vec!["[Int32(Some(1))]", "[Int32(Some(2))]"]
+        );
+
+        let new_reader = db
+            .transaction_manager
+            .begin(IsolationLevel::SnapshotIsolation);
+        assert_eq!(
+            execute(&mut db, &new_reader, "select * from t1").unwrap(),
+            vec![
+                "[Int32(Some(10)), Int32(Some(10))]",
+                "[Int32(Some(3)), Int32(Some(3))]",
+            ]
+        );
+        db.transaction_manager.commit(new_reader).unwrap();
+
+        // the row was updated after the snapshot was taken
+        assert!(matches!(
+            execute(&mut db, &reader, "update t1 set b = 20 where a = 1"),
+            Err(BustubxError::Transaction(_))
+        ));
+        db.transaction_manager.abort(reader).unwrap();
+
+        // no transaction can see old versions anymore
+        assert_eq!(query_rows(&mut db, "select * from t1").len(), 2);
+        if with_index {
+            assert_eq!(index_entry_count(&db), 2);
+        }
     }
 }
@@ -24,4 +24,10 @@ pub enum BustubxError {
 
     #[error("Storage error: {0}")]
     Storage(String),
+
+    #[error("Transaction error: {0}")]
+    Transaction(String),
+
+    #[error("Constraint violation: {0}")]
+    ConstraintViolation(String),
 }
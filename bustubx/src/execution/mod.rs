@@ -2,8 +2,10 @@ pub mod physical_plan;
 
 use std::sync::Arc;
 
+use crate::buffer::BufferPoolManager;
 use crate::catalog::SchemaRef;
 use crate::execution::physical_plan::PhysicalPlan;
+use crate::transaction::{LockManager, Transaction, TransactionManager};
 use crate::{catalog::Catalog, storage::Tuple, BustubxResult};
 
 pub trait VolcanoExecutor {
@@ -19,6 +21,11 @@ pub trait VolcanoExecutor {
 #[derive(derive_new::new)]
 pub struct ExecutionContext<'a> {
     pub catalog: &'a mut Catalog,
+    pub txn: &'a Transaction,
+    pub lock_manager: &'a LockManager,
+    pub transaction_manager: &'a TransactionManager,
+    /// Buffer pool of spill files
+    pub spill_buffer_pool: &'a Arc<BufferPoolManager>,
 }
 
 pub struct ExecutionEngine<'a> {
@@ -48,7 +48,7 @@ impl PhysicalAggregate {
             .iter()
             .map(|expr| {
                 if let Expr::AggregateFunction(aggr) = expr {
-                    Ok(aggr.func_kind.create_accumulator())
+                    aggr.create_accumulator(&self.input.output_schema())
                 } else {
                     Err(BustubxError::Execution(format!(
                         "aggr expr is not AggregateFunction instead of {}",
@@ -89,6 +89,10 @@ impl VolcanoExecutor for PhysicalAggregate {
                     acc.update_value(&self.aggr_exprs[idx].evaluate(&tuple)?)?;
                 }
             }
+            // aggregating without groups yields one row even for an empty input
+            if groups.is_empty() && self.group_exprs.is_empty() {
+                groups.insert(vec![], self.build_accumulators()?);
+            }
 
             for (group_key, accumulators) in groups.into_iter() {
                 let mut values = accumulators
@@ -0,0 +1,66 @@
+use crate::catalog::{SchemaRef, EMPTY_SCHEMA_REF};
+use crate::common::TableReference;
+use crate::planner::logical_plan::AlterTableOperation;
+use crate::transaction::LockMode;
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    storage::Tuple,
+    BustubxResult,
+};
+
+/// Changes the schema of a table. Adding or dropping a column rewrites the table and its
+/// indexes, the old pages are freed when the transaction commits.
+#[derive(Debug, derive_new::new)]
+pub struct PhysicalAlterTable {
+    pub table: TableReference,
+    pub operation: AlterTableOperation,
+}
+
+impl VolcanoExecutor for PhysicalAlterTable {
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        context
+            .lock_manager
+            .lock_table(context.txn, LockMode::Exclusive, &self.table)?;
+        let schema = context.catalog.table_heap(&self.table)?.schema.clone();
+        let page_ids = match &self.operation {
+            AlterTableOperation::AddColumn {
+                column,
+                if_not_exists,
+            } => {
+                if *if_not_exists && schema.index_of(None, &column.name).is_ok() {
+                    return Ok(None);
+                }
+                context.catalog.add_column(&self.table, column.clone())?
+            }
+            AlterTableOperation::DropColumn { name, if_exists } => {
+                if *if_exists && schema.index_of(None, name).is_err() {
+                    return Ok(None);
+                }
+                context.catalog.drop_column(&self.table, name)?
+            }
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                context
+                    .catalog
+                    .rename_column(&self.table, old_name, new_name)?;
+                vec![]
+            }
+            AlterTableOperation::RenameTable { new_name } => {
+                context.catalog.rename_table(&self.table, new_name)?;
+                vec![]
+            }
+        };
+        context
+            .transaction_manager
+            .drop_pages_on_commit(context.txn, page_ids);
+        Ok(None)
+    }
+    fn output_schema(&self) -> SchemaRef {
+        EMPTY_SCHEMA_REF.clone()
+    }
+}
+
+impl std::fmt::Display for PhysicalAlterTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AlterTable: {} {}", self.table, self.operation)
+    }
+}
@@ -0,0 +1,59 @@
+use crate::catalog::{ColumnStatistics, SchemaRef, TableStatistics, EMPTY_SCHEMA_REF};
+use crate::common::TableReference;
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    storage::{TableIterator, Tuple},
+    BustubxResult,
+};
+use std::collections::HashMap;
+
+/// Scans the whole table to collect its statistics and stores them in the catalog.
+#[derive(Debug, derive_new::new)]
+pub struct PhysicalAnalyze {
+    pub table: TableReference,
+    pub table_schema: SchemaRef,
+}
+
+impl VolcanoExecutor for PhysicalAnalyze {
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        let table_heap = context.catalog.table_heap(&self.table)?;
+        let mut iterator = TableIterator::new(table_heap, ..)
+            .with_snapshot(context.transaction_manager.snapshot(context.txn));
+
+        let mut row_count = 0;
+        let mut values = vec![vec![]; self.table_schema.column_count()];
+        while let Some((_, tuple)) = iterator.next()? {
+            row_count += 1;
+            for (column_values, value) in values.iter_mut().zip(tuple.data) {
+                column_values.push(value);
+            }
+        }
+
+        let columns = self
+            .table_schema
+            .columns
+            .iter()
+            .zip(values)
+            .map(|(col, values)| {
+                (
+                    col.name.clone(),
+                    ColumnStatistics::compute(col.data_type, values),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        context
+            .catalog
+            .update_statistics(&self.table, TableStatistics { row_count, columns })?;
+        Ok(None)
+    }
+
+    fn output_schema(&self) -> SchemaRef {
+        EMPTY_SCHEMA_REF.clone()
+    }
+}
+
+impl std::fmt::Display for PhysicalAnalyze {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Analyze: {}", self.table)
+    }
+}
@@ -1,7 +1,8 @@
-use crate::catalog::{SchemaRef, EMPTY_SCHEMA_REF};
+use crate::catalog::{Constraint, ConstraintKind, SchemaRef, EMPTY_SCHEMA_REF};
 use crate::common::TableReference;
 use crate::expression::{ColumnExpr, Expr};
 use crate::planner::logical_plan::OrderByExpr;
+use crate::storage::TableIterator;
 use crate::{
     execution::{ExecutionContext, VolcanoExecutor},
     storage::Tuple,
@@ -15,15 +16,18 @@ pub struct PhysicalCreateIndex {
     pub table: TableReference,
     pub table_schema: SchemaRef,
     pub columns: Vec<OrderByExpr>,
+    pub unique: bool,
 }
 
 impl VolcanoExecutor for PhysicalCreateIndex {
     fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        let mut key_columns = vec![];
         let mut key_indices = vec![];
         for col in self.columns.iter() {
             match col.expr.as_ref() {
                 Expr::Column(ColumnExpr { name, .. }) => {
                     key_indices.push(self.table_schema.index_of(None, name)?);
+                    key_columns.push(name.clone());
                 }
                 _ => {
                     return Err(BustubxError::Execution(format!(
@@ -34,9 +38,56 @@ impl VolcanoExecutor for PhysicalCreateIndex {
             }
         }
         let key_schema = Arc::new(self.table_schema.project(&key_indices)?);
-        context
-            .catalog
-            .create_index(self.name.clone(), &self.table, key_schema)?;
+
+        // index the rows already in the table
+        let mut entries = vec![];
+        let table_heap = context.catalog.table_heap(&self.table)?;
+        let mut iterator = TableIterator::new(table_heap, ..);
+        while let Some((rid, tuple)) = iterator.next()? {
+            entries.push((tuple.project_with_schema(key_schema.clone())?, rid));
+        }
+
+        let index = if self.unique {
+            // duplicates are found before the constraint is added
+            entries.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            if let Some(duplicate) = entries.windows(2).find(|pair| {
+                // null never equals another key
+                pair[0].0 == pair[1].0 && !pair[0].0.data.iter().any(|value| value.is_null())
+            }) {
+                return Err(BustubxError::ConstraintViolation(format!(
+                    "duplicate key ({}) violates unique constraint {}",
+                    duplicate[0]
+                        .0
+                        .data
+                        .iter()
+                        .map(|value| value.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    self.name
+                )));
+            }
+            context.catalog.add_constraint(
+                &self.table,
+                Constraint::new(
+                    self.name.clone(),
+                    ConstraintKind::Unique {
+                        columns: key_columns,
+                    },
+                ),
+            )?;
+            context
+                .catalog
+                .index(&self.table, &self.name)?
+                .ok_or_else(|| BustubxError::Internal(format!("index {} not created", self.name)))?
+        } else {
+            context
+                .catalog
+                .create_index(self.name.clone(), &self.table, key_schema)?
+        };
+        for (key, rid) in entries {
+            index.insert(&key, rid)?;
+        }
+        context.catalog.sync_index_roots(&self.table)?;
         Ok(None)
     }
     fn output_schema(&self) -> SchemaRef {
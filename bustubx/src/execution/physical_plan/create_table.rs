@@ -1,4 +1,4 @@
-use crate::catalog::SchemaRef;
+use crate::catalog::{Constraint, SchemaRef};
 use crate::common::TableReference;
 use crate::{
     catalog::Schema,
@@ -12,6 +12,7 @@ use std::sync::Arc;
 pub struct PhysicalCreateTable {
     pub table: TableReference,
     pub schema: Schema,
+    pub constraints: Vec<Constraint>,
 }
 
 impl VolcanoExecutor for PhysicalCreateTable {
@@ -19,6 +20,11 @@ impl VolcanoExecutor for PhysicalCreateTable {
         context
             .catalog
             .create_table(self.table.clone(), Arc::new(self.schema.clone()))?;
+        for constraint in self.constraints.iter() {
+            context
+                .catalog
+                .add_constraint(&self.table, constraint.clone())?;
+        }
         Ok(None)
     }
     fn output_schema(&self) -> SchemaRef {
@@ -0,0 +1,179 @@
+use crate::catalog::{ConstraintKind, ReferentialAction, SchemaRef, DELETE_OUTPUT_SCHEMA_REF};
+use crate::common::{ScalarValue, TableReference};
+use crate::execution::physical_plan::update::update_tuple;
+use crate::execution::{ExecutionContext, VolcanoExecutor};
+use crate::expression::{Expr, ExprTrait};
+use crate::storage::{RecordId, TableIterator};
+use crate::transaction::{DeadVersion, LockMode};
+use crate::{BustubxError, BustubxResult, Tuple};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+pub struct PhysicalDelete {
+    pub table: TableReference,
+    pub selection: Option<Expr>,
+
+    delete_rows: AtomicU32,
+    table_iterator: Mutex<Option<TableIterator>>,
+}
+
+impl PhysicalDelete {
+    pub fn new(table: TableReference, selection: Option<Expr>) -> Self {
+        Self {
+            table,
+            selection,
+            delete_rows: AtomicU32::new(0),
+            table_iterator: Mutex::new(None),
+        }
+    }
+}
+
+impl VolcanoExecutor for PhysicalDelete {
+    fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
+        self.delete_rows.store(0, Ordering::SeqCst);
+        context
+            .lock_manager
+            .lock_table(context.txn, LockMode::IntentionExclusive, &self.table)?;
+        let table_heap = context.catalog.table_heap(&self.table)?;
+        *self.table_iterator.lock().unwrap() = Some(
+            TableIterator::new(table_heap.clone(), ..)
+                .with_snapshot(context.transaction_manager.snapshot(context.txn)),
+        );
+        Ok(())
+    }
+
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        let Some(table_iterator) = &mut *self.table_iterator.lock().unwrap() else {
+            return Err(BustubxError::Execution(
+                "table iterator not created".to_string(),
+            ));
+        };
+        loop {
+            if let Some((rid, tuple)) = table_iterator.next()? {
+                if let Some(selection) = &self.selection {
+                    if !selection.evaluate(&tuple)?.as_boolean()?.unwrap_or(false) {
+                        continue;
+                    }
+                }
+                if delete_tuple(context, &self.table, rid, &tuple)? {
+                    self.delete_rows.fetch_add(1, Ordering::SeqCst);
+                }
+            } else {
+                return if self.delete_rows.load(Ordering::SeqCst) == 0 {
+                    Ok(None)
+                } else {
+                    let delete_rows = self.delete_rows.swap(0, Ordering::SeqCst);
+                    Ok(Some(Tuple::new(
+                        self.output_schema(),
+                        vec![ScalarValue::Int32(Some(delete_rows as i32))],
+                    )))
+                };
+            }
+        }
+    }
+
+    fn output_schema(&self) -> SchemaRef {
+        DELETE_OUTPUT_SCHEMA_REF.clone()
+    }
+}
+
+/// Deletes a tuple and applies the `ON DELETE` actions of foreign keys referencing it.
+/// Returns false if the tuple was already deleted, e.g. by a cascade of this statement.
+pub(crate) fn delete_tuple(
+    context: &mut ExecutionContext,
+    table_ref: &TableReference,
+    rid: RecordId,
+    tuple: &Tuple,
+) -> BustubxResult<bool> {
+    let table_heap = context.catalog.table_heap(table_ref)?;
+    context
+        .lock_manager
+        .lock_row(context.txn, LockMode::Exclusive, table_ref, rid)?;
+    // mark tuple deleted, index entries are kept for transactions reading
+    // old versions
+    let mut meta = table_heap.tuple_meta(rid)?;
+    context
+        .transaction_manager
+        .check_write_conflict(context.txn, &meta)?;
+    if meta.is_deleted {
+        return Ok(false);
+    }
+    meta.is_deleted = true;
+    meta.delete_txn_id = context.txn.id;
+    table_heap.update_tuple_meta(meta, rid)?;
+    let mut index_entries = vec![];
+    for index in context.catalog.table_indexes(table_ref)? {
+        let key = tuple.project_with_schema(index.key_schema.clone())?;
+        index_entries.push((Arc::downgrade(&index), key));
+    }
+    context.transaction_manager.record_dead_version(
+        context.txn.id,
+        DeadVersion {
+            rid,
+            table_ref: table_ref.clone(),
+            table_heap: Arc::downgrade(&table_heap),
+            index_entries,
+        },
+    );
+
+    // the tuple is deleted first, so cycles of cascades end
+    for (child_ref, foreign_key) in context.catalog.referencing_foreign_keys(table_ref) {
+        let ConstraintKind::ForeignKey {
+            columns, on_delete, ..
+        } = &foreign_key.kind
+        else {
+            continue;
+        };
+        let children = context
+            .catalog
+            .referencing_tuples(&child_ref, &foreign_key, tuple)?;
+        if children.is_empty() {
+            continue;
+        }
+        match on_delete {
+            ReferentialAction::Restrict => {
+                return Err(BustubxError::ConstraintViolation(format!(
+                    "delete on table {} violates foreign key constraint {} on table {}",
+                    table_ref.table(),
+                    foreign_key.name,
+                    child_ref.table()
+                )));
+            }
+            ReferentialAction::Cascade => {
+                context.lock_manager.lock_table(
+                    context.txn,
+                    LockMode::IntentionExclusive,
+                    &child_ref,
+                )?;
+                for (child_rid, child_tuple) in children {
+                    delete_tuple(context, &child_ref, child_rid, &child_tuple)?;
+                }
+            }
+            ReferentialAction::SetNull => {
+                context.lock_manager.lock_table(
+                    context.txn,
+                    LockMode::IntentionExclusive,
+                    &child_ref,
+                )?;
+                for (child_rid, child_tuple) in children {
+                    let mut new_tuple = child_tuple.clone();
+                    for col in columns.iter() {
+                        let idx = new_tuple.schema.index_of(None, col)?;
+                        let data_type = new_tuple.schema.columns[idx].data_type;
+                        new_tuple.data[idx] = ScalarValue::new_empty(data_type);
+                    }
+                    update_tuple(context, &child_ref, child_rid, &child_tuple, new_tuple)?;
+                }
+                context.catalog.sync_index_roots(&child_ref)?;
+            }
+        }
+    }
+    Ok(true)
+}
+
+impl std::fmt::Display for PhysicalDelete {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Delete")
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::catalog::SchemaRef;
+use crate::common::ScalarValue;
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    storage::Tuple,
+    BustubxResult,
+};
+
+use super::PhysicalPlan;
+
+/// Outputs the first occurrence of each tuple of the input, tuples are compared with a
+/// hash set of all tuples seen so far. NULLs are equal to each other here.
+#[derive(Debug)]
+pub struct PhysicalHashDistinct {
+    pub input: Arc<PhysicalPlan>,
+
+    seen: Mutex<HashSet<Vec<ScalarValue>>>,
+}
+
+impl PhysicalHashDistinct {
+    pub fn new(input: Arc<PhysicalPlan>) -> Self {
+        PhysicalHashDistinct {
+            input,
+            seen: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+impl VolcanoExecutor for PhysicalHashDistinct {
+    fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
+        self.input.init(context)?;
+        self.seen.lock().unwrap().clear();
+        Ok(())
+    }
+
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        while let Some(tuple) = self.input.next(context)? {
+            if self.seen.lock().unwrap().insert(tuple.data.clone()) {
+                return Ok(Some(tuple));
+            }
+        }
+        Ok(None)
+    }
+
+    fn output_schema(&self) -> SchemaRef {
+        self.input.output_schema()
+    }
+}
+
+impl std::fmt::Display for PhysicalHashDistinct {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HashDistinct")
+    }
+}
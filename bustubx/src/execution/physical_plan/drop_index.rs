@@ -0,0 +1,49 @@
+use crate::catalog::{SchemaRef, EMPTY_SCHEMA_REF};
+use crate::transaction::LockMode;
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    storage::Tuple,
+    BustubxError, BustubxResult,
+};
+
+/// Removes the index from the catalog, its pages are freed when the transaction commits.
+#[derive(Debug, derive_new::new)]
+pub struct PhysicalDropIndex {
+    pub index_name: String,
+    pub schema_name: Option<String>,
+    pub if_exists: bool,
+}
+
+impl VolcanoExecutor for PhysicalDropIndex {
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        let table = context
+            .catalog
+            .index_table(self.schema_name.as_deref(), &self.index_name)?;
+        let Some(table) = table else {
+            if self.if_exists {
+                return Ok(None);
+            }
+            return Err(BustubxError::Execution(format!(
+                "index {} not created yet",
+                self.index_name
+            )));
+        };
+        context
+            .lock_manager
+            .lock_table(context.txn, LockMode::Exclusive, &table)?;
+        let page_ids = context.catalog.drop_index(&table, &self.index_name)?;
+        context
+            .transaction_manager
+            .drop_pages_on_commit(context.txn, page_ids);
+        Ok(None)
+    }
+    fn output_schema(&self) -> SchemaRef {
+        EMPTY_SCHEMA_REF.clone()
+    }
+}
+
+impl std::fmt::Display for PhysicalDropIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DropIndex: {}", self.index_name)
+    }
+}
@@ -0,0 +1,40 @@
+use crate::catalog::{SchemaRef, EMPTY_SCHEMA_REF};
+use crate::common::TableReference;
+use crate::transaction::LockMode;
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    storage::Tuple,
+    BustubxResult,
+};
+
+/// Removes the table from the catalog, its pages are freed when the transaction commits.
+#[derive(Debug, derive_new::new)]
+pub struct PhysicalDropTable {
+    pub table: TableReference,
+    pub if_exists: bool,
+}
+
+impl VolcanoExecutor for PhysicalDropTable {
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        if self.if_exists && context.catalog.table_heap(&self.table).is_err() {
+            return Ok(None);
+        }
+        context
+            .lock_manager
+            .lock_table(context.txn, LockMode::Exclusive, &self.table)?;
+        let page_ids = context.catalog.drop_table(&self.table)?;
+        context
+            .transaction_manager
+            .drop_pages_on_commit(context.txn, page_ids);
+        Ok(None)
+    }
+    fn output_schema(&self) -> SchemaRef {
+        EMPTY_SCHEMA_REF.clone()
+    }
+}
+
+impl std::fmt::Display for PhysicalDropTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DropTable: {}", self.table)
+    }
+}
@@ -0,0 +1,421 @@
+use log::debug;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::catalog::SchemaRef;
+use crate::common::ScalarValue;
+use crate::expression::{Expr, ExprTrait};
+use crate::storage::codec::TupleCodec;
+use crate::storage::SpillFile;
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    planner::logical_plan::JoinType,
+    storage::Tuple,
+    BustubxError, BustubxResult,
+};
+
+use super::PhysicalPlan;
+
+/// Bytes of build side tuples kept in memory before partitions are spilled to disk
+pub const HASH_JOIN_MEMORY_BUDGET: usize = 1024 * 1024;
+const HASH_JOIN_PARTITIONS: usize = 16;
+
+/// Joins two inputs on equi-predicates. The right input is the build side, all its
+/// tuples are put into a hash table which is probed by tuples of the left input.
+///
+/// If the build side exceeds the memory budget, both inputs are partitioned by the hash
+/// of the join keys into spill files, then partitions are joined one by one.
+///
+/// Null keys never match, as `NULL = NULL` is not true in SQL.
+#[derive(Debug)]
+pub struct PhysicalHashJoin {
+    pub join_type: JoinType,
+    /// Pairs of left key and right key
+    pub on: Vec<(Expr, Expr)>,
+    /// Non-equi part of the join condition evaluated on joined tuples
+    pub filter: Option<Expr>,
+    pub left_input: Arc<PhysicalPlan>,
+    pub right_input: Arc<PhysicalPlan>,
+    pub schema: SchemaRef,
+    pub memory_budget: usize,
+
+    state: Mutex<HashJoinState>,
+}
+
+#[derive(Debug, Default)]
+struct HashJoinState {
+    built: bool,
+    table: HashTable,
+    probe: ProbeSide,
+    /// Partitions not joined yet, each one is a pair of build side and probe side
+    partitions: VecDeque<(SpillFile, SpillFile)>,
+    output: VecDeque<Tuple>,
+}
+
+#[derive(Debug, Default)]
+struct HashTable {
+    tuples: Vec<Tuple>,
+    matched: Vec<bool>,
+    buckets: HashMap<Vec<ScalarValue>, Vec<usize>>,
+}
+
+#[derive(Debug, Default)]
+enum ProbeSide {
+    #[default]
+    Input,
+    Spilled {
+        file: SpillFile,
+        next_page: usize,
+        tuples: VecDeque<Tuple>,
+    },
+    Exhausted,
+}
+
+impl PhysicalHashJoin {
+    pub fn new(
+        join_type: JoinType,
+        on: Vec<(Expr, Expr)>,
+        filter: Option<Expr>,
+        left_input: Arc<PhysicalPlan>,
+        right_input: Arc<PhysicalPlan>,
+        schema: SchemaRef,
+    ) -> Self {
+        PhysicalHashJoin {
+            join_type,
+            on,
+            filter,
+            left_input,
+            right_input,
+            schema,
+            memory_budget: HASH_JOIN_MEMORY_BUDGET,
+            state: Mutex::new(HashJoinState::default()),
+        }
+    }
+
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    fn left_keys(&self, tuple: &Tuple) -> BustubxResult<Vec<ScalarValue>> {
+        self.on.iter().map(|(key, _)| key.evaluate(tuple)).collect()
+    }
+
+    fn right_keys(&self, tuple: &Tuple) -> BustubxResult<Vec<ScalarValue>> {
+        self.on.iter().map(|(_, key)| key.evaluate(tuple)).collect()
+    }
+
+    fn build(
+        &self,
+        context: &mut ExecutionContext,
+        state: &mut HashJoinState,
+    ) -> BustubxResult<()> {
+        let mut tuples = vec![];
+        let mut size = 0;
+        let mut build_partitions: Option<Vec<SpillFile>> = None;
+        while let Some(tuple) = self.right_input.next(context)? {
+            if let Some(partitions) = build_partitions.as_mut() {
+                let keys = self.right_keys(&tuple)?;
+                partitions[partition_of(&keys)].append(&tuple)?;
+                continue;
+            }
+            size += TupleCodec::encode(&tuple).len();
+            tuples.push(tuple);
+            if size > self.memory_budget {
+                debug!("hash join build side exceeds memory budget, spilling to disk");
+                let mut partitions = self.new_partitions(context, self.right_input.output_schema());
+                for tuple in std::mem::take(&mut tuples) {
+                    let keys = self.right_keys(&tuple)?;
+                    partitions[partition_of(&keys)].append(&tuple)?;
+                }
+                build_partitions = Some(partitions);
+            }
+        }
+
+        let Some(build_partitions) = build_partitions else {
+            state.table = self.build_table(tuples)?;
+            state.probe = ProbeSide::Input;
+            return Ok(());
+        };
+
+        let mut probe_partitions = self.new_partitions(context, self.left_input.output_schema());
+        while let Some(tuple) = self.left_input.next(context)? {
+            let keys = self.left_keys(&tuple)?;
+            probe_partitions[partition_of(&keys)].append(&tuple)?;
+        }
+        for (mut build, mut probe) in build_partitions.into_iter().zip(probe_partitions) {
+            build.flush()?;
+            probe.flush()?;
+            state.partitions.push_back((build, probe));
+        }
+        self.next_partition(state)
+    }
+
+    fn new_partitions(&self, context: &ExecutionContext, schema: SchemaRef) -> Vec<SpillFile> {
+        (0..HASH_JOIN_PARTITIONS)
+            .map(|_| SpillFile::new(schema.clone(), context.spill_buffer_pool.clone()))
+            .collect()
+    }
+
+    fn build_table(&self, tuples: Vec<Tuple>) -> BustubxResult<HashTable> {
+        let mut buckets: HashMap<Vec<ScalarValue>, Vec<usize>> = HashMap::new();
+        for (idx, tuple) in tuples.iter().enumerate() {
+            let keys = self.right_keys(tuple)?;
+            if keys.iter().any(|key| key.is_null()) {
+                continue;
+            }
+            buckets.entry(keys).or_default().push(idx);
+        }
+        Ok(HashTable {
+            matched: vec![false; tuples.len()],
+            tuples,
+            buckets,
+        })
+    }
+
+    /// Loads the build side of the next partition into the hash table.
+    fn next_partition(&self, state: &mut HashJoinState) -> BustubxResult<()> {
+        if let Some((build, probe)) = state.partitions.pop_front() {
+            // a partition exceeding the memory budget is not partitioned again
+            state.table = self.build_table(build.read_all()?)?;
+            state.probe = ProbeSide::Spilled {
+                file: probe,
+                next_page: 0,
+                tuples: VecDeque::new(),
+            };
+        } else {
+            state.table = HashTable::default();
+            state.probe = ProbeSide::Exhausted;
+        }
+        Ok(())
+    }
+
+    fn next_probe_tuple(
+        &self,
+        context: &mut ExecutionContext,
+        probe: &mut ProbeSide,
+    ) -> BustubxResult<Option<Tuple>> {
+        match probe {
+            ProbeSide::Input => self.left_input.next(context),
+            ProbeSide::Spilled {
+                file,
+                next_page,
+                tuples,
+            } => {
+                while tuples.is_empty() && *next_page < file.page_count() {
+                    tuples.extend(file.read_page(*next_page)?);
+                    *next_page += 1;
+                }
+                Ok(tuples.pop_front())
+            }
+            ProbeSide::Exhausted => Ok(None),
+        }
+    }
+
+    fn probe(&self, state: &mut HashJoinState, left_tuple: Tuple) -> BustubxResult<()> {
+        let keys = self.left_keys(&left_tuple)?;
+        let mut matched = false;
+        if let Some(candidates) = state.table.buckets.get(&keys) {
+            for idx in candidates {
+                let right_tuple = &state.table.tuples[*idx];
+                let merged = Tuple::try_merge(vec![left_tuple.clone(), right_tuple.clone()])?;
+                if let Some(filter) = &self.filter {
+                    match filter.evaluate(&merged)? {
+                        ScalarValue::Boolean(Some(true)) => {}
+                        ScalarValue::Boolean(_) => continue,
+                        value => {
+                            return Err(BustubxError::Execution(format!(
+                                "hash join filter should be boolean, but got {}",
+                                value
+                            )))
+                        }
+                    }
+                }
+                matched = true;
+                state.table.matched[*idx] = true;
+                state.output.push_back(merged);
+            }
+        }
+        if !matched && matches!(self.join_type, JoinType::LeftOuter | JoinType::FullOuter) {
+            let right_tuple = Tuple::empty(self.right_input.output_schema());
+            state
+                .output
+                .push_back(Tuple::try_merge(vec![left_tuple, right_tuple])?);
+        }
+        Ok(())
+    }
+
+    /// Emits build side tuples which no probe side tuple matched.
+    fn emit_unmatched(&self, state: &mut HashJoinState) -> BustubxResult<()> {
+        if !matches!(self.join_type, JoinType::RightOuter | JoinType::FullOuter) {
+            return Ok(());
+        }
+        let left_tuple = Tuple::empty(self.left_input.output_schema());
+        let table = std::mem::take(&mut state.table);
+        for (tuple, matched) in table.tuples.into_iter().zip(table.matched) {
+            if !matched {
+                state
+                    .output
+                    .push_back(Tuple::try_merge(vec![left_tuple.clone(), tuple])?);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn partition_of(keys: &[ScalarValue]) -> usize {
+    if keys.iter().any(|key| key.is_null()) {
+        return 0;
+    }
+    let mut hasher = DefaultHasher::new();
+    keys.hash(&mut hasher);
+    hasher.finish() as usize % HASH_JOIN_PARTITIONS
+}
+
+impl VolcanoExecutor for PhysicalHashJoin {
+    fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
+        debug!("init hash join executor");
+        self.left_input.init(context)?;
+        self.right_input.init(context)?;
+        *self.state.lock().unwrap() = HashJoinState::default();
+        Ok(())
+    }
+
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        let mut state = self.state.lock().unwrap();
+        if !state.built {
+            self.build(context, &mut state)?;
+            state.built = true;
+        }
+        loop {
+            if let Some(tuple) = state.output.pop_front() {
+                return Ok(Some(tuple));
+            }
+            if matches!(state.probe, ProbeSide::Exhausted) {
+                return Ok(None);
+            }
+            if let Some(left_tuple) = self.next_probe_tuple(context, &mut state.probe)? {
+                self.probe(&mut state, left_tuple)?;
+            } else {
+                self.emit_unmatched(&mut state)?;
+                self.next_partition(&mut state)?;
+            }
+        }
+    }
+
+    fn output_schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl std::fmt::Display for PhysicalHashJoin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "HashJoin: {} On {}",
+            self.join_type,
+            self.on
+                .iter()
+                .map(|(l, r)| format!("{l} = {r}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        if let Some(filter) = self.filter.as_ref() {
+            write!(f, " Filter {filter}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::TableReference;
+    use crate::execution::physical_plan::{PhysicalHashJoin, PhysicalPlan, PhysicalSeqScan};
+    use crate::execution::{ExecutionContext, ExecutionEngine};
+    use crate::expression::{ColumnExpr, Expr};
+    use crate::planner::logical_plan::{build_join_schema, JoinType};
+    use crate::transaction::IsolationLevel;
+    use crate::Database;
+    use std::sync::Arc;
+
+    fn hash_join(db: &mut Database, join_type: JoinType, memory_budget: usize) -> Vec<String> {
+        let scan = |db: &Database, table: &str| {
+            let table_ref = TableReference::bare(table);
+            let schema = db.catalog.table_heap(&table_ref).unwrap().schema.clone();
+            PhysicalSeqScan::new(table_ref, schema, vec![])
+        };
+        let left = scan(db, "t1");
+        let right = scan(db, "t2");
+        let schema = build_join_schema(&left.table_schema, &right.table_schema, join_type).unwrap();
+        let key = |table: &str| {
+            Expr::Column(ColumnExpr {
+                relation: Some(TableReference::bare(table)),
+                name: "a".to_string(),
+            })
+        };
+        let join = PhysicalHashJoin::new(
+            join_type,
+            vec![(key("t1"), key("t2"))],
+            None,
+            Arc::new(PhysicalPlan::SeqScan(left)),
+            Arc::new(PhysicalPlan::SeqScan(right)),
+            Arc::new(schema),
+        )
+        .with_memory_budget(memory_budget);
+
+        let txn = db
+            .transaction_manager
+            .begin(IsolationLevel::ReadUncommitted);
+        let mut engine = ExecutionEngine {
+            context: ExecutionContext::new(
+                &mut db.catalog,
+                &txn,
+                &db.lock_manager,
+                &db.transaction_manager,
+                &db.spill_buffer_pool,
+            ),
+        };
+        let tuples = engine
+            .execute(Arc::new(PhysicalPlan::HashJoin(join)))
+            .unwrap();
+        db.transaction_manager.commit(txn).unwrap();
+
+        let mut rows = tuples
+            .iter()
+            .map(|tuple| format!("{:?}", tuple.data))
+            .collect::<Vec<String>>();
+        rows.sort();
+        rows
+    }
+
+    #[test]
+    pub fn test_hash_join_spill() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        db.run("create table t2 (a int, c int)").unwrap();
+        let values = (0..1000)
+            .map(|i| format!("({i}, {i})"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        db.run(&format!("insert into t1 values {values}")).unwrap();
+        let values = (500..2000)
+            .map(|i| format!("({}, {i})", i / 2))
+            .collect::<Vec<String>>()
+            .join(", ");
+        db.run(&format!("insert into t2 values {values}, (null, 0)"))
+            .unwrap();
+
+        for (join_type, rows) in [
+            (JoinType::Inner, 1500),
+            (JoinType::LeftOuter, 1750),
+            (JoinType::RightOuter, 1501),
+            (JoinType::FullOuter, 1751),
+        ] {
+            let in_memory = hash_join(&mut db, join_type, usize::MAX);
+            assert_eq!(in_memory.len(), rows);
+            assert_eq!(hash_join(&mut db, join_type, 1024), in_memory);
+        }
+    }
+}
@@ -2,17 +2,20 @@ use crate::catalog::SchemaRef;
 use crate::common::TableReference;
 use crate::execution::{ExecutionContext, VolcanoExecutor};
 use crate::storage::index::TreeIndexIterator;
+use crate::transaction::{IsolationLevel, LockMode};
 use crate::{BustubxError, BustubxResult, Tuple};
 use std::ops::{Bound, RangeBounds};
 use std::sync::Mutex;
 
 #[derive(Debug)]
 pub struct PhysicalIndexScan {
-    table_ref: TableReference,
-    index_name: String,
-    table_schema: SchemaRef,
-    start_bound: Bound<Tuple>,
-    end_bound: Bound<Tuple>,
+    pub table_ref: TableReference,
+    pub index_name: String,
+    pub start_bound: Bound<Tuple>,
+    pub end_bound: Bound<Tuple>,
+    /// Indices of the columns to read, all columns if `None`
+    pub projection: Option<Vec<usize>>,
+    pub projected_schema: SchemaRef,
     iterator: Mutex<Option<TreeIndexIterator>>,
 }
 
@@ -26,20 +29,41 @@ impl PhysicalIndexScan {
         Self {
             table_ref,
             index_name,
-            table_schema,
             start_bound: range.start_bound().cloned(),
             end_bound: range.end_bound().cloned(),
+            projection: None,
+            projected_schema: table_schema,
             iterator: Mutex::new(None),
         }
     }
+
+    pub fn with_projection(
+        mut self,
+        projection: Option<Vec<usize>>,
+        projected_schema: SchemaRef,
+    ) -> Self {
+        self.projection = projection;
+        self.projected_schema = projected_schema;
+        self
+    }
 }
 
 impl VolcanoExecutor for PhysicalIndexScan {
     fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
-        let index = context
-            .catalog
-            .index(&self.table_ref, &self.index_name)?
-            .unwrap();
+        if context.txn.isolation_level == IsolationLevel::Serializable {
+            context.lock_manager.lock_table(
+                context.txn,
+                LockMode::IntentionShared,
+                &self.table_ref,
+            )?;
+        }
+        let Some(index) = context.catalog.index(&self.table_ref, &self.index_name)? else {
+            return Err(BustubxError::Execution(format!(
+                "index {} not found on table {}",
+                self.index_name,
+                self.table_ref.table()
+            )));
+        };
         *self.iterator.lock().unwrap() = Some(TreeIndexIterator::new(
             index,
             (self.start_bound.clone(), self.end_bound.clone()),
@@ -55,15 +79,48 @@ impl VolcanoExecutor for PhysicalIndexScan {
             ));
         };
         let table_heap = context.catalog.table_heap(&self.table_ref)?;
-        if let Some(rid) = iterator.next()? {
-            Ok(Some(table_heap.tuple(rid)?))
-        } else {
-            Ok(None)
+        let snapshot = context.transaction_manager.snapshot(context.txn);
+        while let Some((key, rid)) = iterator.next_kv()? {
+            // overflow values of deleted tuples may be freed already
+            let meta = table_heap.tuple_meta(rid)?;
+            if snapshot
+                .as_ref()
+                .map_or(meta.is_deleted, |snapshot| snapshot.sees_deletion(&meta))
+            {
+                continue;
+            }
+            let (meta, tuple) = table_heap.full_tuple(rid)?;
+            let visible_tuple = match &snapshot {
+                Some(snapshot) => snapshot.visible_version(rid, &meta, tuple)?,
+                None => (!meta.is_deleted).then_some(tuple),
+            };
+            let Some(tuple) = visible_tuple else {
+                continue;
+            };
+            // entries of old versions are kept in the index, the key may have been updated
+            if tuple.project_with_schema(key.schema.clone())? != key {
+                continue;
+            }
+            if context.txn.isolation_level == IsolationLevel::Serializable {
+                context.lock_manager.lock_row(
+                    context.txn,
+                    LockMode::Shared,
+                    &self.table_ref,
+                    rid,
+                )?;
+            }
+            if self.projection.is_some() {
+                return Ok(Some(
+                    tuple.project_with_schema(self.projected_schema.clone())?,
+                ));
+            }
+            return Ok(Some(tuple));
         }
+        Ok(None)
     }
 
     fn output_schema(&self) -> SchemaRef {
-        self.table_schema.clone()
+        self.projected_schema.clone()
     }
 }
 
@@ -4,7 +4,8 @@ use std::sync::{atomic::AtomicU32, Arc};
 
 use crate::catalog::{SchemaRef, INSERT_OUTPUT_SCHEMA_REF};
 use crate::common::TableReference;
-use crate::storage::EMPTY_TUPLE_META;
+use crate::storage::TupleMeta;
+use crate::transaction::{LockMode, INVALID_TRANSACTION_ID};
 use crate::{
     common::ScalarValue,
     execution::{ExecutionContext, VolcanoExecutor},
@@ -44,12 +45,16 @@ impl VolcanoExecutor for PhysicalInsert {
         debug!("init insert executor");
         self.input.init(context)?;
         self.insert_rows.store(0, Ordering::SeqCst);
+        context
+            .lock_manager
+            .lock_table(context.txn, LockMode::IntentionExclusive, &self.table)?;
         Ok(())
     }
     fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
         loop {
             let next_tuple = self.input.next(context)?;
             if next_tuple.is_none() {
+                context.catalog.sync_index_roots(&self.table)?;
                 // only return insert_rows when input exhausted
                 return if self.insert_rows.load(Ordering::SeqCst) == 0 {
                     Ok(None)
@@ -84,20 +89,25 @@ impl VolcanoExecutor for PhysicalInsert {
             }
 
             let tuple = Tuple::new(self.table_schema.clone(), full_data);
+            context
+                .catalog
+                .check_constraints(&self.table, &tuple, None)?;
 
             let table_heap = context.catalog.table_heap(&self.table)?;
-            let rid = table_heap.insert_tuple(&EMPTY_TUPLE_META, &tuple)?;
+            let meta = TupleMeta {
+                insert_txn_id: context.txn.id,
+                delete_txn_id: INVALID_TRANSACTION_ID,
+                is_deleted: false,
+            };
+            let rid = table_heap.insert_tuple(&meta, &tuple)?;
+            context
+                .lock_manager
+                .lock_row(context.txn, LockMode::Exclusive, &self.table, rid)?;
 
             let indexes = context.catalog.table_indexes(&self.table)?;
             for index in indexes {
-                if let Ok(key_tuple) = tuple.project_with_schema(index.key_schema.clone()) {
-                    let root_page_id = index.root_page_id.load(Ordering::SeqCst);
-                    index.insert(&key_tuple, rid)?;
-                    let new_root_page_id = index.root_page_id.load(Ordering::SeqCst);
-                    if new_root_page_id != root_page_id {
-                        // TODO update system table
-                    }
-                }
+                let key_tuple = tuple.project_with_schema(index.key_schema.clone())?;
+                index.insert(&key_tuple, rid)?;
             }
 
             self.insert_rows.fetch_add(1, Ordering::SeqCst);
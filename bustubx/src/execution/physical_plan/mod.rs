@@ -1,30 +1,50 @@
 mod aggregate;
+mod alter_table;
+mod analyze;
 mod create_index;
 mod create_table;
+mod delete;
+mod distinct;
+mod drop_index;
+mod drop_table;
 mod empty;
 mod filter;
+mod hash_join;
 mod index_scan;
 mod insert;
 mod limit;
 mod nested_loop_join;
 mod project;
 mod seq_scan;
+mod set_operation;
 mod sort;
+mod sort_merge_join;
+mod union;
 mod update;
 mod values;
 
 pub use aggregate::PhysicalAggregate;
+pub use alter_table::PhysicalAlterTable;
+pub use analyze::PhysicalAnalyze;
 pub use create_index::PhysicalCreateIndex;
 pub use create_table::PhysicalCreateTable;
+pub use delete::PhysicalDelete;
+pub use distinct::PhysicalHashDistinct;
+pub use drop_index::PhysicalDropIndex;
+pub use drop_table::PhysicalDropTable;
 pub use empty::PhysicalEmpty;
 pub use filter::PhysicalFilter;
+pub use hash_join::{PhysicalHashJoin, HASH_JOIN_MEMORY_BUDGET};
 pub use index_scan::PhysicalIndexScan;
 pub use insert::PhysicalInsert;
 pub use limit::PhysicalLimit;
 pub use nested_loop_join::PhysicalNestedLoopJoin;
 pub use project::PhysicalProject;
 pub use seq_scan::PhysicalSeqScan;
+pub use set_operation::PhysicalHashSetOperation;
 pub use sort::PhysicalSort;
+pub use sort_merge_join::PhysicalSortMergeJoin;
+pub use union::PhysicalUnion;
 pub use update::PhysicalUpdate;
 pub use values::PhysicalValues;
 
@@ -48,9 +68,19 @@ pub enum PhysicalPlan {
     Insert(PhysicalInsert),
     Values(PhysicalValues),
     NestedLoopJoin(PhysicalNestedLoopJoin),
+    HashJoin(PhysicalHashJoin),
+    SortMergeJoin(PhysicalSortMergeJoin),
     Sort(PhysicalSort),
     Aggregate(PhysicalAggregate),
     Update(PhysicalUpdate),
+    Delete(PhysicalDelete),
+    Analyze(PhysicalAnalyze),
+    DropTable(PhysicalDropTable),
+    DropIndex(PhysicalDropIndex),
+    AlterTable(PhysicalAlterTable),
+    Union(PhysicalUnion),
+    HashDistinct(PhysicalHashDistinct),
+    HashSetOperation(PhysicalHashSetOperation),
 }
 
 impl PhysicalPlan {
@@ -65,14 +95,40 @@ impl PhysicalPlan {
                 right_input,
                 ..
             }) => vec![left_input, right_input],
+            PhysicalPlan::HashJoin(PhysicalHashJoin {
+                left_input,
+                right_input,
+                ..
+            }) => vec![left_input, right_input],
+            PhysicalPlan::SortMergeJoin(PhysicalSortMergeJoin {
+                left_input,
+                right_input,
+                ..
+            }) => vec![left_input, right_input],
             PhysicalPlan::Sort(PhysicalSort { input, .. }) => vec![input],
             PhysicalPlan::Aggregate(PhysicalAggregate { input, .. }) => vec![input],
+            PhysicalPlan::Union(PhysicalUnion {
+                left_input,
+                right_input,
+                ..
+            }) => vec![left_input, right_input],
+            PhysicalPlan::HashDistinct(PhysicalHashDistinct { input, .. }) => vec![input],
+            PhysicalPlan::HashSetOperation(PhysicalHashSetOperation {
+                left_input,
+                right_input,
+                ..
+            }) => vec![left_input, right_input],
             PhysicalPlan::Empty(_)
             | PhysicalPlan::CreateTable(_)
             | PhysicalPlan::CreateIndex(_)
             | PhysicalPlan::SeqScan(_)
             | PhysicalPlan::IndexScan(_)
             | PhysicalPlan::Update(_)
+            | PhysicalPlan::Delete(_)
+            | PhysicalPlan::Analyze(_)
+            | PhysicalPlan::DropTable(_)
+            | PhysicalPlan::DropIndex(_)
+            | PhysicalPlan::AlterTable(_)
             | PhysicalPlan::Values(_) => vec![],
         }
     }
@@ -92,9 +148,19 @@ impl VolcanoExecutor for PhysicalPlan {
             PhysicalPlan::IndexScan(op) => op.init(context),
             PhysicalPlan::Limit(op) => op.init(context),
             PhysicalPlan::NestedLoopJoin(op) => op.init(context),
+            PhysicalPlan::HashJoin(op) => op.init(context),
+            PhysicalPlan::SortMergeJoin(op) => op.init(context),
             PhysicalPlan::Sort(op) => op.init(context),
             PhysicalPlan::Aggregate(op) => op.init(context),
             PhysicalPlan::Update(op) => op.init(context),
+            PhysicalPlan::Delete(op) => op.init(context),
+            PhysicalPlan::Analyze(op) => op.init(context),
+            PhysicalPlan::DropTable(op) => op.init(context),
+            PhysicalPlan::DropIndex(op) => op.init(context),
+            PhysicalPlan::AlterTable(op) => op.init(context),
+            PhysicalPlan::Union(op) => op.init(context),
+            PhysicalPlan::HashDistinct(op) => op.init(context),
+            PhysicalPlan::HashSetOperation(op) => op.init(context),
         }
     }
 
@@ -111,9 +177,19 @@ impl VolcanoExecutor for PhysicalPlan {
             PhysicalPlan::IndexScan(op) => op.next(context),
             PhysicalPlan::Limit(op) => op.next(context),
             PhysicalPlan::NestedLoopJoin(op) => op.next(context),
+            PhysicalPlan::HashJoin(op) => op.next(context),
+            PhysicalPlan::SortMergeJoin(op) => op.next(context),
             PhysicalPlan::Sort(op) => op.next(context),
             PhysicalPlan::Aggregate(op) => op.next(context),
             PhysicalPlan::Update(op) => op.next(context),
+            PhysicalPlan::Delete(op) => op.next(context),
+            PhysicalPlan::Analyze(op) => op.next(context),
+            PhysicalPlan::DropTable(op) => op.next(context),
+            PhysicalPlan::DropIndex(op) => op.next(context),
+            PhysicalPlan::AlterTable(op) => op.next(context),
+            PhysicalPlan::Union(op) => op.next(context),
+            PhysicalPlan::HashDistinct(op) => op.next(context),
+            PhysicalPlan::HashSetOperation(op) => op.next(context),
         }
     }
 
@@ -130,9 +206,19 @@ impl VolcanoExecutor for PhysicalPlan {
             Self::IndexScan(op) => op.output_schema(),
             Self::Limit(op) => op.output_schema(),
             Self::NestedLoopJoin(op) => op.output_schema(),
+            Self::HashJoin(op) => op.output_schema(),
+            Self::SortMergeJoin(op) => op.output_schema(),
             Self::Sort(op) => op.output_schema(),
             Self::Aggregate(op) => op.output_schema(),
             Self::Update(op) => op.output_schema(),
+            Self::Delete(op) => op.output_schema(),
+            Self::Analyze(op) => op.output_schema(),
+            Self::DropTable(op) => op.output_schema(),
+            Self::DropIndex(op) => op.output_schema(),
+            Self::AlterTable(op) => op.output_schema(),
+            Self::Union(op) => op.output_schema(),
+            Self::HashDistinct(op) => op.output_schema(),
+            Self::HashSetOperation(op) => op.output_schema(),
         }
     }
 }
@@ -151,9 +237,19 @@ impl std::fmt::Display for PhysicalPlan {
             Self::IndexScan(op) => write!(f, "{op}"),
             Self::Limit(op) => write!(f, "{op}"),
             Self::NestedLoopJoin(op) => write!(f, "{op}"),
+            Self::HashJoin(op) => write!(f, "{op}"),
+            Self::SortMergeJoin(op) => write!(f, "{op}"),
             Self::Sort(op) => write!(f, "{op}"),
             Self::Aggregate(op) => write!(f, "{op}"),
             Self::Update(op) => write!(f, "{op}"),
+            Self::Delete(op) => write!(f, "{op}"),
+            Self::Analyze(op) => write!(f, "{op}"),
+            Self::DropTable(op) => write!(f, "{op}"),
+            Self::DropIndex(op) => write!(f, "{op}"),
+            Self::AlterTable(op) => write!(f, "{op}"),
+            Self::Union(op) => write!(f, "{op}"),
+            Self::HashDistinct(op) => write!(f, "{op}"),
+            Self::HashSetOperation(op) => write!(f, "{op}"),
         }
     }
 }
@@ -1,4 +1,5 @@
 use log::debug;
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
 use crate::catalog::SchemaRef;
@@ -8,7 +9,7 @@ use crate::{
     execution::{ExecutionContext, VolcanoExecutor},
     planner::logical_plan::JoinType,
     storage::Tuple,
-    BustubxResult,
+    BustubxError, BustubxResult,
 };
 
 use super::PhysicalPlan;
@@ -21,8 +22,21 @@ pub struct PhysicalNestedLoopJoin {
     pub right_input: Arc<PhysicalPlan>,
     pub schema: SchemaRef,
 
-    left_tuple: Mutex<Option<Tuple>>,
+    state: Mutex<NestedLoopJoinState>,
 }
+
+#[derive(Debug, Default)]
+struct NestedLoopJoinState {
+    left_tuple: Option<Tuple>,
+    left_matched: bool,
+    /// Position of the next right tuple in the right input
+    right_cursor: usize,
+    /// Positions of right tuples which matched any left tuple
+    right_matched: HashSet<usize>,
+    /// Whether right tuples without match are being emitted after all left tuples are joined
+    emitting_unmatched: bool,
+}
+
 impl PhysicalNestedLoopJoin {
     pub fn new(
         join_type: JoinType,
@@ -37,67 +51,90 @@ impl PhysicalNestedLoopJoin {
             left_input,
             right_input,
             schema,
-            left_tuple: Mutex::new(None),
+            state: Mutex::new(NestedLoopJoinState::default()),
+        }
+    }
+
+    fn matches(&self, merged_tuple: &Tuple) -> BustubxResult<bool> {
+        let Some(condition) = &self.condition else {
+            return Ok(true);
+        };
+        match condition.evaluate(merged_tuple)? {
+            ScalarValue::Boolean(v) => Ok(v.unwrap_or(false)),
+            value => Err(BustubxError::Execution(format!(
+                "nested loop join condition should be boolean, but got {}",
+                value
+            ))),
         }
     }
 }
+
 impl VolcanoExecutor for PhysicalNestedLoopJoin {
     fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
         debug!("init nested loop join executor");
         self.left_input.init(context)?;
         self.right_input.init(context)?;
-        *self.left_tuple.lock().unwrap() = None;
+        *self.state.lock().unwrap() = NestedLoopJoinState::default();
         Ok(())
     }
-    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
-        let left_tuple = self.left_tuple.lock().unwrap();
-        let mut left_next_tuple = if left_tuple.is_none() {
-            self.left_input.next(context)?
-        } else {
-            Some(left_tuple.clone().unwrap())
-        };
-        // release mutex
-        drop(left_tuple);
-
-        while left_next_tuple.is_some() {
-            let left_tuple = left_next_tuple.clone().unwrap();
 
-            let mut right_next_tuple = self.right_input.next(context)?;
-            while right_next_tuple.is_some() {
-                let right_tuple = right_next_tuple.unwrap();
-
-                // TODO judge if matches
-                if self.condition.is_none() {
-                    // save latest left_next_result before return
-                    *self.left_tuple.lock().unwrap() = Some(left_tuple.clone());
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        let mut state = self.state.lock().unwrap();
+        let keep_left = matches!(self.join_type, JoinType::LeftOuter | JoinType::FullOuter);
+        let keep_right = matches!(self.join_type, JoinType::RightOuter | JoinType::FullOuter);
 
+        if state.emitting_unmatched {
+            while let Some(right_tuple) = self.right_input.next(context)? {
+                let position = state.right_cursor;
+                state.right_cursor += 1;
+                if !state.right_matched.contains(&position) {
+                    let left_tuple = Tuple::empty(self.left_input.output_schema());
                     return Ok(Some(Tuple::try_merge(vec![left_tuple, right_tuple])?));
-                } else {
-                    let condition = self.condition.clone().unwrap();
-                    let merged_tuple =
-                        Tuple::try_merge(vec![left_tuple.clone(), right_tuple.clone()])?;
-                    let evaluate_res = condition.evaluate(&merged_tuple)?;
-                    // TODO support left/right join after null support added
-                    if let ScalarValue::Boolean(Some(v)) = evaluate_res {
-                        if v {
-                            // save latest left_next_result before return
-                            *self.left_tuple.lock().unwrap() = Some(left_tuple.clone());
+                }
+            }
+            return Ok(None);
+        }
 
-                            return Ok(Some(Tuple::try_merge(vec![left_tuple, right_tuple])?));
-                        }
-                    } else {
-                        panic!("nested loop join condition should be boolean")
+        loop {
+            if state.left_tuple.is_none() {
+                let Some(left_tuple) = self.left_input.next(context)? else {
+                    if keep_right {
+                        // scan the right input again for tuples without match
+                        self.right_input.init(context)?;
+                        state.right_cursor = 0;
+                        state.emitting_unmatched = true;
+                        drop(state);
+                        return self.next(context);
                     }
-                }
+                    return Ok(None);
+                };
+                state.left_tuple = Some(left_tuple);
+                state.left_matched = false;
+                state.right_cursor = 0;
+            }
+            let left_tuple = state.left_tuple.clone().unwrap();
 
-                right_next_tuple = self.right_input.next(context)?;
+            while let Some(right_tuple) = self.right_input.next(context)? {
+                let position = state.right_cursor;
+                state.right_cursor += 1;
+                let merged_tuple = Tuple::try_merge(vec![left_tuple.clone(), right_tuple])?;
+                if self.matches(&merged_tuple)? {
+                    state.left_matched = true;
+                    if keep_right {
+                        state.right_matched.insert(position);
+                    }
+                    return Ok(Some(merged_tuple));
+                }
             }
 
             // reset right executor
             self.right_input.init(context)?;
-            left_next_tuple = self.left_input.next(context)?;
+            state.left_tuple = None;
+            if keep_left && !state.left_matched {
+                let right_tuple = Tuple::empty(self.right_input.output_schema());
+                return Ok(Some(Tuple::try_merge(vec![left_tuple, right_tuple])?));
+            }
         }
-        Ok(None)
     }
 
     fn output_schema(&self) -> SchemaRef {
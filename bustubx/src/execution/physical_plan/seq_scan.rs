@@ -1,7 +1,9 @@
 use std::sync::Mutex;
 
 use crate::catalog::SchemaRef;
-use crate::common::TableReference;
+use crate::common::{ScalarValue, TableReference};
+use crate::expression::{Expr, ExprTrait};
+use crate::transaction::{IsolationLevel, LockMode};
 use crate::{
     execution::{ExecutionContext, VolcanoExecutor},
     storage::{TableIterator, Tuple},
@@ -12,43 +14,111 @@ use crate::{
 pub struct PhysicalSeqScan {
     pub table: TableReference,
     pub table_schema: SchemaRef,
+    /// Predicates the scanned tuples must satisfy
+    pub filters: Vec<Expr>,
+    /// Indices of the columns to read, all columns if `None`
+    pub projection: Option<Vec<usize>>,
+    pub projected_schema: SchemaRef,
 
     iterator: Mutex<Option<TableIterator>>,
 }
 
 impl PhysicalSeqScan {
-    pub fn new(table: TableReference, table_schema: SchemaRef) -> Self {
+    pub fn new(table: TableReference, table_schema: SchemaRef, filters: Vec<Expr>) -> Self {
         PhysicalSeqScan {
             table,
-            table_schema,
+            table_schema: table_schema.clone(),
+            filters,
+            projection: None,
+            projected_schema: table_schema,
             iterator: Mutex::new(None),
         }
     }
+
+    pub fn with_projection(
+        mut self,
+        projection: Option<Vec<usize>>,
+        projected_schema: SchemaRef,
+    ) -> Self {
+        self.projection = projection;
+        self.projected_schema = projected_schema;
+        self
+    }
+
+    fn matches(&self, tuple: &Tuple) -> BustubxResult<bool> {
+        for filter in self.filters.iter() {
+            match filter.evaluate(tuple)? {
+                ScalarValue::Boolean(Some(true)) => {}
+                ScalarValue::Boolean(Some(false)) => return Ok(false),
+                _ => {
+                    return Err(BustubxError::Execution(
+                        "filter predicate value should be boolean".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(true)
+    }
 }
 
 impl VolcanoExecutor for PhysicalSeqScan {
     fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
+        if context.txn.isolation_level == IsolationLevel::Serializable {
+            context
+                .lock_manager
+                .lock_table(context.txn, LockMode::IntentionShared, &self.table)?;
+        }
         let table_heap = context.catalog.table_heap(&self.table)?;
-        *self.iterator.lock().unwrap() = Some(TableIterator::new(table_heap, ..));
+        let mut iterator = TableIterator::new(table_heap, ..)
+            .with_snapshot(context.transaction_manager.snapshot(context.txn));
+        if let Some(projection) = &self.projection {
+            iterator = iterator.with_projection(projection.clone(), self.projected_schema.clone());
+        }
+        *self.iterator.lock().unwrap() = Some(iterator);
         Ok(())
     }
 
-    fn next(&self, _context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
         let Some(iterator) = &mut *self.iterator.lock().unwrap() else {
             return Err(BustubxError::Execution(
                 "table iterator not created".to_string(),
             ));
         };
-        Ok(iterator.next()?.map(|full| full.1))
+        loop {
+            let Some((rid, tuple)) = iterator.next()? else {
+                return Ok(None);
+            };
+            if !self.matches(&tuple)? {
+                continue;
+            }
+            if context.txn.isolation_level == IsolationLevel::Serializable {
+                context
+                    .lock_manager
+                    .lock_row(context.txn, LockMode::Shared, &self.table, rid)?;
+            }
+            return Ok(Some(tuple));
+        }
     }
 
     fn output_schema(&self) -> SchemaRef {
-        self.table_schema.clone()
+        self.projected_schema.clone()
     }
 }
 
 impl std::fmt::Display for PhysicalSeqScan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "SeqScan")
+        write!(f, "SeqScan")?;
+        if !self.filters.is_empty() {
+            write!(
+                f,
+                ": Filters {}",
+                self.filters
+                    .iter()
+                    .map(|e| format!("{e}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::catalog::SchemaRef;
+use crate::common::ScalarValue;
+use crate::planner::logical_plan::SetOperator;
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    storage::Tuple,
+    BustubxError, BustubxResult,
+};
+
+use super::PhysicalPlan;
+
+/// Computes `INTERSECT` and `EXCEPT`. All tuples of the right input are counted in a hash
+/// table, then tuples of the left input are output depending on the count of equal
+/// right tuples. NULLs are equal to each other here.
+///
+/// With `ALL`, a left tuple consumes one of the equal right tuples, so a tuple occurring
+/// m times on the left and n times on the right is output `min(m, n)` times by
+/// `INTERSECT ALL` and `max(m - n, 0)` times by `EXCEPT ALL`.
+#[derive(Debug)]
+pub struct PhysicalHashSetOperation {
+    pub op: SetOperator,
+    pub all: bool,
+    pub left_input: Arc<PhysicalPlan>,
+    pub right_input: Arc<PhysicalPlan>,
+    pub schema: SchemaRef,
+
+    /// Counts of the right tuples, built on the first call of `next`
+    right_counts: Mutex<Option<HashMap<Vec<ScalarValue>, usize>>>,
+}
+
+impl PhysicalHashSetOperation {
+    pub fn new(
+        op: SetOperator,
+        all: bool,
+        left_input: Arc<PhysicalPlan>,
+        right_input: Arc<PhysicalPlan>,
+        schema: SchemaRef,
+    ) -> Self {
+        PhysicalHashSetOperation {
+            op,
+            all,
+            left_input,
+            right_input,
+            schema,
+            right_counts: Mutex::new(None),
+        }
+    }
+
+    fn build(
+        &self,
+        context: &mut ExecutionContext,
+    ) -> BustubxResult<HashMap<Vec<ScalarValue>, usize>> {
+        let mut counts = HashMap::new();
+        while let Some(tuple) = self.right_input.next(context)? {
+            let tuple = conform_tuple(tuple, &self.schema)?;
+            *counts.entry(tuple.data).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Whether the left tuple is output, updates the count of the equal right tuples.
+    fn emit(&self, count: &mut usize) -> BustubxResult<bool> {
+        let emit = match (self.op, self.all) {
+            (SetOperator::Intersect, true) => {
+                let emit = *count > 0;
+                *count = count.saturating_sub(1);
+                emit
+            }
+            // later duplicates find a zero count
+            (SetOperator::Intersect, false) => std::mem::take(count) > 0,
+            (SetOperator::Except, true) => {
+                let emit = *count == 0;
+                *count = count.saturating_sub(1);
+                emit
+            }
+            // later duplicates find a non-zero count
+            (SetOperator::Except, false) => std::mem::replace(count, 1) == 0,
+            (SetOperator::Union, _) => {
+                return Err(BustubxError::Internal(
+                    "union is not a hash set operation".to_string(),
+                ))
+            }
+        };
+        Ok(emit)
+    }
+}
+
+impl VolcanoExecutor for PhysicalHashSetOperation {
+    fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
+        self.left_input.init(context)?;
+        self.right_input.init(context)?;
+        *self.right_counts.lock().unwrap() = None;
+        Ok(())
+    }
+
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        let mut right_counts = self.right_counts.lock().unwrap();
+        if right_counts.is_none() {
+            *right_counts = Some(self.build(context)?);
+        }
+        let counts = right_counts.as_mut().unwrap();
+        while let Some(tuple) = self.left_input.next(context)? {
+            let tuple = conform_tuple(tuple, &self.schema)?;
+            let count = counts.entry(tuple.data.clone()).or_insert(0);
+            if self.emit(count)? {
+                return Ok(Some(tuple));
+            }
+        }
+        Ok(None)
+    }
+
+    fn output_schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl std::fmt::Display for PhysicalHashSetOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Hash{}", self.op)?;
+        if self.all {
+            write!(f, " All")?;
+        }
+        Ok(())
+    }
+}
+
+/// Casts the values of an input tuple to the column types of the set operation.
+pub(crate) fn conform_tuple(tuple: Tuple, schema: &SchemaRef) -> BustubxResult<Tuple> {
+    let data = tuple
+        .data
+        .iter()
+        .zip(schema.columns.iter())
+        .map(|(value, column)| value.cast_to(&column.data_type))
+        .collect::<BustubxResult<Vec<ScalarValue>>>()?;
+    Ok(Tuple::new(schema.clone(), data))
+}
@@ -0,0 +1,359 @@
+use log::debug;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::catalog::SchemaRef;
+use crate::common::ScalarValue;
+use crate::expression::{Expr, ExprTrait};
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    planner::logical_plan::JoinType,
+    storage::Tuple,
+    BustubxError, BustubxResult,
+};
+
+use super::PhysicalPlan;
+
+/// Joins two inputs on equi-predicates by merging them in one pass, both inputs must be
+/// sorted ascending on their join keys with nulls first.
+///
+/// Right tuples with the same key are buffered as a group which is joined with every left
+/// tuple of that key, so duplicate keys on both sides produce all combinations.
+#[derive(Debug)]
+pub struct PhysicalSortMergeJoin {
+    pub join_type: JoinType,
+    /// Pairs of left key and right key, inputs are sorted by keys in this order
+    pub on: Vec<(Expr, Expr)>,
+    /// Non-equi part of the join condition evaluated on joined tuples
+    pub filter: Option<Expr>,
+    pub left_input: Arc<PhysicalPlan>,
+    pub right_input: Arc<PhysicalPlan>,
+    pub schema: SchemaRef,
+
+    state: Mutex<SortMergeJoinState>,
+}
+
+type KeyedTuple = (Tuple, Vec<ScalarValue>);
+
+#[derive(Debug, Default)]
+struct SortMergeJoinState {
+    started: bool,
+    left: Option<KeyedTuple>,
+    right: Option<KeyedTuple>,
+    output: VecDeque<Tuple>,
+}
+
+impl PhysicalSortMergeJoin {
+    pub fn new(
+        join_type: JoinType,
+        on: Vec<(Expr, Expr)>,
+        filter: Option<Expr>,
+        left_input: Arc<PhysicalPlan>,
+        right_input: Arc<PhysicalPlan>,
+        schema: SchemaRef,
+    ) -> Self {
+        PhysicalSortMergeJoin {
+            join_type,
+            on,
+            filter,
+            left_input,
+            right_input,
+            schema,
+            state: Mutex::new(SortMergeJoinState::default()),
+        }
+    }
+
+    fn keep_left(&self) -> bool {
+        matches!(self.join_type, JoinType::LeftOuter | JoinType::FullOuter)
+    }
+
+    fn keep_right(&self) -> bool {
+        matches!(self.join_type, JoinType::RightOuter | JoinType::FullOuter)
+    }
+
+    fn next_left(&self, context: &mut ExecutionContext) -> BustubxResult<Option<KeyedTuple>> {
+        let Some(tuple) = self.left_input.next(context)? else {
+            return Ok(None);
+        };
+        let keys = self
+            .on
+            .iter()
+            .map(|(key, _)| key.evaluate(&tuple))
+            .collect::<BustubxResult<Vec<ScalarValue>>>()?;
+        Ok(Some((tuple, keys)))
+    }
+
+    fn next_right(&self, context: &mut ExecutionContext) -> BustubxResult<Option<KeyedTuple>> {
+        let Some(tuple) = self.right_input.next(context)? else {
+            return Ok(None);
+        };
+        let keys = self
+            .on
+            .iter()
+            .map(|(_, key)| key.evaluate(&tuple))
+            .collect::<BustubxResult<Vec<ScalarValue>>>()?;
+        Ok(Some((tuple, keys)))
+    }
+
+    fn matches(&self, merged_tuple: &Tuple) -> BustubxResult<bool> {
+        let Some(filter) = &self.filter else {
+            return Ok(true);
+        };
+        match filter.evaluate(merged_tuple)? {
+            ScalarValue::Boolean(v) => Ok(v.unwrap_or(false)),
+            value => Err(BustubxError::Execution(format!(
+                "sort merge join filter should be boolean, but got {}",
+                value
+            ))),
+        }
+    }
+
+    fn pad_left(&self, state: &mut SortMergeJoinState, left_tuple: Tuple) -> BustubxResult<()> {
+        if self.keep_left() {
+            let right_tuple = Tuple::empty(self.right_input.output_schema());
+            state
+                .output
+                .push_back(Tuple::try_merge(vec![left_tuple, right_tuple])?);
+        }
+        Ok(())
+    }
+
+    fn pad_right(&self, state: &mut SortMergeJoinState, right_tuple: Tuple) -> BustubxResult<()> {
+        if self.keep_right() {
+            let left_tuple = Tuple::empty(self.left_input.output_schema());
+            state
+                .output
+                .push_back(Tuple::try_merge(vec![left_tuple, right_tuple])?);
+        }
+        Ok(())
+    }
+
+    /// Joins all left and right tuples whose keys equal the keys of `left` and `right`,
+    /// both inputs are advanced past that key.
+    fn merge_group(
+        &self,
+        context: &mut ExecutionContext,
+        state: &mut SortMergeJoinState,
+        left: KeyedTuple,
+        right: KeyedTuple,
+    ) -> BustubxResult<()> {
+        let (right_tuple, group_keys) = right;
+        let mut group = vec![right_tuple];
+        state.right = loop {
+            match self.next_right(context)? {
+                Some((tuple, keys)) if compare_keys(&keys, &group_keys)? == Ordering::Equal => {
+                    group.push(tuple);
+                }
+                next => break next,
+            }
+        };
+
+        let mut group_matched = vec![false; group.len()];
+        let mut next_left = Some(left);
+        while let Some((left_tuple, keys)) = next_left.take() {
+            if compare_keys(&keys, &group_keys)? != Ordering::Equal {
+                next_left = Some((left_tuple, keys));
+                break;
+            }
+            let mut left_matched = false;
+            for (idx, right_tuple) in group.iter().enumerate() {
+                let merged = Tuple::try_merge(vec![left_tuple.clone(), right_tuple.clone()])?;
+                if self.matches(&merged)? {
+                    left_matched = true;
+                    group_matched[idx] = true;
+                    state.output.push_back(merged);
+                }
+            }
+            if !left_matched {
+                self.pad_left(state, left_tuple)?;
+            }
+            next_left = self.next_left(context)?;
+        }
+        state.left = next_left;
+
+        for (right_tuple, matched) in group.into_iter().zip(group_matched) {
+            if !matched {
+                self.pad_right(state, right_tuple)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn has_null(keys: &[ScalarValue]) -> bool {
+    keys.iter().any(|key| key.is_null())
+}
+
+fn compare_keys(left: &[ScalarValue], right: &[ScalarValue]) -> BustubxResult<Ordering> {
+    for (l, r) in left.iter().zip(right.iter()) {
+        let ordering = l.partial_cmp(r).ok_or(BustubxError::Execution(format!(
+            "Can not compare {:?} and {:?}",
+            l, r
+        )))?;
+        if ordering != Ordering::Equal {
+            return Ok(ordering);
+        }
+    }
+    Ok(Ordering::Equal)
+}
+
+impl VolcanoExecutor for PhysicalSortMergeJoin {
+    fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
+        debug!("init sort merge join executor");
+        self.left_input.init(context)?;
+        self.right_input.init(context)?;
+        *self.state.lock().unwrap() = SortMergeJoinState::default();
+        Ok(())
+    }
+
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        let mut state = self.state.lock().unwrap();
+        if !state.started {
+            state.left = self.next_left(context)?;
+            state.right = self.next_right(context)?;
+            state.started = true;
+        }
+        loop {
+            if let Some(tuple) = state.output.pop_front() {
+                return Ok(Some(tuple));
+            }
+            match (state.left.take(), state.right.take()) {
+                (None, None) => return Ok(None),
+                (Some((left_tuple, _)), None) => {
+                    if !self.keep_left() {
+                        return Ok(None);
+                    }
+                    self.pad_left(&mut state, left_tuple)?;
+                    state.left = self.next_left(context)?;
+                }
+                (None, Some((right_tuple, _))) => {
+                    if !self.keep_right() {
+                        return Ok(None);
+                    }
+                    self.pad_right(&mut state, right_tuple)?;
+                    state.right = self.next_right(context)?;
+                }
+                (Some(left), Some(right)) => {
+                    // null keys never match
+                    let ordering = if has_null(&left.1) {
+                        Ordering::Less
+                    } else if has_null(&right.1) {
+                        Ordering::Greater
+                    } else {
+                        compare_keys(&left.1, &right.1)?
+                    };
+                    match ordering {
+                        Ordering::Less => {
+                            self.pad_left(&mut state, left.0)?;
+                            state.left = self.next_left(context)?;
+                            state.right = Some(right);
+                        }
+                        Ordering::Greater => {
+                            self.pad_right(&mut state, right.0)?;
+                            state.right = self.next_right(context)?;
+                            state.left = Some(left);
+                        }
+                        Ordering::Equal => self.merge_group(context, &mut state, left, right)?,
+                    }
+                }
+            }
+        }
+    }
+
+    fn output_schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl std::fmt::Display for PhysicalSortMergeJoin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SortMergeJoin: {} On {}",
+            self.join_type,
+            self.on
+                .iter()
+                .map(|(l, r)| format!("{l} = {r}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        if let Some(filter) = self.filter.as_ref() {
+            write!(f, " Filter {filter}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::execution::physical_plan::{PhysicalPlan, HASH_JOIN_MEMORY_BUDGET};
+    use crate::planner::PhysicalPlanner;
+    use crate::Database;
+
+    fn join_plan(db: &mut Database, sql: &str) -> String {
+        let logical_plan = db.create_logical_plan(sql).unwrap();
+        let physical_planner = PhysicalPlanner {
+            catalog: &db.catalog,
+            hash_join_memory_budget: HASH_JOIN_MEMORY_BUDGET,
+        };
+        let mut plan = &physical_planner.create_physical_plan(logical_plan);
+        while !matches!(
+            plan,
+            PhysicalPlan::SortMergeJoin(_)
+                | PhysicalPlan::HashJoin(_)
+                | PhysicalPlan::NestedLoopJoin(_)
+        ) {
+            plan = plan.inputs()[0];
+        }
+        let inputs = plan
+            .inputs()
+            .iter()
+            .map(|input| match input {
+                PhysicalPlan::Sort(_) => "Sort",
+                PhysicalPlan::IndexScan(_) => "IndexScan",
+                PhysicalPlan::SeqScan(_) => "SeqScan",
+                _ => "Other",
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "{}({})",
+            plan.to_string().split(':').next().unwrap(),
+            inputs.join(", ")
+        )
+    }
+
+    #[test]
+    pub fn test_choose_sort_merge_join() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        db.run("create index idx1 on t1 (a, b)").unwrap();
+        db.run("create table t2 (a int, b int)").unwrap();
+        db.run("create index idx2 on t2 (b, a)").unwrap();
+        db.run("create table t3 (a int, b int)").unwrap();
+
+        assert_eq!(
+            join_plan(&mut db, "select * from t1 join t3 on t1.a = t3.a"),
+            "SortMergeJoin(IndexScan, Sort)"
+        );
+        assert_eq!(
+            join_plan(
+                &mut db,
+                "select * from t2 join t1 on t2.a = t1.b and t1.a = t2.b"
+            ),
+            "SortMergeJoin(IndexScan, IndexScan)"
+        );
+        assert_eq!(
+            join_plan(&mut db, "select * from t3 join t2 on t3.b = t2.b"),
+            "SortMergeJoin(Sort, IndexScan)"
+        );
+        assert_eq!(
+            join_plan(&mut db, "select * from t1 join t3 on t1.b = t3.b"),
+            "HashJoin(SeqScan, SeqScan)"
+        );
+        assert_eq!(
+            join_plan(&mut db, "select * from t1 join t3 on t1.a > t3.a"),
+            "NestedLoopJoin(SeqScan, SeqScan)"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::catalog::SchemaRef;
+use crate::execution::physical_plan::set_operation::conform_tuple;
+use crate::{
+    execution::{ExecutionContext, VolcanoExecutor},
+    storage::Tuple,
+    BustubxResult,
+};
+
+use super::PhysicalPlan;
+
+/// Outputs all tuples of the left input followed by all tuples of the right input.
+#[derive(Debug)]
+pub struct PhysicalUnion {
+    pub left_input: Arc<PhysicalPlan>,
+    pub right_input: Arc<PhysicalPlan>,
+    pub schema: SchemaRef,
+
+    left_exhausted: AtomicBool,
+}
+
+impl PhysicalUnion {
+    pub fn new(
+        left_input: Arc<PhysicalPlan>,
+        right_input: Arc<PhysicalPlan>,
+        schema: SchemaRef,
+    ) -> Self {
+        PhysicalUnion {
+            left_input,
+            right_input,
+            schema,
+            left_exhausted: AtomicBool::new(false),
+        }
+    }
+}
+
+impl VolcanoExecutor for PhysicalUnion {
+    fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
+        self.left_input.init(context)?;
+        self.right_input.init(context)?;
+        self.left_exhausted.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn next(&self, context: &mut ExecutionContext) -> BustubxResult<Option<Tuple>> {
+        if !self.left_exhausted.load(Ordering::SeqCst) {
+            if let Some(tuple) = self.left_input.next(context)? {
+                return conform_tuple(tuple, &self.schema).map(Some);
+            }
+            self.left_exhausted.store(true, Ordering::SeqCst);
+        }
+        match self.right_input.next(context)? {
+            Some(tuple) => conform_tuple(tuple, &self.schema).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn output_schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl std::fmt::Display for PhysicalUnion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Union")
+    }
+}
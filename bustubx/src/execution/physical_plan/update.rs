@@ -1,12 +1,14 @@
-use crate::catalog::{SchemaRef, UPDATE_OUTPUT_SCHEMA_REF};
+use crate::catalog::{ConstraintKind, SchemaRef, UPDATE_OUTPUT_SCHEMA_REF};
 use crate::common::{ScalarValue, TableReference};
 use crate::execution::{ExecutionContext, VolcanoExecutor};
 use crate::expression::{Expr, ExprTrait};
-use crate::storage::{TableIterator, EMPTY_TUPLE};
+use crate::storage::index::{BPlusTreeIndex, TreeIndexIterator};
+use crate::storage::{RecordId, TableIterator};
+use crate::transaction::{DeadVersion, LockMode};
 use crate::{BustubxError, BustubxResult, Tuple};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug)]
 pub struct PhysicalUpdate {
@@ -40,8 +42,14 @@ impl PhysicalUpdate {
 impl VolcanoExecutor for PhysicalUpdate {
     fn init(&self, context: &mut ExecutionContext) -> BustubxResult<()> {
         self.update_rows.store(0, Ordering::SeqCst);
+        context
+            .lock_manager
+            .lock_table(context.txn, LockMode::IntentionExclusive, &self.table)?;
         let table_heap = context.catalog.table_heap(&self.table)?;
-        *self.table_iterator.lock().unwrap() = Some(TableIterator::new(table_heap.clone(), ..));
+        *self.table_iterator.lock().unwrap() = Some(
+            TableIterator::new(table_heap.clone(), ..)
+                .with_snapshot(context.transaction_manager.snapshot(context.txn)),
+        );
         Ok(())
     }
 
@@ -52,8 +60,6 @@ impl VolcanoExecutor for PhysicalUpdate {
                 "table iterator not created".to_string(),
             ));
         };
-        let table_heap = context.catalog.table_heap(&self.table)?;
-
         loop {
             if let Some((rid, mut tuple)) = table_iterator.next()? {
                 if let Some(selection) = &self.selection {
@@ -61,16 +67,18 @@ impl VolcanoExecutor for PhysicalUpdate {
                         continue;
                     }
                 }
-                // update tuple data
+                // update tuple data, all assignments see the old row values
+                let old_tuple = tuple.clone();
                 for (col_name, value_expr) in self.assignments.iter() {
                     let index = tuple.schema.index_of(None, &col_name)?;
                     let col_datatype = tuple.schema.columns[index].data_type;
-                    let new_value = value_expr.evaluate(&EMPTY_TUPLE)?.cast_to(&col_datatype)?;
+                    let new_value = value_expr.evaluate(&old_tuple)?.cast_to(&col_datatype)?;
                     tuple.data[index] = new_value;
                 }
-                table_heap.update_tuple(rid, tuple)?;
+                update_tuple(context, &self.table, rid, &old_tuple, tuple)?;
                 self.update_rows.fetch_add(1, Ordering::SeqCst);
             } else {
+                context.catalog.sync_index_roots(&self.table)?;
                 return if self.update_rows.load(Ordering::SeqCst) == 0 {
                     Ok(None)
                 } else {
@@ -89,6 +97,103 @@ impl VolcanoExecutor for PhysicalUpdate {
     }
 }
 
+/// Replaces a tuple after checking the constraints of the table and that no foreign key
+/// references a changed key. Index roots have to be synced by the caller.
+pub(crate) fn update_tuple(
+    context: &mut ExecutionContext,
+    table_ref: &TableReference,
+    rid: RecordId,
+    old_tuple: &Tuple,
+    tuple: Tuple,
+) -> BustubxResult<()> {
+    let table_heap = context.catalog.table_heap(table_ref)?;
+    context
+        .lock_manager
+        .lock_row(context.txn, LockMode::Exclusive, table_ref, rid)?;
+    let mut meta = table_heap.tuple_meta(rid)?;
+    context
+        .transaction_manager
+        .check_write_conflict(context.txn, &meta)?;
+
+    context
+        .catalog
+        .check_constraints(table_ref, &tuple, Some(rid))?;
+    for (child_ref, foreign_key) in context.catalog.referencing_foreign_keys(table_ref) {
+        let ConstraintKind::ForeignKey {
+            referenced_columns, ..
+        } = &foreign_key.kind
+        else {
+            continue;
+        };
+        let mut key_changed = false;
+        for col in referenced_columns.iter() {
+            let idx = tuple.schema.index_of(None, col)?;
+            key_changed |= tuple.data[idx] != old_tuple.data[idx];
+        }
+        if key_changed
+            && !context
+                .catalog
+                .referencing_tuples(&child_ref, &foreign_key, old_tuple)?
+                .is_empty()
+        {
+            return Err(BustubxError::ConstraintViolation(format!(
+                "update on table {} violates foreign key constraint {} on table {}",
+                table_ref.table(),
+                foreign_key.name,
+                child_ref.table()
+            )));
+        }
+    }
+    context
+        .transaction_manager
+        .record_update(context.txn, rid, &meta, old_tuple, &tuple)?;
+
+    // old index entries are kept for transactions reading old versions
+    let mut index_entries = vec![];
+    for index in context.catalog.table_indexes(table_ref)? {
+        let old_key = old_tuple.project_with_schema(index.key_schema.clone())?;
+        let new_key = tuple.project_with_schema(index.key_schema.clone())?;
+        if new_key == old_key {
+            continue;
+        }
+        // the key may be changed back to the one of an old version
+        if !index_contains(&index, &new_key, rid)? {
+            index.insert(&new_key, rid)?;
+        }
+        index_entries.push((Arc::downgrade(&index), old_key));
+    }
+    if !index_entries.is_empty() {
+        context.transaction_manager.record_dead_version(
+            context.txn.id,
+            DeadVersion {
+                rid,
+                table_ref: table_ref.clone(),
+                table_heap: Arc::downgrade(&table_heap),
+                index_entries,
+            },
+        );
+    }
+
+    let overflow_page_ids = table_heap.overflow_page_ids(rid)?;
+    table_heap.update_tuple(rid, tuple)?;
+    meta.insert_txn_id = context.txn.id;
+    table_heap.update_tuple_meta(meta, rid)?;
+    context
+        .transaction_manager
+        .drop_pages_on_commit(context.txn, overflow_page_ids);
+    Ok(())
+}
+
+fn index_contains(index: &Arc<BPlusTreeIndex>, key: &Tuple, rid: RecordId) -> BustubxResult<bool> {
+    let mut iterator = TreeIndexIterator::new(index.clone(), key.clone()..=key.clone());
+    while let Some(entry_rid) = iterator.next()? {
+        if entry_rid == rid {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 impl std::fmt::Display for PhysicalUpdate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Update")
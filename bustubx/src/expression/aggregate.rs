@@ -1,7 +1,9 @@
 use crate::catalog::{Column, DataType, Schema};
 use crate::common::ScalarValue;
-use crate::expression::{Expr, ExprTrait};
-use crate::function::AggregateFunctionKind;
+use crate::expression::{Expr, ExprTrait, Literal};
+use crate::function::{
+    Accumulator, AggregateFunctionKind, DistinctAccumulator, StringAggAccumulator,
+};
 use crate::{BustubxError, BustubxResult, Tuple};
 use std::fmt::Debug;
 
@@ -15,28 +17,60 @@ pub struct AggregateFunction {
     pub distinct: bool,
 }
 
+impl AggregateFunction {
+    pub fn create_accumulator(&self, input_schema: &Schema) -> BustubxResult<Box<dyn Accumulator>> {
+        let accumulator: Box<dyn Accumulator> = match self.func_kind {
+            AggregateFunctionKind::StringAgg => {
+                Box::new(StringAggAccumulator::new(self.separator()?))
+            }
+            kind => kind.create_accumulator(&self.data_type(input_schema)?),
+        };
+        if self.distinct {
+            Ok(Box::new(DistinctAccumulator::new(accumulator)))
+        } else {
+            Ok(accumulator)
+        }
+    }
+
+    /// The separator of `string_agg`, which has to be a constant.
+    fn separator(&self) -> BustubxResult<String> {
+        match self.args.get(1) {
+            Some(Expr::Literal(Literal {
+                value: ScalarValue::Varchar(separator),
+            })) => Ok(separator.clone().unwrap_or_default()),
+            _ => Err(BustubxError::NotSupport(format!(
+                "The separator of {} must be a constant string",
+                self
+            ))),
+        }
+    }
+}
+
 impl ExprTrait for AggregateFunction {
-    fn data_type(&self, _input_schema: &Schema) -> BustubxResult<DataType> {
-        match self.func_kind {
-            AggregateFunctionKind::Count => Ok(DataType::Int64),
-            AggregateFunctionKind::Avg => Ok(DataType::Float64),
+    fn data_type(&self, input_schema: &Schema) -> BustubxResult<DataType> {
+        let arg_types = self
+            .args
+            .iter()
+            .map(|arg| arg.data_type(input_schema))
+            .collect::<BustubxResult<Vec<DataType>>>()?;
+        let data_type = self.func_kind.return_type(&arg_types)?;
+        if self.func_kind == AggregateFunctionKind::StringAgg {
+            self.separator()?;
         }
+        Ok(data_type)
     }
 
     fn nullable(&self, _input_schema: &Schema) -> BustubxResult<bool> {
-        Ok(true)
+        Ok(self.func_kind != AggregateFunctionKind::Count)
     }
 
+    /// Evaluates the aggregated argument, further arguments are constants.
     fn evaluate(&self, tuple: &Tuple) -> BustubxResult<ScalarValue> {
-        match self.func_kind {
-            AggregateFunctionKind::Count | AggregateFunctionKind::Avg => {
-                let expr = self.args.first().ok_or(BustubxError::Internal(format!(
-                    "aggregate function {} should have one arg instead of {:?}",
-                    self.func_kind, self.args
-                )))?;
-                expr.evaluate(tuple)
-            }
-        }
+        let expr = self.args.first().ok_or(BustubxError::Internal(format!(
+            "aggregate function {} should have at least one arg instead of {:?}",
+            self.func_kind, self.args
+        )))?;
+        expr.evaluate(tuple)
     }
 
     fn to_column(&self, input_schema: &Schema) -> BustubxResult<Column> {
@@ -50,6 +84,12 @@ impl ExprTrait for AggregateFunction {
 
 impl std::fmt::Display for AggregateFunction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.func_kind)
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>();
+        let distinct = if self.distinct { "DISTINCT " } else { "" };
+        write!(f, "{}({}{})", self.func_kind, distinct, args.join(", "))
     }
 }
@@ -1,5 +1,7 @@
 use crate::catalog::Schema;
 use crate::catalog::{Column, DataType};
+use crate::common::decimal;
+use crate::common::temporal::{self, MICROS_PER_DAY};
 use crate::common::ScalarValue;
 use crate::error::BustubxResult;
 use crate::expression::{Expr, ExprTrait};
@@ -20,8 +22,8 @@ pub struct BinaryExpr {
 
 impl ExprTrait for BinaryExpr {
     fn data_type(&self, input_schema: &Schema) -> BustubxResult<DataType> {
-        let _left_type = self.left.data_type(input_schema)?;
-        let _right_type = self.right.data_type(input_schema)?;
+        let left_type = self.left.data_type(input_schema)?;
+        let right_type = self.right.data_type(input_schema)?;
         match self.op {
             BinaryOp::Gt
             | BinaryOp::Lt
@@ -31,10 +33,12 @@ impl ExprTrait for BinaryExpr {
             | BinaryOp::NotEq
             | BinaryOp::And
             | BinaryOp::Or => Ok(DataType::Boolean),
-            BinaryOp::Plus => todo!(),
-            BinaryOp::Minus => todo!(),
-            BinaryOp::Multiply => todo!(),
-            BinaryOp::Divide => todo!(),
+            BinaryOp::Plus
+            | BinaryOp::Minus
+            | BinaryOp::Multiply
+            | BinaryOp::Divide
+            | BinaryOp::Modulo => arithmetic_type(&left_type, self.op, &right_type),
+            BinaryOp::StringConcat => Ok(DataType::Varchar(None)),
         }
     }
 
@@ -46,6 +50,18 @@ impl ExprTrait for BinaryExpr {
         let l = self.left.evaluate(tuple)?;
         let r = self.right.evaluate(tuple)?;
         match self.op {
+            BinaryOp::Plus
+            | BinaryOp::Minus
+            | BinaryOp::Multiply
+            | BinaryOp::Divide
+            | BinaryOp::Modulo => evaluate_arithmetic_op(l, self.op, r),
+            BinaryOp::StringConcat => {
+                if l.is_null() || r.is_null() {
+                    Ok(ScalarValue::Varchar(None))
+                } else {
+                    Ok(ScalarValue::Varchar(Some(format!("{l}{r}"))))
+                }
+            }
             BinaryOp::Gt => evaluate_comparison(l, r, &[Ordering::Greater]),
             BinaryOp::Lt => evaluate_comparison(l, r, &[Ordering::Less]),
             BinaryOp::GtEq => evaluate_comparison(l, r, &[Ordering::Greater, Ordering::Equal]),
@@ -62,10 +78,11 @@ impl ExprTrait for BinaryExpr {
                     }
                 }
             }
-            _ => Err(BustubxError::NotSupport(format!(
-                "binary operator {:?} not support evaluating yet",
-                self.op
-            ))),
+            BinaryOp::Or => {
+                let l_bool = l.as_boolean()?;
+                let r_bool = r.as_boolean()?;
+                Ok((l_bool.unwrap_or(false) || r_bool.unwrap_or(false)).into())
+            }
         }
     }
 
@@ -83,8 +100,7 @@ fn evaluate_comparison(
     right: ScalarValue,
     accepted_orderings: &[Ordering],
 ) -> BustubxResult<ScalarValue> {
-    let coercion_type =
-        DataType::comparison_numeric_coercion(&left.data_type(), &right.data_type())?;
+    let coercion_type = DataType::comparison_coercion(&left.data_type(), &right.data_type())?;
     let order = left
         .cast_to(&coercion_type)?
         .partial_cmp(&right.cast_to(&coercion_type)?)
@@ -97,12 +113,292 @@ fn evaluate_comparison(
     )))
 }
 
+/// Result type of the arithmetic operator `op` applied to `l` and `r`.
+pub(crate) fn arithmetic_type(l: &DataType, op: BinaryOp, r: &DataType) -> BustubxResult<DataType> {
+    if l.is_temporal() || r.is_temporal() {
+        temporal_arithmetic_type(l, op, r)
+    } else if is_decimal_arithmetic(l, r) {
+        decimal_arithmetic_type(l, op, r)
+    } else {
+        DataType::comparison_numeric_coercion(l, r)
+    }
+}
+
+/// Applies the arithmetic operator `op` to `left` and `right`.
+pub(crate) fn evaluate_arithmetic_op(
+    left: ScalarValue,
+    op: BinaryOp,
+    right: ScalarValue,
+) -> BustubxResult<ScalarValue> {
+    if left.data_type().is_temporal() || right.data_type().is_temporal() {
+        return evaluate_temporal_arithmetic(left, op, right);
+    }
+    if is_decimal_arithmetic(&left.data_type(), &right.data_type()) {
+        return evaluate_decimal_arithmetic(left, op, right);
+    }
+    match op {
+        BinaryOp::Plus => evaluate_arithmetic(left, right, ScalarValue::checked_add),
+        BinaryOp::Minus => evaluate_arithmetic(left, right, ScalarValue::checked_sub),
+        BinaryOp::Multiply => evaluate_arithmetic(left, right, ScalarValue::checked_mul),
+        BinaryOp::Divide => evaluate_arithmetic(left, right, ScalarValue::checked_div),
+        BinaryOp::Modulo => evaluate_arithmetic(left, right, ScalarValue::checked_rem),
+        _ => Err(BustubxError::Execution(format!(
+            "{} is not an arithmetic operator",
+            op
+        ))),
+    }
+}
+
+fn evaluate_arithmetic(
+    left: ScalarValue,
+    right: ScalarValue,
+    op: fn(&ScalarValue, &ScalarValue) -> BustubxResult<ScalarValue>,
+) -> BustubxResult<ScalarValue> {
+    let coercion_type =
+        DataType::comparison_numeric_coercion(&left.data_type(), &right.data_type())?;
+    op(
+        &left.cast_to(&coercion_type)?,
+        &right.cast_to(&coercion_type)?,
+    )
+}
+
+/// Arithmetic of a decimal with a decimal or an integer is exact, with a float it's done
+/// in floating point.
+fn is_decimal_arithmetic(l: &DataType, r: &DataType) -> bool {
+    (matches!(l, DataType::Decimal(_, _)) || matches!(r, DataType::Decimal(_, _)))
+        && l.decimal_precision_scale().is_some()
+        && r.decimal_precision_scale().is_some()
+}
+
+/// Result type of decimal arithmetic, the precision is capped at 38 digits.
+fn decimal_arithmetic_type(l: &DataType, op: BinaryOp, r: &DataType) -> BustubxResult<DataType> {
+    let error = || BustubxError::Plan(format!("Can not apply {} to {} and {}", op, l, r));
+    let (p1, s1) = l.decimal_precision_scale().ok_or_else(error)?;
+    let (p2, s2) = r.decimal_precision_scale().ok_or_else(error)?;
+    let (precision, scale) = match op {
+        BinaryOp::Plus | BinaryOp::Minus => {
+            let scale = s1.max(s2);
+            ((p1 - s1).max(p2 - s2) + scale + 1, scale)
+        }
+        BinaryOp::Multiply => (p1 + p2 + 1, s1 + s2),
+        BinaryOp::Divide => {
+            let scale = (s1 + p2 + 1).max(6);
+            (p1 - s1 + s2 + scale, scale)
+        }
+        BinaryOp::Modulo => {
+            let scale = s1.max(s2);
+            ((p1 - s1).min(p2 - s2) + scale, scale)
+        }
+        _ => return Err(error()),
+    };
+    let (precision, scale) = decimal::adjust_precision_scale(precision, scale);
+    Ok(DataType::Decimal(precision, scale))
+}
+
+fn evaluate_decimal_arithmetic(
+    left: ScalarValue,
+    op: BinaryOp,
+    right: ScalarValue,
+) -> BustubxResult<ScalarValue> {
+    let data_type = decimal_arithmetic_type(&left.data_type(), op, &right.data_type())?;
+    let DataType::Decimal(precision, scale) = data_type else {
+        return Err(BustubxError::Internal(format!(
+            "{} is not a decimal type",
+            data_type
+        )));
+    };
+    let overflow =
+        || BustubxError::Execution(format!("Arithmetic overflow: {} {} {}", left, op, right));
+    let to_decimal = |value: &ScalarValue| -> BustubxResult<Option<(i128, u8)>> {
+        let (precision, scale) = value
+            .data_type()
+            .decimal_precision_scale()
+            .unwrap_or((38, 0));
+        match value.cast_to(&DataType::Decimal(precision, scale))? {
+            ScalarValue::Decimal(v, _, scale) => Ok(v.map(|v| (v, scale))),
+            _ => Err(overflow()),
+        }
+    };
+    let (Some((l, ls)), Some((r, rs))) = (to_decimal(&left)?, to_decimal(&right)?) else {
+        return Ok(ScalarValue::Decimal(None, precision, scale));
+    };
+    if matches!(op, BinaryOp::Divide | BinaryOp::Modulo) && r == 0 {
+        return Err(BustubxError::Execution("Division by zero".to_string()));
+    }
+    let rescale = |v: i128, from: u8| decimal::rescale(v, from, scale);
+    let value = match op {
+        BinaryOp::Plus => rescale(l, ls)
+            .zip(rescale(r, rs))
+            .and_then(|(l, r)| l.checked_add(r)),
+        BinaryOp::Minus => rescale(l, ls)
+            .zip(rescale(r, rs))
+            .and_then(|(l, r)| l.checked_sub(r)),
+        BinaryOp::Modulo => rescale(l, ls)
+            .zip(rescale(r, rs))
+            .and_then(|(l, r)| l.checked_rem(r)),
+        BinaryOp::Multiply => l.checked_mul(r).and_then(|v| rescale(v, ls + rs)),
+        // l / r has scale ls - rs, shift the dividend or the divisor to get the result scale
+        BinaryOp::Divide => {
+            let shift = scale as i32 + rs as i32 - ls as i32;
+            if shift >= 0 {
+                decimal::pow10(shift as u8)
+                    .and_then(|p| l.checked_mul(p))
+                    .map(|l| decimal::div_round(l, r))
+            } else {
+                decimal::pow10(shift.unsigned_abs() as u8)
+                    .and_then(|p| r.checked_mul(p))
+                    .map(|r| decimal::div_round(l, r))
+            }
+        }
+        _ => None,
+    };
+    match value {
+        Some(v) if decimal::is_within_precision(v, precision) => {
+            Ok(ScalarValue::Decimal(Some(v), precision, scale))
+        }
+        _ => Err(overflow()),
+    }
+}
+
+/// Result type of arithmetic with a date, time, timestamp or interval operand.
+fn temporal_arithmetic_type(l: &DataType, op: BinaryOp, r: &DataType) -> BustubxResult<DataType> {
+    use DataType::*;
+    let (l, r) = commute(l, op, r, temporal_rank);
+    match (l, op, r) {
+        (Date, BinaryOp::Plus | BinaryOp::Minus, t) if t.is_integer() => Ok(Date),
+        (Date, BinaryOp::Minus, Date) => Ok(Int32),
+        (Date, BinaryOp::Plus, Time) => Ok(Timestamp),
+        (Date | Timestamp, BinaryOp::Plus | BinaryOp::Minus, Interval) => Ok(Timestamp),
+        (Timestamp, BinaryOp::Minus, Timestamp) => Ok(Interval),
+        (Time, BinaryOp::Plus | BinaryOp::Minus, Interval) => Ok(Time),
+        (Time, BinaryOp::Minus, Time) => Ok(Interval),
+        (Interval, BinaryOp::Plus | BinaryOp::Minus, Interval) => Ok(Interval),
+        (Interval, BinaryOp::Multiply | BinaryOp::Divide, t) if t.is_numeric() => Ok(Interval),
+        _ => Err(BustubxError::Plan(format!(
+            "Can not apply {} to {} and {}",
+            op, l, r
+        ))),
+    }
+}
+
+/// Orders the operands of `+` and `*` by `rank`, so that e.g. `interval + date` is handled
+/// as `date + interval`.
+fn commute<'a, T>(l: &'a T, op: BinaryOp, r: &'a T, rank: fn(&T) -> u8) -> (&'a T, &'a T) {
+    if matches!(op, BinaryOp::Plus | BinaryOp::Multiply) && rank(l) < rank(r) {
+        (r, l)
+    } else {
+        (l, r)
+    }
+}
+
+fn temporal_rank(data_type: &DataType) -> u8 {
+    match data_type {
+        DataType::Timestamp => 4,
+        DataType::Date => 3,
+        DataType::Time => 2,
+        DataType::Interval => 1,
+        _ => 0,
+    }
+}
+
+fn evaluate_temporal_arithmetic(
+    left: ScalarValue,
+    op: BinaryOp,
+    right: ScalarValue,
+) -> BustubxResult<ScalarValue> {
+    use ScalarValue::*;
+    let data_type = temporal_arithmetic_type(&left.data_type(), op, &right.data_type())?;
+    if left.is_null() || right.is_null() {
+        return Ok(ScalarValue::new_empty(data_type));
+    }
+    let overflow =
+        || BustubxError::Execution(format!("Arithmetic overflow: {} {} {}", left, op, right));
+    let (l, r) = commute(&left, op, &right, |v| temporal_rank(&v.data_type()));
+    let negate = |interval: &temporal::Interval| match op {
+        BinaryOp::Minus => interval.checked_neg(),
+        _ => Some(*interval),
+    };
+    let value = match (l, op, r) {
+        (Date(Some(date)), BinaryOp::Minus, Date(Some(other))) => {
+            Int32(Some(date.checked_sub(*other).ok_or_else(overflow)?))
+        }
+        (Date(Some(date)), BinaryOp::Plus | BinaryOp::Minus, days)
+            if days.data_type().is_integer() =>
+        {
+            let Int32(Some(days)) = days.cast_to(&DataType::Int32)? else {
+                return Err(overflow());
+            };
+            let date = if op == BinaryOp::Minus {
+                date.checked_sub(days)
+            } else {
+                date.checked_add(days)
+            };
+            Date(Some(date.ok_or_else(overflow)?))
+        }
+        (Date(Some(date)), _, Time(Some(time))) => Timestamp(Some(
+            (*date as i64)
+                .checked_mul(MICROS_PER_DAY)
+                .and_then(|micros| micros.checked_add(*time))
+                .ok_or_else(overflow)?,
+        )),
+        (Date(_) | Timestamp(_), _, Interval(Some(interval))) => {
+            let Timestamp(Some(timestamp)) = l.cast_to(&DataType::Timestamp)? else {
+                return Err(overflow());
+            };
+            Timestamp(Some(
+                negate(interval)
+                    .and_then(|interval| temporal::timestamp_add_interval(timestamp, &interval))
+                    .ok_or_else(overflow)?,
+            ))
+        }
+        (Timestamp(Some(timestamp)), _, Timestamp(Some(other))) => Interval(Some(
+            temporal::timestamp_sub_timestamp(*timestamp, *other).ok_or_else(overflow)?,
+        )),
+        (Time(Some(time)), _, Interval(Some(interval))) => {
+            let micros = negate(interval).ok_or_else(overflow)?.micros % MICROS_PER_DAY;
+            Time(Some((time + micros).rem_euclid(MICROS_PER_DAY)))
+        }
+        (Time(Some(time)), _, Time(Some(other))) => {
+            Interval(Some(temporal::Interval::new(0, 0, time - other)))
+        }
+        (Interval(Some(interval)), BinaryOp::Plus, Interval(Some(other))) => {
+            Interval(Some(interval.checked_add(other).ok_or_else(overflow)?))
+        }
+        (Interval(Some(interval)), BinaryOp::Minus, Interval(Some(other))) => {
+            Interval(Some(interval.checked_sub(other).ok_or_else(overflow)?))
+        }
+        (Interval(Some(interval)), _, factor) => {
+            let Float64(Some(factor)) = factor.cast_to(&DataType::Float64)? else {
+                return Err(overflow());
+            };
+            let interval = if op == BinaryOp::Divide {
+                if factor == 0.0 {
+                    return Err(BustubxError::Execution("Division by zero".to_string()));
+                }
+                interval.checked_div_f64(factor)
+            } else {
+                interval.checked_mul_f64(factor)
+            };
+            Interval(Some(interval.ok_or_else(overflow)?))
+        }
+        _ => {
+            return Err(BustubxError::Execution(format!(
+                "Can not apply {} to {:?} and {:?}",
+                op, left, right
+            )))
+        }
+    };
+    Ok(value)
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum BinaryOp {
     Plus,
     Minus,
     Multiply,
     Divide,
+    Modulo,
+    StringConcat,
     Gt,
     Lt,
     GtEq,
@@ -122,6 +418,8 @@ impl TryFrom<&sqlparser::ast::BinaryOperator> for BinaryOp {
             sqlparser::ast::BinaryOperator::Minus => Ok(BinaryOp::Minus),
             sqlparser::ast::BinaryOperator::Multiply => Ok(BinaryOp::Multiply),
             sqlparser::ast::BinaryOperator::Divide => Ok(BinaryOp::Divide),
+            sqlparser::ast::BinaryOperator::Modulo => Ok(BinaryOp::Modulo),
+            sqlparser::ast::BinaryOperator::StringConcat => Ok(BinaryOp::StringConcat),
             sqlparser::ast::BinaryOperator::Gt => Ok(BinaryOp::Gt),
             sqlparser::ast::BinaryOperator::Lt => Ok(BinaryOp::Lt),
             sqlparser::ast::BinaryOperator::GtEq => Ok(BinaryOp::GtEq),
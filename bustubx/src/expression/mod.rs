@@ -4,14 +4,17 @@ mod binary;
 mod cast;
 mod column;
 mod literal;
+mod scalar;
 mod util;
 
 pub use aggregate::AggregateFunction;
 pub use alias::Alias;
-pub use binary::BinaryExpr;
+pub(crate) use binary::{arithmetic_type, evaluate_arithmetic_op};
+pub use binary::{BinaryExpr, BinaryOp};
 pub use cast::Cast;
 pub use column::ColumnExpr;
 pub use literal::Literal;
+pub use scalar::ScalarFunction;
 pub use util::*;
 
 use crate::catalog::Schema;
@@ -49,6 +52,8 @@ pub enum Expr {
     Cast(Cast),
     /// Represents the call of an aggregate built-in function with arguments.
     AggregateFunction(AggregateFunction),
+    /// Represents the call of a built-in scalar function with arguments.
+    ScalarFunction(ScalarFunction),
 }
 
 impl ExprTrait for Expr {
@@ -60,6 +65,7 @@ impl ExprTrait for Expr {
             Expr::Binary(binary) => binary.data_type(input_schema),
             Expr::Cast(cast) => cast.data_type(input_schema),
             Expr::AggregateFunction(aggr) => aggr.data_type(input_schema),
+            Expr::ScalarFunction(func) => func.data_type(input_schema),
         }
     }
 
@@ -71,6 +77,7 @@ impl ExprTrait for Expr {
             Expr::Binary(binary) => binary.nullable(input_schema),
             Expr::Cast(cast) => cast.nullable(input_schema),
             Expr::AggregateFunction(aggr) => aggr.nullable(input_schema),
+            Expr::ScalarFunction(func) => func.nullable(input_schema),
         }
     }
 
@@ -82,6 +89,7 @@ impl ExprTrait for Expr {
             Expr::Binary(binary) => binary.evaluate(tuple),
             Expr::Cast(cast) => cast.evaluate(tuple),
             Expr::AggregateFunction(aggr) => aggr.evaluate(tuple),
+            Expr::ScalarFunction(func) => func.evaluate(tuple),
         }
     }
 
@@ -93,6 +101,7 @@ impl ExprTrait for Expr {
             Expr::Binary(binary) => binary.to_column(input_schema),
             Expr::Cast(cast) => cast.to_column(input_schema),
             Expr::AggregateFunction(aggr) => aggr.to_column(input_schema),
+            Expr::ScalarFunction(func) => func.to_column(input_schema),
         }
     }
 }
@@ -106,6 +115,7 @@ impl std::fmt::Display for Expr {
             Expr::Binary(e) => write!(f, "{e}"),
             Expr::Cast(e) => write!(f, "{e}"),
             Expr::AggregateFunction(e) => write!(f, "{e}"),
+            Expr::ScalarFunction(e) => write!(f, "{e}"),
         }
     }
 }
@@ -0,0 +1,71 @@
+use crate::catalog::{Column, DataType, Schema};
+use crate::common::ScalarValue;
+use crate::expression::{Expr, ExprTrait};
+use crate::function::ScalarFunctionKind;
+use crate::{BustubxResult, Tuple};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScalarFunction {
+    /// the function kind
+    pub func_kind: ScalarFunctionKind,
+    /// List of expressions to feed to the functions as arguments
+    pub args: Vec<Expr>,
+}
+
+impl ExprTrait for ScalarFunction {
+    fn data_type(&self, input_schema: &Schema) -> BustubxResult<DataType> {
+        let arg_types = self
+            .args
+            .iter()
+            .map(|arg| arg.data_type(input_schema))
+            .collect::<BustubxResult<Vec<DataType>>>()?;
+        let literal_args = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                Expr::Literal(literal) => Some(&literal.value),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        self.func_kind
+            .return_type_of_args(&arg_types, &literal_args)
+    }
+
+    fn nullable(&self, input_schema: &Schema) -> BustubxResult<bool> {
+        let arg_nullables = self
+            .args
+            .iter()
+            .map(|arg| arg.nullable(input_schema))
+            .collect::<BustubxResult<Vec<bool>>>()?;
+        Ok(self.func_kind.nullable(&arg_nullables))
+    }
+
+    fn evaluate(&self, tuple: &Tuple) -> BustubxResult<ScalarValue> {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.evaluate(tuple))
+            .collect::<BustubxResult<Vec<ScalarValue>>>()?;
+        self.func_kind
+            .evaluate(&args, &self.data_type(&tuple.schema)?)
+    }
+
+    fn to_column(&self, input_schema: &Schema) -> BustubxResult<Column> {
+        Ok(Column::new(
+            format!("{}", self),
+            self.data_type(input_schema)?,
+            self.nullable(input_schema)?,
+        ))
+    }
+}
+
+impl std::fmt::Display for ScalarFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}({})", self.func_kind, args.join(", "))
+    }
+}
@@ -1,6 +1,7 @@
-use crate::catalog::SchemaRef;
-use crate::expression::{Alias, Cast, ColumnExpr, Expr};
+use crate::catalog::{Schema, SchemaRef};
+use crate::expression::{Alias, BinaryExpr, BinaryOp, Cast, ColumnExpr, Expr};
 use crate::BustubxResult;
+use std::collections::HashSet;
 
 /// Convert an expression into Column expression
 pub fn columnize_expr(e: &Expr, input_schema: &SchemaRef) -> BustubxResult<Expr> {
@@ -16,7 +17,32 @@ pub fn columnize_expr(e: &Expr, input_schema: &SchemaRef) -> BustubxResult<Expr>
         })),
         _ => {
             let name = e.to_string();
-            let idx = input_schema.index_of(None, name.as_str())?;
+            let idx = match input_schema.index_of(None, name.as_str()) {
+                Ok(idx) => idx,
+                // an expression over columns of the input, e.g. `sum(a) + 1`
+                Err(err) => {
+                    return match e {
+                        Expr::Literal(_) => Ok(e.clone()),
+                        Expr::Binary(BinaryExpr { left, op, right }) => {
+                            Ok(Expr::Binary(BinaryExpr {
+                                left: Box::new(columnize_expr(left, input_schema)?),
+                                op: *op,
+                                right: Box::new(columnize_expr(right, input_schema)?),
+                            }))
+                        }
+                        Expr::ScalarFunction(func) => {
+                            let mut func = func.clone();
+                            func.args = func
+                                .args
+                                .iter()
+                                .map(|arg| columnize_expr(arg, input_schema))
+                                .collect::<BustubxResult<Vec<_>>>()?;
+                            Ok(Expr::ScalarFunction(func))
+                        }
+                        _ => Err(err),
+                    }
+                }
+            };
             let col = input_schema.column_with_index(idx)?;
             Ok(Expr::Column(ColumnExpr {
                 relation: col.relation.clone(),
@@ -25,3 +51,105 @@ pub fn columnize_expr(e: &Expr, input_schema: &SchemaRef) -> BustubxResult<Expr>
         }
     }
 }
+
+/// Collects the aggregate functions the expression contains, each one once.
+pub fn collect_aggregates(expr: &Expr, aggregates: &mut Vec<Expr>) {
+    match expr {
+        Expr::AggregateFunction(_) => {
+            if !aggregates.contains(expr) {
+                aggregates.push(expr.clone());
+            }
+        }
+        Expr::Column(_) | Expr::Literal(_) => {}
+        Expr::Alias(alias) => collect_aggregates(&alias.expr, aggregates),
+        Expr::Cast(cast) => collect_aggregates(&cast.expr, aggregates),
+        Expr::Binary(binary) => {
+            collect_aggregates(&binary.left, aggregates);
+            collect_aggregates(&binary.right, aggregates);
+        }
+        Expr::ScalarFunction(func) => {
+            for arg in func.args.iter() {
+                collect_aggregates(arg, aggregates);
+            }
+        }
+    }
+}
+
+/// Splits a predicate into its conjuncts, e.g. `a AND (b AND c)` into `[a, b, c]`
+pub fn split_conjunction(expr: &Expr) -> Vec<Expr> {
+    match expr {
+        Expr::Binary(BinaryExpr {
+            left,
+            op: BinaryOp::And,
+            right,
+        }) => {
+            let mut exprs = split_conjunction(left);
+            exprs.extend(split_conjunction(right));
+            exprs
+        }
+        _ => vec![expr.clone()],
+    }
+}
+
+/// Whether the expression refers to columns and only to columns of the schema.
+pub fn refers_only_to(expr: &Expr, schema: &Schema) -> bool {
+    fn visit(expr: &Expr, schema: &Schema, has_column: &mut bool) -> bool {
+        match expr {
+            Expr::Column(column) => {
+                *has_column = true;
+                schema
+                    .index_of(column.relation.as_ref(), &column.name)
+                    .is_ok()
+            }
+            Expr::Literal(_) => true,
+            Expr::Alias(alias) => visit(&alias.expr, schema, has_column),
+            Expr::Cast(cast) => visit(&cast.expr, schema, has_column),
+            Expr::Binary(binary) => {
+                visit(&binary.left, schema, has_column) && visit(&binary.right, schema, has_column)
+            }
+            Expr::AggregateFunction(_) => false,
+            Expr::ScalarFunction(func) => {
+                func.args.iter().all(|arg| visit(arg, schema, has_column))
+            }
+        }
+    }
+    let mut has_column = false;
+    visit(expr, schema, &mut has_column) && has_column
+}
+
+/// Combines predicates with AND, returns `None` if there is no predicate.
+pub fn conjunction(predicates: impl IntoIterator<Item = Expr>) -> Option<Expr> {
+    predicates.into_iter().reduce(|l, r| {
+        Expr::Binary(BinaryExpr {
+            left: Box::new(l),
+            op: BinaryOp::And,
+            right: Box::new(r),
+        })
+    })
+}
+
+/// Collects the columns the expression refers to.
+pub fn collect_columns(expr: &Expr, columns: &mut HashSet<ColumnExpr>) {
+    match expr {
+        Expr::Column(column) => {
+            columns.insert(column.clone());
+        }
+        Expr::Literal(_) => {}
+        Expr::Alias(alias) => collect_columns(&alias.expr, columns),
+        Expr::Cast(cast) => collect_columns(&cast.expr, columns),
+        Expr::Binary(binary) => {
+            collect_columns(&binary.left, columns);
+            collect_columns(&binary.right, columns);
+        }
+        Expr::AggregateFunction(aggr) => {
+            for arg in aggr.args.iter() {
+                collect_columns(arg, columns);
+            }
+        }
+        Expr::ScalarFunction(func) => {
+            for arg in func.args.iter() {
+                collect_columns(arg, columns);
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::common::ScalarValue;
+use crate::function::Accumulator;
+use crate::BustubxResult;
+
+/// Collects all values, including NULLs, into the text form of an array like
+/// postgres, e.g. `{1,NULL,"a b"}`.
+#[derive(Debug, Clone)]
+pub struct ArrayAggAccumulator {
+    elements: Vec<String>,
+}
+
+impl ArrayAggAccumulator {
+    pub fn new() -> Self {
+        Self { elements: vec![] }
+    }
+}
+
+impl Accumulator for ArrayAggAccumulator {
+    fn update_value(&mut self, value: &ScalarValue) -> BustubxResult<()> {
+        let element = if value.is_null() {
+            "NULL".to_string()
+        } else {
+            quote_element(&value.to_string())
+        };
+        self.elements.push(element);
+        Ok(())
+    }
+
+    fn evaluate(&self) -> BustubxResult<ScalarValue> {
+        if self.elements.is_empty() {
+            return Ok(ScalarValue::Varchar(None));
+        }
+        Ok(ScalarValue::Varchar(Some(format!(
+            "{{{}}}",
+            self.elements.join(",")
+        ))))
+    }
+}
+
+/// Double quotes an element which would otherwise be ambiguous in the array text.
+fn quote_element(element: &str) -> String {
+    let needs_quotes = element.is_empty()
+        || element.eq_ignore_ascii_case("null")
+        || element
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '{' | '}' | ',' | '"' | '\\'));
+    if !needs_quotes {
+        return element.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in element.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
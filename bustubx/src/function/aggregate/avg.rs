@@ -1,19 +1,28 @@
 use crate::catalog::DataType;
+use crate::common::decimal::{self, MAX_DECIMAL_PRECISION};
 use crate::common::ScalarValue;
+use crate::expression::{evaluate_arithmetic_op, BinaryOp};
 use crate::function::Accumulator;
-use crate::{BustubxError, BustubxResult};
+use crate::BustubxResult;
 
 #[derive(Debug, Clone)]
 pub struct AvgAccumulator {
-    sum: Option<f64>,
+    sum: ScalarValue,
     count: u64,
+    data_type: DataType,
 }
 
 impl AvgAccumulator {
-    pub fn new() -> Self {
+    /// Decimals are summed exactly at the scale of the average, other numbers as floats.
+    pub fn new(data_type: DataType) -> Self {
+        let sum_type = match data_type {
+            DataType::Decimal(_, scale) => DataType::Decimal(MAX_DECIMAL_PRECISION, scale),
+            _ => DataType::Float64,
+        };
         Self {
-            sum: None,
+            sum: ScalarValue::new_empty(sum_type),
             count: 0,
+            data_type,
         }
     }
 }
@@ -21,28 +30,32 @@ impl AvgAccumulator {
 impl Accumulator for AvgAccumulator {
     fn update_value(&mut self, value: &ScalarValue) -> BustubxResult<()> {
         if !value.is_null() {
-            let value = match value.cast_to(&DataType::Float64)? {
-                ScalarValue::Float64(Some(v)) => v,
-                _ => {
-                    return Err(BustubxError::Internal(format!(
-                        "Failed to cast value {} to float64",
-                        value
-                    )))
-                }
+            let sum_type = self.sum.data_type();
+            let value = value.cast_to(&sum_type)?;
+            self.sum = if self.sum.is_null() {
+                value
+            } else {
+                evaluate_arithmetic_op(self.sum.clone(), BinaryOp::Plus, value)?
+                    .cast_to(&sum_type)?
             };
-
-            match self.sum {
-                Some(sum) => self.sum = Some(sum + value),
-                None => self.sum = Some(value),
-            }
             self.count += 1;
         }
         Ok(())
     }
 
     fn evaluate(&self) -> BustubxResult<ScalarValue> {
-        Ok(ScalarValue::Float64(
-            self.sum.map(|f| f / self.count as f64),
-        ))
+        match (&self.sum, self.data_type) {
+            (ScalarValue::Decimal(Some(sum), _, scale), DataType::Decimal(precision, _)) => {
+                Ok(ScalarValue::Decimal(
+                    Some(decimal::div_round(*sum, self.count as i128)),
+                    precision,
+                    *scale,
+                ))
+            }
+            (ScalarValue::Float64(Some(sum)), _) => {
+                Ok(ScalarValue::Float64(Some(sum / self.count as f64)))
+            }
+            _ => Ok(ScalarValue::new_empty(self.data_type)),
+        }
     }
 }
@@ -0,0 +1,42 @@
+use crate::common::ScalarValue;
+use crate::function::Accumulator;
+use crate::{BustubxError, BustubxResult};
+
+#[derive(Debug, Clone)]
+pub struct BoolAndOrAccumulator {
+    value: Option<bool>,
+    is_and: bool,
+}
+
+impl BoolAndOrAccumulator {
+    pub fn new(is_and: bool) -> Self {
+        Self {
+            value: None,
+            is_and,
+        }
+    }
+}
+
+impl Accumulator for BoolAndOrAccumulator {
+    fn update_value(&mut self, value: &ScalarValue) -> BustubxResult<()> {
+        match value {
+            ScalarValue::Boolean(Some(v)) => {
+                self.value = Some(match self.value {
+                    Some(current) if self.is_and => current && *v,
+                    Some(current) => current || *v,
+                    None => *v,
+                });
+                Ok(())
+            }
+            ScalarValue::Boolean(None) => Ok(()),
+            _ => Err(BustubxError::Execution(format!(
+                "{} is not a boolean",
+                value
+            ))),
+        }
+    }
+
+    fn evaluate(&self) -> BustubxResult<ScalarValue> {
+        Ok(ScalarValue::Boolean(self.value))
+    }
+}
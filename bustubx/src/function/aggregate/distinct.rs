@@ -0,0 +1,34 @@
+use crate::common::ScalarValue;
+use crate::function::Accumulator;
+use crate::BustubxResult;
+use std::collections::HashSet;
+
+/// Feeds only the first occurrence of each value to the inner accumulator, for
+/// `DISTINCT` aggregates.
+#[derive(Debug)]
+pub struct DistinctAccumulator {
+    seen: HashSet<ScalarValue>,
+    inner: Box<dyn Accumulator>,
+}
+
+impl DistinctAccumulator {
+    pub fn new(inner: Box<dyn Accumulator>) -> Self {
+        Self {
+            seen: HashSet::new(),
+            inner,
+        }
+    }
+}
+
+impl Accumulator for DistinctAccumulator {
+    fn update_value(&mut self, value: &ScalarValue) -> BustubxResult<()> {
+        if self.seen.insert(value.clone()) {
+            self.inner.update_value(value)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> BustubxResult<ScalarValue> {
+        self.inner.evaluate()
+    }
+}
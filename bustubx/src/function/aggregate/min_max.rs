@@ -0,0 +1,44 @@
+use crate::catalog::DataType;
+use crate::common::ScalarValue;
+use crate::function::Accumulator;
+use crate::{BustubxError, BustubxResult};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone)]
+pub struct MinMaxAccumulator {
+    value: ScalarValue,
+    /// `Ordering::Greater` for max and `Ordering::Less` for min
+    ordering: Ordering,
+}
+
+impl MinMaxAccumulator {
+    pub fn new(data_type: DataType, ordering: Ordering) -> Self {
+        Self {
+            value: ScalarValue::new_empty(data_type),
+            ordering,
+        }
+    }
+}
+
+impl Accumulator for MinMaxAccumulator {
+    fn update_value(&mut self, value: &ScalarValue) -> BustubxResult<()> {
+        if value.is_null() {
+            return Ok(());
+        }
+        if self.value.is_null() {
+            self.value = value.clone();
+            return Ok(());
+        }
+        let ordering = value.partial_cmp(&self.value).ok_or_else(|| {
+            BustubxError::Execution(format!("Cannot compare {} with {}", value, self.value))
+        })?;
+        if ordering == self.ordering {
+            self.value = value.clone();
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> BustubxResult<ScalarValue> {
+        Ok(self.value.clone())
+    }
+}
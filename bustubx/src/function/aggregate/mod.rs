@@ -1,36 +1,128 @@
+mod array_agg;
 mod avg;
+mod bool_and_or;
 mod count;
+mod distinct;
+mod min_max;
+mod string_agg;
+mod sum;
+mod variance;
 
+pub use array_agg::ArrayAggAccumulator;
 pub use avg::AvgAccumulator;
+pub use bool_and_or::BoolAndOrAccumulator;
 pub use count::CountAccumulator;
+pub use distinct::DistinctAccumulator;
+pub use min_max::MinMaxAccumulator;
+use std::cmp::Ordering;
 use std::fmt::Debug;
+pub use string_agg::StringAggAccumulator;
+pub use sum::SumAccumulator;
+pub use variance::VarianceAccumulator;
 
+use crate::catalog::DataType;
+use crate::common::decimal::MAX_DECIMAL_PRECISION;
 use crate::common::ScalarValue;
-use crate::BustubxResult;
-use strum::{EnumIter, IntoEnumIterator};
+use crate::{BustubxError, BustubxResult};
 
-#[derive(Clone, PartialEq, Eq, Debug, EnumIter)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
 pub enum AggregateFunctionKind {
     Count,
     Avg,
+    Sum,
+    Min,
+    Max,
+    /// Sample standard deviation.
+    #[strum(to_string = "stddev", serialize = "stddev_samp")]
+    Stddev,
+    StddevPop,
+    /// Sample variance.
+    #[strum(to_string = "variance", serialize = "var_samp")]
+    Variance,
+    VarPop,
+    StringAgg,
+    #[strum(to_string = "bool_and", serialize = "every")]
+    BoolAnd,
+    BoolOr,
+    /// Collects the values into the text form of an array, e.g. `{a,b,NULL}`.
+    ArrayAgg,
 }
 
 impl AggregateFunctionKind {
-    pub fn create_accumulator(&self) -> Box<dyn Accumulator> {
+    /// Creates the accumulator of every kind except `string_agg`, which needs its
+    /// separator.
+    pub fn create_accumulator(&self, return_type: &DataType) -> Box<dyn Accumulator> {
         match self {
             AggregateFunctionKind::Count => Box::new(CountAccumulator::new()),
-            AggregateFunctionKind::Avg => Box::new(AvgAccumulator::new()),
+            AggregateFunctionKind::Avg => Box::new(AvgAccumulator::new(*return_type)),
+            AggregateFunctionKind::Sum => Box::new(SumAccumulator::new(*return_type)),
+            AggregateFunctionKind::Min => {
+                Box::new(MinMaxAccumulator::new(*return_type, Ordering::Less))
+            }
+            AggregateFunctionKind::Max => {
+                Box::new(MinMaxAccumulator::new(*return_type, Ordering::Greater))
+            }
+            AggregateFunctionKind::Stddev => Box::new(VarianceAccumulator::new(false, true)),
+            AggregateFunctionKind::StddevPop => Box::new(VarianceAccumulator::new(true, true)),
+            AggregateFunctionKind::Variance => Box::new(VarianceAccumulator::new(false, false)),
+            AggregateFunctionKind::VarPop => Box::new(VarianceAccumulator::new(true, false)),
+            AggregateFunctionKind::StringAgg => Box::new(StringAggAccumulator::new(String::new())),
+            AggregateFunctionKind::BoolAnd => Box::new(BoolAndOrAccumulator::new(true)),
+            AggregateFunctionKind::BoolOr => Box::new(BoolAndOrAccumulator::new(false)),
+            AggregateFunctionKind::ArrayAgg => Box::new(ArrayAggAccumulator::new()),
         }
     }
 
     pub fn find(name: &str) -> Option<Self> {
-        AggregateFunctionKind::iter().find(|kind| kind.to_string().eq_ignore_ascii_case(name))
+        name.parse().ok()
     }
-}
 
-impl std::fmt::Display for AggregateFunctionKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
+    /// Checks the argument types and returns the type of the result.
+    pub fn return_type(&self, arg_types: &[DataType]) -> BustubxResult<DataType> {
+        let error = || {
+            BustubxError::Plan(format!(
+                "The aggregate function {} does not support arguments {:?}",
+                self, arg_types
+            ))
+        };
+        match (self, arg_types) {
+            (AggregateFunctionKind::Count, [_]) => Ok(DataType::Int64),
+            // averages of decimals keep up to 4 more digits of the fraction
+            (AggregateFunctionKind::Avg, [DataType::Decimal(precision, scale)]) => {
+                let extra = (precision + 4).min(MAX_DECIMAL_PRECISION) - precision;
+                Ok(DataType::Decimal(precision + extra, scale + extra))
+            }
+            (AggregateFunctionKind::Avg, [t]) if t.is_numeric() => Ok(DataType::Float64),
+            // sums of small integers fit into a bigint, sums of bigints need a decimal
+            (AggregateFunctionKind::Sum, [DataType::Int64 | DataType::UInt64]) => {
+                Ok(DataType::Decimal(38, 0))
+            }
+            (AggregateFunctionKind::Sum, [t]) if t.is_integer() => Ok(DataType::Int64),
+            (AggregateFunctionKind::Sum, [DataType::Float32 | DataType::Float64]) => {
+                Ok(DataType::Float64)
+            }
+            (AggregateFunctionKind::Sum, [DataType::Decimal(_, scale)]) => {
+                Ok(DataType::Decimal(38, *scale))
+            }
+            (AggregateFunctionKind::Min | AggregateFunctionKind::Max, [t]) => Ok(*t),
+            (
+                AggregateFunctionKind::Stddev
+                | AggregateFunctionKind::StddevPop
+                | AggregateFunctionKind::Variance
+                | AggregateFunctionKind::VarPop,
+                [t],
+            ) if t.is_numeric() => Ok(DataType::Float64),
+            (AggregateFunctionKind::StringAgg, [DataType::Varchar(_), DataType::Varchar(_)]) => {
+                Ok(DataType::Varchar(None))
+            }
+            (
+                AggregateFunctionKind::BoolAnd | AggregateFunctionKind::BoolOr,
+                [DataType::Boolean],
+            ) => Ok(DataType::Boolean),
+            (AggregateFunctionKind::ArrayAgg, [_]) => Ok(DataType::Varchar(None)),
+            _ => Err(error()),
+        }
     }
 }
 
@@ -0,0 +1,37 @@
+use crate::common::ScalarValue;
+use crate::function::Accumulator;
+use crate::BustubxResult;
+
+#[derive(Debug, Clone)]
+pub struct StringAggAccumulator {
+    result: Option<String>,
+    separator: String,
+}
+
+impl StringAggAccumulator {
+    pub fn new(separator: String) -> Self {
+        Self {
+            result: None,
+            separator,
+        }
+    }
+}
+
+impl Accumulator for StringAggAccumulator {
+    fn update_value(&mut self, value: &ScalarValue) -> BustubxResult<()> {
+        if let ScalarValue::Varchar(Some(value)) = value {
+            match self.result.as_mut() {
+                Some(result) => {
+                    result.push_str(&self.separator);
+                    result.push_str(value);
+                }
+                None => self.result = Some(value.clone()),
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> BustubxResult<ScalarValue> {
+        Ok(ScalarValue::Varchar(self.result.clone()))
+    }
+}
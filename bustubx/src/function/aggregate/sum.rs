@@ -0,0 +1,39 @@
+use crate::catalog::DataType;
+use crate::common::ScalarValue;
+use crate::expression::{evaluate_arithmetic_op, BinaryOp};
+use crate::function::Accumulator;
+use crate::BustubxResult;
+
+#[derive(Debug, Clone)]
+pub struct SumAccumulator {
+    sum: ScalarValue,
+    data_type: DataType,
+}
+
+impl SumAccumulator {
+    pub fn new(data_type: DataType) -> Self {
+        Self {
+            sum: ScalarValue::new_empty(data_type),
+            data_type,
+        }
+    }
+}
+
+impl Accumulator for SumAccumulator {
+    fn update_value(&mut self, value: &ScalarValue) -> BustubxResult<()> {
+        if !value.is_null() {
+            let value = value.cast_to(&self.data_type)?;
+            self.sum = if self.sum.is_null() {
+                value
+            } else {
+                evaluate_arithmetic_op(self.sum.clone(), BinaryOp::Plus, value)?
+                    .cast_to(&self.data_type)?
+            };
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> BustubxResult<ScalarValue> {
+        Ok(self.sum.clone())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::catalog::DataType;
+use crate::common::ScalarValue;
+use crate::function::Accumulator;
+use crate::{BustubxError, BustubxResult};
+
+/// Computes the variance or standard deviation with Welford's online algorithm.
+#[derive(Debug, Clone)]
+pub struct VarianceAccumulator {
+    count: u64,
+    mean: f64,
+    /// sum of squared differences from the mean
+    m2: f64,
+    population: bool,
+    stddev: bool,
+}
+
+impl VarianceAccumulator {
+    pub fn new(population: bool, stddev: bool) -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            population,
+            stddev,
+        }
+    }
+}
+
+impl Accumulator for VarianceAccumulator {
+    fn update_value(&mut self, value: &ScalarValue) -> BustubxResult<()> {
+        if !value.is_null() {
+            let value = match value.cast_to(&DataType::Float64)? {
+                ScalarValue::Float64(Some(v)) => v,
+                _ => {
+                    return Err(BustubxError::Internal(format!(
+                        "Failed to cast value {} to float64",
+                        value
+                    )))
+                }
+            };
+            self.count += 1;
+            let delta = value - self.mean;
+            self.mean += delta / self.count as f64;
+            self.m2 += delta * (value - self.mean);
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> BustubxResult<ScalarValue> {
+        let divisor = if self.population {
+            self.count
+        } else {
+            self.count.saturating_sub(1)
+        };
+        if divisor == 0 {
+            return Ok(ScalarValue::Float64(None));
+        }
+        let variance = self.m2 / divisor as f64;
+        Ok(ScalarValue::Float64(Some(if self.stddev {
+            variance.sqrt()
+        } else {
+            variance
+        })))
+    }
+}
@@ -2,3 +2,4 @@ mod aggregate;
 mod scalar;
 
 pub use aggregate::*;
+pub use scalar::*;
@@ -0,0 +1,42 @@
+use crate::catalog::DataType;
+use crate::common::ScalarValue;
+use crate::BustubxResult;
+use std::cmp::Ordering;
+
+/// The first argument which is not NULL.
+pub fn coalesce(args: &[ScalarValue], data_type: &DataType) -> BustubxResult<ScalarValue> {
+    match args.iter().find(|arg| !arg.is_null()) {
+        Some(arg) => arg.cast_to(data_type),
+        None => Ok(ScalarValue::new_empty(*data_type)),
+    }
+}
+
+/// NULL if both arguments are equal, otherwise the first one.
+pub fn nullif(value: &ScalarValue, other: &ScalarValue) -> BustubxResult<ScalarValue> {
+    if value.is_null() || other.is_null() {
+        return Ok(value.clone());
+    }
+    let data_type = DataType::comparison_coercion(&value.data_type(), &other.data_type())?;
+    if value.cast_to(&data_type)? == other.cast_to(&data_type)? {
+        Ok(ScalarValue::new_empty(value.data_type()))
+    } else {
+        Ok(value.clone())
+    }
+}
+
+/// The largest (`Ordering::Greater`) or smallest (`Ordering::Less`) argument, NULL
+/// arguments are ignored.
+pub fn extreme(
+    args: &[ScalarValue],
+    data_type: &DataType,
+    ordering: Ordering,
+) -> BustubxResult<ScalarValue> {
+    let mut result = ScalarValue::new_empty(*data_type);
+    for arg in args.iter().filter(|arg| !arg.is_null()) {
+        let arg = arg.cast_to(data_type)?;
+        if result.is_null() || arg.partial_cmp(&result) == Some(ordering) {
+            result = arg;
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,167 @@
+use crate::catalog::DataType;
+use crate::common::temporal::{self, Interval, MICROS_PER_DAY, MICROS_PER_HOUR, MICROS_PER_MINUTE};
+use crate::common::ScalarValue;
+use crate::{BustubxError, BustubxResult};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
+
+pub fn now() -> ScalarValue {
+    ScalarValue::Timestamp(Some(Utc::now().timestamp_micros()))
+}
+
+pub fn current_date() -> ScalarValue {
+    ScalarValue::Date(Some(temporal::naive_to_date(Utc::now().date_naive())))
+}
+
+pub fn current_time() -> ScalarValue {
+    ScalarValue::Time(Some(temporal::naive_to_time(Utc::now().time())))
+}
+
+/// `date_part(field, source)` and `extract(field from source)`, e.g. the year of a date.
+pub fn date_part(field: &str, source: &ScalarValue) -> BustubxResult<ScalarValue> {
+    let field = field.to_ascii_lowercase();
+    let value = match source {
+        ScalarValue::Date(_) | ScalarValue::Timestamp(_) => {
+            let ScalarValue::Timestamp(Some(timestamp)) = source.cast_to(&DataType::Timestamp)?
+            else {
+                return Ok(ScalarValue::Float64(None));
+            };
+            temporal::timestamp_to_naive(timestamp)
+                .and_then(|datetime| timestamp_part(&field, timestamp, datetime))
+        }
+        ScalarValue::Time(Some(time)) => time_part(&field, *time),
+        ScalarValue::Interval(Some(interval)) => interval_part(&field, interval),
+        _ => None,
+    };
+    value.map(|v| ScalarValue::Float64(Some(v))).ok_or_else(|| {
+        BustubxError::Execution(format!(
+            "Unit \"{}\" not supported for {}",
+            field,
+            source.data_type()
+        ))
+    })
+}
+
+fn timestamp_part(field: &str, timestamp: i64, datetime: NaiveDateTime) -> Option<f64> {
+    let year = datetime.year();
+    let value = match field {
+        "millennium" | "millenium" => millennium(year) as f64,
+        "century" => century(year) as f64,
+        "decade" => year.div_euclid(10) as f64,
+        "year" => year as f64,
+        "isoyear" => datetime.iso_week().year() as f64,
+        "quarter" => ((datetime.month() - 1) / 3 + 1) as f64,
+        "month" => datetime.month() as f64,
+        "week" => datetime.iso_week().week() as f64,
+        "day" => datetime.day() as f64,
+        "dow" => datetime.weekday().num_days_from_sunday() as f64,
+        "isodow" => datetime.weekday().number_from_monday() as f64,
+        "doy" => datetime.ordinal() as f64,
+        "epoch" => timestamp as f64 / 1e6,
+        _ => time_part(field, temporal::naive_to_time(datetime.time()))?,
+    };
+    Some(value)
+}
+
+fn time_part(field: &str, time: i64) -> Option<f64> {
+    let time = temporal::time_to_naive(time)?;
+    let micros = time.second() as f64 * 1e6 + (time.nanosecond() / 1000) as f64;
+    let value = match field {
+        "hour" => time.hour() as f64,
+        "minute" => time.minute() as f64,
+        "second" => micros / 1e6,
+        "millisecond" | "milliseconds" => micros / 1e3,
+        "microsecond" | "microseconds" => micros,
+        "epoch" => temporal::naive_to_time(time) as f64 / 1e6,
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn interval_part(field: &str, interval: &Interval) -> Option<f64> {
+    let years = interval.months / 12;
+    let micros = interval.micros % MICROS_PER_MINUTE;
+    let value = match field {
+        "millennium" | "millenium" => (years / 1000) as f64,
+        "century" => (years / 100) as f64,
+        "decade" => (years / 10) as f64,
+        "year" => years as f64,
+        "quarter" => (interval.months % 12 / 3 + 1) as f64,
+        "month" => (interval.months % 12) as f64,
+        "day" => interval.days as f64,
+        "hour" => (interval.micros / MICROS_PER_HOUR) as f64,
+        "minute" => (interval.micros / MICROS_PER_MINUTE % 60) as f64,
+        "second" => micros as f64 / 1e6,
+        "millisecond" | "milliseconds" => micros as f64 / 1e3,
+        "microsecond" | "microseconds" => micros as f64,
+        // a year is 365.25 days and a month 30 days like postgres
+        "epoch" => {
+            years as f64 * 365.25 * 86400.0
+                + (interval.months % 12) as f64 * 30.0 * 86400.0
+                + interval.days as f64 * 86400.0
+                + interval.micros as f64 / 1e6
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// `date_trunc(field, source)`, truncates a timestamp to the precision of `field`.
+pub fn date_trunc(field: &str, timestamp: i64) -> BustubxResult<ScalarValue> {
+    let field = field.to_ascii_lowercase();
+    let truncate = |unit: i64| timestamp - timestamp.rem_euclid(unit);
+    let value = match field.as_str() {
+        "microsecond" | "microseconds" => Some(timestamp),
+        "millisecond" | "milliseconds" => Some(truncate(1000)),
+        "second" => Some(truncate(temporal::MICROS_PER_SECOND)),
+        "minute" => Some(truncate(MICROS_PER_MINUTE)),
+        "hour" => Some(truncate(MICROS_PER_HOUR)),
+        "day" => Some(truncate(MICROS_PER_DAY)),
+        _ => temporal::timestamp_to_naive(timestamp)
+            .and_then(|datetime| truncate_date(&field, datetime.date()))
+            .map(|date| temporal::naive_to_timestamp(date.and_time(Default::default()))),
+    };
+    value
+        .map(|v| ScalarValue::Timestamp(Some(v)))
+        .ok_or_else(|| {
+            BustubxError::Execution(format!(
+                "Unit \"{}\" not supported for {}",
+                field,
+                DataType::Timestamp
+            ))
+        })
+}
+
+fn truncate_date(field: &str, date: NaiveDate) -> Option<NaiveDate> {
+    let year = date.year();
+    match field {
+        "week" => date.checked_sub_days(chrono::Days::new(
+            date.weekday().num_days_from_monday() as u64
+        )),
+        "month" => date.with_day(1),
+        "quarter" => NaiveDate::from_ymd_opt(year, (date.month() - 1) / 3 * 3 + 1, 1),
+        "year" => NaiveDate::from_ymd_opt(year, 1, 1),
+        "decade" => NaiveDate::from_ymd_opt(year - year.rem_euclid(10), 1, 1),
+        "century" => NaiveDate::from_ymd_opt((century(year) - 1) * 100 + 1, 1, 1),
+        "millennium" | "millenium" => {
+            NaiveDate::from_ymd_opt((millennium(year) - 1) * 1000 + 1, 1, 1)
+        }
+        _ => None,
+    }
+}
+
+/// The 21st century starts with 2001.
+fn century(year: i32) -> i32 {
+    if year > 0 {
+        (year + 99) / 100
+    } else {
+        -((99 - year) / 100)
+    }
+}
+
+fn millennium(year: i32) -> i32 {
+    if year > 0 {
+        (year + 999) / 1000
+    } else {
+        -((999 - year) / 1000)
+    }
+}
@@ -0,0 +1,129 @@
+use crate::catalog::DataType;
+use crate::common::decimal::{self, MAX_DECIMAL_PRECISION};
+use crate::common::ScalarValue;
+use crate::{BustubxError, BustubxResult};
+
+pub fn abs(value: &ScalarValue, data_type: &DataType) -> BustubxResult<ScalarValue> {
+    apply(
+        value,
+        data_type,
+        |v, scale| Some((v.checked_abs()?, scale)),
+        f64::abs,
+    )
+}
+
+/// Rounds half away from zero to `digits` after the decimal point, a negative `digits`
+/// rounds to the left of it.
+pub fn round(value: &ScalarValue, digits: i64, data_type: &DataType) -> BustubxResult<ScalarValue> {
+    apply(
+        value,
+        data_type,
+        |v, scale| {
+            let dropped = scale as i64 - digits;
+            if dropped <= 0 {
+                return Some((v, scale));
+            }
+            match u8::try_from(dropped).ok().and_then(decimal::pow10) {
+                Some(unit) => Some((decimal::div_round(v, unit).checked_mul(unit)?, scale)),
+                // the unit exceeds any value
+                None => Some((0, scale)),
+            }
+        },
+        |v| {
+            let factor = 10f64.powi(digits.clamp(-400, 400) as i32);
+            let rounded = (v * factor).round() / factor;
+            if rounded.is_finite() {
+                rounded
+            } else {
+                v
+            }
+        },
+    )
+}
+
+pub fn floor(value: &ScalarValue, data_type: &DataType) -> BustubxResult<ScalarValue> {
+    apply(
+        value,
+        data_type,
+        |v, scale| Some((v.div_euclid(decimal::pow10(scale)?), 0)),
+        f64::floor,
+    )
+}
+
+pub fn ceil(value: &ScalarValue, data_type: &DataType) -> BustubxResult<ScalarValue> {
+    apply(
+        value,
+        data_type,
+        |v, scale| Some((-(-v).div_euclid(decimal::pow10(scale)?), 0)),
+        f64::ceil,
+    )
+}
+
+pub fn power(base: f64, exponent: f64) -> BustubxResult<ScalarValue> {
+    if base == 0.0 && exponent < 0.0 {
+        return Err(BustubxError::Execution(
+            "zero raised to a negative power is undefined".to_string(),
+        ));
+    }
+    if base < 0.0 && exponent.fract() != 0.0 {
+        return Err(BustubxError::Execution(
+            "a negative number raised to a non-integer power yields a complex result".to_string(),
+        ));
+    }
+    let value = base.powf(exponent);
+    if !value.is_finite() && base.is_finite() && exponent.is_finite() {
+        return Err(BustubxError::Execution(format!(
+            "Arithmetic overflow: power({}, {})",
+            base, exponent
+        )));
+    }
+    Ok(ScalarValue::Float64(Some(value)))
+}
+
+pub fn sqrt(value: f64) -> BustubxResult<ScalarValue> {
+    if value < 0.0 {
+        return Err(BustubxError::Execution(
+            "cannot take square root of a negative number".to_string(),
+        ));
+    }
+    Ok(ScalarValue::Float64(Some(value.sqrt())))
+}
+
+/// Result type of `round`, `floor` and `ceil` of a decimal, which keeps its integer digits
+/// and `kept_scale` digits of the fraction.
+pub fn decimal_rounding_type(precision: u8, scale: u8, kept_scale: u8) -> DataType {
+    DataType::Decimal(
+        (precision - scale + 1 + kept_scale).min(MAX_DECIMAL_PRECISION),
+        kept_scale,
+    )
+}
+
+/// Applies `int_op` to integers and decimals as a value with a scale, and `float_op`
+/// to floats. The result is cast to `data_type`.
+fn apply(
+    value: &ScalarValue,
+    data_type: &DataType,
+    int_op: impl Fn(i128, u8) -> Option<(i128, u8)>,
+    float_op: impl Fn(f64) -> f64,
+) -> BustubxResult<ScalarValue> {
+    let overflow =
+        || BustubxError::Execution(format!("{} is out of range for {}", value, data_type));
+    let result = match value {
+        ScalarValue::Float32(Some(v)) => ScalarValue::Float64(Some(float_op(*v as f64))),
+        ScalarValue::Float64(Some(v)) => ScalarValue::Float64(Some(float_op(*v))),
+        _ => {
+            let (precision, scale) =
+                value.data_type().decimal_precision_scale().ok_or_else(|| {
+                    BustubxError::Execution(format!("{} is not a number", value.data_type()))
+                })?;
+            let ScalarValue::Decimal(Some(v), _, scale) =
+                value.cast_to(&DataType::Decimal(precision, scale))?
+            else {
+                return Ok(ScalarValue::new_empty(*data_type));
+            };
+            let (v, scale) = int_op(v, scale).ok_or_else(overflow)?;
+            ScalarValue::Decimal(Some(v), MAX_DECIMAL_PRECISION, scale)
+        }
+    };
+    result.cast_to(data_type)
+}
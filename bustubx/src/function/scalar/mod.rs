@@ -1 +1,333 @@
+mod conditional;
+mod datetime;
+mod math;
+mod string;
 
+use crate::catalog::DataType;
+use crate::common::ScalarValue;
+use crate::expression::{arithmetic_type, evaluate_arithmetic_op, BinaryOp};
+use crate::{BustubxError, BustubxResult};
+use std::cmp::Ordering;
+pub use string::TrimSide;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum ScalarFunctionKind {
+    #[strum(to_string = "now", serialize = "current_timestamp")]
+    Now,
+    CurrentDate,
+    CurrentTime,
+    DatePart,
+    DateTrunc,
+    Lower,
+    Upper,
+    #[strum(
+        to_string = "length",
+        serialize = "char_length",
+        serialize = "character_length"
+    )]
+    Length,
+    #[strum(to_string = "substr", serialize = "substring")]
+    Substr,
+    #[strum(to_string = "trim", serialize = "btrim")]
+    Trim,
+    Ltrim,
+    Rtrim,
+    Replace,
+    Concat,
+    Position,
+    Abs,
+    Round,
+    Floor,
+    #[strum(to_string = "ceil", serialize = "ceiling")]
+    Ceil,
+    #[strum(to_string = "power", serialize = "pow")]
+    Power,
+    Sqrt,
+    Mod,
+    Coalesce,
+    Nullif,
+    Greatest,
+    Least,
+}
+
+impl ScalarFunctionKind {
+    pub fn find(name: &str) -> Option<Self> {
+        name.parse().ok()
+    }
+
+    /// Function of the `TRIM([LEADING | TRAILING | BOTH] ...)` syntax.
+    pub fn trim(side: TrimSide) -> Self {
+        match side {
+            TrimSide::Both => ScalarFunctionKind::Trim,
+            TrimSide::Leading => ScalarFunctionKind::Ltrim,
+            TrimSide::Trailing => ScalarFunctionKind::Rtrim,
+        }
+    }
+
+    /// Strict functions return NULL if any argument is NULL.
+    fn is_strict(&self) -> bool {
+        !matches!(
+            self,
+            ScalarFunctionKind::Concat
+                | ScalarFunctionKind::Coalesce
+                | ScalarFunctionKind::Nullif
+                | ScalarFunctionKind::Greatest
+                | ScalarFunctionKind::Least
+        )
+    }
+
+    /// Whether the result can be NULL given whether each argument can be.
+    pub fn nullable(&self, arg_nullables: &[bool]) -> bool {
+        match self {
+            ScalarFunctionKind::Concat => false,
+            ScalarFunctionKind::Nullif => true,
+            ScalarFunctionKind::Coalesce
+            | ScalarFunctionKind::Greatest
+            | ScalarFunctionKind::Least => arg_nullables.iter().all(|nullable| *nullable),
+            _ => arg_nullables.iter().any(|nullable| *nullable),
+        }
+    }
+
+    /// Checks the argument types and returns the type of the result.
+    pub fn return_type(&self, arg_types: &[DataType]) -> BustubxResult<DataType> {
+        let error = || {
+            BustubxError::Plan(format!(
+                "The function {} does not support arguments {:?}",
+                self, arg_types
+            ))
+        };
+        match (self, arg_types) {
+            (ScalarFunctionKind::Now, []) => Ok(DataType::Timestamp),
+            (ScalarFunctionKind::CurrentDate, []) => Ok(DataType::Date),
+            (ScalarFunctionKind::CurrentTime, []) => Ok(DataType::Time),
+            (ScalarFunctionKind::DatePart, [DataType::Varchar(_), source])
+                if source.is_temporal() =>
+            {
+                Ok(DataType::Float64)
+            }
+            (
+                ScalarFunctionKind::DateTrunc,
+                [DataType::Varchar(_), DataType::Date | DataType::Timestamp],
+            ) => Ok(DataType::Timestamp),
+            (ScalarFunctionKind::Lower | ScalarFunctionKind::Upper, [DataType::Varchar(_)]) => {
+                Ok(DataType::Varchar(None))
+            }
+            (ScalarFunctionKind::Length, [DataType::Varchar(_) | DataType::Blob]) => {
+                Ok(DataType::Int64)
+            }
+            (ScalarFunctionKind::Substr, [DataType::Varchar(_), positions @ ..])
+                if matches!(positions.len(), 1 | 2)
+                    && positions.iter().all(DataType::is_integer) =>
+            {
+                Ok(DataType::Varchar(None))
+            }
+            (
+                ScalarFunctionKind::Trim | ScalarFunctionKind::Ltrim | ScalarFunctionKind::Rtrim,
+                [DataType::Varchar(_)] | [DataType::Varchar(_), DataType::Varchar(_)],
+            ) => Ok(DataType::Varchar(None)),
+            (
+                ScalarFunctionKind::Replace,
+                [DataType::Varchar(_), DataType::Varchar(_), DataType::Varchar(_)],
+            ) => Ok(DataType::Varchar(None)),
+            (ScalarFunctionKind::Concat, [_, ..]) => Ok(DataType::Varchar(None)),
+            (ScalarFunctionKind::Position, [DataType::Varchar(_), DataType::Varchar(_)]) => {
+                Ok(DataType::Int64)
+            }
+            (ScalarFunctionKind::Abs, [t]) if t.is_numeric() => Ok(*t),
+            (
+                ScalarFunctionKind::Round | ScalarFunctionKind::Floor | ScalarFunctionKind::Ceil,
+                [DataType::Decimal(precision, scale)],
+            ) => Ok(math::decimal_rounding_type(*precision, *scale, 0)),
+            (ScalarFunctionKind::Round, [DataType::Decimal(precision, scale), digits])
+                if digits.is_integer() =>
+            {
+                Ok(math::decimal_rounding_type(*precision, *scale, *scale))
+            }
+            (
+                ScalarFunctionKind::Round | ScalarFunctionKind::Floor | ScalarFunctionKind::Ceil,
+                [t],
+            ) if t.is_numeric() => Ok(*t),
+            (ScalarFunctionKind::Round, [t, digits]) if t.is_numeric() && digits.is_integer() => {
+                Ok(*t)
+            }
+            (ScalarFunctionKind::Power, [base, exponent])
+                if base.is_numeric() && exponent.is_numeric() =>
+            {
+                Ok(DataType::Float64)
+            }
+            (ScalarFunctionKind::Sqrt, [t]) if t.is_numeric() => Ok(DataType::Float64),
+            (ScalarFunctionKind::Mod, [l, r]) if l.is_numeric() && r.is_numeric() => {
+                arithmetic_type(l, BinaryOp::Modulo, r)
+            }
+            (
+                ScalarFunctionKind::Coalesce
+                | ScalarFunctionKind::Greatest
+                | ScalarFunctionKind::Least,
+                [_, ..],
+            ) => DataType::common_type(arg_types).map_err(|_| error()),
+            (ScalarFunctionKind::Nullif, [l, r]) => DataType::comparison_coercion(l, r)
+                .map(|_| *l)
+                .map_err(|_| error()),
+            _ => Err(error()),
+        }
+    }
+
+    /// Like `return_type`, but knows the arguments which are literals. The NULL literal is
+    /// typed as `Int8`, so NULL arguments may also take another type the function accepts.
+    /// Rounding a decimal to literal digits keeps only those digits of the fraction.
+    pub fn return_type_of_args(
+        &self,
+        arg_types: &[DataType],
+        literal_args: &[Option<&ScalarValue>],
+    ) -> BustubxResult<DataType> {
+        if let (
+            ScalarFunctionKind::Round,
+            [DataType::Decimal(precision, scale), digits],
+            [_, Some(literal)],
+        ) = (self, arg_types, literal_args)
+        {
+            if digits.is_integer() && !literal.is_null() {
+                let kept_scale = int_arg(literal)?.clamp(0, *scale as i64) as u8;
+                return Ok(math::decimal_rounding_type(*precision, *scale, kept_scale));
+            }
+        }
+
+        const NULL_TYPES: [DataType; 3] =
+            [DataType::Int8, DataType::Varchar(None), DataType::Timestamp];
+        let error = match self.return_type(arg_types) {
+            Ok(return_type) => return Ok(return_type),
+            Err(e) => e,
+        };
+        let null_positions = (0..arg_types.len())
+            .filter(|idx| literal_args[*idx].is_some_and(ScalarValue::is_null))
+            .collect::<Vec<_>>();
+        let mut types = arg_types.to_vec();
+        for combination in 1..NULL_TYPES.len().pow(null_positions.len() as u32) {
+            let mut rest = combination;
+            for idx in null_positions.iter() {
+                types[*idx] = NULL_TYPES[rest % NULL_TYPES.len()];
+                rest /= NULL_TYPES.len();
+            }
+            if let Ok(return_type) = self.return_type(&types) {
+                return Ok(return_type);
+            }
+        }
+        Err(error)
+    }
+
+    /// Evaluates the function with the `return_type` it was planned with.
+    pub fn evaluate(
+        &self,
+        args: &[ScalarValue],
+        return_type: &DataType,
+    ) -> BustubxResult<ScalarValue> {
+        let return_type = *return_type;
+        if self.is_strict() && args.iter().any(ScalarValue::is_null) {
+            return Ok(ScalarValue::new_empty(return_type));
+        }
+        match (self, args) {
+            (ScalarFunctionKind::Now, []) => Ok(datetime::now()),
+            (ScalarFunctionKind::CurrentDate, []) => Ok(datetime::current_date()),
+            (ScalarFunctionKind::CurrentTime, []) => Ok(datetime::current_time()),
+            (ScalarFunctionKind::DatePart, [ScalarValue::Varchar(Some(field)), source]) => {
+                datetime::date_part(field, source)
+            }
+            (ScalarFunctionKind::DateTrunc, [ScalarValue::Varchar(Some(field)), source]) => {
+                match source.cast_to(&DataType::Timestamp)? {
+                    ScalarValue::Timestamp(Some(timestamp)) => {
+                        datetime::date_trunc(field, timestamp)
+                    }
+                    _ => Ok(ScalarValue::Timestamp(None)),
+                }
+            }
+            (ScalarFunctionKind::Lower, [ScalarValue::Varchar(Some(s))]) => {
+                Ok(ScalarValue::Varchar(Some(s.to_lowercase())))
+            }
+            (ScalarFunctionKind::Upper, [ScalarValue::Varchar(Some(s))]) => {
+                Ok(ScalarValue::Varchar(Some(s.to_uppercase())))
+            }
+            (ScalarFunctionKind::Length, [value]) => string::length(value),
+            (ScalarFunctionKind::Substr, [ScalarValue::Varchar(Some(s)), start]) => Ok(
+                ScalarValue::Varchar(Some(string::substr(s, int_arg(start)?, None)?)),
+            ),
+            (ScalarFunctionKind::Substr, [ScalarValue::Varchar(Some(s)), start, count]) => {
+                Ok(ScalarValue::Varchar(Some(string::substr(
+                    s,
+                    int_arg(start)?,
+                    Some(int_arg(count)?),
+                )?)))
+            }
+            (
+                ScalarFunctionKind::Trim | ScalarFunctionKind::Ltrim | ScalarFunctionKind::Rtrim,
+                [ScalarValue::Varchar(Some(s)), chars @ ..],
+            ) => {
+                let chars = match chars {
+                    [ScalarValue::Varchar(Some(chars))] => chars.as_str(),
+                    _ => " ",
+                };
+                let side = match self {
+                    ScalarFunctionKind::Ltrim => TrimSide::Leading,
+                    ScalarFunctionKind::Rtrim => TrimSide::Trailing,
+                    _ => TrimSide::Both,
+                };
+                Ok(ScalarValue::Varchar(Some(string::trim(s, chars, side))))
+            }
+            (
+                ScalarFunctionKind::Replace,
+                [ScalarValue::Varchar(Some(s)), ScalarValue::Varchar(Some(from)), ScalarValue::Varchar(Some(to))],
+            ) => Ok(ScalarValue::Varchar(Some(string::replace(s, from, to)))),
+            (ScalarFunctionKind::Concat, args) => Ok(string::concat(args)),
+            (
+                ScalarFunctionKind::Position,
+                [ScalarValue::Varchar(Some(substring)), ScalarValue::Varchar(Some(s))],
+            ) => Ok(ScalarValue::Int64(Some(string::position(substring, s)))),
+            (ScalarFunctionKind::Abs, [value]) => math::abs(value, &return_type),
+            (ScalarFunctionKind::Round, [value]) => math::round(value, 0, &return_type),
+            (ScalarFunctionKind::Round, [value, digits]) => {
+                math::round(value, int_arg(digits)?, &return_type)
+            }
+            (ScalarFunctionKind::Floor, [value]) => math::floor(value, &return_type),
+            (ScalarFunctionKind::Ceil, [value]) => math::ceil(value, &return_type),
+            (ScalarFunctionKind::Power, [base, exponent]) => {
+                math::power(float_arg(base)?, float_arg(exponent)?)
+            }
+            (ScalarFunctionKind::Sqrt, [value]) => math::sqrt(float_arg(value)?),
+            (ScalarFunctionKind::Mod, [l, r]) => {
+                evaluate_arithmetic_op(l.clone(), BinaryOp::Modulo, r.clone())
+            }
+            (ScalarFunctionKind::Coalesce, args) => conditional::coalesce(args, &return_type),
+            (ScalarFunctionKind::Nullif, [value, other]) => conditional::nullif(value, other),
+            (ScalarFunctionKind::Greatest, args) => {
+                conditional::extreme(args, &return_type, Ordering::Greater)
+            }
+            (ScalarFunctionKind::Least, args) => {
+                conditional::extreme(args, &return_type, Ordering::Less)
+            }
+            _ => Err(BustubxError::Execution(format!(
+                "The function {} does not support arguments {:?}",
+                self, args
+            ))),
+        }
+    }
+}
+
+fn int_arg(value: &ScalarValue) -> BustubxResult<i64> {
+    match value.cast_to(&DataType::Int64)? {
+        ScalarValue::Int64(Some(v)) => Ok(v),
+        _ => Err(BustubxError::Execution(format!(
+            "{} is not an integer",
+            value
+        ))),
+    }
+}
+
+fn float_arg(value: &ScalarValue) -> BustubxResult<f64> {
+    match value.cast_to(&DataType::Float64)? {
+        ScalarValue::Float64(Some(v)) => Ok(v),
+        _ => Err(BustubxError::Execution(format!(
+            "{} is not a number",
+            value
+        ))),
+    }
+}
@@ -0,0 +1,110 @@
+use crate::common::ScalarValue;
+use crate::{BustubxError, BustubxResult};
+
+/// Which ends of a string `trim` removes characters from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TrimSide {
+    Both,
+    Leading,
+    Trailing,
+}
+
+/// Number of characters of a string or bytes of a blob.
+pub fn length(value: &ScalarValue) -> BustubxResult<ScalarValue> {
+    let length = match value {
+        ScalarValue::Varchar(v) => v.as_ref().map(|s| s.chars().count()),
+        ScalarValue::Blob(v) => v.as_ref().map(|b| b.len()),
+        _ => {
+            return Err(BustubxError::Execution(format!(
+                "length does not support {}",
+                value.data_type()
+            )))
+        }
+    };
+    Ok(ScalarValue::Int64(length.map(|l| l as i64)))
+}
+
+/// `substr(s, start[, count])`, characters are counted from 1 and positions before the
+/// start of the string count towards `count` like postgres.
+pub fn substr(s: &str, start: i64, count: Option<i64>) -> BustubxResult<String> {
+    let end = match count {
+        Some(count) if count < 0 => {
+            return Err(BustubxError::Execution(
+                "negative substring length not allowed".to_string(),
+            ))
+        }
+        Some(count) => start.saturating_add(count),
+        None => i64::MAX,
+    };
+    let skip = start.max(1) - 1;
+    let take = end.saturating_sub(start.max(1)).max(0);
+    Ok(s.chars()
+        .skip(skip as usize)
+        .take(usize::try_from(take).unwrap_or(usize::MAX))
+        .collect())
+}
+
+/// Removes the longest prefix and/or suffix of `s` made of the characters in `chars`.
+pub fn trim(s: &str, chars: &str, side: TrimSide) -> String {
+    let matches = |c: char| chars.contains(c);
+    match side {
+        TrimSide::Both => s.trim_matches(matches),
+        TrimSide::Leading => s.trim_start_matches(matches),
+        TrimSide::Trailing => s.trim_end_matches(matches),
+    }
+    .to_string()
+}
+
+/// Replaces all occurrences of `from` in `s` with `to`.
+pub fn replace(s: &str, from: &str, to: &str) -> String {
+    if from.is_empty() {
+        s.to_string()
+    } else {
+        s.replace(from, to)
+    }
+}
+
+/// Concatenates the text of all arguments, NULL arguments are ignored.
+pub fn concat(args: &[ScalarValue]) -> ScalarValue {
+    let result = args
+        .iter()
+        .filter(|arg| !arg.is_null())
+        .map(|arg| arg.to_string())
+        .collect::<String>();
+    ScalarValue::Varchar(Some(result))
+}
+
+/// Position of the first character of `substring` in `s` counted from 1, 0 if it is
+/// not found.
+pub fn position(substring: &str, s: &str) -> i64 {
+    s.find(substring)
+        .map(|idx| s[..idx].chars().count() as i64 + 1)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substr() {
+        assert_eq!(substr("hello", 2, Some(3)).unwrap(), "ell");
+        assert_eq!(substr("hello", 2, None).unwrap(), "ello");
+        assert_eq!(substr("hello", 0, Some(3)).unwrap(), "he");
+        assert_eq!(substr("hello", -5, Some(3)).unwrap(), "");
+        assert_eq!(substr("hello", 4, Some(10)).unwrap(), "lo");
+        assert_eq!(substr("héllo", 2, Some(2)).unwrap(), "él");
+        assert!(substr("hello", 1, Some(-1)).is_err());
+    }
+
+    #[test]
+    fn test_trim_and_position() {
+        assert_eq!(trim("xxabcxx", "x", TrimSide::Both), "abc");
+        assert_eq!(trim("xyabcyx", "xy", TrimSide::Leading), "abcyx");
+        assert_eq!(trim("  abc  ", " ", TrimSide::Trailing), "  abc");
+        assert_eq!(position("lo", "hello"), 4);
+        assert_eq!(position("é", "héllo"), 2);
+        assert_eq!(position("z", "hello"), 0);
+        assert_eq!(position("", "hello"), 1);
+    }
+}
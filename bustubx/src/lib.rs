@@ -9,6 +9,7 @@ mod function;
 mod optimizer;
 mod parser;
 mod planner;
+mod recovery;
 mod storage;
 mod transaction;
 
@@ -0,0 +1,307 @@
+use crate::catalog::{TableStatistics, DEFAULT_SELECTIVITY};
+use crate::expression::{BinaryExpr, BinaryOp, ColumnExpr, Expr};
+use crate::planner::logical_plan::{
+    Aggregate, EmptyRelation, Filter, Join, JoinType, Limit, LogicalPlan, Project, SetOperation,
+    SetOperator, Sort, TableScan, Values,
+};
+use std::ops::Bound;
+use std::sync::Arc;
+
+/// Cost of reading one tuple by a sequential scan
+pub const SEQ_TUPLE_COST: f64 = 1.0;
+/// Cost of reading one tuple through an index, which reads the table heap at random
+pub const INDEX_TUPLE_COST: f64 = 4.0;
+/// Cost of putting one tuple into the hash table of a hash join
+pub const HASH_BUILD_TUPLE_COST: f64 = 2.0;
+/// Cost of probing the hash table of a hash join with one tuple
+pub const HASH_PROBE_TUPLE_COST: f64 = 1.0;
+/// Cost of merging one tuple of a sort merge join
+pub const MERGE_TUPLE_COST: f64 = 1.0;
+
+/// Estimates the size of plans and the cost of physical operators from the statistics
+/// collected by `ANALYZE`. Estimates are only available if all tables read by a plan
+/// have statistics.
+pub struct CostModel;
+
+impl CostModel {
+    /// Estimated number of tuples produced by the plan.
+    pub fn estimate_rows(&self, plan: &LogicalPlan) -> Option<f64> {
+        match plan {
+            LogicalPlan::TableScan(TableScan {
+                filters,
+                limit,
+                statistics,
+                ..
+            }) => {
+                let statistics = statistics.as_ref()?;
+                let mut rows = statistics.row_count as f64;
+                for filter in filters.iter() {
+                    rows *= self.selectivity(filter, plan);
+                }
+                Some(limit.map_or(rows, |limit| rows.min(limit as f64)))
+            }
+            LogicalPlan::Filter(Filter { predicate, input }) => {
+                Some(self.estimate_rows(input)? * self.selectivity(predicate, input))
+            }
+            LogicalPlan::Project(Project { input, .. }) | LogicalPlan::Sort(Sort { input, .. }) => {
+                self.estimate_rows(input)
+            }
+            LogicalPlan::Limit(Limit {
+                limit: Some(limit),
+                offset,
+                input,
+            }) => Some((self.estimate_rows(input)? - *offset as f64).clamp(0.0, *limit as f64)),
+            LogicalPlan::Limit(Limit { offset, input, .. }) => {
+                Some((self.estimate_rows(input)? - *offset as f64).max(0.0))
+            }
+            LogicalPlan::Join(Join {
+                left,
+                right,
+                join_type,
+                condition,
+                ..
+            }) => {
+                let left_rows = self.estimate_rows(left)?;
+                let right_rows = self.estimate_rows(right)?;
+                let rows = left_rows
+                    * right_rows
+                    * condition
+                        .as_ref()
+                        .map_or(1.0, |condition| self.selectivity(condition, plan));
+                Some(match join_type {
+                    JoinType::Inner | JoinType::Cross => rows,
+                    JoinType::LeftOuter => rows.max(left_rows),
+                    JoinType::RightOuter => rows.max(right_rows),
+                    JoinType::FullOuter => rows.max(left_rows).max(right_rows),
+                })
+            }
+            LogicalPlan::Aggregate(Aggregate {
+                input, group_exprs, ..
+            }) => {
+                let input_rows = self.estimate_rows(input)?;
+                if group_exprs.is_empty() {
+                    return Some(1.0);
+                }
+                // each group has a distinct combination of the grouped values
+                let mut groups = 1.0;
+                for expr in group_exprs.iter() {
+                    match self.column_statistics(expr, input) {
+                        Some((statistics, column)) => {
+                            groups *= statistics.distinct_count(&column.name)?.max(1) as f64
+                        }
+                        None => return Some(input_rows),
+                    }
+                }
+                Some(groups.min(input_rows))
+            }
+            LogicalPlan::SetOperation(SetOperation {
+                left, right, op, ..
+            }) => {
+                let left_rows = self.estimate_rows(left)?;
+                let right_rows = self.estimate_rows(right)?;
+                Some(match op {
+                    SetOperator::Union => left_rows + right_rows,
+                    SetOperator::Intersect => left_rows.min(right_rows),
+                    SetOperator::Except => left_rows,
+                })
+            }
+            LogicalPlan::Values(Values { values, .. }) => Some(values.len() as f64),
+            LogicalPlan::EmptyRelation(EmptyRelation {
+                produce_one_row, ..
+            }) => Some(if *produce_one_row { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+
+    /// Estimated fraction of the tuples of the plan which satisfy the predicate.
+    pub fn selectivity(&self, predicate: &Expr, plan: &LogicalPlan) -> f64 {
+        let Expr::Binary(BinaryExpr { left, op, right }) = predicate else {
+            return DEFAULT_SELECTIVITY;
+        };
+        match op {
+            BinaryOp::And => self.selectivity(left, plan) * self.selectivity(right, plan),
+            BinaryOp::Or => {
+                let left = self.selectivity(left, plan);
+                let right = self.selectivity(right, plan);
+                left + right - left * right
+            }
+            BinaryOp::Eq
+            | BinaryOp::NotEq
+            | BinaryOp::Gt
+            | BinaryOp::GtEq
+            | BinaryOp::Lt
+            | BinaryOp::LtEq => self
+                .comparison_selectivity(left, *op, right, plan)
+                .unwrap_or(DEFAULT_SELECTIVITY),
+            _ => DEFAULT_SELECTIVITY,
+        }
+    }
+
+    fn comparison_selectivity(
+        &self,
+        left: &Expr,
+        op: BinaryOp,
+        right: &Expr,
+        plan: &LogicalPlan,
+    ) -> Option<f64> {
+        let (column, value, op) = match (left, right) {
+            (Expr::Column(_), Expr::Literal(literal)) => (left, &literal.value, op),
+            (Expr::Literal(literal), Expr::Column(_)) => {
+                let op = match op {
+                    BinaryOp::Gt => BinaryOp::Lt,
+                    BinaryOp::GtEq => BinaryOp::LtEq,
+                    BinaryOp::Lt => BinaryOp::Gt,
+                    BinaryOp::LtEq => BinaryOp::GtEq,
+                    op => op,
+                };
+                (right, &literal.value, op)
+            }
+            (Expr::Column(_), Expr::Column(_)) if op == BinaryOp::Eq => {
+                // every value of the side with fewer distinct values finds a match
+                let (left_statistics, left) = self.column_statistics(left, plan)?;
+                let (right_statistics, right) = self.column_statistics(right, plan)?;
+                let distinct_count = left_statistics
+                    .distinct_count(&left.name)?
+                    .max(right_statistics.distinct_count(&right.name)?)
+                    .max(1);
+                return Some(1.0 / distinct_count as f64);
+            }
+            _ => return None,
+        };
+        let (statistics, column) = self.column_statistics(column, plan)?;
+        let data_type = statistics.column(&column.name)?.min.data_type();
+        let value = value.cast_to(&data_type).ok()?;
+        match op {
+            BinaryOp::Eq => statistics.eq_selectivity(&column.name, &value),
+            BinaryOp::NotEq => statistics
+                .eq_selectivity(&column.name, &value)
+                .map(|selectivity| 1.0 - selectivity),
+            BinaryOp::Gt => statistics.range_selectivity(
+                &column.name,
+                Bound::Excluded(&value),
+                Bound::Unbounded,
+            ),
+            BinaryOp::GtEq => statistics.range_selectivity(
+                &column.name,
+                Bound::Included(&value),
+                Bound::Unbounded,
+            ),
+            BinaryOp::Lt => statistics.range_selectivity(
+                &column.name,
+                Bound::Unbounded,
+                Bound::Excluded(&value),
+            ),
+            BinaryOp::LtEq => statistics.range_selectivity(
+                &column.name,
+                Bound::Unbounded,
+                Bound::Included(&value),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Finds the statistics of the table column an expression of the plan refers to.
+    /// Columns computed by projections or aggregations have no statistics.
+    fn column_statistics(
+        &self,
+        expr: &Expr,
+        plan: &LogicalPlan,
+    ) -> Option<(Arc<TableStatistics>, ColumnExpr)> {
+        let Expr::Column(column) = expr else {
+            return None;
+        };
+        match plan {
+            LogicalPlan::TableScan(TableScan {
+                table_schema,
+                statistics,
+                ..
+            }) => {
+                table_schema
+                    .index_of(column.relation.as_ref(), &column.name)
+                    .ok()?;
+                Some((statistics.clone()?, column.clone()))
+            }
+            LogicalPlan::Filter(Filter { input, .. })
+            | LogicalPlan::Sort(Sort { input, .. })
+            | LogicalPlan::Limit(Limit { input, .. }) => self.column_statistics(expr, input),
+            LogicalPlan::Join(Join { left, right, .. }) => self
+                .column_statistics(expr, left)
+                .or_else(|| self.column_statistics(expr, right)),
+            _ => None,
+        }
+    }
+
+    /// Estimated cost of scanning a table sequentially.
+    pub fn seq_scan_cost(&self, rows: f64) -> f64 {
+        rows * SEQ_TUPLE_COST
+    }
+
+    /// Estimated cost of scanning the tuples within an index range.
+    pub fn index_scan_cost(&self, rows: f64) -> f64 {
+        rows * INDEX_TUPLE_COST
+    }
+
+    pub fn sort_cost(&self, rows: f64) -> f64 {
+        rows * rows.max(2.0).log2()
+    }
+
+    /// Estimated cost of a hash join building the hash table from the right input.
+    pub fn hash_join_cost(&self, left_rows: f64, right_rows: f64) -> f64 {
+        right_rows * HASH_BUILD_TUPLE_COST + left_rows * HASH_PROBE_TUPLE_COST
+    }
+
+    /// Estimated cost of a sort merge join, excluding the cost of sorting its inputs.
+    pub fn merge_join_cost(&self, left_rows: f64, right_rows: f64) -> f64 {
+        (left_rows + right_rows) * MERGE_TUPLE_COST
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::optimizer::{CostModel, LogicalOptimizer};
+    use crate::Database;
+
+    fn estimate_rows(db: &mut Database, sql: &str) -> Option<f64> {
+        let plan = db.create_logical_plan(sql).unwrap();
+        let plan = LogicalOptimizer::new().optimize(&plan).unwrap();
+        CostModel.estimate_rows(&plan)
+    }
+
+    #[test]
+    pub fn test_estimate_rows() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        db.run("create table t2 (a int, c int)").unwrap();
+        for i in 0..100 {
+            db.run(&format!("insert into t1 values ({i}, {})", i % 10))
+                .unwrap();
+        }
+        for i in 0..20 {
+            db.run(&format!("insert into t2 values ({i}, {i})"))
+                .unwrap();
+        }
+        assert_eq!(estimate_rows(&mut db, "select * from t1"), None);
+
+        db.run("analyze table t1").unwrap();
+        db.run("analyze table t2").unwrap();
+        assert_eq!(estimate_rows(&mut db, "select * from t1"), Some(100.0));
+        assert_eq!(
+            estimate_rows(&mut db, "select * from t1 where b = 3"),
+            Some(10.0)
+        );
+        let rows = estimate_rows(&mut db, "select * from t1 where a >= 90").unwrap();
+        assert!((rows - 10.0).abs() < 2.0, "{rows}");
+        assert_eq!(
+            estimate_rows(&mut db, "select * from t1 join t2 on t1.a = t2.a"),
+            Some(20.0)
+        );
+        assert_eq!(
+            estimate_rows(&mut db, "select b, count(a) from t1 group by b"),
+            Some(10.0)
+        );
+        assert_eq!(
+            estimate_rows(&mut db, "select * from t1 limit 5"),
+            Some(5.0)
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use crate::error::BustubxResult;
-use crate::optimizer::rule::{EliminateLimit, MergeLimit, PushDownLimit};
+use crate::optimizer::rule::{
+    EliminateLimit, MergeLimit, PushDownFilter, PushDownLimit, PushDownProjection, ReorderJoin,
+};
 use crate::planner::logical_plan::LogicalPlan;
 use std::sync::Arc;
 
@@ -41,6 +43,9 @@ impl LogicalOptimizer {
             Arc::new(EliminateLimit {}),
             Arc::new(MergeLimit {}),
             Arc::new(PushDownLimit {}),
+            Arc::new(PushDownFilter {}),
+            Arc::new(ReorderJoin {}),
+            Arc::new(PushDownProjection {}),
         ];
 
         Self {
@@ -1,4 +1,6 @@
+mod cost_model;
 mod logical_optimizer;
 pub mod rule;
 
+pub use cost_model::*;
 pub use logical_optimizer::{LogicalOptimizer, LogicalOptimizerRule};
@@ -1,7 +1,13 @@
 mod eliminate_limit;
 mod merge_limit;
+mod push_down_filter;
 mod push_down_limit;
+mod push_down_projection;
+mod reorder_join;
 
 pub use eliminate_limit::EliminateLimit;
 pub use merge_limit::MergeLimit;
+pub use push_down_filter::PushDownFilter;
 pub use push_down_limit::PushDownLimit;
+pub use push_down_projection::PushDownProjection;
+pub use reorder_join::ReorderJoin;
@@ -0,0 +1,324 @@
+use crate::expression::{conjunction, refers_only_to, split_conjunction, Expr};
+use crate::optimizer::logical_optimizer::ApplyOrder;
+use crate::optimizer::LogicalOptimizerRule;
+use crate::planner::logical_plan::{Filter, Join, JoinType, LogicalPlan, Project, Sort, TableScan};
+use crate::BustubxResult;
+use std::sync::Arc;
+
+/// Moves filter predicates as close to the table scans as possible, so that tuples are
+/// dropped before they flow through the rest of the plan.
+///
+/// Predicates are split into conjuncts, each of them is pushed below projections and
+/// sorts, into the side of a join it refers to and finally into [`TableScan::filters`].
+/// Predicates on the null-supplying side of an outer join are not pushed through it
+/// as they would also filter out the tuples padded with nulls.
+pub struct PushDownFilter;
+
+impl LogicalOptimizerRule for PushDownFilter {
+    fn try_optimize(&self, plan: &LogicalPlan) -> BustubxResult<Option<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Filter(filter) => self.push_down_filter(filter),
+            LogicalPlan::Join(join) => Ok(push_down_join_condition(join)),
+            _ => Ok(None),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "PushDownFilter"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+impl PushDownFilter {
+    fn push_down_filter(&self, filter: &Filter) -> BustubxResult<Option<LogicalPlan>> {
+        let predicates = split_conjunction(&filter.predicate);
+        match filter.input.as_ref() {
+            LogicalPlan::Filter(child) => {
+                let mut merged = split_conjunction(&child.predicate);
+                merged.extend(predicates);
+                let plan = with_filter(child.input.as_ref().clone(), merged);
+                self.try_optimize(&plan)
+                    .map(|opt_plan| opt_plan.or(Some(plan)))
+            }
+            LogicalPlan::Sort(sort) => Ok(Some(LogicalPlan::Sort(Sort {
+                order_by: sort.order_by.clone(),
+                input: Arc::new(with_filter(sort.input.as_ref().clone(), predicates)),
+                limit: sort.limit,
+            }))),
+            LogicalPlan::Project(project) => {
+                let mut pushed = vec![];
+                let mut kept = vec![];
+                for predicate in predicates {
+                    match replace_projected_columns(&predicate, project) {
+                        Some(rewritten) => pushed.push(rewritten),
+                        None => kept.push(predicate),
+                    }
+                }
+                if pushed.is_empty() {
+                    return Ok(None);
+                }
+                let project = LogicalPlan::Project(Project {
+                    exprs: project.exprs.clone(),
+                    input: Arc::new(with_filter(project.input.as_ref().clone(), pushed)),
+                    schema: project.schema.clone(),
+                });
+                Ok(Some(with_filter(project, kept)))
+            }
+            LogicalPlan::Join(join) => Ok(push_down_into_join(join, predicates)),
+            LogicalPlan::TableScan(scan) => {
+                let mut filters = scan.filters.clone();
+                for predicate in predicates {
+                    if !filters.contains(&predicate) {
+                        filters.push(predicate);
+                    }
+                }
+                Ok(Some(LogicalPlan::TableScan(TableScan {
+                    filters,
+                    ..scan.clone()
+                })))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Pushes predicates of a filter above the join into its inputs. Predicates referring to
+/// both sides of an inner join become part of its condition.
+fn push_down_into_join(join: &Join, predicates: Vec<Expr>) -> Option<LogicalPlan> {
+    let push_left = matches!(
+        join.join_type,
+        JoinType::Inner | JoinType::Cross | JoinType::LeftOuter
+    );
+    let push_right = matches!(
+        join.join_type,
+        JoinType::Inner | JoinType::Cross | JoinType::RightOuter
+    );
+    let into_condition = matches!(join.join_type, JoinType::Inner | JoinType::Cross);
+
+    let mut left = vec![];
+    let mut right = vec![];
+    let mut condition = join
+        .condition
+        .as_ref()
+        .map(split_conjunction)
+        .unwrap_or_default();
+    let mut kept = vec![];
+    for predicate in predicates {
+        if push_left && refers_only_to(&predicate, join.left.schema()) {
+            left.push(predicate);
+        } else if push_right && refers_only_to(&predicate, join.right.schema()) {
+            right.push(predicate);
+        } else if into_condition && refers_only_to(&predicate, &join.schema) {
+            condition.push(predicate);
+        } else {
+            kept.push(predicate);
+        }
+    }
+    let condition = conjunction(condition);
+    if left.is_empty() && right.is_empty() && condition == join.condition {
+        return None;
+    }
+
+    let join_type = if join.join_type == JoinType::Cross && condition.is_some() {
+        JoinType::Inner
+    } else {
+        join.join_type
+    };
+    let join = LogicalPlan::Join(Join {
+        left: Arc::new(with_filter(join.left.as_ref().clone(), left)),
+        right: Arc::new(with_filter(join.right.as_ref().clone(), right)),
+        join_type,
+        condition,
+        schema: join.schema.clone(),
+    });
+    Some(with_filter(join, kept))
+}
+
+/// Pushes predicates of a join condition which refer to one side only into that side,
+/// unless it's the preserved side of an outer join whose tuples are kept anyway.
+fn push_down_join_condition(join: &Join) -> Option<LogicalPlan> {
+    let condition = join.condition.as_ref()?;
+    let push_left = matches!(join.join_type, JoinType::Inner | JoinType::RightOuter);
+    let push_right = matches!(join.join_type, JoinType::Inner | JoinType::LeftOuter);
+
+    let mut left = vec![];
+    let mut right = vec![];
+    let mut kept = vec![];
+    for predicate in split_conjunction(condition) {
+        if push_left && refers_only_to(&predicate, join.left.schema()) {
+            left.push(predicate);
+        } else if push_right && refers_only_to(&predicate, join.right.schema()) {
+            right.push(predicate);
+        } else {
+            kept.push(predicate);
+        }
+    }
+    if left.is_empty() && right.is_empty() {
+        return None;
+    }
+    Some(LogicalPlan::Join(Join {
+        left: Arc::new(with_filter(join.left.as_ref().clone(), left)),
+        right: Arc::new(with_filter(join.right.as_ref().clone(), right)),
+        join_type: join.join_type,
+        condition: conjunction(kept),
+        schema: join.schema.clone(),
+    }))
+}
+
+/// Rewrites a predicate on the output of a projection to one on its input by replacing
+/// columns with the projected expressions. Returns `None` if a column can't be resolved.
+fn replace_projected_columns(predicate: &Expr, project: &Project) -> Option<Expr> {
+    let replaced = match predicate {
+        Expr::Column(column) => {
+            let idx = project
+                .schema
+                .index_of(column.relation.as_ref(), &column.name)
+                .ok()?;
+            match &project.exprs[idx] {
+                Expr::Alias(alias) => alias.expr.as_ref().clone(),
+                expr => expr.clone(),
+            }
+        }
+        Expr::Literal(_) => predicate.clone(),
+        Expr::Alias(alias) => replace_projected_columns(&alias.expr, project)?,
+        Expr::Cast(cast) => {
+            let mut cast = cast.clone();
+            cast.expr = Box::new(replace_projected_columns(&cast.expr, project)?);
+            Expr::Cast(cast)
+        }
+        Expr::Binary(binary) => {
+            let mut binary = binary.clone();
+            binary.left = Box::new(replace_projected_columns(&binary.left, project)?);
+            binary.right = Box::new(replace_projected_columns(&binary.right, project)?);
+            Expr::Binary(binary)
+        }
+        Expr::AggregateFunction(_) => return None,
+        Expr::ScalarFunction(func) => {
+            let mut func = func.clone();
+            func.args = func
+                .args
+                .iter()
+                .map(|arg| replace_projected_columns(arg, project))
+                .collect::<Option<Vec<_>>>()?;
+            Expr::ScalarFunction(func)
+        }
+    };
+    if matches!(replaced, Expr::AggregateFunction(_)) {
+        return None;
+    }
+    Some(replaced)
+}
+
+fn with_filter(input: LogicalPlan, predicates: Vec<Expr>) -> LogicalPlan {
+    match conjunction(predicates) {
+        Some(predicate) => LogicalPlan::Filter(Filter {
+            predicate,
+            input: Arc::new(input),
+        }),
+        None => input,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::optimizer::rule::PushDownFilter;
+    use crate::optimizer::LogicalOptimizer;
+    use crate::planner::logical_plan::{JoinType, LogicalPlan};
+    use crate::Database;
+    use std::sync::Arc;
+
+    fn build_optimizer() -> LogicalOptimizer {
+        LogicalOptimizer::with_rules(vec![Arc::new(PushDownFilter)])
+    }
+
+    fn optimize(db: &mut Database, sql: &str) -> LogicalPlan {
+        let plan = db.create_logical_plan(sql).unwrap();
+        build_optimizer().optimize(&plan).unwrap()
+    }
+
+    fn scan_filters(plan: &LogicalPlan) -> Vec<String> {
+        match plan {
+            LogicalPlan::TableScan(scan) => scan.filters.iter().map(|f| f.to_string()).collect(),
+            plan => panic!("{} should be table scan", plan),
+        }
+    }
+
+    #[test]
+    fn push_down_filter_to_table_scan() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+
+        let plan = optimize(&mut db, "select a from t1 where a > 1 and b < 2");
+        let LogicalPlan::Project(project) = plan else {
+            panic!("the first node should be project");
+        };
+        assert_eq!(scan_filters(&project.input), vec!["(a Gt 1)", "(b Lt 2)"]);
+
+        let plan = optimize(
+            &mut db,
+            "select * from (select a as x, b from t1) where x = 1 order by b",
+        );
+        let LogicalPlan::Sort(sort) = plan else {
+            panic!("the first node should be sort");
+        };
+        let LogicalPlan::Project(project) = sort.input.as_ref() else {
+            panic!("the second node should be project");
+        };
+        let LogicalPlan::Project(project) = project.input.as_ref() else {
+            panic!("the third node should be project");
+        };
+        assert_eq!(scan_filters(&project.input), vec!["(a Eq 1)"]);
+    }
+
+    #[test]
+    fn push_down_filter_into_join() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        db.run("create table t2 (a int, c int)").unwrap();
+
+        let plan = optimize(
+            &mut db,
+            "select * from t1, t2 where t1.a = t2.a and t1.b > 1 and t2.c < 2",
+        );
+        let LogicalPlan::Project(project) = plan else {
+            panic!("the first node should be project");
+        };
+        let LogicalPlan::Join(join) = project.input.as_ref() else {
+            panic!("the second node should be join");
+        };
+        assert_eq!(join.join_type, JoinType::Inner);
+        assert_eq!(
+            join.condition.as_ref().unwrap().to_string(),
+            "(t1.a Eq t2.a)"
+        );
+        assert_eq!(scan_filters(&join.left), vec!["(t1.b Gt 1)"]);
+        assert_eq!(scan_filters(&join.right), vec!["(t2.c Lt 2)"]);
+
+        // only the preserved side of an outer join takes predicates of the filter above,
+        // only the other side takes predicates of the join condition
+        let plan = optimize(
+            &mut db,
+            "select * from t1 left join t2 on t1.a = t2.a and t1.b = 1 and t2.c = 2 \
+            where t1.b > 1 and t2.c < 2",
+        );
+        let LogicalPlan::Project(project) = plan else {
+            panic!("the first node should be project");
+        };
+        let LogicalPlan::Filter(filter) = project.input.as_ref() else {
+            panic!("the second node should be filter");
+        };
+        assert_eq!(filter.predicate.to_string(), "(t2.c Lt 2)");
+        let LogicalPlan::Join(join) = filter.input.as_ref() else {
+            panic!("the third node should be join");
+        };
+        assert_eq!(
+            join.condition.as_ref().unwrap().to_string(),
+            "((t1.a Eq t2.a) And (t1.b Eq 1))"
+        );
+        assert_eq!(scan_filters(&join.left), vec!["(t1.b Gt 1)"]);
+        assert_eq!(scan_filters(&join.right), vec!["(t2.c Eq 2)"]);
+    }
+}
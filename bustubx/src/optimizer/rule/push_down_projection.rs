@@ -0,0 +1,244 @@
+use crate::catalog::{Column, Schema};
+use crate::expression::{collect_columns, ColumnExpr};
+use crate::optimizer::LogicalOptimizerRule;
+use crate::planner::logical_plan::{
+    build_join_schema, Aggregate, Filter, Join, Limit, LogicalPlan, Project, Sort, TableScan,
+};
+use crate::BustubxResult;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Computes the columns each node of the plan needs from its input, so that table
+/// scans only read the columns which are used by the query. Projections drop expressions
+/// which are not needed above them.
+pub struct PushDownProjection;
+
+impl LogicalOptimizerRule for PushDownProjection {
+    fn try_optimize(&self, plan: &LogicalPlan) -> BustubxResult<Option<LogicalPlan>> {
+        let required = output_columns(plan.schema());
+        prune_columns(plan, &required).map(Some)
+    }
+
+    fn name(&self) -> &str {
+        "PushDownProjection"
+    }
+}
+
+fn prune_columns(plan: &LogicalPlan, required: &HashSet<ColumnExpr>) -> BustubxResult<LogicalPlan> {
+    match plan {
+        LogicalPlan::Project(Project {
+            exprs,
+            input,
+            schema,
+        }) => {
+            let mut indices = (0..exprs.len())
+                .filter(|idx| is_required(&schema.columns[*idx], required))
+                .collect::<Vec<usize>>();
+            if indices.is_empty() {
+                indices.push(0);
+            }
+            let exprs = indices
+                .iter()
+                .map(|idx| exprs[*idx].clone())
+                .collect::<Vec<_>>();
+            let mut input_required = HashSet::new();
+            for expr in exprs.iter() {
+                collect_columns(expr, &mut input_required);
+            }
+            Ok(LogicalPlan::Project(Project {
+                exprs,
+                input: Arc::new(prune_columns(input, &input_required)?),
+                schema: Arc::new(schema.project(&indices)?),
+            }))
+        }
+        LogicalPlan::Filter(Filter { predicate, input }) => {
+            let mut input_required = required.clone();
+            collect_columns(predicate, &mut input_required);
+            Ok(LogicalPlan::Filter(Filter {
+                predicate: predicate.clone(),
+                input: Arc::new(prune_columns(input, &input_required)?),
+            }))
+        }
+        LogicalPlan::Sort(Sort {
+            order_by,
+            input,
+            limit,
+        }) => {
+            let mut input_required = required.clone();
+            for order_by_expr in order_by.iter() {
+                collect_columns(&order_by_expr.expr, &mut input_required);
+            }
+            Ok(LogicalPlan::Sort(Sort {
+                order_by: order_by.clone(),
+                input: Arc::new(prune_columns(input, &input_required)?),
+                limit: *limit,
+            }))
+        }
+        LogicalPlan::Limit(Limit {
+            limit,
+            offset,
+            input,
+        }) => Ok(LogicalPlan::Limit(Limit {
+            limit: *limit,
+            offset: *offset,
+            input: Arc::new(prune_columns(input, required)?),
+        })),
+        LogicalPlan::Join(Join {
+            left,
+            right,
+            join_type,
+            condition,
+            ..
+        }) => {
+            let mut input_required = required.clone();
+            if let Some(condition) = condition {
+                collect_columns(condition, &mut input_required);
+            }
+            let left = prune_columns(left, &input_required)?;
+            let right = prune_columns(right, &input_required)?;
+            let schema = build_join_schema(left.schema(), right.schema(), *join_type)?;
+            Ok(LogicalPlan::Join(Join {
+                left: Arc::new(left),
+                right: Arc::new(right),
+                join_type: *join_type,
+                condition: condition.clone(),
+                schema: Arc::new(schema),
+            }))
+        }
+        LogicalPlan::Aggregate(Aggregate {
+            input,
+            group_exprs,
+            aggr_exprs,
+            schema,
+        }) => {
+            let mut input_required = HashSet::new();
+            for expr in group_exprs.iter().chain(aggr_exprs.iter()) {
+                collect_columns(expr, &mut input_required);
+            }
+            Ok(LogicalPlan::Aggregate(Aggregate {
+                input: Arc::new(prune_columns(input, &input_required)?),
+                group_exprs: group_exprs.clone(),
+                aggr_exprs: aggr_exprs.clone(),
+                schema: schema.clone(),
+            }))
+        }
+        LogicalPlan::TableScan(scan) => {
+            let mut scan_required = required.clone();
+            for filter in scan.filters.iter() {
+                collect_columns(filter, &mut scan_required);
+            }
+            let mut projection = (0..scan.table_schema.column_count())
+                .filter(|idx| is_required(&scan.table_schema.columns[*idx], &scan_required))
+                .collect::<Vec<usize>>();
+            if projection.len() == scan.table_schema.column_count() {
+                return Ok(LogicalPlan::TableScan(TableScan {
+                    projection: None,
+                    projected_schema: scan.table_schema.clone(),
+                    ..scan.clone()
+                }));
+            }
+            if projection.is_empty() {
+                // keep one column to produce the right number of rows
+                projection.push(0);
+            }
+            Ok(LogicalPlan::TableScan(TableScan {
+                projected_schema: Arc::new(scan.table_schema.project(&projection)?),
+                projection: Some(projection),
+                ..scan.clone()
+            }))
+        }
+        _ => {
+            // other nodes need all columns of their inputs
+            let inputs = plan
+                .inputs()
+                .into_iter()
+                .map(|input| prune_columns(input, &output_columns(input.schema())))
+                .collect::<BustubxResult<Vec<_>>>()?;
+            if inputs.is_empty() {
+                Ok(plan.clone())
+            } else {
+                plan.with_new_inputs(&inputs)
+            }
+        }
+    }
+}
+
+fn output_columns(schema: &Schema) -> HashSet<ColumnExpr> {
+    schema
+        .columns
+        .iter()
+        .map(|col| ColumnExpr {
+            relation: col.relation.clone(),
+            name: col.name.clone(),
+        })
+        .collect()
+}
+
+fn is_required(column: &Column, required: &HashSet<ColumnExpr>) -> bool {
+    required.iter().any(|req| {
+        req.name == column.name
+            && match (&req.relation, &column.relation) {
+                (Some(req_relation), Some(relation)) => req_relation.resolved_eq(relation),
+                (Some(_), None) => false,
+                (None, _) => true,
+            }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::optimizer::rule::{PushDownFilter, PushDownProjection};
+    use crate::optimizer::LogicalOptimizer;
+    use crate::planner::logical_plan::LogicalPlan;
+    use crate::Database;
+    use std::sync::Arc;
+
+    fn build_optimizer() -> LogicalOptimizer {
+        LogicalOptimizer::with_rules(vec![Arc::new(PushDownFilter), Arc::new(PushDownProjection)])
+    }
+
+    fn scan_projections(plan: &LogicalPlan) -> Vec<Option<Vec<usize>>> {
+        match plan {
+            LogicalPlan::TableScan(scan) => vec![scan.projection.clone()],
+            plan => plan
+                .inputs()
+                .into_iter()
+                .flat_map(scan_projections)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn push_down_projection() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b varchar, c int)").unwrap();
+        db.run("create table t2 (a int, d varchar, e int)").unwrap();
+
+        let optimize = |db: &mut Database, sql: &str| {
+            let plan = db.create_logical_plan(sql).unwrap();
+            scan_projections(&build_optimizer().optimize(&plan).unwrap())
+        };
+
+        assert_eq!(optimize(&mut db, "select * from t1"), vec![None]);
+        assert_eq!(optimize(&mut db, "select c from t1"), vec![Some(vec![2])]);
+        assert_eq!(
+            optimize(&mut db, "select a from t1 where c > 1 order by a"),
+            vec![Some(vec![0, 2])]
+        );
+        assert_eq!(
+            optimize(
+                &mut db,
+                "select t2.e from t1 join t2 on t1.a = t2.a where t1.b = 'x'"
+            ),
+            vec![Some(vec![0, 1]), Some(vec![0, 2])]
+        );
+        assert_eq!(
+            optimize(&mut db, "select x from (select a as x, b, c + 1 from t1)"),
+            vec![Some(vec![0])]
+        );
+        assert_eq!(
+            optimize(&mut db, "select count(b) from t1 group by c"),
+            vec![Some(vec![1, 2])]
+        );
+    }
+}
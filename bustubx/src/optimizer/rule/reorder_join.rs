@@ -0,0 +1,477 @@
+use crate::catalog::{Schema, DEFAULT_SELECTIVITY};
+use crate::common::TableReference;
+use crate::expression::{collect_columns, conjunction, split_conjunction, ColumnExpr, Expr};
+use crate::optimizer::logical_optimizer::ApplyOrder;
+use crate::optimizer::{CostModel, LogicalOptimizerRule};
+use crate::planner::logical_plan::{
+    build_join_schema, Filter, Join, JoinType, LogicalPlan, Project, TableScan,
+};
+use crate::BustubxResult;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Joins of up to this many relations are ordered by dynamic programming, larger ones
+/// greedily.
+const MAX_DP_RELATIONS: usize = 10;
+
+/// Number of tuples assumed for relations without statistics.
+const DEFAULT_ROW_COUNT: f64 = 1000.0;
+
+/// Reorders trees of inner and cross joins, e.g. the ones built from the tables listed in
+/// `FROM`, so that the estimated number of intermediate tuples is the smallest.
+///
+/// The relations of a join tree are enumerated by dynamic programming over all subsets,
+/// or greedily by joining the pair with the smallest result first if there are too many.
+/// The predicates of the join conditions are attached to the lowest join which has all
+/// the columns they refer to, so cross joins become inner joins wherever a predicate
+/// connects both sides. The tree is only replaced if the new order is cheaper, and a
+/// projection on top keeps the columns in their original order.
+///
+/// Should run after [`PushDownFilter`](crate::optimizer::rule::PushDownFilter) has
+/// moved the predicates of `WHERE` into the join conditions.
+pub struct ReorderJoin;
+
+impl LogicalOptimizerRule for ReorderJoin {
+    fn try_optimize(&self, plan: &LogicalPlan) -> BustubxResult<Option<LogicalPlan>> {
+        let LogicalPlan::Join(join) = plan else {
+            return Ok(None);
+        };
+        if !is_reorderable(join) {
+            return Ok(None);
+        }
+
+        let mut relations = vec![];
+        let mut predicates = vec![];
+        let tree = flatten_join_tree(plan, &mut relations, &mut predicates);
+        // relations are tracked in the bits of an u64
+        if relations.len() < 3 || relations.len() > u64::BITS as usize {
+            return Ok(None);
+        }
+        let Some(graph) = JoinGraph::try_new(plan, relations, predicates) else {
+            return Ok(None);
+        };
+
+        let best = if graph.relations.len() <= MAX_DP_RELATIONS {
+            graph.dp_order()
+        } else {
+            graph.greedy_order()
+        };
+        // keep the current order unless the new one is clearly cheaper, which also keeps
+        // the rule from changing an already reordered tree in later passes
+        if graph.cost(&best) >= graph.cost(&tree) * (1.0 - 1e-9) {
+            return Ok(None);
+        }
+
+        let reordered = graph.build(&best)?;
+        if same_columns(reordered.schema(), &join.schema) {
+            return Ok(Some(reordered));
+        }
+        let exprs = join
+            .schema
+            .columns
+            .iter()
+            .map(|column| {
+                Expr::Column(ColumnExpr {
+                    relation: column.relation.clone(),
+                    name: column.name.clone(),
+                })
+            })
+            .collect();
+        Ok(Some(LogicalPlan::Project(Project {
+            exprs,
+            input: Arc::new(reordered),
+            schema: join.schema.clone(),
+        })))
+    }
+
+    fn name(&self) -> &str {
+        "ReorderJoin"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+/// Order of joining relations, leaves are indexes into [`JoinGraph::relations`].
+#[derive(Debug, Clone)]
+enum JoinTree {
+    Relation(usize),
+    Join(Box<JoinTree>, Box<JoinTree>),
+}
+
+impl JoinTree {
+    /// Bit set of the relations in the tree.
+    fn relations(&self) -> u64 {
+        match self {
+            JoinTree::Relation(idx) => 1 << idx,
+            JoinTree::Join(left, right) => left.relations() | right.relations(),
+        }
+    }
+}
+
+struct JoinGraph {
+    relations: Vec<LogicalPlan>,
+    rows: Vec<f64>,
+    /// Predicates with the bit set of the relations they refer to and their selectivity
+    predicates: Vec<(Expr, u64, f64)>,
+}
+
+impl JoinGraph {
+    /// Returns `None` if a column can't be told apart from columns of other relations, as
+    /// columns are resolved by name and the first match would change with the order.
+    fn try_new(plan: &LogicalPlan, relations: Vec<LogicalPlan>, exprs: Vec<Expr>) -> Option<Self> {
+        let schema = plan.schema();
+        if schema
+            .columns
+            .iter()
+            .any(|column| !is_unique(schema, column.relation.as_ref(), &column.name))
+        {
+            return None;
+        }
+
+        let all_relations = u64::MAX >> (u64::BITS as usize - relations.len());
+        let mut predicates = vec![];
+        for expr in exprs {
+            let mut columns = HashSet::new();
+            collect_columns(&expr, &mut columns);
+            let mut refers_to = 0;
+            for column in columns {
+                if !is_unique(schema, column.relation.as_ref(), &column.name) {
+                    return None;
+                }
+                refers_to |= 1
+                    << relations.iter().position(|relation| {
+                        relation
+                            .schema()
+                            .index_of(column.relation.as_ref(), &column.name)
+                            .is_ok()
+                    })?;
+            }
+            if refers_to == 0 {
+                refers_to = all_relations;
+            }
+            let selectivity = CostModel.selectivity(&expr, plan);
+            predicates.push((expr, refers_to, selectivity));
+        }
+        let rows = relations.iter().map(estimate_rows).collect();
+        Some(Self {
+            relations,
+            rows,
+            predicates,
+        })
+    }
+
+    /// Estimated number of tuples produced by joining the relations of the bit set.
+    fn rows(&self, relations: u64) -> f64 {
+        let mut rows = 1.0;
+        for (idx, relation_rows) in self.rows.iter().enumerate() {
+            if relations & (1 << idx) != 0 {
+                rows *= relation_rows;
+            }
+        }
+        for (_, refers_to, selectivity) in self.predicates.iter() {
+            if refers_to & !relations == 0 {
+                rows *= selectivity;
+            }
+        }
+        rows
+    }
+
+    /// Total number of tuples produced by the joins of the tree.
+    fn cost(&self, tree: &JoinTree) -> f64 {
+        match tree {
+            JoinTree::Relation(_) => 0.0,
+            JoinTree::Join(left, right) => {
+                self.rows(tree.relations()) + self.cost(left) + self.cost(right)
+            }
+        }
+    }
+
+    /// Finds the cheapest tree by building the cheapest join of every subset of relations
+    /// from the cheapest joins of its smaller subsets.
+    fn dp_order(&self) -> JoinTree {
+        let count = self.relations.len();
+        // cheapest cost and left side of the join of each subset
+        let mut best: Vec<(f64, u64)> = vec![(0.0, 0); 1 << count];
+        for relations in 1..(1u64 << count) {
+            if relations.count_ones() < 2 {
+                continue;
+            }
+            let lowest = relations & relations.wrapping_neg();
+            let rows = self.rows(relations);
+            let mut left = (relations - 1) & relations;
+            while left > 0 {
+                // the left side keeps the first relation, so that the textual order wins
+                // among equally cheap trees
+                if left & lowest != 0 {
+                    let right = relations & !left;
+                    let cost = rows + best[left as usize].0 + best[right as usize].0;
+                    let current = &mut best[relations as usize];
+                    if current.1 == 0 || cost < current.0 {
+                        *current = (cost, left);
+                    }
+                }
+                left = (left - 1) & relations;
+            }
+        }
+
+        fn build(best: &[(f64, u64)], relations: u64) -> JoinTree {
+            if relations.count_ones() == 1 {
+                return JoinTree::Relation(relations.trailing_zeros() as usize);
+            }
+            let left = best[relations as usize].1;
+            JoinTree::Join(
+                Box::new(build(best, left)),
+                Box::new(build(best, relations & !left)),
+            )
+        }
+        build(&best, (1 << count) - 1)
+    }
+
+    /// Repeatedly joins the two trees whose join produces the fewest tuples.
+    fn greedy_order(&self) -> JoinTree {
+        let mut trees = (0..self.relations.len())
+            .map(JoinTree::Relation)
+            .collect::<Vec<_>>();
+        while trees.len() > 1 {
+            let mut best: Option<(f64, usize, usize)> = None;
+            for i in 0..trees.len() {
+                for j in (i + 1)..trees.len() {
+                    let rows = self.rows(trees[i].relations() | trees[j].relations());
+                    if best.is_none_or(|(best_rows, ..)| rows < best_rows) {
+                        best = Some((rows, i, j));
+                    }
+                }
+            }
+            let (_, i, j) = best.expect("at least two trees");
+            let right = trees.remove(j);
+            let left = trees.remove(i);
+            trees.insert(i, JoinTree::Join(Box::new(left), Box::new(right)));
+        }
+        trees.remove(0)
+    }
+
+    fn build(&self, tree: &JoinTree) -> BustubxResult<LogicalPlan> {
+        let relations = tree.relations();
+        let mut predicates = self
+            .predicates
+            .iter()
+            .filter(|(_, refers_to, _)| refers_to & !relations == 0);
+        match tree {
+            JoinTree::Relation(idx) => {
+                let relation = self.relations[*idx].clone();
+                let predicates = predicates.map(|(expr, ..)| expr.clone());
+                Ok(match conjunction(predicates) {
+                    Some(predicate) => LogicalPlan::Filter(Filter {
+                        predicate,
+                        input: Arc::new(relation),
+                    }),
+                    None => relation,
+                })
+            }
+            JoinTree::Join(left_tree, right_tree) => {
+                let left_relations = left_tree.relations();
+                let right_relations = right_tree.relations();
+                let condition = conjunction(
+                    predicates
+                        .by_ref()
+                        .filter(|(_, refers_to, _)| {
+                            refers_to & !left_relations != 0 && refers_to & !right_relations != 0
+                        })
+                        .map(|(expr, ..)| expr.clone()),
+                );
+                let left = self.build(left_tree)?;
+                let right = self.build(right_tree)?;
+                let join_type = if condition.is_some() {
+                    JoinType::Inner
+                } else {
+                    JoinType::Cross
+                };
+                let schema = build_join_schema(left.schema(), right.schema(), join_type)?;
+                Ok(LogicalPlan::Join(Join {
+                    left: Arc::new(left),
+                    right: Arc::new(right),
+                    join_type,
+                    condition,
+                    schema: Arc::new(schema),
+                }))
+            }
+        }
+    }
+}
+
+fn is_reorderable(join: &Join) -> bool {
+    matches!(join.join_type, JoinType::Inner | JoinType::Cross)
+}
+
+/// Collects the relations joined by a tree of inner and cross joins and the predicates of
+/// their conditions.
+fn flatten_join_tree(
+    plan: &LogicalPlan,
+    relations: &mut Vec<LogicalPlan>,
+    predicates: &mut Vec<Expr>,
+) -> JoinTree {
+    match plan {
+        LogicalPlan::Join(join) if is_reorderable(join) => {
+            if let Some(condition) = &join.condition {
+                predicates.extend(split_conjunction(condition));
+            }
+            let left = flatten_join_tree(&join.left, relations, predicates);
+            let right = flatten_join_tree(&join.right, relations, predicates);
+            JoinTree::Join(Box::new(left), Box::new(right))
+        }
+        _ => {
+            relations.push(plan.clone());
+            JoinTree::Relation(relations.len() - 1)
+        }
+    }
+}
+
+/// Estimated number of tuples of a relation, falls back to defaults without statistics.
+fn estimate_rows(plan: &LogicalPlan) -> f64 {
+    if let Some(rows) = CostModel.estimate_rows(plan) {
+        return rows;
+    }
+    match plan {
+        LogicalPlan::TableScan(TableScan { filters, limit, .. }) => {
+            let rows = DEFAULT_ROW_COUNT * DEFAULT_SELECTIVITY.powi(filters.len() as i32);
+            limit.map_or(rows, |limit| rows.min(limit as f64))
+        }
+        LogicalPlan::Filter(Filter { input, .. }) => estimate_rows(input) * DEFAULT_SELECTIVITY,
+        _ => DEFAULT_ROW_COUNT,
+    }
+}
+
+/// Whether exactly one column of the schema matches the relation and name.
+fn is_unique(schema: &Schema, relation: Option<&TableReference>, name: &str) -> bool {
+    schema
+        .columns
+        .iter()
+        .filter(|column| match (relation, &column.relation) {
+            (Some(relation), Some(column_relation)) => {
+                relation.resolved_eq(column_relation) && name == column.name
+            }
+            (Some(_), None) => false,
+            (None, _) => name == column.name,
+        })
+        .count()
+        == 1
+}
+
+fn same_columns(left: &Schema, right: &Schema) -> bool {
+    left.columns.len() == right.columns.len()
+        && left
+            .columns
+            .iter()
+            .zip(right.columns.iter())
+            .all(|(l, r)| l.relation == r.relation && l.name == r.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::optimizer::rule::{PushDownFilter, ReorderJoin};
+    use crate::optimizer::LogicalOptimizer;
+    use crate::planner::logical_plan::{JoinType, LogicalPlan};
+    use crate::Database;
+    use std::sync::Arc;
+
+    fn optimize(db: &mut Database, sql: &str) -> LogicalPlan {
+        let plan = db.create_logical_plan(sql).unwrap();
+        LogicalOptimizer::with_rules(vec![Arc::new(PushDownFilter), Arc::new(ReorderJoin)])
+            .optimize(&plan)
+            .unwrap()
+    }
+
+    /// Formats the join tree with the names of the scanned tables, e.g. `((t1 t2) t3)`.
+    fn join_order(plan: &LogicalPlan) -> String {
+        match plan {
+            LogicalPlan::Join(join) => {
+                format!("({} {})", join_order(&join.left), join_order(&join.right))
+            }
+            LogicalPlan::TableScan(scan) => scan.table_ref.table().to_string(),
+            plan => join_order(plan.inputs()[0]),
+        }
+    }
+
+    fn find_joins(plan: &LogicalPlan, joins: &mut Vec<(JoinType, Option<String>)>) {
+        if let LogicalPlan::Join(join) = plan {
+            joins.push((
+                join.join_type,
+                join.condition.as_ref().map(|c| c.to_string()),
+            ));
+        }
+        for input in plan.inputs() {
+            find_joins(input, joins);
+        }
+    }
+
+    #[test]
+    fn reorder_join_avoids_cross_join() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        db.run("create table t2 (b int, c int)").unwrap();
+        db.run("create table t3 (c int, d int)").unwrap();
+
+        let plan = optimize(
+            &mut db,
+            "select * from t1, t3, t2 where t1.b = t2.b and t2.c = t3.c",
+        );
+        assert_eq!(join_order(&plan), "((t1 t2) t3)");
+        let mut joins = vec![];
+        find_joins(&plan, &mut joins);
+        assert_eq!(
+            joins,
+            vec![
+                (JoinType::Inner, Some("(t2.c Eq t3.c)".to_string())),
+                (JoinType::Inner, Some("(t1.b Eq t2.b)".to_string())),
+            ]
+        );
+        // columns keep the order of the tables in FROM
+        let LogicalPlan::Project(project) = &plan else {
+            panic!("the first node should be project");
+        };
+        let LogicalPlan::Project(project) = project.input.as_ref() else {
+            panic!("the second node should be project");
+        };
+        assert_eq!(
+            project
+                .exprs
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>(),
+            vec!["t1.a", "t1.b", "t3.c", "t3.d", "t2.b", "t2.c"]
+        );
+
+        // the order is kept if there is nothing to gain
+        let plan = optimize(
+            &mut db,
+            "select * from t1, t2, t3 where t1.b = t2.b and t2.c = t3.c",
+        );
+        assert_eq!(join_order(&plan), "((t1 t2) t3)");
+    }
+
+    #[test]
+    fn reorder_join_by_statistics() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int)").unwrap();
+        db.run("create table t2 (a int, b int)").unwrap();
+        db.run("create table t3 (b int)").unwrap();
+        for i in 0..50 {
+            db.run(&format!("insert into t1 values ({i})")).unwrap();
+            db.run(&format!("insert into t2 values ({i}, {})", i % 5))
+                .unwrap();
+        }
+        db.run("insert into t3 values (1)").unwrap();
+        db.run("analyze table t1").unwrap();
+        db.run("analyze table t2").unwrap();
+        db.run("analyze table t3").unwrap();
+
+        // joining the single tuple of t3 first keeps the intermediate result small
+        let plan = optimize(
+            &mut db,
+            "select * from t1, t2, t3 where t1.a = t2.a and t2.b = t3.b",
+        );
+        assert_eq!(join_order(&plan), "(t1 (t2 t3))");
+    }
+}
@@ -1,11 +1,108 @@
 use crate::error::BustubxResult;
-use sqlparser::{ast::Statement, dialect::PostgreSqlDialect, parser::Parser};
+use sqlparser::{
+    ast::{Expr, Query, SetExpr, Statement, Values},
+    dialect::PostgreSqlDialect,
+    keywords::Keyword,
+    parser::{Parser, ParserError},
+    tokenizer::Token,
+};
 
+/// Parses statements like `Parser::parse_sql`, and `INSERT ... DEFAULT VALUES` which
+/// sqlparser doesn't support. It becomes an insert of a single empty row.
 pub fn parse_sql(sql: &str) -> BustubxResult<Vec<Statement>> {
-    let stmts = Parser::parse_sql(&PostgreSqlDialect {}, sql)?;
+    let dialect = PostgreSqlDialect {};
+    let mut parser = Parser::new(&dialect).try_with_sql(sql)?;
+    let mut stmts = Vec::new();
+    let mut expecting_statement_delimiter = false;
+    loop {
+        // ignore empty statements (between successive statement delimiters)
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_statement_delimiter = false;
+        }
+        if parser.peek_token() == Token::EOF {
+            break;
+        }
+        if expecting_statement_delimiter {
+            parser.expected::<()>("end of statement", parser.peek_token())?;
+        }
+
+        let stmt = if is_insert_default_values(&parser) {
+            parse_insert_default_values(&mut parser)?
+        } else {
+            parser.parse_statement()?
+        };
+        stmts.push(stmt);
+        expecting_statement_delimiter = true;
+    }
     Ok(stmts)
 }
 
+pub fn parse_expr(sql: &str) -> BustubxResult<Expr> {
+    let expr = Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(sql)?
+        .parse_expr()?;
+    Ok(expr)
+}
+
+fn is_keyword(token: &Token, keyword: Keyword) -> bool {
+    matches!(token, Token::Word(word) if word.keyword == keyword)
+}
+
+/// Looks ahead for `INSERT [INTO] <name> DEFAULT VALUES` without consuming tokens.
+fn is_insert_default_values(parser: &Parser) -> bool {
+    if !is_keyword(&parser.peek_nth_token(0).token, Keyword::INSERT) {
+        return false;
+    }
+    let mut n = 1;
+    if is_keyword(&parser.peek_nth_token(n).token, Keyword::INTO) {
+        n += 1;
+    }
+    loop {
+        if !matches!(parser.peek_nth_token(n).token, Token::Word(_)) {
+            return false;
+        }
+        n += 1;
+        if parser.peek_nth_token(n).token != Token::Period {
+            break;
+        }
+        n += 1;
+    }
+    is_keyword(&parser.peek_nth_token(n).token, Keyword::DEFAULT)
+        && is_keyword(&parser.peek_nth_token(n + 1).token, Keyword::VALUES)
+}
+
+fn parse_insert_default_values(parser: &mut Parser) -> Result<Statement, ParserError> {
+    parser.expect_keyword(Keyword::INSERT)?;
+    let into = parser.parse_keyword(Keyword::INTO);
+    let table_name = parser.parse_object_name()?;
+    parser.expect_keywords(&[Keyword::DEFAULT, Keyword::VALUES])?;
+    let source = Query {
+        with: None,
+        body: Box::new(SetExpr::Values(Values {
+            explicit_row: false,
+            rows: vec![vec![]],
+        })),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+        fetch: None,
+        locks: vec![],
+    };
+    Ok(Statement::Insert {
+        or: None,
+        into,
+        table_name,
+        columns: vec![],
+        overwrite: false,
+        source: Box::new(source),
+        partitioned: None,
+        after_columns: vec![],
+        table: false,
+        on: None,
+        returning: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -15,4 +112,22 @@ mod tests {
         let stmts = super::parse_sql(sql).unwrap();
         println!("{:#?}", stmts[0]);
     }
+
+    #[test]
+    pub fn test_parse_insert_default_values() {
+        let stmts = super::parse_sql("insert into s.t1 default values; select 1").unwrap();
+        assert_eq!(stmts.len(), 2);
+        let sqlparser::ast::Statement::Insert {
+            table_name, source, ..
+        } = &stmts[0]
+        else {
+            panic!("not an insert: {}", stmts[0]);
+        };
+        assert_eq!(table_name.to_string(), "s.t1");
+        assert!(matches!(
+            source.body.as_ref(),
+            sqlparser::ast::SetExpr::Values(values) if values.rows == vec![vec![]]
+        ));
+        assert!(super::parse_sql("insert into t1 default").is_err());
+    }
 }
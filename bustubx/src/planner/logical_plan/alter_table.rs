@@ -0,0 +1,37 @@
+use crate::catalog::Column;
+use crate::common::TableReference;
+
+#[derive(derive_new::new, Debug, Clone)]
+pub struct AlterTable {
+    pub table: TableReference,
+    pub operation: AlterTableOperation,
+}
+
+#[derive(Debug, Clone)]
+pub enum AlterTableOperation {
+    AddColumn { column: Column, if_not_exists: bool },
+    DropColumn { name: String, if_exists: bool },
+    RenameColumn { old_name: String, new_name: String },
+    RenameTable { new_name: String },
+}
+
+impl std::fmt::Display for AlterTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AlterTable: {} {}", self.table, self.operation)
+    }
+}
+
+impl std::fmt::Display for AlterTableOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlterTableOperation::AddColumn { column, .. } => {
+                write!(f, "AddColumn {}", column.name)
+            }
+            AlterTableOperation::DropColumn { name, .. } => write!(f, "DropColumn {name}"),
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                write!(f, "RenameColumn {old_name} To {new_name}")
+            }
+            AlterTableOperation::RenameTable { new_name } => write!(f, "RenameTable To {new_name}"),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use crate::catalog::SchemaRef;
+use crate::common::TableReference;
+
+#[derive(derive_new::new, Debug, Clone)]
+pub struct Analyze {
+    pub table: TableReference,
+    pub table_schema: SchemaRef,
+}
+
+impl std::fmt::Display for Analyze {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Analyze: {}", self.table)
+    }
+}
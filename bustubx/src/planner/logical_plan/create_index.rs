@@ -8,6 +8,8 @@ pub struct CreateIndex {
     pub table: TableReference,
     pub table_schema: SchemaRef,
     pub columns: Vec<OrderByExpr>,
+    /// Unique indexes are created as a `UNIQUE` constraint of the table.
+    pub unique: bool,
 }
 
 impl std::fmt::Display for CreateIndex {
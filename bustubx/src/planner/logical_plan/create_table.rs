@@ -1,10 +1,11 @@
-use crate::catalog::Column;
+use crate::catalog::{Column, Constraint};
 use crate::common::TableReference;
 
 #[derive(Debug, Clone)]
 pub struct CreateTable {
     pub name: TableReference,
     pub columns: Vec<Column>,
+    pub constraints: Vec<Constraint>,
 }
 
 impl std::fmt::Display for CreateTable {
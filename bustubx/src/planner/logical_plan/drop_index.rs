@@ -0,0 +1,13 @@
+#[derive(derive_new::new, Debug, Clone)]
+pub struct DropIndex {
+    pub index_name: String,
+    /// Schema of the indexed table, the default schema if `None`
+    pub schema_name: Option<String>,
+    pub if_exists: bool,
+}
+
+impl std::fmt::Display for DropIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DropIndex: {}", self.index_name)
+    }
+}
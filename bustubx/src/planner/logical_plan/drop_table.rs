@@ -0,0 +1,13 @@
+use crate::common::TableReference;
+
+#[derive(derive_new::new, Debug, Clone)]
+pub struct DropTable {
+    pub table: TableReference,
+    pub if_exists: bool,
+}
+
+impl std::fmt::Display for DropTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DropTable: {}", self.table)
+    }
+}
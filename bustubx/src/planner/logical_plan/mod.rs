@@ -1,12 +1,18 @@
 mod aggregate;
+mod alter_table;
+mod analyze;
 mod create_index;
 mod create_table;
+mod delete;
+mod drop_index;
+mod drop_table;
 mod empty_relation;
 mod filter;
 mod insert;
 mod join;
 mod limit;
 mod project;
+mod set_operation;
 mod sort;
 mod table_scan;
 mod update;
@@ -14,14 +20,20 @@ mod util;
 mod values;
 
 pub use aggregate::Aggregate;
+pub use alter_table::{AlterTable, AlterTableOperation};
+pub use analyze::Analyze;
 pub use create_index::CreateIndex;
 pub use create_table::CreateTable;
+pub use delete::Delete;
+pub use drop_index::DropIndex;
+pub use drop_table::DropTable;
 pub use empty_relation::EmptyRelation;
 pub use filter::Filter;
 pub use insert::Insert;
 pub use join::{Join, JoinType};
 pub use limit::Limit;
 pub use project::Project;
+pub use set_operation::{SetOperation, SetOperator};
 pub use sort::{OrderByExpr, Sort};
 pub use table_scan::TableScan;
 pub use update::Update;
@@ -29,7 +41,8 @@ pub use util::*;
 pub use values::Values;
 
 use crate::catalog::{
-    SchemaRef, EMPTY_SCHEMA_REF, INSERT_OUTPUT_SCHEMA_REF, UPDATE_OUTPUT_SCHEMA_REF,
+    SchemaRef, DELETE_OUTPUT_SCHEMA_REF, EMPTY_SCHEMA_REF, INSERT_OUTPUT_SCHEMA_REF,
+    UPDATE_OUTPUT_SCHEMA_REF,
 };
 use crate::{BustubxError, BustubxResult};
 use std::sync::Arc;
@@ -49,6 +62,12 @@ pub enum LogicalPlan {
     EmptyRelation(EmptyRelation),
     Aggregate(Aggregate),
     Update(Update),
+    Delete(Delete),
+    Analyze(Analyze),
+    DropTable(DropTable),
+    DropIndex(DropIndex),
+    AlterTable(AlterTable),
+    SetOperation(SetOperation),
 }
 
 impl LogicalPlan {
@@ -61,12 +80,20 @@ impl LogicalPlan {
             LogicalPlan::Join(Join { schema, .. }) => schema,
             LogicalPlan::Limit(Limit { input, .. }) => input.schema(),
             LogicalPlan::Project(Project { schema, .. }) => schema,
-            LogicalPlan::TableScan(TableScan { table_schema, .. }) => table_schema,
+            LogicalPlan::TableScan(TableScan {
+                projected_schema, ..
+            }) => projected_schema,
             LogicalPlan::Sort(Sort { input, .. }) => input.schema(),
             LogicalPlan::Values(Values { schema, .. }) => schema,
             LogicalPlan::EmptyRelation(EmptyRelation { schema, .. }) => schema,
             LogicalPlan::Aggregate(Aggregate { schema, .. }) => schema,
             LogicalPlan::Update(_) => &UPDATE_OUTPUT_SCHEMA_REF,
+            LogicalPlan::Delete(_) => &DELETE_OUTPUT_SCHEMA_REF,
+            LogicalPlan::Analyze(_) => &EMPTY_SCHEMA_REF,
+            LogicalPlan::DropTable(_) => &EMPTY_SCHEMA_REF,
+            LogicalPlan::DropIndex(_) => &EMPTY_SCHEMA_REF,
+            LogicalPlan::AlterTable(_) => &EMPTY_SCHEMA_REF,
+            LogicalPlan::SetOperation(SetOperation { schema, .. }) => schema,
         }
     }
 
@@ -79,11 +106,17 @@ impl LogicalPlan {
             LogicalPlan::Project(Project { input, .. }) => vec![input],
             LogicalPlan::Sort(Sort { input, .. }) => vec![input],
             LogicalPlan::Aggregate(Aggregate { input, .. }) => vec![input],
+            LogicalPlan::SetOperation(SetOperation { left, right, .. }) => vec![left, right],
             LogicalPlan::CreateTable(_)
             | LogicalPlan::CreateIndex(_)
             | LogicalPlan::TableScan(_)
             | LogicalPlan::Values(_)
             | LogicalPlan::Update(_)
+            | LogicalPlan::Delete(_)
+            | LogicalPlan::Analyze(_)
+            | LogicalPlan::DropTable(_)
+            | LogicalPlan::DropIndex(_)
+            | LogicalPlan::AlterTable(_)
             | LogicalPlan::EmptyRelation(_) => vec![],
         }
     }
@@ -144,7 +177,7 @@ impl LogicalPlan {
                 ),
                 right: Arc::new(
                     inputs
-                        .first()
+                        .get(1)
                         .ok_or_else(|| {
                             BustubxError::Internal(format!(
                                 "inputs {:?} should have at least two",
@@ -227,11 +260,45 @@ impl LogicalPlan {
                         .clone(),
                 ),
             })),
+            LogicalPlan::SetOperation(SetOperation {
+                op, all, schema, ..
+            }) => Ok(LogicalPlan::SetOperation(SetOperation {
+                left: Arc::new(
+                    inputs
+                        .first()
+                        .ok_or_else(|| {
+                            BustubxError::Internal(format!(
+                                "inputs {:?} should have at least two",
+                                inputs
+                            ))
+                        })?
+                        .clone(),
+                ),
+                right: Arc::new(
+                    inputs
+                        .get(1)
+                        .ok_or_else(|| {
+                            BustubxError::Internal(format!(
+                                "inputs {:?} should have at least two",
+                                inputs
+                            ))
+                        })?
+                        .clone(),
+                ),
+                op: *op,
+                all: *all,
+                schema: schema.clone(),
+            })),
             LogicalPlan::CreateTable(_)
             | LogicalPlan::CreateIndex(_)
             | LogicalPlan::TableScan(_)
             | LogicalPlan::Values(_)
             | LogicalPlan::Update(_)
+            | LogicalPlan::Delete(_)
+            | LogicalPlan::Analyze(_)
+            | LogicalPlan::DropTable(_)
+            | LogicalPlan::DropIndex(_)
+            | LogicalPlan::AlterTable(_)
             | LogicalPlan::EmptyRelation(_) => Ok(self.clone()),
         }
     }
@@ -253,6 +320,12 @@ impl std::fmt::Display for LogicalPlan {
             LogicalPlan::EmptyRelation(v) => write!(f, "{v}"),
             LogicalPlan::Aggregate(v) => write!(f, "{v}"),
             LogicalPlan::Update(v) => write!(f, "{v}"),
+            LogicalPlan::Delete(v) => write!(f, "{v}"),
+            LogicalPlan::Analyze(v) => write!(f, "{v}"),
+            LogicalPlan::DropTable(v) => write!(f, "{v}"),
+            LogicalPlan::DropIndex(v) => write!(f, "{v}"),
+            LogicalPlan::AlterTable(v) => write!(f, "{v}"),
+            LogicalPlan::SetOperation(v) => write!(f, "{v}"),
         }
     }
 }
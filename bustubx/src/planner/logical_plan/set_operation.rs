@@ -0,0 +1,41 @@
+use crate::catalog::SchemaRef;
+use crate::planner::logical_plan::LogicalPlan;
+use std::sync::Arc;
+
+/// Combines the tuples of two inputs with the same number of columns, the columns of
+/// both inputs have the types of the output schema.
+#[derive(derive_new::new, Debug, Clone)]
+pub struct SetOperation {
+    pub left: Arc<LogicalPlan>,
+    pub right: Arc<LogicalPlan>,
+    pub op: SetOperator,
+    /// Whether duplicates are kept, otherwise each tuple is output once
+    pub all: bool,
+    pub schema: SchemaRef,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOperator {
+    // select ... union [all] select ...
+    Union,
+    // select ... intersect [all] select ...
+    Intersect,
+    // select ... except [all] select ...
+    Except,
+}
+
+impl std::fmt::Display for SetOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.op)?;
+        if self.all {
+            write!(f, " All")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for SetOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
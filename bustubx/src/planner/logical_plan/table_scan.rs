@@ -1,17 +1,48 @@
-use crate::catalog::SchemaRef;
+use crate::catalog::{SchemaRef, TableStatistics};
 use crate::common::TableReference;
 use crate::expression::Expr;
+use std::sync::Arc;
 
 #[derive(derive_new::new, Debug, Clone)]
 pub struct TableScan {
     pub table_ref: TableReference,
     pub table_schema: SchemaRef,
+    /// Indices of the columns to read, all columns if `None`
+    pub projection: Option<Vec<usize>>,
+    /// Schema of the columns to read
+    pub projected_schema: SchemaRef,
     pub filters: Vec<Expr>,
     pub limit: Option<usize>,
+    /// Statistics collected by `ANALYZE`, `None` if the table was never analyzed
+    pub statistics: Option<Arc<TableStatistics>>,
 }
 
 impl std::fmt::Display for TableScan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "TableScan: {}", self.table_ref)
+        write!(f, "TableScan: {}", self.table_ref)?;
+        if self.projection.is_some() {
+            write!(
+                f,
+                " Projection {}",
+                self.projected_schema
+                    .columns
+                    .iter()
+                    .map(|col| col.name.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        if !self.filters.is_empty() {
+            write!(
+                f,
+                " Filters {}",
+                self.filters
+                    .iter()
+                    .map(|e| format!("{e}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        Ok(())
     }
 }
@@ -1,6 +1,10 @@
+use crate::catalog::DataType;
+use crate::common::temporal::Interval;
 use crate::common::{ScalarValue, TableReference};
-use crate::expression::{AggregateFunction, BinaryExpr, ColumnExpr, Expr, Literal};
-use crate::function::AggregateFunctionKind;
+use crate::expression::{
+    AggregateFunction, BinaryExpr, BinaryOp, Cast, ColumnExpr, Expr, Literal, ScalarFunction,
+};
+use crate::function::{AggregateFunctionKind, ScalarFunctionKind, TrimSide};
 use crate::planner::LogicalPlanner;
 use crate::{BustubxError, BustubxResult};
 
@@ -20,6 +24,44 @@ impl LogicalPlanner<'_> {
                     right,
                 }))
             }
+            sqlparser::ast::Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let expr = self.bind_expr(expr)?;
+                let (low_op, high_op, op) = if *negated {
+                    (BinaryOp::Lt, BinaryOp::Gt, BinaryOp::Or)
+                } else {
+                    (BinaryOp::GtEq, BinaryOp::LtEq, BinaryOp::And)
+                };
+                Ok(Expr::Binary(BinaryExpr {
+                    left: Box::new(Expr::Binary(BinaryExpr {
+                        left: Box::new(expr.clone()),
+                        op: low_op,
+                        right: Box::new(self.bind_expr(low)?),
+                    })),
+                    op,
+                    right: Box::new(Expr::Binary(BinaryExpr {
+                        left: Box::new(expr),
+                        op: high_op,
+                        right: Box::new(self.bind_expr(high)?),
+                    })),
+                }))
+            }
+            sqlparser::ast::Expr::UnaryOp { op, expr } => match (op, expr.as_ref()) {
+                (sqlparser::ast::UnaryOperator::Plus, _) => self.bind_expr(expr),
+                (
+                    sqlparser::ast::UnaryOperator::Minus,
+                    sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(s, long)),
+                ) => self.bind_value(&sqlparser::ast::Value::Number(format!("-{s}"), *long)),
+                _ => Err(BustubxError::NotSupport(format!(
+                    "sqlparser expr {} not supported",
+                    sql
+                ))),
+            },
+            sqlparser::ast::Expr::Nested(expr) => self.bind_expr(expr),
             sqlparser::ast::Expr::Value(value) => self.bind_value(value),
             sqlparser::ast::Expr::CompoundIdentifier(idents) => match idents.as_slice() {
                 [col] => Ok(Expr::Column(ColumnExpr {
@@ -51,6 +93,102 @@ impl LogicalPlanner<'_> {
                 ))),
             },
             sqlparser::ast::Expr::Function(function) => self.bind_function(function),
+            sqlparser::ast::Expr::Cast { expr, data_type } => Ok(Expr::Cast(Cast {
+                expr: Box::new(self.bind_expr(expr)?),
+                data_type: data_type.try_into()?,
+            })),
+            sqlparser::ast::Expr::TypedString { data_type, value } => Ok(Expr::Literal(Literal {
+                value: ScalarValue::Varchar(Some(value.clone()))
+                    .cast_to(&DataType::try_from(data_type)?)?,
+            })),
+            sqlparser::ast::Expr::Interval(interval) => self.bind_interval(interval),
+            sqlparser::ast::Expr::Extract { field, expr } => {
+                Ok(Expr::ScalarFunction(ScalarFunction {
+                    func_kind: ScalarFunctionKind::DatePart,
+                    args: vec![
+                        Expr::Literal(Literal {
+                            value: field.to_string().into(),
+                        }),
+                        self.bind_expr(expr)?,
+                    ],
+                }))
+            }
+            sqlparser::ast::Expr::Substring {
+                expr,
+                substring_from,
+                substring_for,
+            } => {
+                let mut args = vec![
+                    self.bind_expr(expr)?,
+                    match substring_from {
+                        Some(from) => self.bind_expr(from)?,
+                        None => Expr::Literal(Literal { value: 1i64.into() }),
+                    },
+                ];
+                if let Some(count) = substring_for {
+                    args.push(self.bind_expr(count)?);
+                }
+                Ok(Expr::ScalarFunction(ScalarFunction {
+                    func_kind: ScalarFunctionKind::Substr,
+                    args,
+                }))
+            }
+            sqlparser::ast::Expr::Trim {
+                expr,
+                trim_where,
+                trim_what,
+            } => {
+                let side = match trim_where {
+                    None | Some(sqlparser::ast::TrimWhereField::Both) => TrimSide::Both,
+                    Some(sqlparser::ast::TrimWhereField::Leading) => TrimSide::Leading,
+                    Some(sqlparser::ast::TrimWhereField::Trailing) => TrimSide::Trailing,
+                };
+                let mut args = vec![self.bind_expr(expr)?];
+                if let Some(chars) = trim_what {
+                    args.push(self.bind_expr(chars)?);
+                }
+                Ok(Expr::ScalarFunction(ScalarFunction {
+                    func_kind: ScalarFunctionKind::trim(side),
+                    args,
+                }))
+            }
+            sqlparser::ast::Expr::ArrayAgg(array_agg) => {
+                if array_agg.order_by.is_some() || array_agg.limit.is_some() {
+                    return Err(BustubxError::NotSupport(format!(
+                        "sqlparser expr {} not supported",
+                        sql
+                    )));
+                }
+                Ok(Expr::AggregateFunction(AggregateFunction {
+                    func_kind: AggregateFunctionKind::ArrayAgg,
+                    args: vec![self.bind_expr(&array_agg.expr)?],
+                    distinct: array_agg.distinct,
+                }))
+            }
+            sqlparser::ast::Expr::Position { expr, r#in } => {
+                Ok(Expr::ScalarFunction(ScalarFunction {
+                    func_kind: ScalarFunctionKind::Position,
+                    args: vec![self.bind_expr(expr)?, self.bind_expr(r#in)?],
+                }))
+            }
+            sqlparser::ast::Expr::Ceil { expr, field }
+            | sqlparser::ast::Expr::Floor { expr, field } => {
+                if *field != sqlparser::ast::DateTimeField::NoDateTime {
+                    return Err(BustubxError::NotSupport(format!(
+                        "sqlparser expr {} not supported",
+                        sql
+                    )));
+                }
+                let func_kind = if matches!(sql, sqlparser::ast::Expr::Ceil { .. }) {
+                    ScalarFunctionKind::Ceil
+                } else {
+                    ScalarFunctionKind::Floor
+                };
+                Ok(Expr::ScalarFunction(ScalarFunction {
+                    func_kind,
+                    args: vec![self.bind_expr(expr)?],
+                }))
+            }
             _ => Err(BustubxError::NotSupport(format!(
                 "sqlparser expr {} not supported",
                 sql
@@ -78,6 +216,9 @@ impl LogicalPlanner<'_> {
             sqlparser::ast::Value::SingleQuotedString(s) => Ok(Expr::Literal(Literal {
                 value: s.clone().into(),
             })),
+            sqlparser::ast::Value::HexStringLiteral(s) => Ok(Expr::Literal(Literal {
+                value: ScalarValue::Varchar(Some(format!("\\x{s}"))).cast_to(&DataType::Blob)?,
+            })),
             _ => Err(BustubxError::NotSupport(format!(
                 "sqlparser value {} not supported",
                 value
@@ -85,6 +226,46 @@ impl LogicalPlanner<'_> {
         }
     }
 
+    /// Binds `INTERVAL '1 day'` or `INTERVAL '2' HOUR` to an interval literal.
+    fn bind_interval(&self, interval: &sqlparser::ast::Interval) -> BustubxResult<Expr> {
+        // sqlparser parses `INTERVAL '1 day' + x` as `INTERVAL ('1 day' + x)`
+        if let sqlparser::ast::Expr::BinaryOp { left, op, right } = interval.value.as_ref() {
+            let left = sqlparser::ast::Interval {
+                value: left.clone(),
+                ..interval.clone()
+            };
+            return Ok(Expr::Binary(BinaryExpr {
+                left: Box::new(self.bind_interval(&left)?),
+                op: op.try_into()?,
+                right: Box::new(self.bind_expr(right)?),
+            }));
+        }
+        let value = match interval.value.as_ref() {
+            sqlparser::ast::Expr::Value(
+                sqlparser::ast::Value::SingleQuotedString(s) | sqlparser::ast::Value::Number(s, _),
+            ) => s.clone(),
+            _ => {
+                return Err(BustubxError::NotSupport(format!(
+                    "interval value {} not supported",
+                    interval.value
+                )))
+            }
+        };
+        if interval.last_field.is_some() {
+            return Err(BustubxError::NotSupport(format!(
+                "interval {} not supported",
+                interval
+            )));
+        }
+        let value = match interval.leading_field {
+            Some(field) if !value.contains(':') => format!("{value} {field}"),
+            _ => value,
+        };
+        Ok(Expr::Literal(Literal {
+            value: value.parse::<Interval>()?.into(),
+        }))
+    }
+
     pub fn bind_function(&self, function: &sqlparser::ast::Function) -> BustubxResult<Expr> {
         let name = function.name.to_string();
 
@@ -101,6 +282,15 @@ impl LogicalPlanner<'_> {
             }));
         }
 
+        if let Some(func_kind) = ScalarFunctionKind::find(name.as_str()) {
+            let args = function
+                .args
+                .iter()
+                .map(|arg| self.bind_function_arg(arg))
+                .collect::<BustubxResult<Vec<Expr>>>()?;
+            return Ok(Expr::ScalarFunction(ScalarFunction { func_kind, args }));
+        }
+
         Err(BustubxError::Plan(format!(
             "The function {} is not supported",
             function
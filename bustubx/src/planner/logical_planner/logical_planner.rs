@@ -14,15 +14,19 @@ pub struct LogicalPlanner<'a> {
 impl<'a> LogicalPlanner<'a> {
     pub fn plan(&mut self, stmt: &sqlparser::ast::Statement) -> BustubxResult<LogicalPlan> {
         match stmt {
-            sqlparser::ast::Statement::CreateTable { name, columns, .. } => {
-                self.plan_create_table(name, columns)
-            }
+            sqlparser::ast::Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                ..
+            } => self.plan_create_table(name, columns, constraints),
             sqlparser::ast::Statement::CreateIndex {
                 name,
                 table_name,
                 columns,
+                unique,
                 ..
-            } => self.plan_create_index(name, table_name, columns),
+            } => self.plan_create_index(name, table_name, columns, *unique),
             sqlparser::ast::Statement::Query(query) => self.plan_query(query),
             sqlparser::ast::Statement::Insert {
                 table_name,
@@ -36,6 +40,19 @@ impl<'a> LogicalPlanner<'a> {
                 selection,
                 ..
             } => self.plan_update(table, assignments, selection),
+            sqlparser::ast::Statement::Delete {
+                from, selection, ..
+            } => self.plan_delete(from, selection),
+            sqlparser::ast::Statement::Analyze { table_name, .. } => self.plan_analyze(table_name),
+            sqlparser::ast::Statement::Drop {
+                object_type,
+                if_exists,
+                names,
+                ..
+            } => self.plan_drop(object_type, *if_exists, names),
+            sqlparser::ast::Statement::AlterTable { name, operation } => {
+                self.plan_alter_table(name, operation)
+            }
             _ => unimplemented!(),
         }
     }
@@ -1,7 +1,11 @@
 mod bind_expr;
 mod logical_planner;
+mod plan_alter_table;
+mod plan_analyze;
 mod plan_create_index;
 mod plan_create_table;
+mod plan_delete;
+mod plan_drop;
 mod plan_insert;
 mod plan_query;
 mod plan_set_expr;
@@ -0,0 +1,70 @@
+use crate::planner::logical_plan::{AlterTable, AlterTableOperation, LogicalPlan};
+use crate::{BustubxError, BustubxResult};
+
+use super::LogicalPlanner;
+
+impl<'a> LogicalPlanner<'a> {
+    pub fn plan_alter_table(
+        &self,
+        name: &sqlparser::ast::ObjectName,
+        operation: &sqlparser::ast::AlterTableOperation,
+    ) -> BustubxResult<LogicalPlan> {
+        let table = self.bind_table_name(name)?;
+        let operation = match operation {
+            sqlparser::ast::AlterTableOperation::AddColumn {
+                if_not_exists,
+                column_def,
+                ..
+            } => {
+                if column_def.options.iter().any(|option_def| {
+                    matches!(
+                        option_def.option,
+                        sqlparser::ast::ColumnOption::Unique { .. }
+                            | sqlparser::ast::ColumnOption::Check(_)
+                            | sqlparser::ast::ColumnOption::ForeignKey { .. }
+                    )
+                }) {
+                    return Err(BustubxError::NotSupport(
+                        "Cannot add a column with constraints".to_string(),
+                    ));
+                }
+                AlterTableOperation::AddColumn {
+                    column: self.bind_column_def(&table, column_def)?,
+                    if_not_exists: *if_not_exists,
+                }
+            }
+            sqlparser::ast::AlterTableOperation::DropColumn {
+                column_name,
+                if_exists,
+                ..
+            } => AlterTableOperation::DropColumn {
+                name: column_name.value.clone(),
+                if_exists: *if_exists,
+            },
+            sqlparser::ast::AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => AlterTableOperation::RenameColumn {
+                old_name: old_column_name.value.clone(),
+                new_name: new_column_name.value.clone(),
+            },
+            sqlparser::ast::AlterTableOperation::RenameTable { table_name } => {
+                let new_table = self.bind_table_name(table_name)?;
+                if new_table.schema().is_some() && new_table.schema() != table.schema() {
+                    return Err(BustubxError::NotSupport(
+                        "Cannot move a table into another schema".to_string(),
+                    ));
+                }
+                AlterTableOperation::RenameTable {
+                    new_name: new_table.table().to_string(),
+                }
+            }
+            _ => {
+                return Err(BustubxError::NotSupport(format!(
+                    "ALTER TABLE {operation} is not supported"
+                )))
+            }
+        };
+        Ok(LogicalPlan::AlterTable(AlterTable { table, operation }))
+    }
+}
@@ -0,0 +1,18 @@
+use crate::planner::logical_plan::{Analyze, LogicalPlan};
+use crate::BustubxResult;
+
+use super::LogicalPlanner;
+
+impl<'a> LogicalPlanner<'a> {
+    pub fn plan_analyze(
+        &self,
+        table_name: &sqlparser::ast::ObjectName,
+    ) -> BustubxResult<LogicalPlan> {
+        let table = self.bind_table_name(table_name)?;
+        let table_schema = self.context.catalog.table_heap(&table)?.schema.clone();
+        Ok(LogicalPlan::Analyze(Analyze {
+            table,
+            table_schema,
+        }))
+    }
+}
@@ -9,6 +9,7 @@ impl<'a> LogicalPlanner<'a> {
         index_name: &sqlparser::ast::ObjectName,
         table_name: &sqlparser::ast::ObjectName,
         columns: &[sqlparser::ast::OrderByExpr],
+        unique: bool,
     ) -> BustubxResult<LogicalPlan> {
         let index_name = index_name.0.first().map_or(
             Err(BustubxError::Plan(format!(
@@ -28,6 +29,7 @@ impl<'a> LogicalPlanner<'a> {
             table,
             table_schema,
             columns: columns_expr,
+            unique,
         }))
     }
 }
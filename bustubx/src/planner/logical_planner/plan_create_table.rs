@@ -1,10 +1,11 @@
 use crate::{BustubxError, BustubxResult};
 use std::collections::HashSet;
 
-use crate::catalog::{Column, DataType};
-use crate::common::ScalarValue;
-use crate::expression::Expr;
+use crate::catalog::{Column, Constraint, ConstraintKind, DataType, ReferentialAction};
+use crate::common::{ScalarValue, TableReference};
+use crate::expression::{collect_columns, ExprTrait};
 use crate::planner::logical_plan::{CreateTable, LogicalPlan};
+use crate::storage::EMPTY_TUPLE;
 
 use super::LogicalPlanner;
 
@@ -13,49 +14,281 @@ impl<'a> LogicalPlanner<'a> {
         &self,
         name: &sqlparser::ast::ObjectName,
         column_defs: &Vec<sqlparser::ast::ColumnDef>,
+        table_constraints: &[sqlparser::ast::TableConstraint],
     ) -> BustubxResult<LogicalPlan> {
         let name = self.bind_table_name(name)?;
         let mut columns = vec![];
+        let mut constraints = ConstraintNames::default();
         for col_def in column_defs {
-            let data_type: DataType = (&col_def.data_type).try_into()?;
-            let not_null: bool = col_def
-                .options
-                .iter()
-                .any(|opt| matches!(opt.option, sqlparser::ast::ColumnOption::NotNull));
-            let default_expr: Option<&sqlparser::ast::Expr> = col_def
-                .options
-                .iter()
-                .find(|opt| matches!(opt.option, sqlparser::ast::ColumnOption::Default(_)))
-                .map(|opt| {
-                    if let sqlparser::ast::ColumnOption::Default(expr) = &opt.option {
-                        expr
-                    } else {
-                        unreachable!()
+            columns.push(self.bind_column_def(&name, col_def)?);
+            for option_def in col_def.options.iter() {
+                let constraint_name = option_def.name.as_ref().map(|ident| ident.value.clone());
+                let kind = match &option_def.option {
+                    sqlparser::ast::ColumnOption::Unique { is_primary } => {
+                        key_constraint(*is_primary, vec![col_def.name.value.clone()])
+                    }
+                    sqlparser::ast::ColumnOption::Check(expr) => {
+                        self.bind_check_constraint(expr)?
                     }
-                });
-            let default = if let Some(expr) = default_expr {
-                let expr = self.bind_expr(expr)?;
-                match expr {
-                    Expr::Literal(lit) => lit.value.cast_to(&data_type)?,
-                    _ => {
-                        return Err(BustubxError::Internal(
-                            "The expr is not literal".to_string(),
-                        ))
+                    sqlparser::ast::ColumnOption::ForeignKey {
+                        foreign_table,
+                        referred_columns,
+                        on_delete,
+                        on_update,
+                    } => self.bind_foreign_key(
+                        &name,
+                        vec![col_def.name.value.clone()],
+                        foreign_table,
+                        referred_columns,
+                        on_delete,
+                        on_update,
+                    )?,
+                    _ => continue,
+                };
+                let prefix = match &kind {
+                    ConstraintKind::Check { .. } => {
+                        format!("{}_{}", name.table(), col_def.name.value)
                     }
+                    _ => name.table().to_string(),
+                };
+                constraints.push(constraint_name, &prefix, kind)?;
+            }
+        }
+        for table_constraint in table_constraints {
+            match table_constraint {
+                sqlparser::ast::TableConstraint::Unique {
+                    name: constraint_name,
+                    columns: key_columns,
+                    is_primary,
+                } => {
+                    let key_columns = key_columns.iter().map(|col| col.value.clone()).collect();
+                    constraints.push(
+                        constraint_name.as_ref().map(|ident| ident.value.clone()),
+                        name.table(),
+                        key_constraint(*is_primary, key_columns),
+                    )?;
                 }
-            } else {
-                ScalarValue::new_empty(data_type)
-            };
-
-            columns.push(
-                Column::new(col_def.name.value.clone(), data_type, !not_null)
-                    .with_relation(Some(name.clone()))
-                    .with_default(default),
-            )
+                sqlparser::ast::TableConstraint::Check {
+                    name: constraint_name,
+                    expr,
+                } => {
+                    constraints.push(
+                        constraint_name.as_ref().map(|ident| ident.value.clone()),
+                        name.table(),
+                        self.bind_check_constraint(expr)?,
+                    )?;
+                }
+                sqlparser::ast::TableConstraint::ForeignKey {
+                    name: constraint_name,
+                    columns: fk_columns,
+                    foreign_table,
+                    referred_columns,
+                    on_delete,
+                    on_update,
+                } => {
+                    let fk_columns = fk_columns.iter().map(|col| col.value.clone()).collect();
+                    constraints.push(
+                        constraint_name.as_ref().map(|ident| ident.value.clone()),
+                        name.table(),
+                        self.bind_foreign_key(
+                            &name,
+                            fk_columns,
+                            foreign_table,
+                            referred_columns,
+                            on_delete,
+                            on_update,
+                        )?,
+                    )?;
+                }
+                _ => {
+                    return Err(BustubxError::NotSupport(format!(
+                        "table constraint {table_constraint} is not supported"
+                    )))
+                }
+            }
         }
+        let mut constraints = constraints.constraints;
+        // foreign keys come last, so they can reference keys of the new table
+        constraints
+            .sort_by_key(|constraint| matches!(constraint.kind, ConstraintKind::ForeignKey { .. }));
 
         check_column_name_conflict(&columns)?;
-        Ok(LogicalPlan::CreateTable(CreateTable { name, columns }))
+        for constraint in constraints.iter() {
+            for col_name in constraint.columns() {
+                let Some(col) = columns.iter_mut().find(|col| col.name == col_name) else {
+                    return Err(BustubxError::Plan(format!(
+                        "Column {} of constraint {} does not exist",
+                        col_name, constraint.name
+                    )));
+                };
+                // primary key columns are implicitly NOT NULL
+                if matches!(constraint.kind, ConstraintKind::PrimaryKey { .. }) {
+                    col.nullable = false;
+                }
+            }
+        }
+        Ok(LogicalPlan::CreateTable(CreateTable {
+            name,
+            columns,
+            constraints,
+        }))
+    }
+
+    fn bind_check_constraint(&self, expr: &sqlparser::ast::Expr) -> BustubxResult<ConstraintKind> {
+        Ok(ConstraintKind::Check {
+            expr: self.bind_expr(expr)?,
+            sql: expr.to_string(),
+        })
+    }
+
+    fn bind_foreign_key(
+        &self,
+        table: &TableReference,
+        columns: Vec<String>,
+        foreign_table: &sqlparser::ast::ObjectName,
+        referred_columns: &[sqlparser::ast::Ident],
+        on_delete: &Option<sqlparser::ast::ReferentialAction>,
+        on_update: &Option<sqlparser::ast::ReferentialAction>,
+    ) -> BustubxResult<ConstraintKind> {
+        let referenced_table = self.bind_table_name(foreign_table)?;
+        if !referenced_table.resolved_eq(table) {
+            self.context.catalog.table_heap(&referenced_table)?;
+        }
+        let on_delete = match on_delete {
+            None
+            | Some(sqlparser::ast::ReferentialAction::Restrict)
+            | Some(sqlparser::ast::ReferentialAction::NoAction) => ReferentialAction::Restrict,
+            Some(sqlparser::ast::ReferentialAction::Cascade) => ReferentialAction::Cascade,
+            Some(sqlparser::ast::ReferentialAction::SetNull) => ReferentialAction::SetNull,
+            Some(action) => {
+                return Err(BustubxError::NotSupport(format!(
+                    "ON DELETE {action} is not supported"
+                )))
+            }
+        };
+        // referenced keys can't be updated while they are referenced
+        if let Some(action) = on_update {
+            if !matches!(
+                action,
+                sqlparser::ast::ReferentialAction::Restrict
+                    | sqlparser::ast::ReferentialAction::NoAction
+            ) {
+                return Err(BustubxError::NotSupport(format!(
+                    "ON UPDATE {action} is not supported"
+                )));
+            }
+        }
+        Ok(ConstraintKind::ForeignKey {
+            columns,
+            referenced_table,
+            referenced_columns: referred_columns
+                .iter()
+                .map(|col| col.value.clone())
+                .collect(),
+            on_delete,
+        })
+    }
+
+    pub fn bind_column_def(
+        &self,
+        table: &TableReference,
+        col_def: &sqlparser::ast::ColumnDef,
+    ) -> BustubxResult<Column> {
+        let data_type: DataType = (&col_def.data_type).try_into()?;
+        let not_null: bool = col_def
+            .options
+            .iter()
+            .any(|opt| matches!(opt.option, sqlparser::ast::ColumnOption::NotNull));
+        let default_expr: Option<&sqlparser::ast::Expr> = col_def
+            .options
+            .iter()
+            .find(|opt| matches!(opt.option, sqlparser::ast::ColumnOption::Default(_)))
+            .map(|opt| {
+                if let sqlparser::ast::ColumnOption::Default(expr) = &opt.option {
+                    expr
+                } else {
+                    unreachable!()
+                }
+            });
+        // defaults are constant, so they are evaluated once
+        let default = if let Some(expr) = default_expr {
+            let expr = self.bind_expr(expr)?;
+            let mut columns = HashSet::new();
+            collect_columns(&expr, &mut columns);
+            if !columns.is_empty() {
+                return Err(BustubxError::Plan(format!(
+                    "Default value of column {} cannot reference columns",
+                    col_def.name.value
+                )));
+            }
+            expr.evaluate(&EMPTY_TUPLE)?.cast_to(&data_type)?
+        } else {
+            ScalarValue::new_empty(data_type)
+        };
+
+        Ok(
+            Column::new(col_def.name.value.clone(), data_type, !not_null)
+                .with_relation(Some(table.clone()))
+                .with_default(default),
+        )
+    }
+}
+
+fn key_constraint(is_primary: bool, columns: Vec<String>) -> ConstraintKind {
+    if is_primary {
+        ConstraintKind::PrimaryKey { columns }
+    } else {
+        ConstraintKind::Unique { columns }
+    }
+}
+
+/// Names constraints like PostgreSQL, `<table>_pkey`, `<table>_<columns>_key`,
+/// `<table>_<columns>_fkey` and `<table>_<column>_check`, with a number appended when the name is taken.
+#[derive(Default)]
+struct ConstraintNames {
+    constraints: Vec<Constraint>,
+}
+
+impl ConstraintNames {
+    fn push(
+        &mut self,
+        name: Option<String>,
+        prefix: &str,
+        kind: ConstraintKind,
+    ) -> BustubxResult<()> {
+        let taken = |name: &str| self.constraints.iter().any(|c| c.name == name);
+        let name = match name {
+            Some(name) => {
+                if taken(&name) {
+                    return Err(BustubxError::Plan(format!(
+                        "Constraint names have conflict on '{}'",
+                        name
+                    )));
+                }
+                name
+            }
+            None => {
+                let base = match &kind {
+                    ConstraintKind::PrimaryKey { .. } => format!("{prefix}_pkey"),
+                    ConstraintKind::Unique { columns } => {
+                        format!("{}_{}_key", prefix, columns.join("_"))
+                    }
+                    ConstraintKind::Check { .. } => format!("{prefix}_check"),
+                    ConstraintKind::ForeignKey { columns, .. } => {
+                        format!("{}_{}_fkey", prefix, columns.join("_"))
+                    }
+                };
+                let mut name = base.clone();
+                let mut suffix = 0;
+                while taken(&name) {
+                    suffix += 1;
+                    name = format!("{base}{suffix}");
+                }
+                name
+            }
+        };
+        self.constraints.push(Constraint::new(name, kind));
+        Ok(())
     }
 }
 
@@ -0,0 +1,46 @@
+use crate::planner::logical_plan::{Delete, LogicalPlan};
+use crate::planner::LogicalPlanner;
+use crate::{BustubxError, BustubxResult};
+
+impl<'a> LogicalPlanner<'a> {
+    pub fn plan_delete(
+        &self,
+        from: &[sqlparser::ast::TableWithJoins],
+        selection: &Option<sqlparser::ast::Expr>,
+    ) -> BustubxResult<LogicalPlan> {
+        let [table] = from else {
+            return Err(BustubxError::Plan(format!(
+                "Only support deleting from one table instead of {}",
+                from.len()
+            )));
+        };
+        if !table.joins.is_empty() {
+            return Err(BustubxError::Plan(format!(
+                "table {} is not supported",
+                table
+            )));
+        }
+        let table_ref = match &table.relation {
+            sqlparser::ast::TableFactor::Table { name, .. } => self.bind_table_name(name)?,
+            _ => {
+                return Err(BustubxError::Plan(format!(
+                    "table {} is not supported",
+                    table
+                )))
+            }
+        };
+
+        let table_schema = self.context.catalog.table_heap(&table_ref)?.schema.clone();
+
+        let selection = match selection {
+            Some(e) => Some(self.bind_expr(e)?),
+            None => None,
+        };
+
+        Ok(LogicalPlan::Delete(Delete {
+            table: table_ref,
+            table_schema,
+            selection,
+        }))
+    }
+}
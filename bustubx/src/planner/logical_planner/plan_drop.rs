@@ -0,0 +1,44 @@
+use crate::planner::logical_plan::{DropIndex, DropTable, LogicalPlan};
+use crate::{BustubxError, BustubxResult};
+
+use super::LogicalPlanner;
+
+impl<'a> LogicalPlanner<'a> {
+    pub fn plan_drop(
+        &self,
+        object_type: &sqlparser::ast::ObjectType,
+        if_exists: bool,
+        names: &[sqlparser::ast::ObjectName],
+    ) -> BustubxResult<LogicalPlan> {
+        let [name] = names else {
+            return Err(BustubxError::NotSupport(
+                "only support dropping one object".to_string(),
+            ));
+        };
+        match object_type {
+            sqlparser::ast::ObjectType::Table => Ok(LogicalPlan::DropTable(DropTable {
+                table: self.bind_table_name(name)?,
+                if_exists,
+            })),
+            sqlparser::ast::ObjectType::Index => {
+                let (schema_name, index_name) = match name.0.as_slice() {
+                    [index] => (None, index.value.clone()),
+                    [schema, index] => (Some(schema.value.clone()), index.value.clone()),
+                    _ => {
+                        return Err(BustubxError::Plan(format!(
+                            "Index name {name} is not expected"
+                        )))
+                    }
+                };
+                Ok(LogicalPlan::DropIndex(DropIndex {
+                    index_name,
+                    schema_name,
+                    if_exists,
+                }))
+            }
+            _ => Err(BustubxError::NotSupport(format!(
+                "DROP {object_type} is not supported"
+            ))),
+        }
+    }
+}
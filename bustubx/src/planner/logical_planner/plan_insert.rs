@@ -1,6 +1,7 @@
 use crate::BustubxResult;
 use std::sync::Arc;
 
+use crate::catalog::EMPTY_SCHEMA_REF;
 use crate::planner::logical_plan::{Insert, LogicalPlan, Values};
 
 use super::LogicalPlanner;
@@ -16,7 +17,14 @@ impl<'a> LogicalPlanner<'a> {
         let table = self.bind_table_name(table_name)?;
         let table_schema = self.context.catalog.table_heap(&table)?.schema.clone();
 
-        let projected_schema = if columns_ident.is_empty() {
+        // `INSERT ... DEFAULT VALUES` is parsed as an empty row, all columns get defaults
+        let default_values = matches!(
+            source.body.as_ref(),
+            sqlparser::ast::SetExpr::Values(values) if values.rows == vec![vec![]]
+        );
+        let projected_schema = if default_values {
+            EMPTY_SCHEMA_REF.clone()
+        } else if columns_ident.is_empty() {
             table_schema.clone()
         } else {
             let columns: Vec<String> = columns_ident
@@ -1,8 +1,8 @@
-use crate::catalog::{Column, Schema};
-use crate::expression::{columnize_expr, Alias, ColumnExpr, Expr, ExprTrait};
+use crate::catalog::{Column, DataType, Schema};
+use crate::expression::{collect_aggregates, columnize_expr, Alias, ColumnExpr, Expr, ExprTrait};
 use crate::planner::logical_plan::{
     build_join_schema, project_schema, EmptyRelation, Filter, Join, LogicalPlan, Project,
-    TableScan, Values,
+    SetOperation, SetOperator, TableScan, Values,
 };
 use crate::planner::logical_plan::{Aggregate, JoinType};
 use crate::planner::LogicalPlanner;
@@ -15,6 +15,13 @@ impl LogicalPlanner<'_> {
         match set_expr {
             sqlparser::ast::SetExpr::Select(select) => self.plan_select(select),
             sqlparser::ast::SetExpr::Values(values) => self.plan_values(values),
+            sqlparser::ast::SetExpr::Query(query) => self.plan_query(query),
+            sqlparser::ast::SetExpr::SetOperation {
+                op,
+                set_quantifier,
+                left,
+                right,
+            } => self.plan_set_operation(op, set_quantifier, left, right),
             _ => Err(BustubxError::Plan(format!(
                 "Failed to plan set expr: {}",
                 set_expr
@@ -22,35 +29,105 @@ impl LogicalPlanner<'_> {
         }
     }
 
+    /// Plans a set operation whose columns have the common types of the columns of both
+    /// inputs and the names of the left input.
+    pub fn plan_set_operation(
+        &self,
+        op: &sqlparser::ast::SetOperator,
+        set_quantifier: &sqlparser::ast::SetQuantifier,
+        left: &sqlparser::ast::SetExpr,
+        right: &sqlparser::ast::SetExpr,
+    ) -> BustubxResult<LogicalPlan> {
+        let left = self.plan_set_expr(left)?;
+        let right = self.plan_set_expr(right)?;
+        let op = match op {
+            sqlparser::ast::SetOperator::Union => SetOperator::Union,
+            sqlparser::ast::SetOperator::Intersect => SetOperator::Intersect,
+            sqlparser::ast::SetOperator::Except => SetOperator::Except,
+        };
+        let all = match set_quantifier {
+            sqlparser::ast::SetQuantifier::All => true,
+            sqlparser::ast::SetQuantifier::Distinct | sqlparser::ast::SetQuantifier::None => false,
+        };
+
+        let left_columns = &left.schema().columns;
+        let right_columns = &right.schema().columns;
+        if left_columns.len() != right_columns.len() {
+            return Err(BustubxError::Plan(format!(
+                "each {} query must have the same number of columns, {} and {}",
+                op,
+                left_columns.len(),
+                right_columns.len()
+            )));
+        }
+        let columns = left_columns
+            .iter()
+            .zip(right_columns.iter())
+            .map(|(l, r)| {
+                let data_type =
+                    DataType::common_type(&[l.data_type, r.data_type]).map_err(|_| {
+                        BustubxError::Plan(format!(
+                            "{} types {} and {} cannot be matched",
+                            op, l.data_type, r.data_type
+                        ))
+                    })?;
+                // only tuples of the left input are output by except
+                let nullable = l.nullable || (op != SetOperator::Except && r.nullable);
+                Ok(Column::new(l.name.clone(), data_type, nullable))
+            })
+            .collect::<BustubxResult<Vec<Column>>>()?;
+
+        Ok(LogicalPlan::SetOperation(SetOperation {
+            left: Arc::new(left),
+            right: Arc::new(right),
+            op,
+            all,
+            schema: Arc::new(Schema::new(columns)),
+        }))
+    }
+
     pub fn plan_select(&self, select: &sqlparser::ast::Select) -> BustubxResult<LogicalPlan> {
         let table_scan = self.plan_from_tables(&select.from)?;
         let selection = self.plan_selection(table_scan, &select.selection)?;
-        let aggregate = self.plan_aggregate(selection, &select.projection, &select.group_by)?;
-        self.plan_project(aggregate, &select.projection)
+        let having = select
+            .having
+            .as_ref()
+            .map(|having| self.bind_expr(having))
+            .transpose()?;
+        let aggregate = self.plan_aggregate(
+            selection,
+            &select.projection,
+            &select.group_by,
+            having.as_ref(),
+        )?;
+        let having = self.plan_having(aggregate, having)?;
+        self.plan_project(having, &select.projection)
     }
 
+    /// Plans the aggregate of the select list and the HAVING predicate. Aggregates which
+    /// only appear in the HAVING predicate are computed as well.
     pub fn plan_aggregate(
         &self,
         input: LogicalPlan,
         project: &Vec<sqlparser::ast::SelectItem>,
         group_by: &[sqlparser::ast::Expr],
+        having: Option<&Expr>,
     ) -> BustubxResult<LogicalPlan> {
         let mut exprs = vec![];
         for select_item in project {
             exprs.extend(self.bind_select_item(&input, select_item)?);
         }
 
-        let aggr_exprs = exprs
-            .iter()
-            .filter(|e| matches!(e, Expr::AggregateFunction(_)))
-            .cloned()
-            .collect::<Vec<Expr>>();
+        let mut aggr_exprs = vec![];
+        for expr in exprs.iter().chain(having) {
+            collect_aggregates(expr, &mut aggr_exprs);
+        }
         let group_exprs = group_by
             .iter()
             .map(|e| self.bind_expr(e))
             .collect::<BustubxResult<Vec<Expr>>>()?;
 
-        if aggr_exprs.is_empty() && group_exprs.is_empty() {
+        if aggr_exprs.is_empty() && group_exprs.is_empty() && having.is_none() {
             Ok(input)
         } else {
             let mut columns = aggr_exprs
@@ -72,6 +149,27 @@ impl LogicalPlanner<'_> {
         }
     }
 
+    /// Filters the output of the aggregate with the HAVING predicate.
+    pub fn plan_having(
+        &self,
+        input: LogicalPlan,
+        having: Option<Expr>,
+    ) -> BustubxResult<LogicalPlan> {
+        match having {
+            None => Ok(input),
+            Some(predicate) => {
+                let predicate = columnize_expr(&predicate, input.schema())?;
+                // fails if the predicate refers to a column which is neither grouped nor
+                // aggregated
+                predicate.data_type(input.schema())?;
+                Ok(LogicalPlan::Filter(Filter {
+                    input: Arc::new(input),
+                    predicate,
+                }))
+            }
+        }
+    }
+
     pub fn plan_project(
         &self,
         input: LogicalPlan,
@@ -199,13 +297,13 @@ impl LogicalPlanner<'_> {
                 self.plan_join(left, right, constraint, JoinType::Inner)
             }
             sqlparser::ast::JoinOperator::LeftOuter(constraint) => {
-                self.plan_join(left, right, constraint, JoinType::Inner)
+                self.plan_join(left, right, constraint, JoinType::LeftOuter)
             }
             sqlparser::ast::JoinOperator::RightOuter(constraint) => {
-                self.plan_join(left, right, constraint, JoinType::Inner)
+                self.plan_join(left, right, constraint, JoinType::RightOuter)
             }
             sqlparser::ast::JoinOperator::FullOuter(constraint) => {
-                self.plan_join(left, right, constraint, JoinType::Inner)
+                self.plan_join(left, right, constraint, JoinType::FullOuter)
             }
             sqlparser::ast::JoinOperator::CrossJoin => self.plan_cross_join(left, right),
             _ => Err(BustubxError::Plan(format!(
@@ -269,11 +367,15 @@ impl LogicalPlanner<'_> {
                 // TODO handle alias
                 let table_ref = self.bind_table_name(name)?;
                 let schema = self.context.catalog.table_heap(&table_ref)?.schema.clone();
+                let statistics = self.context.catalog.table_statistics(&table_ref)?;
                 Ok(LogicalPlan::TableScan(TableScan {
                     table_ref,
-                    table_schema: schema,
+                    table_schema: schema.clone(),
+                    projection: None,
+                    projected_schema: schema,
                     filters: vec![],
                     limit: None,
+                    statistics,
                 }))
             }
             sqlparser::ast::TableFactor::NestedJoin {
@@ -1,25 +1,47 @@
-use crate::catalog::{Catalog, Schema, DEFAULT_SCHEMA_NAME};
+use crate::catalog::{Catalog, DataType, Schema, SchemaRef, DEFAULT_SCHEMA_NAME};
+use crate::common::{ScalarValue, TableReference};
+use crate::expression::{
+    conjunction, refers_only_to, split_conjunction, BinaryExpr, BinaryOp, Cast, ColumnExpr, Expr,
+    ExprTrait,
+};
+use crate::storage::index::BPlusTreeIndex;
+use crate::storage::Tuple;
+use std::ops::Bound;
 use std::sync::Arc;
 
+use crate::optimizer::CostModel;
 use crate::planner::logical_plan::{
-    Aggregate, CreateIndex, CreateTable, EmptyRelation, Filter, Insert, Join, Limit, LogicalPlan,
-    Project, Sort, TableScan, Update, Values,
+    build_join_schema, Aggregate, AlterTable, Analyze, CreateIndex, CreateTable, Delete, DropIndex,
+    DropTable, EmptyRelation, Filter, Insert, Join, JoinType, Limit, LogicalPlan, OrderByExpr,
+    Project, SetOperation, SetOperator, Sort, TableScan, Update, Values,
 };
 
+use crate::execution::physical_plan::PhysicalHashJoin;
 use crate::execution::physical_plan::PhysicalLimit;
 use crate::execution::physical_plan::PhysicalNestedLoopJoin;
 use crate::execution::physical_plan::PhysicalPlan;
 use crate::execution::physical_plan::PhysicalProject;
 use crate::execution::physical_plan::PhysicalSeqScan;
 use crate::execution::physical_plan::PhysicalSort;
+use crate::execution::physical_plan::PhysicalSortMergeJoin;
 use crate::execution::physical_plan::PhysicalValues;
-use crate::execution::physical_plan::{PhysicalAggregate, PhysicalCreateTable};
-use crate::execution::physical_plan::{PhysicalCreateIndex, PhysicalEmpty};
+use crate::execution::physical_plan::{PhysicalAggregate, PhysicalAnalyze, PhysicalCreateTable};
+use crate::execution::physical_plan::{PhysicalAlterTable, PhysicalDropIndex, PhysicalDropTable};
+use crate::execution::physical_plan::{PhysicalCreateIndex, PhysicalDelete, PhysicalEmpty};
 use crate::execution::physical_plan::{PhysicalFilter, PhysicalIndexScan};
+use crate::execution::physical_plan::{
+    PhysicalHashDistinct, PhysicalHashSetOperation, PhysicalUnion,
+};
 use crate::execution::physical_plan::{PhysicalInsert, PhysicalUpdate};
 
+/// Pairs of left key and right key of an equi-join
+type JoinKeys = Vec<(Expr, Expr)>;
+/// Start and end bound of an index scan
+type KeyRange = (Bound<Tuple>, Bound<Tuple>);
+
 pub struct PhysicalPlanner<'a> {
     pub catalog: &'a Catalog,
+    pub hash_join_memory_budget: usize,
 }
 
 impl PhysicalPlanner<'_> {
@@ -30,19 +52,27 @@ impl PhysicalPlanner<'_> {
 
     fn build_plan(&self, logical_plan: Arc<LogicalPlan>) -> PhysicalPlan {
         let plan = match logical_plan.as_ref() {
-            LogicalPlan::CreateTable(CreateTable { name, columns }) => PhysicalPlan::CreateTable(
-                PhysicalCreateTable::new(name.clone(), Schema::new(columns.clone())),
-            ),
+            LogicalPlan::CreateTable(CreateTable {
+                name,
+                columns,
+                constraints,
+            }) => PhysicalPlan::CreateTable(PhysicalCreateTable::new(
+                name.clone(),
+                Schema::new(columns.clone()),
+                constraints.clone(),
+            )),
             LogicalPlan::CreateIndex(CreateIndex {
                 index_name,
                 table,
                 table_schema,
                 columns,
+                unique,
             }) => PhysicalPlan::CreateIndex(PhysicalCreateIndex::new(
                 index_name.clone(),
                 table.clone(),
                 table_schema.clone(),
                 columns.clone(),
+                *unique,
             )),
             LogicalPlan::Insert(Insert {
                 table,
@@ -74,47 +104,18 @@ impl PhysicalPlanner<'_> {
                 ))
             }
             LogicalPlan::Filter(Filter { predicate, input }) => {
-                let input_physical_plan = self.build_plan(input.clone());
+                let input_physical_plan = match input.as_ref() {
+                    LogicalPlan::TableScan(table_scan) => {
+                        self.plan_table_scan(table_scan, split_conjunction(predicate))
+                    }
+                    _ => self.build_plan(input.clone()),
+                };
                 PhysicalPlan::Filter(PhysicalFilter::new(
                     predicate.clone(),
                     Arc::new(input_physical_plan),
                 ))
             }
-            LogicalPlan::TableScan(TableScan {
-                table_ref,
-                table_schema,
-                filters: _,
-                limit: _,
-            }) => {
-                // TODO fix testing
-                if let Some(catalog_table) = self
-                    .catalog
-                    .schemas
-                    .get(table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME))
-                    .unwrap()
-                    .tables
-                    .get(table_ref.table())
-                {
-                    if !catalog_table.indexes.is_empty() {
-                        PhysicalPlan::IndexScan(PhysicalIndexScan::new(
-                            table_ref.clone(),
-                            catalog_table.indexes.keys().next().unwrap().clone(),
-                            table_schema.clone(),
-                            ..,
-                        ))
-                    } else {
-                        PhysicalPlan::SeqScan(PhysicalSeqScan::new(
-                            table_ref.clone(),
-                            table_schema.clone(),
-                        ))
-                    }
-                } else {
-                    PhysicalPlan::SeqScan(PhysicalSeqScan::new(
-                        table_ref.clone(),
-                        table_schema.clone(),
-                    ))
-                }
-            }
+            LogicalPlan::TableScan(table_scan) => self.plan_table_scan(table_scan, vec![]),
             LogicalPlan::Limit(Limit {
                 limit,
                 offset,
@@ -136,13 +137,27 @@ impl PhysicalPlanner<'_> {
             }) => {
                 let left_physical_plan = self.build_plan((*left).clone());
                 let right_physical_plan = self.build_plan((*right).clone());
-                PhysicalPlan::NestedLoopJoin(PhysicalNestedLoopJoin::new(
+                let (on, filter) = match condition {
+                    Some(condition) => extract_equi_keys(condition, left.schema(), right.schema()),
+                    None => (vec![], None),
+                };
+                if on.is_empty() {
+                    return PhysicalPlan::NestedLoopJoin(PhysicalNestedLoopJoin::new(
+                        *join_type,
+                        condition.clone(),
+                        Arc::new(left_physical_plan),
+                        Arc::new(right_physical_plan),
+                        schema.clone(),
+                    ));
+                }
+                self.plan_equi_join(
                     *join_type,
-                    condition.clone(),
-                    Arc::new(left_physical_plan),
-                    Arc::new(right_physical_plan),
-                    schema.clone(),
-                ))
+                    on,
+                    filter,
+                    (left, left_physical_plan),
+                    (right, right_physical_plan),
+                    schema,
+                )
             }
             LogicalPlan::Sort(Sort {
                 order_by: expr,
@@ -188,7 +203,814 @@ impl PhysicalPlanner<'_> {
                 assignments.clone(),
                 selection.clone(),
             )),
+            LogicalPlan::Delete(Delete {
+                table, selection, ..
+            }) => PhysicalPlan::Delete(PhysicalDelete::new(table.clone(), selection.clone())),
+            LogicalPlan::Analyze(Analyze {
+                table,
+                table_schema,
+            }) => PhysicalPlan::Analyze(PhysicalAnalyze::new(table.clone(), table_schema.clone())),
+            LogicalPlan::DropTable(DropTable { table, if_exists }) => {
+                PhysicalPlan::DropTable(PhysicalDropTable::new(table.clone(), *if_exists))
+            }
+            LogicalPlan::DropIndex(DropIndex {
+                index_name,
+                schema_name,
+                if_exists,
+            }) => PhysicalPlan::DropIndex(PhysicalDropIndex::new(
+                index_name.clone(),
+                schema_name.clone(),
+                *if_exists,
+            )),
+            LogicalPlan::SetOperation(SetOperation {
+                left,
+                right,
+                op,
+                all,
+                schema,
+            }) => {
+                let left_physical_plan = Arc::new(self.build_plan(left.clone()));
+                let right_physical_plan = Arc::new(self.build_plan(right.clone()));
+                match op {
+                    SetOperator::Union => {
+                        let union = PhysicalPlan::Union(PhysicalUnion::new(
+                            left_physical_plan,
+                            right_physical_plan,
+                            schema.clone(),
+                        ));
+                        if *all {
+                            union
+                        } else {
+                            PhysicalPlan::HashDistinct(PhysicalHashDistinct::new(Arc::new(union)))
+                        }
+                    }
+                    SetOperator::Intersect | SetOperator::Except => {
+                        PhysicalPlan::HashSetOperation(PhysicalHashSetOperation::new(
+                            *op,
+                            *all,
+                            left_physical_plan,
+                            right_physical_plan,
+                            schema.clone(),
+                        ))
+                    }
+                }
+            }
+            LogicalPlan::AlterTable(AlterTable { table, operation }) => {
+                PhysicalPlan::AlterTable(PhysicalAlterTable::new(table.clone(), operation.clone()))
+            }
         };
         plan
     }
+
+    /// Scans the table through the index which restricts the scanned keys most by the
+    /// predicates and filters of the scan, or sequentially if no index can be used. The
+    /// filters of the scan are evaluated on the scanned tuples, other predicates must
+    /// still be evaluated by the caller.
+    fn plan_table_scan(&self, table_scan: &TableScan, mut predicates: Vec<Expr>) -> PhysicalPlan {
+        let TableScan {
+            table_ref,
+            table_schema,
+            projection,
+            projected_schema,
+            filters,
+            statistics,
+            ..
+        } = table_scan;
+        predicates.extend(filters.iter().cloned());
+
+        let mut candidates = vec![];
+        for (index_name, index) in self.indexes(table_ref) {
+            if let Some((score, range, restrictions)) =
+                index_range(&index.key_schema, table_schema, &predicates)
+            {
+                candidates.push((score, index_name, range, restrictions));
+            }
+        }
+        let best = match statistics {
+            // with statistics, an index is only used if reading the matching tuples
+            // through it is cheaper than reading the whole table
+            Some(statistics) => {
+                let cost_model = CostModel;
+                let scan = LogicalPlan::TableScan(table_scan.clone());
+                let rows = statistics.row_count as f64;
+                let index_cost = |restrictions: &Vec<Expr>| {
+                    let selectivity = restrictions.iter().fold(1.0, |selectivity, predicate| {
+                        selectivity * cost_model.selectivity(predicate, &scan)
+                    });
+                    cost_model.index_scan_cost(rows * selectivity)
+                };
+                candidates
+                    .into_iter()
+                    .map(|candidate| (index_cost(&candidate.3), candidate))
+                    .filter(|(cost, _)| *cost < cost_model.seq_scan_cost(rows))
+                    .min_by(|(a, _), (b, _)| a.total_cmp(b))
+                    .map(|(_, candidate)| candidate)
+            }
+            None => candidates
+                .into_iter()
+                .rev()
+                .max_by_key(|(score, ..)| *score),
+        };
+        match best {
+            Some((_, index_name, range, _)) => with_filter(
+                PhysicalPlan::IndexScan(
+                    PhysicalIndexScan::new(
+                        table_ref.clone(),
+                        index_name,
+                        table_schema.clone(),
+                        range,
+                    )
+                    .with_projection(projection.clone(), projected_schema.clone()),
+                ),
+                filters.clone(),
+            ),
+            None => PhysicalPlan::SeqScan(
+                PhysicalSeqScan::new(table_ref.clone(), table_schema.clone(), filters.clone())
+                    .with_projection(projection.clone(), projected_schema.clone()),
+            ),
+        }
+    }
+
+    /// Indexes of the table ordered by name.
+    fn indexes(&self, table_ref: &TableReference) -> Vec<(String, Arc<BPlusTreeIndex>)> {
+        let mut indexes = self
+            .catalog
+            .schemas
+            .get(table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME))
+            .and_then(|schema| schema.tables.get(table_ref.table()))
+            .map(|table| {
+                table
+                    .indexes
+                    .iter()
+                    .map(|(name, index)| (name.clone(), index.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        indexes.sort_by(|a, b| a.0.cmp(&b.0));
+        indexes
+    }
+
+    /// Plans a join with equi-keys as a sort merge join if an input is already sorted by
+    /// the keys or can be scanned in key order through an index, otherwise as a hash join.
+    /// If both inputs have statistics, the cheaper one is chosen instead and the smaller
+    /// input becomes the build side of a hash join.
+    fn plan_equi_join(
+        &self,
+        join_type: JoinType,
+        on: JoinKeys,
+        filter: Option<Expr>,
+        (left, left_physical_plan): (&LogicalPlan, PhysicalPlan),
+        (right, right_physical_plan): (&LogicalPlan, PhysicalPlan),
+        schema: &SchemaRef,
+    ) -> PhysicalPlan {
+        let left_index = self.key_order_index(&left_physical_plan, on.iter().map(|(l, _)| l));
+        let right_index = self.key_order_index(&right_physical_plan, on.iter().map(|(_, r)| r));
+        let key_order =
+            |plan: &PhysicalPlan, index: &Option<(String, Arc<BPlusTreeIndex>)>| match index {
+                Some((_, index)) => column_names(&index.key_schema),
+                None => self.sort_order(plan),
+            };
+        let merge_join = merge_join_keys(
+            &key_order(&left_physical_plan, &left_index),
+            &key_order(&right_physical_plan, &right_index),
+            &on,
+        );
+
+        let cost_model = CostModel;
+        let rows = cost_model
+            .estimate_rows(left)
+            .zip(cost_model.estimate_rows(right));
+        let merge_join = match (merge_join, rows) {
+            (Some((on, left_sorted, right_sorted)), Some((left_rows, right_rows))) => {
+                // reading a table in key order through an index costs more than reading
+                // it sequentially
+                let input_cost = |sorted: bool, upgraded: bool, rows: f64| match (sorted, upgraded)
+                {
+                    (true, true) => {
+                        cost_model.index_scan_cost(rows) - cost_model.seq_scan_cost(rows)
+                    }
+                    (true, false) => 0.0,
+                    (false, _) => cost_model.sort_cost(rows),
+                };
+                let merge_join_cost = cost_model.merge_join_cost(left_rows, right_rows)
+                    + input_cost(left_sorted, left_index.is_some(), left_rows)
+                    + input_cost(right_sorted, right_index.is_some(), right_rows);
+                let hash_join_cost = cost_model
+                    .hash_join_cost(left_rows, right_rows)
+                    .min(cost_model.hash_join_cost(right_rows, left_rows));
+                (merge_join_cost <= hash_join_cost).then_some((on, left_sorted, right_sorted))
+            }
+            (merge_join, _) => merge_join,
+        };
+
+        if let Some((on, left_sorted, right_sorted)) = merge_join {
+            // an input already ordered by an index saves one sort
+            let left_physical_plan = match (left_sorted, left_index) {
+                (true, Some((index_name, _))) => {
+                    scan_in_index_order(left_physical_plan, index_name)
+                }
+                (true, None) => left_physical_plan,
+                (false, _) => sort_by_keys(left_physical_plan, on.iter().map(|(l, _)| l)),
+            };
+            let right_physical_plan = match (right_sorted, right_index) {
+                (true, Some((index_name, _))) => {
+                    scan_in_index_order(right_physical_plan, index_name)
+                }
+                (true, None) => right_physical_plan,
+                (false, _) => sort_by_keys(right_physical_plan, on.iter().map(|(_, r)| r)),
+            };
+            return PhysicalPlan::SortMergeJoin(PhysicalSortMergeJoin::new(
+                join_type,
+                on,
+                filter,
+                Arc::new(left_physical_plan),
+                Arc::new(right_physical_plan),
+                schema.clone(),
+            ));
+        }
+
+        // the right input is the build side of a hash join
+        if let Some((left_rows, right_rows)) = rows {
+            if left_rows < right_rows {
+                if let Some((swapped_join_type, swapped_schema, exprs)) =
+                    swap_join_inputs(join_type, left.schema(), right.schema(), schema)
+                {
+                    let swapped = PhysicalPlan::HashJoin(
+                        PhysicalHashJoin::new(
+                            swapped_join_type,
+                            on.into_iter().map(|(l, r)| (r, l)).collect(),
+                            filter,
+                            Arc::new(right_physical_plan),
+                            Arc::new(left_physical_plan),
+                            swapped_schema,
+                        )
+                        .with_memory_budget(self.hash_join_memory_budget),
+                    );
+                    return PhysicalPlan::Project(PhysicalProject::new(
+                        exprs,
+                        schema.clone(),
+                        Arc::new(swapped),
+                    ));
+                }
+            }
+        }
+        PhysicalPlan::HashJoin(
+            PhysicalHashJoin::new(
+                join_type,
+                on,
+                filter,
+                Arc::new(left_physical_plan),
+                Arc::new(right_physical_plan),
+                schema.clone(),
+            )
+            .with_memory_budget(self.hash_join_memory_budget),
+        )
+    }
+
+    /// Finds an index to scan a sequentially scanned table in the order of the given
+    /// join keys, so that a sort merge join doesn't need to sort the input.
+    fn key_order_index<'k>(
+        &self,
+        plan: &PhysicalPlan,
+        keys: impl Iterator<Item = &'k Expr>,
+    ) -> Option<(String, Arc<BPlusTreeIndex>)> {
+        let PhysicalPlan::SeqScan(PhysicalSeqScan { table, .. }) = plan else {
+            return None;
+        };
+        let mut key_names = keys
+            .map(|key| match key {
+                Expr::Column(column) => Some(column.name.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<String>>>()?;
+        if key_names.is_empty() {
+            return None;
+        }
+        key_names.sort();
+        self.indexes(table).into_iter().find(|(_, index)| {
+            if index.key_schema.columns.len() < key_names.len() {
+                return false;
+            }
+            let mut leading_names = column_names(&index.key_schema);
+            leading_names.truncate(key_names.len());
+            leading_names.sort();
+            leading_names == key_names
+        })
+    }
+
+    /// Names of columns the output of the plan is sorted by.
+    fn sort_order(&self, plan: &PhysicalPlan) -> Vec<String> {
+        match plan {
+            PhysicalPlan::IndexScan(PhysicalIndexScan {
+                table_ref,
+                index_name,
+                ..
+            }) => match self.catalog.index(table_ref, index_name) {
+                Ok(Some(index)) => column_names(&index.key_schema),
+                _ => vec![],
+            },
+            PhysicalPlan::Filter(PhysicalFilter { input, .. }) => self.sort_order(input),
+            _ => vec![],
+        }
+    }
+}
+
+/// Reorders join keys to the sort order of an input, so that a sort merge join can
+/// reuse it. Returns `None` if neither input is sorted by the join keys, otherwise
+/// the keys and whether each input is sorted by them.
+fn merge_join_keys(
+    left_order: &[String],
+    right_order: &[String],
+    on: &[(Expr, Expr)],
+) -> Option<(JoinKeys, bool, bool)> {
+    if let Some(on) = align_keys(on, left_order, |(l, _)| l) {
+        let right_sorted = align_keys(&on, right_order, |(_, r)| r).as_ref() == Some(&on);
+        Some((on, true, right_sorted))
+    } else {
+        align_keys(on, right_order, |(_, r)| r).map(|on| (on, false, true))
+    }
+}
+
+/// Replaces a sequential scan by a full scan of the index.
+fn scan_in_index_order(plan: PhysicalPlan, index_name: String) -> PhysicalPlan {
+    let PhysicalPlan::SeqScan(PhysicalSeqScan {
+        table,
+        table_schema,
+        filters,
+        projection,
+        projected_schema,
+        ..
+    }) = plan
+    else {
+        return plan;
+    };
+    with_filter(
+        PhysicalPlan::IndexScan(
+            PhysicalIndexScan::new(table, index_name, table_schema, ..)
+                .with_projection(projection, projected_schema),
+        ),
+        filters,
+    )
+}
+
+/// Plans swapping the inputs of a join, returns the join type and schema of the swapped
+/// join and the expressions restoring the original column order. Returns `None` if the
+/// join type can't be swapped or columns can't be told apart by name.
+fn swap_join_inputs(
+    join_type: JoinType,
+    left_schema: &Schema,
+    right_schema: &Schema,
+    schema: &Schema,
+) -> Option<(JoinType, SchemaRef, Vec<Expr>)> {
+    let swapped_join_type = match join_type {
+        JoinType::Inner => JoinType::Inner,
+        JoinType::LeftOuter => JoinType::RightOuter,
+        JoinType::RightOuter => JoinType::LeftOuter,
+        JoinType::Cross | JoinType::FullOuter => return None,
+    };
+    let unambiguous = schema.columns.iter().enumerate().all(|(idx, col)| {
+        col.relation.is_some()
+            && schema.index_of(col.relation.as_ref(), &col.name).ok() == Some(idx)
+    });
+    if !unambiguous {
+        return None;
+    }
+    let swapped_schema = build_join_schema(right_schema, left_schema, swapped_join_type).ok()?;
+    let exprs = schema
+        .columns
+        .iter()
+        .map(|col| {
+            Expr::Column(ColumnExpr {
+                relation: col.relation.clone(),
+                name: col.name.clone(),
+            })
+        })
+        .collect();
+    Some((swapped_join_type, Arc::new(swapped_schema), exprs))
+}
+
+fn column_names(schema: &Schema) -> Vec<String> {
+    schema.columns.iter().map(|col| col.name.clone()).collect()
+}
+
+fn with_filter(input: PhysicalPlan, predicates: Vec<Expr>) -> PhysicalPlan {
+    match conjunction(predicates) {
+        Some(predicate) => PhysicalPlan::Filter(PhysicalFilter::new(predicate, Arc::new(input))),
+        None => input,
+    }
+}
+
+/// Finds the range of index keys which contains all tuples satisfying the predicates.
+/// Equality predicates on leading key columns form a key prefix, range predicates on the
+/// next column bound the scan further. Returns `None` if the leading key column isn't
+/// restricted, otherwise the range, a score of how much it restricts the scan and the
+/// predicates restricting it.
+fn index_range(
+    key_schema: &SchemaRef,
+    table_schema: &Schema,
+    predicates: &[Expr],
+) -> Option<(usize, KeyRange, Vec<Expr>)> {
+    let mut restricting = vec![];
+    let mut prefix = vec![];
+    let mut lower: Option<(ScalarValue, bool)> = None;
+    let mut upper: Option<(ScalarValue, bool)> = None;
+    for key_column in key_schema.columns.iter() {
+        let (restrictions, predicates): (Vec<(BinaryOp, ScalarValue)>, Vec<Expr>) = predicates
+            .iter()
+            .filter_map(|predicate| {
+                key_restriction(
+                    predicate,
+                    &key_column.name,
+                    key_column.data_type,
+                    table_schema,
+                )
+                .map(|restriction| (restriction, predicate.clone()))
+            })
+            .unzip();
+        if let Some(idx) = restrictions.iter().position(|(op, _)| *op == BinaryOp::Eq) {
+            prefix.push(restrictions[idx].1.clone());
+            restricting.push(predicates[idx].clone());
+            continue;
+        }
+        restricting.extend(predicates);
+        // keep the tightest bound, an exclusive one is tighter than an inclusive one
+        for (op, value) in restrictions {
+            let inclusive = matches!(op, BinaryOp::GtEq | BinaryOp::LtEq);
+            match op {
+                BinaryOp::Gt | BinaryOp::GtEq
+                    if lower.as_ref().is_none_or(|(v, incl)| {
+                        value > *v || (value == *v && *incl && !inclusive)
+                    }) =>
+                {
+                    lower = Some((value, inclusive));
+                }
+                BinaryOp::Lt | BinaryOp::LtEq
+                    if upper.as_ref().is_none_or(|(v, incl)| {
+                        value < *v || (value == *v && *incl && !inclusive)
+                    }) =>
+                {
+                    upper = Some((value, inclusive));
+                }
+                _ => {}
+            }
+        }
+        break;
+    }
+    if prefix.is_empty() && lower.is_none() && upper.is_none() {
+        return None;
+    }
+
+    let bound = |value: Option<(ScalarValue, bool)>| -> Option<Bound<Tuple>> {
+        let (values, inclusive) = match value {
+            Some((value, inclusive)) => {
+                let mut values = prefix.clone();
+                values.push(value);
+                (values, inclusive)
+            }
+            None if !prefix.is_empty() => (prefix.clone(), true),
+            None => return Some(Bound::Unbounded),
+        };
+        let indices = (0..values.len()).collect::<Vec<usize>>();
+        let tuple = Tuple::new(Arc::new(key_schema.project(&indices).ok()?), values);
+        Some(if inclusive {
+            Bound::Included(tuple)
+        } else {
+            Bound::Excluded(tuple)
+        })
+    };
+    let score = prefix.len() * 2 + usize::from(lower.is_some() || upper.is_some());
+    Some((score, (bound(lower)?, bound(upper)?), restricting))
+}
+
+/// Normalizes a predicate comparing the column with a literal to `column op value`, the
+/// value is cast to the column type if that doesn't lose precision.
+fn key_restriction(
+    predicate: &Expr,
+    column_name: &str,
+    column_type: DataType,
+    table_schema: &Schema,
+) -> Option<(BinaryOp, ScalarValue)> {
+    let Expr::Binary(BinaryExpr { left, op, right }) = predicate else {
+        return None;
+    };
+    let (column, value, op) = match (left.as_ref(), right.as_ref()) {
+        (Expr::Column(column), Expr::Literal(literal)) => (column, &literal.value, *op),
+        (Expr::Literal(literal), Expr::Column(column)) => {
+            let op = match op {
+                BinaryOp::Gt => BinaryOp::Lt,
+                BinaryOp::GtEq => BinaryOp::LtEq,
+                BinaryOp::Lt => BinaryOp::Gt,
+                BinaryOp::LtEq => BinaryOp::GtEq,
+                op => *op,
+            };
+            (column, &literal.value, op)
+        }
+        _ => return None,
+    };
+    if !matches!(
+        op,
+        BinaryOp::Eq | BinaryOp::Gt | BinaryOp::GtEq | BinaryOp::Lt | BinaryOp::LtEq
+    ) || column.name != column_name
+        || table_schema
+            .index_of(column.relation.as_ref(), &column.name)
+            .is_err()
+        || value.is_null()
+    {
+        return None;
+    }
+    let key_value = value.cast_to(&column_type).ok()?;
+    if key_value.cast_to(&value.data_type()).ok()? != *value {
+        return None;
+    }
+    Some((op, key_value))
+}
+
+/// Splits a join condition into pairs of left and right keys of its equi-predicates and
+/// the remaining predicates. Keys of different types are cast to a common type so that
+/// equal values hash the same.
+fn extract_equi_keys(
+    condition: &Expr,
+    left_schema: &Schema,
+    right_schema: &Schema,
+) -> (JoinKeys, Option<Expr>) {
+    let mut on = vec![];
+    let mut filters = vec![];
+    for predicate in split_conjunction(condition) {
+        if let Expr::Binary(BinaryExpr {
+            left,
+            op: BinaryOp::Eq,
+            right,
+        }) = &predicate
+        {
+            let keys = if refers_only_to(left, left_schema) && refers_only_to(right, right_schema) {
+                Some((left.as_ref().clone(), right.as_ref().clone()))
+            } else if refers_only_to(left, right_schema) && refers_only_to(right, left_schema) {
+                Some((right.as_ref().clone(), left.as_ref().clone()))
+            } else {
+                None
+            };
+            if let Some(keys) = keys.and_then(|(l, r)| coerce_keys(l, r, left_schema, right_schema))
+            {
+                on.push(keys);
+                continue;
+            }
+        }
+        filters.push(predicate);
+    }
+    (on, conjunction(filters))
+}
+
+fn coerce_keys(
+    left: Expr,
+    right: Expr,
+    left_schema: &Schema,
+    right_schema: &Schema,
+) -> Option<(Expr, Expr)> {
+    let left_type = left.data_type(left_schema).ok()?;
+    let right_type = right.data_type(right_schema).ok()?;
+    if left_type == right_type {
+        return Some((left, right));
+    }
+    if matches!(left_type, DataType::Varchar(_)) && matches!(right_type, DataType::Varchar(_)) {
+        return Some((left, right));
+    }
+    let data_type = DataType::comparison_coercion(&left_type, &right_type).ok()?;
+    let cast = |expr: Expr, from: DataType| {
+        if from == data_type {
+            expr
+        } else {
+            Expr::Cast(Cast {
+                expr: Box::new(expr),
+                data_type,
+            })
+        }
+    };
+    Some((cast(left, left_type), cast(right, right_type)))
+}
+
+/// Reorders keys so that the keys of one side are the leading columns of `order`.
+fn align_keys(
+    on: &[(Expr, Expr)],
+    order: &[String],
+    side: fn(&(Expr, Expr)) -> &Expr,
+) -> Option<JoinKeys> {
+    if order.len() < on.len() {
+        return None;
+    }
+    let mut remaining = on.to_vec();
+    let mut aligned = vec![];
+    for name in order.iter().take(on.len()) {
+        let pos = remaining
+            .iter()
+            .position(|keys| matches!(side(keys), Expr::Column(column) if &column.name == name))?;
+        aligned.push(remaining.remove(pos));
+    }
+    Some(aligned)
+}
+
+fn sort_by_keys<'a>(input: PhysicalPlan, keys: impl Iterator<Item = &'a Expr>) -> PhysicalPlan {
+    let order_bys = keys
+        .map(|key| OrderByExpr {
+            expr: Box::new(key.clone()),
+            asc: true,
+            nulls_first: true,
+        })
+        .collect();
+    PhysicalPlan::Sort(PhysicalSort::new(order_bys, Arc::new(input)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::execution::physical_plan::{
+        PhysicalIndexScan, PhysicalPlan, HASH_JOIN_MEMORY_BUDGET,
+    };
+    use crate::planner::PhysicalPlanner;
+    use crate::storage::Tuple;
+    use crate::Database;
+    use std::ops::Bound;
+
+    fn scan_plan(db: &mut Database, sql: &str) -> String {
+        let logical_plan = db.create_logical_plan(sql).unwrap();
+        let physical_planner = PhysicalPlanner {
+            catalog: &db.catalog,
+            hash_join_memory_budget: HASH_JOIN_MEMORY_BUDGET,
+        };
+        let mut plan = &physical_planner.create_physical_plan(logical_plan);
+        while !plan.inputs().is_empty() {
+            plan = plan.inputs()[0];
+        }
+        let format_start = |bound: &Bound<Tuple>| match bound {
+            Bound::Included(tuple) => format!("[{}", format_values(tuple)),
+            Bound::Excluded(tuple) => format!("({}", format_values(tuple)),
+            Bound::Unbounded => "(".to_string(),
+        };
+        let format_end = |bound: &Bound<Tuple>| match bound {
+            Bound::Included(tuple) => format!("{}]", format_values(tuple)),
+            Bound::Excluded(tuple) => format!("{})", format_values(tuple)),
+            Bound::Unbounded => ")".to_string(),
+        };
+        match plan {
+            PhysicalPlan::IndexScan(PhysicalIndexScan {
+                index_name,
+                start_bound,
+                end_bound,
+                ..
+            }) => format!(
+                "IndexScan({} {}, {})",
+                index_name,
+                format_start(start_bound),
+                format_end(end_bound)
+            ),
+            PhysicalPlan::SeqScan(_) => "SeqScan".to_string(),
+            _ => "Other".to_string(),
+        }
+    }
+
+    fn format_values(tuple: &Tuple) -> String {
+        tuple
+            .data
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    pub fn test_choose_index_range() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int, c int)").unwrap();
+        db.run("create index idx1 on t1 (a)").unwrap();
+        db.run("create index idx2 on t1 (b, c)").unwrap();
+
+        assert_eq!(scan_plan(&mut db, "select * from t1"), "SeqScan");
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where c = 1"),
+            "SeqScan"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a = 1"),
+            "IndexScan(idx1 [1, 1])"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a > 1 and 5 >= a and a > 2"),
+            "IndexScan(idx1 (2, 5])"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a between 1 and 3"),
+            "IndexScan(idx1 [1, 3])"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a < 2.5"),
+            "SeqScan"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a > 1 and b = 2 and c < 3"),
+            "IndexScan(idx2 [2, 2 3))"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where b >= 2"),
+            "IndexScan(idx2 [2, ))"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a = 1 and b > 2"),
+            "IndexScan(idx1 [1, 1])"
+        );
+    }
+
+    fn join_plan(db: &mut Database, sql: &str) -> String {
+        let logical_plan = db.create_logical_plan(sql).unwrap();
+        let physical_planner = PhysicalPlanner {
+            catalog: &db.catalog,
+            hash_join_memory_budget: HASH_JOIN_MEMORY_BUDGET,
+        };
+        let mut plan = &physical_planner.create_physical_plan(logical_plan);
+        while !matches!(
+            plan,
+            PhysicalPlan::SortMergeJoin(_) | PhysicalPlan::HashJoin(_)
+        ) {
+            plan = plan.inputs()[0];
+        }
+        let tables = plan
+            .inputs()
+            .into_iter()
+            .map(|mut input| {
+                while !input.inputs().is_empty() {
+                    input = input.inputs()[0];
+                }
+                match input {
+                    PhysicalPlan::SeqScan(scan) => scan.table.table().to_string(),
+                    PhysicalPlan::IndexScan(scan) => scan.table_ref.table().to_string(),
+                    _ => "Other".to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "{}({})",
+            plan.to_string().split(':').next().unwrap(),
+            tables.join(", ")
+        )
+    }
+
+    #[test]
+    pub fn test_choose_plan_by_cost() {
+        let mut db = Database::new_temp().unwrap();
+        db.run("create table t1 (a int, b int)").unwrap();
+        db.run("create index idx1 on t1 (a)").unwrap();
+        db.run("create index idx2 on t1 (b)").unwrap();
+        db.run("create table t2 (a int, c int)").unwrap();
+        for i in 0..100 {
+            db.run(&format!("insert into t1 values ({i}, {})", i % 2))
+                .unwrap();
+        }
+        for i in 0..10 {
+            db.run(&format!("insert into t2 values ({i}, {i})"))
+                .unwrap();
+        }
+
+        // without statistics any restricted index is used
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a > 5"),
+            "IndexScan(idx1 (5, ))"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where b = 1"),
+            "IndexScan(idx2 [1, 1])"
+        );
+        assert_eq!(
+            join_plan(&mut db, "select * from t2 join t1 on t2.a = t1.a"),
+            "SortMergeJoin(t2, t1)"
+        );
+
+        db.run("analyze table t1").unwrap();
+        db.run("analyze table t2").unwrap();
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a = 5"),
+            "IndexScan(idx1 [5, 5])"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a > 95"),
+            "IndexScan(idx1 (95, ))"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a > 5"),
+            "SeqScan"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where b = 1"),
+            "SeqScan"
+        );
+        assert_eq!(
+            scan_plan(&mut db, "select * from t1 where a > 5 and b = 1"),
+            "SeqScan"
+        );
+        // the smaller input is the build side
+        assert_eq!(
+            join_plan(&mut db, "select * from t2 join t1 on t2.a = t1.a"),
+            "HashJoin(t1, t2)"
+        );
+        assert_eq!(
+            join_plan(&mut db, "select * from t1 left join t2 on t1.a = t2.a"),
+            "HashJoin(t1, t2)"
+        );
+    }
 }
@@ -0,0 +1,352 @@
+use log::debug;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::buffer::PageId;
+use crate::recovery::{LogRecord, LogRecordBody, Lsn, INVALID_LSN};
+use crate::storage::codec::{CommonCodec, LogRecordCodec};
+use crate::transaction::{TransactionId, INVALID_TRANSACTION_ID};
+use crate::{BustubxError, BustubxResult};
+
+const LOG_FILE_MAGIC: u32 = 0x4258_574C;
+
+/**
+ * Log file format:
+ *  ----------------------------------------------------------------
+ *  | Magic (4) | BaseLSN (8) | LogRecord_1 | LogRecord_2 | ... |
+ *  ----------------------------------------------------------------
+ *  The lsn of a log record is `BaseLSN` plus its offset after the file header, so lsns keep
+ *  increasing after the log is truncated by a checkpoint.
+ */
+const LOG_FILE_HEADER_SIZE: u64 = 12;
+
+#[derive(Debug)]
+pub struct LogManager {
+    log_file: Mutex<File>,
+    /// Records appended but not written to the log file yet
+    log_buffer: Mutex<LogBuffer>,
+    base_lsn: AtomicU64,
+    /// All records whose lsn is less than `persistent_lsn` are durable
+    persistent_lsn: AtomicU64,
+    /// Last lsn of each transaction which has written log records but not finished yet
+    running_txns: Mutex<HashMap<TransactionId, Lsn>>,
+    /// Database executes statements one by one, so page writes belong to this transaction
+    current_txn: AtomicU64,
+}
+
+#[derive(Debug)]
+struct LogBuffer {
+    bytes: Vec<u8>,
+    next_lsn: Lsn,
+}
+
+impl LogManager {
+    pub fn try_new(log_path: impl AsRef<Path>) -> BustubxResult<Self> {
+        let mut log_file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(log_path)?;
+
+        let file_len = log_file.metadata()?.len();
+        let base_lsn = if file_len < LOG_FILE_HEADER_SIZE {
+            Self::write_header(&mut log_file, 1)?;
+            1
+        } else {
+            let mut buf = [0; LOG_FILE_HEADER_SIZE as usize];
+            log_file.read_exact(&mut buf)?;
+            let (magic, offset) = CommonCodec::decode_u32(&buf)?;
+            if magic != LOG_FILE_MAGIC {
+                return Err(BustubxError::Storage("Invalid log file".to_string()));
+            }
+            CommonCodec::decode_u64(&buf[offset..])?.0
+        };
+
+        let log_manager = Self {
+            log_file: Mutex::new(log_file),
+            log_buffer: Mutex::new(LogBuffer {
+                bytes: Vec::new(),
+                next_lsn: base_lsn,
+            }),
+            base_lsn: AtomicU64::new(base_lsn),
+            persistent_lsn: AtomicU64::new(base_lsn),
+            running_txns: Mutex::new(HashMap::new()),
+            current_txn: AtomicU64::new(INVALID_TRANSACTION_ID),
+        };
+
+        // skip existing records, a torn record at the tail is cut off
+        let records = log_manager.read_all_records()?;
+        let next_lsn = records
+            .last()
+            .map(|record| record.lsn + LogRecordCodec::encode(record).len() as u64)
+            .unwrap_or(base_lsn);
+        log_manager
+            .log_file
+            .lock()
+            .unwrap()
+            .set_len(LOG_FILE_HEADER_SIZE + next_lsn - base_lsn)?;
+        log_manager.log_buffer.lock().unwrap().next_lsn = next_lsn;
+        log_manager.persistent_lsn.store(next_lsn, Ordering::SeqCst);
+        debug!("Initialized log_manager next_lsn: {}", next_lsn);
+        Ok(log_manager)
+    }
+
+    /// Marks `txn_id` as the transaction the following page writes belong to.
+    pub fn begin(&self, txn_id: TransactionId) {
+        self.current_txn.store(txn_id, Ordering::SeqCst);
+    }
+
+    pub fn current_txn(&self) -> TransactionId {
+        self.current_txn.load(Ordering::SeqCst)
+    }
+
+    pub fn append(&self, txn_id: TransactionId, body: LogRecordBody) -> BustubxResult<Lsn> {
+        let mut running_txns = self.running_txns.lock().unwrap();
+        let prev_lsn = running_txns.get(&txn_id).copied().unwrap_or(INVALID_LSN);
+
+        let mut log_buffer = self.log_buffer.lock().unwrap();
+        let lsn = log_buffer.next_lsn;
+        let is_finished = matches!(body, LogRecordBody::Commit | LogRecordBody::Abort);
+        let bytes = LogRecordCodec::encode(&LogRecord {
+            lsn,
+            prev_lsn,
+            txn_id,
+            body,
+        });
+        log_buffer.next_lsn += bytes.len() as u64;
+        log_buffer.bytes.extend(bytes);
+
+        // changes made outside transactions are never undone
+        if txn_id != INVALID_TRANSACTION_ID {
+            if is_finished {
+                running_txns.remove(&txn_id);
+            } else {
+                running_txns.insert(txn_id, lsn);
+            }
+        }
+        Ok(lsn)
+    }
+
+    /// Logs a page change made by the current transaction.
+    pub fn append_page_update(
+        &self,
+        page_id: PageId,
+        offset: u16,
+        before: Vec<u8>,
+        after: Vec<u8>,
+    ) -> BustubxResult<Lsn> {
+        self.append(
+            self.current_txn(),
+            LogRecordBody::PageUpdate {
+                page_id,
+                offset,
+                before,
+                after,
+            },
+        )
+    }
+
+    /// Makes the transaction durable. Transactions without any change don't write log.
+    pub fn commit(&self, txn_id: TransactionId) -> BustubxResult<()> {
+        self.current_txn
+            .store(INVALID_TRANSACTION_ID, Ordering::SeqCst);
+        if !self.running_txns.lock().unwrap().contains_key(&txn_id) {
+            return Ok(());
+        }
+        self.append(txn_id, LogRecordBody::Commit)?;
+        self.flush_all()
+    }
+
+    /// Undoes all changes of the transaction from its newest log record to the oldest one.
+    /// Every undone change is logged as a compensation record and handed to `apply`
+    /// together with the lsn of that record.
+    ///
+    /// Returns whether the transaction had any change.
+    pub fn rollback<F>(&self, txn_id: TransactionId, apply: F) -> BustubxResult<bool>
+    where
+        F: FnMut(PageId, u16, &[u8], Lsn) -> BustubxResult<()>,
+    {
+        self.current_txn
+            .store(INVALID_TRANSACTION_ID, Ordering::SeqCst);
+        if !self.undo(txn_id, INVALID_LSN, apply)? {
+            return Ok(false);
+        }
+        self.append(txn_id, LogRecordBody::Abort)?;
+        self.flush_all()?;
+        Ok(true)
+    }
+
+    /// Last lsn written by the transaction, changes made after it can be undone
+    /// by `rollback_to`.
+    pub fn savepoint(&self, txn_id: TransactionId) -> Lsn {
+        self.running_txns
+            .lock()
+            .unwrap()
+            .get(&txn_id)
+            .copied()
+            .unwrap_or(INVALID_LSN)
+    }
+
+    /// Undoes changes of the transaction made after `savepoint`, the transaction keeps running.
+    ///
+    /// Returns whether there was any change to undo.
+    pub fn rollback_to<F>(
+        &self,
+        txn_id: TransactionId,
+        savepoint: Lsn,
+        apply: F,
+    ) -> BustubxResult<bool>
+    where
+        F: FnMut(PageId, u16, &[u8], Lsn) -> BustubxResult<()>,
+    {
+        self.undo(txn_id, savepoint, apply)
+    }
+
+    fn undo<F>(&self, txn_id: TransactionId, savepoint: Lsn, mut apply: F) -> BustubxResult<bool>
+    where
+        F: FnMut(PageId, u16, &[u8], Lsn) -> BustubxResult<()>,
+    {
+        let last_lsn = self.savepoint(txn_id);
+        if last_lsn == savepoint {
+            return Ok(false);
+        }
+
+        let mut undo_lsn = last_lsn;
+        while undo_lsn != INVALID_LSN && undo_lsn > savepoint {
+            let record = self.read_record(undo_lsn)?;
+            undo_lsn = match record.body {
+                LogRecordBody::PageUpdate {
+                    page_id,
+                    offset,
+                    before,
+                    ..
+                } => {
+                    let clr_lsn = self.append(
+                        txn_id,
+                        LogRecordBody::Compensation {
+                            page_id,
+                            offset,
+                            after: before.clone(),
+                            undo_next_lsn: record.prev_lsn,
+                        },
+                    )?;
+                    apply(page_id, offset, &before, clr_lsn)?;
+                    record.prev_lsn
+                }
+                LogRecordBody::Compensation { undo_next_lsn, .. } => undo_next_lsn,
+                LogRecordBody::Commit | LogRecordBody::Abort => {
+                    return Err(BustubxError::Internal(format!(
+                        "Cannot rollback finished transaction {}",
+                        txn_id
+                    )))
+                }
+            };
+        }
+        Ok(true)
+    }
+
+    /// Makes sure all records up to and including `lsn` are durable.
+    pub fn flush(&self, lsn: Lsn) -> BustubxResult<()> {
+        if lsn < self.persistent_lsn.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.flush_all()
+    }
+
+    pub fn flush_all(&self) -> BustubxResult<()> {
+        let mut log_buffer = self.log_buffer.lock().unwrap();
+        if log_buffer.bytes.is_empty() {
+            return Ok(());
+        }
+        let mut guard = self.log_file.lock().unwrap();
+        guard.seek(SeekFrom::End(0))?;
+        guard.write_all(&log_buffer.bytes)?;
+        guard.sync_data()?;
+        log_buffer.bytes.clear();
+        self.persistent_lsn
+            .store(log_buffer.next_lsn, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn has_running_txns(&self) -> bool {
+        !self.running_txns.lock().unwrap().is_empty()
+    }
+
+    /// Registers transactions found unfinished by recovery so that their compensation
+    /// records are chained correctly.
+    pub fn load_running_txns(&self, txns: HashMap<TransactionId, Lsn>) {
+        *self.running_txns.lock().unwrap() = txns;
+    }
+
+    /// Discards all log records. Must only be called when all dirty pages were flushed
+    /// and no transaction is running.
+    pub fn truncate(&self) -> BustubxResult<()> {
+        if self.has_running_txns() {
+            return Err(BustubxError::Internal(
+                "Cannot truncate log while transactions are running".to_string(),
+            ));
+        }
+        self.flush_all()?;
+        let next_lsn = self.log_buffer.lock().unwrap().next_lsn;
+        let mut guard = self.log_file.lock().unwrap();
+        Self::write_header(&mut guard, next_lsn)?;
+        guard.set_len(LOG_FILE_HEADER_SIZE)?;
+        guard.sync_data()?;
+        self.base_lsn.store(next_lsn, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn read_record(&self, lsn: Lsn) -> BustubxResult<LogRecord> {
+        self.flush_all()?;
+        let base_lsn = self.base_lsn.load(Ordering::SeqCst);
+        if lsn < base_lsn {
+            return Err(BustubxError::Internal(format!(
+                "Log record {} has been truncated",
+                lsn
+            )));
+        }
+        let mut guard = self.log_file.lock().unwrap();
+        guard.seek(SeekFrom::Start(LOG_FILE_HEADER_SIZE + lsn - base_lsn))?;
+        let mut size_buf = [0; 4];
+        guard.read_exact(&mut size_buf)?;
+        let (size, _) = CommonCodec::decode_u32(&size_buf)?;
+        let mut buf = size_buf.to_vec();
+        buf.resize(size as usize, 0);
+        guard.read_exact(&mut buf[4..])?;
+        let (record, _) = LogRecordCodec::decode(&buf)?;
+        Ok(record)
+    }
+
+    /// Reads all complete records in the log file.
+    pub fn read_all_records(&self) -> BustubxResult<Vec<LogRecord>> {
+        let mut bytes = Vec::new();
+        let mut guard = self.log_file.lock().unwrap();
+        guard.seek(SeekFrom::Start(LOG_FILE_HEADER_SIZE))?;
+        guard.read_to_end(&mut bytes)?;
+        drop(guard);
+
+        let mut records = Vec::new();
+        let mut left_bytes = bytes.as_slice();
+        while !left_bytes.is_empty() {
+            let Ok((record, offset)) = LogRecordCodec::decode(left_bytes) else {
+                break;
+            };
+            left_bytes = &left_bytes[offset..];
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    fn write_header(log_file: &mut File, base_lsn: Lsn) -> BustubxResult<()> {
+        let mut bytes = CommonCodec::encode_u32(LOG_FILE_MAGIC);
+        bytes.extend(CommonCodec::encode_u64(base_lsn));
+        log_file.seek(SeekFrom::Start(0))?;
+        log_file.write_all(&bytes)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::buffer::PageId;
+use crate::transaction::TransactionId;
+
+pub type Lsn = u64;
+pub const INVALID_LSN: Lsn = 0;
+
+/// Every table page and index page stores the lsn of the last log record applied to it
+/// in its first bytes.
+pub const PAGE_LSN_SIZE: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    pub lsn: Lsn,
+    /// Previous log record of the same transaction
+    pub prev_lsn: Lsn,
+    pub txn_id: TransactionId,
+    pub body: LogRecordBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogRecordBody {
+    /// Bytes `[offset, offset + after.len())` of a page changed from `before` to `after`.
+    PageUpdate {
+        page_id: PageId,
+        offset: u16,
+        before: Vec<u8>,
+        after: Vec<u8>,
+    },
+    /// Compensation log record (CLR) written when undoing a `PageUpdate`.
+    /// It is redo-only, `undo_next_lsn` points to the next record to undo.
+    Compensation {
+        page_id: PageId,
+        offset: u16,
+        after: Vec<u8>,
+        undo_next_lsn: Lsn,
+    },
+    Commit,
+    /// Written after all changes of the transaction have been undone.
+    Abort,
+}
+
+impl LogRecord {
+    /// Returns the page change this record redoes, if any.
+    pub fn redo_data(&self) -> Option<(PageId, u16, &[u8])> {
+        match &self.body {
+            LogRecordBody::PageUpdate {
+                page_id,
+                offset,
+                after,
+                ..
+            }
+            | LogRecordBody::Compensation {
+                page_id,
+                offset,
+                after,
+                ..
+            } => Some((*page_id, *offset, after.as_slice())),
+            LogRecordBody::Commit | LogRecordBody::Abort => None,
+        }
+    }
+}
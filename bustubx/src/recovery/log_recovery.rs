@@ -0,0 +1,105 @@
+use log::info;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::buffer::{PageId, BUSTUBX_PAGE_SIZE};
+use crate::recovery::{LogManager, LogRecordBody, Lsn, PAGE_LSN_SIZE};
+use crate::storage::codec::CommonCodec;
+use crate::storage::DiskManager;
+use crate::transaction::{TransactionId, INVALID_TRANSACTION_ID};
+use crate::BustubxResult;
+
+/// ARIES style recovery which runs before the buffer pool is created, so pages are read from
+/// and written to disk directly.
+pub struct LogRecovery {
+    disk_manager: Arc<DiskManager>,
+    log_manager: Arc<LogManager>,
+    pages: HashMap<PageId, [u8; BUSTUBX_PAGE_SIZE]>,
+}
+
+impl LogRecovery {
+    pub fn new(disk_manager: Arc<DiskManager>, log_manager: Arc<LogManager>) -> Self {
+        Self {
+            disk_manager,
+            log_manager,
+            pages: HashMap::new(),
+        }
+    }
+
+    pub fn recover(&mut self) -> BustubxResult<()> {
+        let records = self.log_manager.read_all_records()?;
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        // analysis: find transactions which neither committed nor aborted
+        let mut loser_txns: HashMap<TransactionId, Lsn> = HashMap::new();
+        for record in records.iter() {
+            if record.txn_id == INVALID_TRANSACTION_ID {
+                continue;
+            }
+            match record.body {
+                LogRecordBody::Commit | LogRecordBody::Abort => {
+                    loser_txns.remove(&record.txn_id);
+                }
+                _ => {
+                    loser_txns.insert(record.txn_id, record.lsn);
+                }
+            }
+        }
+
+        // redo: repeat history for pages older than the log record
+        for record in records.iter() {
+            if let Some((page_id, offset, data)) = record.redo_data() {
+                self.apply(page_id, offset, data, record.lsn, true)?;
+            }
+        }
+
+        // undo: roll back losers from the newest one
+        let mut losers = loser_txns.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+        losers.sort_by_key(|(_, last_lsn)| std::cmp::Reverse(*last_lsn));
+        let log_manager = self.log_manager.clone();
+        log_manager.load_running_txns(loser_txns);
+        for (txn_id, _) in losers.iter() {
+            log_manager.rollback(*txn_id, |page_id, offset, data, lsn| {
+                self.apply(page_id, offset, data, lsn, false)
+            })?;
+        }
+
+        for (page_id, data) in self.pages.iter() {
+            self.disk_manager.write_page(*page_id, data)?;
+        }
+        self.disk_manager.sync()?;
+        self.log_manager.truncate()?;
+        info!(
+            "Recovered from {} log records, {} transactions rolled back",
+            records.len(),
+            losers.len()
+        );
+        Ok(())
+    }
+
+    fn apply(
+        &mut self,
+        page_id: PageId,
+        offset: u16,
+        data: &[u8],
+        lsn: Lsn,
+        check_page_lsn: bool,
+    ) -> BustubxResult<()> {
+        let page = match self.pages.entry(page_id) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(self.disk_manager.read_page(page_id)?)
+            }
+        };
+        let (page_lsn, _) = CommonCodec::decode_u64(&page[0..PAGE_LSN_SIZE])?;
+        if check_page_lsn && page_lsn >= lsn {
+            return Ok(());
+        }
+        let offset = offset as usize;
+        page[offset..offset + data.len()].copy_from_slice(data);
+        page[0..PAGE_LSN_SIZE].copy_from_slice(&CommonCodec::encode_u64(lsn));
+        Ok(())
+    }
+}
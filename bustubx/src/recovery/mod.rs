@@ -0,0 +1,7 @@
+mod log_manager;
+mod log_record;
+mod log_recovery;
+
+pub use log_manager::LogManager;
+pub use log_record::*;
+pub use log_recovery::LogRecovery;
@@ -27,7 +27,7 @@ impl BPlusTreePageCodec {
         }
 
         // not consume left_bytes
-        let (page_type, _) = BPlusTreePageTypeCodec::decode(bytes)?;
+        let (page_type, _) = BPlusTreePageTypeCodec::peek(bytes)?;
 
         match page_type {
             BPlusTreePageType::LeafPage => {
@@ -72,7 +72,7 @@ impl BPlusTreeLeafPageCodec {
         let mut left_bytes = bytes;
 
         // not consume left_bytes
-        let (page_type, _) = BPlusTreePageTypeCodec::decode(left_bytes)?;
+        let (page_type, _) = BPlusTreePageTypeCodec::peek(left_bytes)?;
 
         if matches!(page_type, BPlusTreePageType::LeafPage) {
             let (header, offset) = BPlusTreeLeafPageHeaderCodec::decode(left_bytes)?;
@@ -135,7 +135,7 @@ impl BPlusTreeInternalPageCodec {
         let mut left_bytes = bytes;
 
         // not consume left_bytes
-        let (page_type, _) = BPlusTreePageTypeCodec::decode(left_bytes)?;
+        let (page_type, _) = BPlusTreePageTypeCodec::peek(left_bytes)?;
 
         if matches!(page_type, BPlusTreePageType::InternalPage) {
             let (header, offset) = BPlusTreeInternalPageHeaderCodec::decode(left_bytes)?;
@@ -178,6 +178,12 @@ impl BPlusTreePageTypeCodec {
         }
     }
 
+    /// Decodes the page type of a whole index page, which follows the page lsn.
+    pub fn peek(page_bytes: &[u8]) -> BustubxResult<DecodedData<BPlusTreePageType>> {
+        let (_lsn, offset) = CommonCodec::decode_u64(page_bytes)?;
+        Self::decode(&page_bytes[offset..])
+    }
+
     pub fn decode(bytes: &[u8]) -> BustubxResult<DecodedData<BPlusTreePageType>> {
         let (flag, offset) = CommonCodec::decode_u8(bytes)?;
         match flag {
@@ -193,6 +199,7 @@ pub struct BPlusTreeLeafPageHeaderCodec;
 impl BPlusTreeLeafPageHeaderCodec {
     pub fn encode(header: &BPlusTreeLeafPageHeader) -> Vec<u8> {
         let mut bytes = Vec::new();
+        bytes.extend(CommonCodec::encode_u64(header.lsn));
         bytes.extend(BPlusTreePageTypeCodec::encode(&header.page_type));
         bytes.extend(CommonCodec::encode_u32(header.current_size));
         bytes.extend(CommonCodec::encode_u32(header.max_size));
@@ -203,6 +210,9 @@ impl BPlusTreeLeafPageHeaderCodec {
     pub fn decode(bytes: &[u8]) -> BustubxResult<DecodedData<BPlusTreeLeafPageHeader>> {
         let mut left_bytes = bytes;
 
+        let (lsn, offset) = CommonCodec::decode_u64(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+
         let (page_type, offset) = BPlusTreePageTypeCodec::decode(left_bytes)?;
         left_bytes = &left_bytes[offset..];
 
@@ -217,6 +227,7 @@ impl BPlusTreeLeafPageHeaderCodec {
 
         Ok((
             BPlusTreeLeafPageHeader {
+                lsn,
                 page_type,
                 current_size,
                 max_size,
@@ -232,6 +243,7 @@ pub struct BPlusTreeInternalPageHeaderCodec;
 impl BPlusTreeInternalPageHeaderCodec {
     pub fn encode(header: &BPlusTreeInternalPageHeader) -> Vec<u8> {
         let mut bytes = Vec::new();
+        bytes.extend(CommonCodec::encode_u64(header.lsn));
         bytes.extend(BPlusTreePageTypeCodec::encode(&header.page_type));
         bytes.extend(CommonCodec::encode_u32(header.current_size));
         bytes.extend(CommonCodec::encode_u32(header.max_size));
@@ -241,6 +253,9 @@ impl BPlusTreeInternalPageHeaderCodec {
     pub fn decode(bytes: &[u8]) -> BustubxResult<DecodedData<BPlusTreeInternalPageHeader>> {
         let mut left_bytes = bytes;
 
+        let (lsn, offset) = CommonCodec::decode_u64(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+
         let (page_type, offset) = BPlusTreePageTypeCodec::decode(left_bytes)?;
         left_bytes = &left_bytes[offset..];
 
@@ -252,6 +267,7 @@ impl BPlusTreeInternalPageHeaderCodec {
 
         Ok((
             BPlusTreeInternalPageHeader {
+                lsn,
                 page_type,
                 current_size,
                 max_size,
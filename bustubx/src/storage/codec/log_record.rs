@@ -0,0 +1,179 @@
+use crate::recovery::{LogRecord, LogRecordBody};
+use crate::storage::codec::{CommonCodec, DecodedData};
+use crate::{BustubxError, BustubxResult};
+
+/**
+ * Log record format (size in bytes):
+ *  ----------------------------------------------------------------------
+ *  | Size (4) | LSN (8) | PrevLSN (8) | TxnId (8) | RecordType (1) | ... |
+ *  ----------------------------------------------------------------------
+ *  `Size` is the size of the whole record including itself.
+ */
+pub struct LogRecordCodec;
+
+impl LogRecordCodec {
+    pub fn encode(record: &LogRecord) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(CommonCodec::encode_u64(record.lsn));
+        bytes.extend(CommonCodec::encode_u64(record.prev_lsn));
+        bytes.extend(CommonCodec::encode_u64(record.txn_id));
+        match &record.body {
+            LogRecordBody::PageUpdate {
+                page_id,
+                offset,
+                before,
+                after,
+            } => {
+                bytes.extend(CommonCodec::encode_u8(1));
+                bytes.extend(CommonCodec::encode_u32(*page_id));
+                bytes.extend(CommonCodec::encode_u16(*offset));
+                bytes.extend(CommonCodec::encode_u16(after.len() as u16));
+                bytes.extend(before);
+                bytes.extend(after);
+            }
+            LogRecordBody::Compensation {
+                page_id,
+                offset,
+                after,
+                undo_next_lsn,
+            } => {
+                bytes.extend(CommonCodec::encode_u8(2));
+                bytes.extend(CommonCodec::encode_u32(*page_id));
+                bytes.extend(CommonCodec::encode_u16(*offset));
+                bytes.extend(CommonCodec::encode_u16(after.len() as u16));
+                bytes.extend(after);
+                bytes.extend(CommonCodec::encode_u64(*undo_next_lsn));
+            }
+            LogRecordBody::Commit => bytes.extend(CommonCodec::encode_u8(3)),
+            LogRecordBody::Abort => bytes.extend(CommonCodec::encode_u8(4)),
+        }
+        let mut record_bytes = CommonCodec::encode_u32(bytes.len() as u32 + 4);
+        record_bytes.extend(bytes);
+        record_bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> BustubxResult<DecodedData<LogRecord>> {
+        let (size, _) = CommonCodec::decode_u32(bytes)?;
+        if (size as usize) > bytes.len() {
+            return Err(BustubxError::Storage(format!(
+                "Log record size {} exceeds {} bytes left",
+                size,
+                bytes.len()
+            )));
+        }
+        let mut left_bytes = &bytes[4..size as usize];
+
+        let (lsn, offset) = CommonCodec::decode_u64(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+        let (prev_lsn, offset) = CommonCodec::decode_u64(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+        let (txn_id, offset) = CommonCodec::decode_u64(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+        let (record_type, offset) = CommonCodec::decode_u8(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+
+        let body = match record_type {
+            1 | 2 => {
+                let (page_id, offset) = CommonCodec::decode_u32(left_bytes)?;
+                left_bytes = &left_bytes[offset..];
+                let (data_offset, offset) = CommonCodec::decode_u16(left_bytes)?;
+                left_bytes = &left_bytes[offset..];
+                let (len, offset) = CommonCodec::decode_u16(left_bytes)?;
+                left_bytes = &left_bytes[offset..];
+                let len = len as usize;
+                let data_count = if record_type == 1 { 2 } else { 1 };
+                if left_bytes.len() < len * data_count {
+                    return Err(BustubxError::Storage(
+                        "Log record page data is truncated".to_string(),
+                    ));
+                }
+                if record_type == 1 {
+                    LogRecordBody::PageUpdate {
+                        page_id,
+                        offset: data_offset,
+                        before: left_bytes[0..len].to_vec(),
+                        after: left_bytes[len..2 * len].to_vec(),
+                    }
+                } else {
+                    let after = left_bytes[0..len].to_vec();
+                    let (undo_next_lsn, _) = CommonCodec::decode_u64(&left_bytes[len..])?;
+                    LogRecordBody::Compensation {
+                        page_id,
+                        offset: data_offset,
+                        after,
+                        undo_next_lsn,
+                    }
+                }
+            }
+            3 => LogRecordBody::Commit,
+            4 => LogRecordBody::Abort,
+            _ => {
+                return Err(BustubxError::Storage(format!(
+                    "Invalid log record type {}",
+                    record_type
+                )))
+            }
+        };
+
+        Ok((
+            LogRecord {
+                lsn,
+                prev_lsn,
+                txn_id,
+                body,
+            },
+            size as usize,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::recovery::{LogRecord, LogRecordBody};
+    use crate::storage::codec::LogRecordCodec;
+
+    #[test]
+    fn log_record_codec() {
+        let records = [
+            LogRecord {
+                lsn: 10,
+                prev_lsn: 0,
+                txn_id: 1,
+                body: LogRecordBody::PageUpdate {
+                    page_id: 5,
+                    offset: 100,
+                    before: vec![0, 0, 0],
+                    after: vec![1, 2, 3],
+                },
+            },
+            LogRecord {
+                lsn: 60,
+                prev_lsn: 10,
+                txn_id: 1,
+                body: LogRecordBody::Compensation {
+                    page_id: 5,
+                    offset: 100,
+                    after: vec![0, 0, 0],
+                    undo_next_lsn: 0,
+                },
+            },
+            LogRecord {
+                lsn: 90,
+                prev_lsn: 60,
+                txn_id: 1,
+                body: LogRecordBody::Abort,
+            },
+        ];
+        let mut bytes = vec![];
+        for record in records.iter() {
+            bytes.extend(LogRecordCodec::encode(record));
+        }
+        let mut left_bytes = bytes.as_slice();
+        for record in records.iter() {
+            let (new_record, offset) = LogRecordCodec::decode(left_bytes).unwrap();
+            left_bytes = &left_bytes[offset..];
+            assert_eq!(&new_record, record);
+        }
+        assert!(left_bytes.is_empty());
+    }
+}
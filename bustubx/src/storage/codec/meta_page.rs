@@ -7,6 +7,7 @@ pub struct MetaPageCodec;
 impl MetaPageCodec {
     pub fn encode(page: &MetaPage) -> Vec<u8> {
         let mut bytes = Vec::new();
+        bytes.extend(CommonCodec::encode_u32(page.format_version));
         bytes.extend(CommonCodec::encode_u32(page.major_version));
         bytes.extend(CommonCodec::encode_u32(page.minor_version));
         bytes.extend(CommonCodec::encode_u32(page.freelist_page_id));
@@ -22,12 +23,23 @@ impl MetaPageCodec {
         bytes.extend(CommonCodec::encode_u32(
             page.information_schema_indexes_first_page_id,
         ));
+        bytes.extend(CommonCodec::encode_u32(
+            page.information_schema_statistics_first_page_id,
+        ));
+        bytes.extend(CommonCodec::encode_u32(
+            page.information_schema_constraints_first_page_id,
+        ));
+        bytes.extend(CommonCodec::encode_u32(
+            page.information_schema_foreign_keys_first_page_id,
+        ));
         bytes
     }
 
     pub fn decode(bytes: &[u8]) -> BustubxResult<DecodedData<MetaPage>> {
         let mut left_bytes = bytes;
 
+        let (format_version, offset) = CommonCodec::decode_u32(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
         let (major_version, offset) = CommonCodec::decode_u32(left_bytes)?;
         left_bytes = &left_bytes[offset..];
         let (minor_version, offset) = CommonCodec::decode_u32(left_bytes)?;
@@ -46,9 +58,19 @@ impl MetaPageCodec {
         let (information_schema_indexes_first_page_id, offset) =
             CommonCodec::decode_u32(left_bytes)?;
         left_bytes = &left_bytes[offset..];
+        let (information_schema_statistics_first_page_id, offset) =
+            CommonCodec::decode_u32(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+        let (information_schema_constraints_first_page_id, offset) =
+            CommonCodec::decode_u32(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+        let (information_schema_foreign_keys_first_page_id, offset) =
+            CommonCodec::decode_u32(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
 
         Ok((
             MetaPage {
+                format_version,
                 major_version,
                 minor_version,
                 freelist_page_id,
@@ -56,6 +78,9 @@ impl MetaPageCodec {
                 information_schema_tables_first_page_id,
                 information_schema_columns_first_page_id,
                 information_schema_indexes_first_page_id,
+                information_schema_statistics_first_page_id,
+                information_schema_constraints_first_page_id,
+                information_schema_foreign_keys_first_page_id,
             },
             bytes.len() - left_bytes.len(),
         ))
@@ -1,7 +1,9 @@
 mod common;
 mod freelist_page;
 mod index_page;
+mod log_record;
 mod meta_page;
+mod overflow_page;
 mod scalar;
 mod table_page;
 mod tuple;
@@ -9,8 +11,10 @@ mod tuple;
 pub use common::CommonCodec;
 pub use freelist_page::{FreelistPageCodec, FreelistPageHeaderCodec};
 pub use index_page::*;
+pub use log_record::LogRecordCodec;
 pub use meta_page::MetaPageCodec;
-pub use scalar::ScalarValueCodec;
+pub use overflow_page::{OverflowPageCodec, OverflowPageHeaderCodec};
+pub use scalar::{ScalarValueCodec, OVERFLOW_POINTER_SIZE};
 pub use table_page::*;
 pub use tuple::TupleCodec;
 
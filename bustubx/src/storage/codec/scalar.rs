@@ -1,7 +1,15 @@
 use crate::catalog::DataType;
+use crate::common::temporal::Interval;
 use crate::common::ScalarValue;
 use crate::storage::codec::{CommonCodec, DecodedData};
-use crate::BustubxResult;
+use crate::storage::OverflowPointer;
+use crate::{BustubxError, BustubxResult};
+
+/// Length of a variable length value which is stored in overflow pages, the
+/// `OverflowPointer` follows it instead of the value.
+const OVERFLOW_MARKER: u16 = u16::MAX;
+/// Size of an `OverflowPointer` after the marker.
+pub const OVERFLOW_POINTER_SIZE: usize = 8;
 
 pub struct ScalarValueCodec;
 
@@ -19,13 +27,17 @@ impl ScalarValueCodec {
             ScalarValue::UInt64(Some(v)) => CommonCodec::encode_u64(*v),
             ScalarValue::Float32(Some(v)) => CommonCodec::encode_f32(*v),
             ScalarValue::Float64(Some(v)) => CommonCodec::encode_f64(*v),
-            ScalarValue::Varchar(Some(v)) => {
-                if v.len() > u16::MAX as usize {
-                    panic!("Varchar length is greater than u16::Max")
-                }
+            ScalarValue::Varchar(Some(v)) => Self::encode_variable(&CommonCodec::encode_string(v)),
+            ScalarValue::Blob(Some(v)) => Self::encode_variable(v),
+            ScalarValue::Decimal(Some(v), _, _) => CommonCodec::encode_i128(*v),
+            ScalarValue::Date(Some(v)) => CommonCodec::encode_i32(*v),
+            ScalarValue::Time(Some(v)) => CommonCodec::encode_i64(*v),
+            ScalarValue::Timestamp(Some(v)) => CommonCodec::encode_i64(*v),
+            ScalarValue::Interval(Some(v)) => {
                 let mut bytes = vec![];
-                bytes.extend(CommonCodec::encode_u16(v.len() as u16));
-                bytes.extend(CommonCodec::encode_string(v));
+                bytes.extend(CommonCodec::encode_i32(v.months));
+                bytes.extend(CommonCodec::encode_i32(v.days));
+                bytes.extend(CommonCodec::encode_i64(v.micros));
                 bytes
             }
             // null
@@ -40,10 +52,99 @@ impl ScalarValueCodec {
             | ScalarValue::UInt64(None)
             | ScalarValue::Float32(None)
             | ScalarValue::Float64(None)
-            | ScalarValue::Varchar(None) => vec![],
+            | ScalarValue::Varchar(None)
+            | ScalarValue::Blob(None)
+            | ScalarValue::Decimal(None, _, _)
+            | ScalarValue::Date(None)
+            | ScalarValue::Time(None)
+            | ScalarValue::Timestamp(None)
+            | ScalarValue::Interval(None) => vec![],
+        }
+    }
+
+    /// Length of the encoded non-null value at the start of `bytes` without decoding it.
+    pub fn encoded_len(bytes: &[u8], data_type: DataType) -> BustubxResult<usize> {
+        match data_type {
+            DataType::Boolean | DataType::Int8 | DataType::UInt8 => Ok(1),
+            DataType::Int16 | DataType::UInt16 => Ok(2),
+            DataType::Int32 | DataType::UInt32 | DataType::Float32 | DataType::Date => Ok(4),
+            DataType::Int64
+            | DataType::UInt64
+            | DataType::Float64
+            | DataType::Time
+            | DataType::Timestamp => Ok(8),
+            DataType::Decimal(_, _) | DataType::Interval => Ok(16),
+            DataType::Varchar(_) | DataType::Blob => {
+                let (length, offset) = CommonCodec::decode_u16(bytes)?;
+                if length == OVERFLOW_MARKER {
+                    Ok(offset + OVERFLOW_POINTER_SIZE)
+                } else {
+                    Ok(offset + length as usize)
+                }
+            }
+        }
+    }
+
+    fn encode_variable(bytes: &[u8]) -> Vec<u8> {
+        if bytes.len() >= OVERFLOW_MARKER as usize {
+            panic!("Variable length value is too long to be stored inline")
+        }
+        let mut encoded = CommonCodec::encode_u16(bytes.len() as u16);
+        encoded.extend(bytes);
+        encoded
+    }
+
+    /// Bytes of a variable length value, which can be stored in overflow pages.
+    pub fn overflow_bytes(value: &ScalarValue) -> Option<&[u8]> {
+        match value {
+            ScalarValue::Varchar(Some(v)) => Some(v.as_bytes()),
+            ScalarValue::Blob(Some(v)) => Some(v),
+            _ => None,
         }
     }
 
+    /// Rebuilds a value from the bytes read from its overflow pages.
+    pub fn from_overflow_bytes(bytes: Vec<u8>, data_type: DataType) -> BustubxResult<ScalarValue> {
+        match data_type {
+            DataType::Varchar(_) => Ok(ScalarValue::Varchar(Some(
+                String::from_utf8(bytes)
+                    .map_err(|e| BustubxError::Storage(format!("Failed to decode string {}", e)))?,
+            ))),
+            DataType::Blob => Ok(ScalarValue::Blob(Some(bytes))),
+            _ => Err(BustubxError::Storage(format!(
+                "{} values are not stored in overflow pages",
+                data_type
+            ))),
+        }
+    }
+
+    pub fn encode_overflow_pointer(pointer: &OverflowPointer) -> Vec<u8> {
+        let mut bytes = CommonCodec::encode_u16(OVERFLOW_MARKER);
+        bytes.extend(CommonCodec::encode_u32(pointer.first_page_id));
+        bytes.extend(CommonCodec::encode_u32(pointer.len));
+        bytes
+    }
+
+    /// Decodes the pointer if the value at the start of `bytes` is stored in overflow pages.
+    pub fn decode_overflow_pointer(
+        bytes: &[u8],
+        data_type: DataType,
+    ) -> BustubxResult<Option<DecodedData<OverflowPointer>>> {
+        if !matches!(data_type, DataType::Varchar(_) | DataType::Blob) {
+            return Ok(None);
+        }
+        let (length, offset1) = CommonCodec::decode_u16(bytes)?;
+        if length != OVERFLOW_MARKER {
+            return Ok(None);
+        }
+        let (first_page_id, offset2) = CommonCodec::decode_u32(&bytes[offset1..])?;
+        let (len, offset3) = CommonCodec::decode_u32(&bytes[offset1 + offset2..])?;
+        Ok(Some((
+            OverflowPointer::new(first_page_id, len),
+            offset1 + offset2 + offset3,
+        )))
+    }
+
     pub fn decode(bytes: &[u8], data_type: DataType) -> BustubxResult<DecodedData<ScalarValue>> {
         match data_type {
             DataType::Boolean => {
@@ -94,6 +195,11 @@ impl ScalarValueCodec {
                 let mut left_bytes = bytes;
 
                 let (length, offset) = CommonCodec::decode_u16(left_bytes)?;
+                if length == OVERFLOW_MARKER {
+                    return Err(BustubxError::Storage(
+                        "Value is stored in overflow pages".to_string(),
+                    ));
+                }
                 left_bytes = &left_bytes[offset..];
 
                 let (value, offset) = CommonCodec::decode_string(&left_bytes[0..length as usize])?;
@@ -104,6 +210,48 @@ impl ScalarValueCodec {
                     bytes.len() - left_bytes.len(),
                 ))
             }
+            DataType::Blob => {
+                let (length, offset) = CommonCodec::decode_u16(bytes)?;
+                if length == OVERFLOW_MARKER {
+                    return Err(BustubxError::Storage(
+                        "Value is stored in overflow pages".to_string(),
+                    ));
+                }
+                let end = offset + length as usize;
+                if bytes.len() < end {
+                    return Err(BustubxError::Storage(format!(
+                        "bytes length {} is less than {}",
+                        bytes.len(),
+                        end
+                    )));
+                }
+                Ok((ScalarValue::Blob(Some(bytes[offset..end].to_vec())), end))
+            }
+            DataType::Decimal(precision, scale) => {
+                let (value, offset) = CommonCodec::decode_i128(bytes)?;
+                Ok((ScalarValue::Decimal(Some(value), precision, scale), offset))
+            }
+            DataType::Date => {
+                let (value, offset) = CommonCodec::decode_i32(bytes)?;
+                Ok((ScalarValue::Date(Some(value)), offset))
+            }
+            DataType::Time => {
+                let (value, offset) = CommonCodec::decode_i64(bytes)?;
+                Ok((ScalarValue::Time(Some(value)), offset))
+            }
+            DataType::Timestamp => {
+                let (value, offset) = CommonCodec::decode_i64(bytes)?;
+                Ok((ScalarValue::Timestamp(Some(value)), offset))
+            }
+            DataType::Interval => {
+                let (months, offset1) = CommonCodec::decode_i32(bytes)?;
+                let (days, offset2) = CommonCodec::decode_i32(&bytes[offset1..])?;
+                let (micros, offset3) = CommonCodec::decode_i64(&bytes[offset1 + offset2..])?;
+                Ok((
+                    ScalarValue::Interval(Some(Interval::new(months, days, micros))),
+                    offset1 + offset2 + offset3,
+                ))
+            }
         }
     }
 }
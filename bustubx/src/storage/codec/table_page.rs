@@ -40,6 +40,7 @@ pub struct TablePageHeaderCodec;
 impl TablePageHeaderCodec {
     pub fn encode(header: &TablePageHeader) -> Vec<u8> {
         let mut bytes = Vec::new();
+        bytes.extend(CommonCodec::encode_u64(header.lsn));
         bytes.extend(CommonCodec::encode_u32(header.next_page_id));
         bytes.extend(CommonCodec::encode_u16(header.num_tuples));
         bytes.extend(CommonCodec::encode_u16(header.num_deleted_tuples));
@@ -52,6 +53,9 @@ impl TablePageHeaderCodec {
     pub fn decode(bytes: &[u8]) -> BustubxResult<DecodedData<TablePageHeader>> {
         let mut left_bytes = bytes;
 
+        let (lsn, offset) = CommonCodec::decode_u64(left_bytes)?;
+        left_bytes = &left_bytes[offset..];
+
         let (next_page_id, offset) = CommonCodec::decode_u32(left_bytes)?;
         left_bytes = &left_bytes[offset..];
 
@@ -69,6 +73,7 @@ impl TablePageHeaderCodec {
         }
         Ok((
             TablePageHeader {
+                lsn,
                 next_page_id,
                 num_tuples,
                 num_deleted_tuples,
@@ -150,7 +155,7 @@ mod tests {
     use crate::buffer::INVALID_PAGE_ID;
     use crate::catalog::{Column, DataType, Schema};
     use crate::storage::codec::table_page::TablePageHeaderCodec;
-    use crate::storage::codec::TablePageCodec;
+    use crate::storage::codec::{TablePageCodec, TupleCodec};
     use crate::storage::{TablePage, TupleMeta};
     use crate::Tuple;
     use std::sync::Arc;
@@ -175,8 +180,12 @@ mod tests {
         };
 
         let mut table_page = TablePage::new(schema.clone(), INVALID_PAGE_ID);
-        table_page.insert_tuple(&tuple1_meta, &tuple1).unwrap();
-        table_page.insert_tuple(&tuple2_meta, &tuple2).unwrap();
+        table_page
+            .insert_tuple(&tuple1_meta, &TupleCodec::encode(&tuple1))
+            .unwrap();
+        table_page
+            .insert_tuple(&tuple2_meta, &TupleCodec::encode(&tuple2))
+            .unwrap();
 
         let (new_page, _) =
             TablePageCodec::decode(&TablePageCodec::encode(&table_page), schema.clone()).unwrap();
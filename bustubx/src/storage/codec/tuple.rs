@@ -1,18 +1,31 @@
-use crate::catalog::SchemaRef;
+use crate::buffer::BufferPoolManager;
+use crate::catalog::{DataType, SchemaRef};
 use crate::common::{DynamicBitmap, ScalarValue};
 use crate::storage::codec::{DecodedData, ScalarValueCodec};
+use crate::storage::OverflowPointer;
 use crate::{BustubxError, BustubxResult, Tuple};
+use std::collections::HashMap;
 
 pub struct TupleCodec;
 
 impl TupleCodec {
     pub fn encode(tuple: &Tuple) -> Vec<u8> {
+        Self::encode_with_pointers(tuple, &HashMap::new())
+    }
+
+    /// Encodes the values at the keys of `pointers` as pointers to their overflow pages.
+    pub fn encode_with_pointers(
+        tuple: &Tuple,
+        pointers: &HashMap<usize, OverflowPointer>,
+    ) -> Vec<u8> {
         // null map
         let mut null_map = DynamicBitmap::new();
         let mut attributes = Vec::new();
         for (idx, value) in tuple.data.iter().enumerate() {
             null_map.set(idx, value.is_null());
-            if !value.is_null() {
+            if let Some(pointer) = pointers.get(&idx) {
+                attributes.extend(ScalarValueCodec::encode_overflow_pointer(pointer));
+            } else if !value.is_null() {
                 attributes.extend(ScalarValueCodec::encode(value));
             }
         }
@@ -23,6 +36,15 @@ impl TupleCodec {
     }
 
     pub fn decode(bytes: &[u8], schema: SchemaRef) -> BustubxResult<DecodedData<Tuple>> {
+        Self::decode_with_overflow(bytes, schema, None)
+    }
+
+    /// Decodes a tuple, values stored in overflow pages are read from `buffer_pool`.
+    pub fn decode_with_overflow(
+        bytes: &[u8],
+        schema: SchemaRef,
+        buffer_pool: Option<&BufferPoolManager>,
+    ) -> BustubxResult<DecodedData<Tuple>> {
         let mut total_offset = 0;
 
         let null_map_bytes = schema.column_count().div_ceil(8);
@@ -38,7 +60,7 @@ impl TupleCodec {
             if null {
                 data.push(ScalarValue::new_empty(col.data_type));
             } else {
-                let (value, offset) = ScalarValueCodec::decode(bytes, col.data_type)?;
+                let (value, offset) = Self::decode_value(bytes, col.data_type, buffer_pool)?;
                 data.push(value);
                 total_offset += offset;
                 bytes = &bytes[offset..];
@@ -47,6 +69,95 @@ impl TupleCodec {
 
         Ok((Tuple::new(schema, data), total_offset))
     }
+
+    /// Decodes only the columns of `schema` at `projection`, other values are skipped
+    /// without being decoded.
+    pub fn decode_projected(
+        bytes: &[u8],
+        schema: &SchemaRef,
+        projection: &[usize],
+        projected_schema: SchemaRef,
+        buffer_pool: Option<&BufferPoolManager>,
+    ) -> BustubxResult<Tuple> {
+        let null_map_bytes = schema.column_count().div_ceil(8);
+        let null_map = DynamicBitmap::from_bytes(&bytes[0..null_map_bytes]);
+        let mut bytes = &bytes[null_map_bytes..];
+
+        let last_projected = projection.iter().max().copied();
+        let mut values = vec![None; schema.column_count()];
+        for (idx, col) in schema.columns.iter().enumerate() {
+            if Some(idx) > last_projected {
+                break;
+            }
+            let null = null_map.get(idx).ok_or(BustubxError::Internal(
+                "null map size should be greater than or equal to col count".to_string(),
+            ))?;
+            if null {
+                values[idx] = Some(ScalarValue::new_empty(col.data_type));
+            } else if projection.contains(&idx) {
+                let (value, offset) = Self::decode_value(bytes, col.data_type, buffer_pool)?;
+                values[idx] = Some(value);
+                bytes = &bytes[offset..];
+            } else {
+                let offset = ScalarValueCodec::encoded_len(bytes, col.data_type)?;
+                bytes = &bytes[offset..];
+            }
+        }
+
+        let data = projection
+            .iter()
+            .map(|idx| {
+                values[*idx].clone().ok_or(BustubxError::Internal(format!(
+                    "projected column {} out of range",
+                    idx
+                )))
+            })
+            .collect::<BustubxResult<Vec<ScalarValue>>>()?;
+        Ok(Tuple::new(projected_schema, data))
+    }
+
+    /// Pointers to the overflow pages of the values of an encoded tuple.
+    pub fn overflow_pointers(
+        bytes: &[u8],
+        schema: &SchemaRef,
+    ) -> BustubxResult<Vec<OverflowPointer>> {
+        let null_map_bytes = schema.column_count().div_ceil(8);
+        let null_map = DynamicBitmap::from_bytes(&bytes[0..null_map_bytes]);
+        let mut bytes = &bytes[null_map_bytes..];
+
+        let mut pointers = vec![];
+        for (idx, col) in schema.columns.iter().enumerate() {
+            if null_map.get(idx).unwrap_or(false) {
+                continue;
+            }
+            if let Some((pointer, _)) =
+                ScalarValueCodec::decode_overflow_pointer(bytes, col.data_type)?
+            {
+                pointers.push(pointer);
+            }
+            let offset = ScalarValueCodec::encoded_len(bytes, col.data_type)?;
+            bytes = &bytes[offset..];
+        }
+        Ok(pointers)
+    }
+
+    fn decode_value(
+        bytes: &[u8],
+        data_type: DataType,
+        buffer_pool: Option<&BufferPoolManager>,
+    ) -> BustubxResult<DecodedData<ScalarValue>> {
+        match ScalarValueCodec::decode_overflow_pointer(bytes, data_type)? {
+            Some((pointer, offset)) => {
+                let buffer_pool = buffer_pool.ok_or(BustubxError::Internal(
+                    "buffer pool is required to read overflow pages".to_string(),
+                ))?;
+                let value =
+                    ScalarValueCodec::from_overflow_bytes(pointer.read(buffer_pool)?, data_type)?;
+                Ok((value, offset))
+            }
+            None => ScalarValueCodec::decode(bytes, data_type),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -74,9 +185,26 @@ mod tests {
                 "aabb".to_string().into(),
             ],
         );
-        let new_tuple = TupleCodec::decode(&TupleCodec::encode(&tuple), schema)
+        let new_tuple = TupleCodec::decode(&TupleCodec::encode(&tuple), schema.clone())
             .unwrap()
             .0;
         assert_eq!(new_tuple, tuple);
+
+        let projected_schema = Arc::new(schema.project(&[3, 1]).unwrap());
+        let projected_tuple = TupleCodec::decode_projected(
+            &TupleCodec::encode(&tuple),
+            &schema,
+            &[3, 1],
+            projected_schema.clone(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            projected_tuple,
+            Tuple::new(
+                projected_schema,
+                vec!["aabb".to_string().into(), ScalarValue::Int32(None)]
+            )
+        );
     }
 }
@@ -12,7 +12,7 @@ use crate::error::{BustubxError, BustubxResult};
 
 use crate::buffer::{PageId, BUSTUBX_PAGE_SIZE, INVALID_PAGE_ID};
 use crate::storage::codec::{FreelistPageCodec, MetaPageCodec};
-use crate::storage::{FreelistPage, MetaPage, META_PAGE_SIZE};
+use crate::storage::{FreelistPage, MetaPage, FORMAT_VERSION, META_PAGE_SIZE};
 
 static EMPTY_PAGE: [u8; BUSTUBX_PAGE_SIZE] = [0; BUSTUBX_PAGE_SIZE];
 
@@ -25,27 +25,39 @@ pub struct DiskManager {
 
 impl DiskManager {
     pub fn try_new(db_path: impl AsRef<Path>) -> BustubxResult<Self> {
-        let mut is_new_file = false;
-        let (db_file, meta) = if db_path.as_ref().exists() {
-            let mut db_file = std::fs::OpenOptions::new()
-                .read(true)
-                .write(true)
-                .open(db_path)?;
-            let mut buf = vec![0; *META_PAGE_SIZE];
-            db_file.read_exact(&mut buf)?;
-            let (meta_page, _) = MetaPageCodec::decode(&buf)?;
-            (db_file, meta_page)
-        } else {
-            is_new_file = true;
-            let mut db_file = std::fs::OpenOptions::new()
-                .read(true)
-                .write(true)
-                .create(true)
-                .open(db_path)?;
+        let is_new_file = !db_path.as_ref().exists();
+        let db_file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(is_new_file)
+            .open(db_path)?;
+        Self::try_from_file(db_file, is_new_file)
+    }
+
+    /// Uses an anonymous temporary file, which is removed once closed even if the
+    /// process crashes.
+    pub fn try_new_temp() -> BustubxResult<Self> {
+        Self::try_from_file(tempfile::tempfile()?, true)
+    }
+
+    fn try_from_file(mut db_file: File, is_new_file: bool) -> BustubxResult<Self> {
+        let meta = if is_new_file {
             let meta_page = MetaPage::try_new()?;
             #[allow(clippy::unused_io_amount)]
             db_file.write(&MetaPageCodec::encode(&meta_page))?;
-            (db_file, meta_page)
+            meta_page
+        } else {
+            let mut buf = vec![0; *META_PAGE_SIZE];
+            db_file.read_exact(&mut buf)?;
+            let (meta_page, _) = MetaPageCodec::decode(&buf)?;
+            // files of older versions start with the major version 0
+            if meta_page.format_version != FORMAT_VERSION {
+                return Err(BustubxError::Storage(format!(
+                    "incompatible database file format version {}, expected {}",
+                    meta_page.format_version, FORMAT_VERSION
+                )));
+            }
+            meta_page
         };
 
         // calculate next page id
@@ -75,6 +87,9 @@ impl DiskManager {
             let information_schema_tables_first_page_id = disk_manager.allocate_page()?;
             let information_schema_columns_first_page_id = disk_manager.allocate_page()?;
             let information_schema_indexes_first_page_id = disk_manager.allocate_page()?;
+            let information_schema_statistics_first_page_id = disk_manager.allocate_page()?;
+            let information_schema_constraints_first_page_id = disk_manager.allocate_page()?;
+            let information_schema_foreign_keys_first_page_id = disk_manager.allocate_page()?;
 
             let mut meta = disk_manager.meta.write().unwrap();
             meta.freelist_page_id = freelist_page_id;
@@ -85,6 +100,12 @@ impl DiskManager {
                 information_schema_columns_first_page_id;
             meta.information_schema_indexes_first_page_id =
                 information_schema_indexes_first_page_id;
+            meta.information_schema_statistics_first_page_id =
+                information_schema_statistics_first_page_id;
+            meta.information_schema_constraints_first_page_id =
+                information_schema_constraints_first_page_id;
+            meta.information_schema_foreign_keys_first_page_id =
+                information_schema_foreign_keys_first_page_id;
             drop(meta);
             disk_manager.write_meta_page()?;
         }
@@ -230,6 +251,13 @@ impl DiskManager {
         Ok(())
     }
 
+    /// Forces written pages to disk, e.g. before the log records which could redo
+    /// them are dropped.
+    pub fn sync(&self) -> BustubxResult<()> {
+        self.db_file.lock().unwrap().sync_all()?;
+        Ok(())
+    }
+
     pub fn db_file_len(&self) -> BustubxResult<u64> {
         let guard = self.db_file.lock().unwrap();
         let meta = guard.metadata()?;
@@ -241,7 +269,7 @@ impl DiskManager {
 mod tests {
     use crate::buffer::BUSTUBX_PAGE_SIZE;
     use crate::storage::codec::MetaPageCodec;
-    use crate::storage::EMPTY_META_PAGE;
+    use crate::storage::{EMPTY_META_PAGE, FORMAT_VERSION};
     use tempfile::TempDir;
 
     #[test]
@@ -252,7 +280,7 @@ mod tests {
         let disk_manager = super::DiskManager::try_new(temp_path).unwrap();
 
         let page_id1 = disk_manager.allocate_page().unwrap();
-        assert_eq!(page_id1, 6);
+        assert_eq!(page_id1, 9);
         let mut page1 = vec![1, 2, 3];
         page1.extend(vec![0; BUSTUBX_PAGE_SIZE - 3]);
         disk_manager.write_page(page_id1, &page1).unwrap();
@@ -260,7 +288,7 @@ mod tests {
         assert_eq!(page, page1.as_slice());
 
         let page_id2 = disk_manager.allocate_page().unwrap();
-        assert_eq!(page_id2, 7);
+        assert_eq!(page_id2, 10);
         let mut page2 = vec![0; BUSTUBX_PAGE_SIZE - 3];
         page2.extend(vec![4, 5, 6]);
         disk_manager.write_page(page_id2, &page2).unwrap();
@@ -270,7 +298,7 @@ mod tests {
         let db_file_len = disk_manager.db_file_len().unwrap();
         assert_eq!(
             db_file_len as usize,
-            BUSTUBX_PAGE_SIZE * 7 + MetaPageCodec::encode(&EMPTY_META_PAGE).len()
+            BUSTUBX_PAGE_SIZE * 10 + MetaPageCodec::encode(&EMPTY_META_PAGE).len()
         );
     }
 
@@ -290,4 +318,20 @@ mod tests {
         let page_id4 = disk_manager.allocate_page().unwrap();
         assert_eq!(page_id1, page_id4);
     }
+
+    #[test]
+    pub fn test_disk_manager_rejects_other_format_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("test.db");
+
+        let disk_manager = super::DiskManager::try_new(&temp_path).unwrap();
+        disk_manager.meta.write().unwrap().format_version = FORMAT_VERSION + 1;
+        disk_manager.write_meta_page().unwrap();
+        drop(disk_manager);
+
+        let err = super::DiskManager::try_new(&temp_path).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("incompatible database file format version"));
+    }
 }
@@ -1,3 +1,4 @@
+use std::cmp::Ordering as CmpOrdering;
 use std::collections::VecDeque;
 use std::ops::{Bound, RangeBounds};
 use std::sync::atomic::Ordering;
@@ -6,6 +7,7 @@ use std::sync::Arc;
 use crate::buffer::{AtomicPageId, PageId, PageRef, INVALID_PAGE_ID};
 use crate::catalog::SchemaRef;
 use crate::common::util::page_bytes_to_array;
+use crate::common::ScalarValue;
 use crate::storage::codec::{
     BPlusTreeInternalPageCodec, BPlusTreeLeafPageCodec, BPlusTreePageCodec,
 };
@@ -90,12 +92,10 @@ impl BPlusTreeIndex {
             // 向右分裂出一个新page
             let internalkv = self.split(&mut curr_tree_page)?;
 
-            curr_page
-                .write()
-                .unwrap()
-                .set_data(page_bytes_to_array(&BPlusTreePageCodec::encode(
-                    &curr_tree_page,
-                )));
+            self.buffer_pool.write_page(
+                &curr_page,
+                page_bytes_to_array(&BPlusTreePageCodec::encode(&curr_tree_page)),
+            )?;
 
             let curr_page_id = curr_page.read().unwrap().page_id;
             if let Some(parent_page_id) = context.read_set.pop_back() {
@@ -103,7 +103,7 @@ impl BPlusTreeIndex {
                 let (parent_page, mut parent_tree_page) = self
                     .buffer_pool
                     .fetch_tree_page(parent_page_id, self.key_schema.clone())?;
-                parent_tree_page.insert_internalkv(internalkv);
+                parent_tree_page.insert_internalkv(curr_page_id, internalkv);
 
                 curr_page = parent_page;
                 curr_tree_page = parent_tree_page;
@@ -121,9 +121,12 @@ impl BPlusTreeIndex {
                 );
                 new_root_internal_page.insert(internalkv.0, internalkv.1);
 
-                new_root_page.write().unwrap().set_data(page_bytes_to_array(
-                    &BPlusTreeInternalPageCodec::encode(&new_root_internal_page),
-                ));
+                self.buffer_pool.write_page(
+                    &new_root_page,
+                    page_bytes_to_array(&BPlusTreeInternalPageCodec::encode(
+                        &new_root_internal_page,
+                    )),
+                )?;
 
                 // 更新root page id
                 self.root_page_id.store(new_root_page_id, Ordering::SeqCst);
@@ -133,38 +136,104 @@ impl BPlusTreeIndex {
             }
         }
 
-        curr_page
-            .write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&BPlusTreePageCodec::encode(
-                &curr_tree_page,
-            )));
+        self.buffer_pool.write_page(
+            &curr_page,
+            page_bytes_to_array(&BPlusTreePageCodec::encode(&curr_tree_page)),
+        )?;
 
         Ok(())
     }
 
-    pub fn delete(&self, key: &Tuple) -> BustubxResult<()> {
+    /// Removes the entry of `key` pointing to `rid`. Entries of a key may span several
+    /// leaf pages, they are walked from the leftmost one until the key changes.
+    pub fn delete(&self, key: &Tuple, rid: RecordId) -> BustubxResult<()> {
         if self.is_empty() {
             return Ok(());
         }
         let mut context = Context::new(self.root_page_id.load(Ordering::SeqCst));
         // 找到leaf page
-        let Some(leaf_page) = self.find_leaf_page(key, &mut context)? else {
+        let Some(mut leaf_page) = self.find_leaf_page_by(key, &mut context, true)? else {
             return Err(BustubxError::Storage(
                 "Cannot find leaf page to delete".to_string(),
             ));
         };
-        let (mut leaf_tree_page, _) = BPlusTreeLeafPageCodec::decode(
-            leaf_page.read().unwrap().data(),
-            self.key_schema.clone(),
+        let first_leaf_page_id = leaf_page.read().unwrap().page_id;
+        loop {
+            let (mut leaf_tree_page, _) = BPlusTreeLeafPageCodec::decode(
+                leaf_page.read().unwrap().data(),
+                self.key_schema.clone(),
+            )?;
+            if leaf_tree_page.delete(key, rid) {
+                let leaf_page_id = leaf_page.read().unwrap().page_id;
+                if leaf_page_id != first_leaf_page_id {
+                    context = Context::new(self.root_page_id.load(Ordering::SeqCst));
+                    if !self.find_leaf_path(
+                        context.root_page_id,
+                        key,
+                        leaf_page_id,
+                        &mut context,
+                    )? {
+                        return Err(BustubxError::Storage(
+                            "Cannot find path to leaf page".to_string(),
+                        ));
+                    }
+                }
+                return self.delete_from_leaf(leaf_page, leaf_tree_page, context);
+            }
+            // 后续leaf page不会再有该key
+            let next_page_id = leaf_tree_page.header.next_page_id;
+            if next_page_id == INVALID_PAGE_ID
+                || leaf_tree_page.array.last().is_some_and(|kv| &kv.0 > key)
+            {
+                return Ok(());
+            }
+            leaf_page = self.buffer_pool.fetch_page(next_page_id)?;
+        }
+    }
+
+    /// Collects the internal pages from `page_id` down to the leaf page `leaf_page_id`
+    /// into the read set. Only subtrees whose separators admit `key` are searched.
+    fn find_leaf_path(
+        &self,
+        page_id: PageId,
+        key: &Tuple,
+        leaf_page_id: PageId,
+        context: &mut Context,
+    ) -> BustubxResult<bool> {
+        let (_, tree_page) = self
+            .buffer_pool
+            .fetch_tree_page(page_id, self.key_schema.clone())?;
+        let BPlusTreePage::Internal(internal_page) = tree_page else {
+            return Ok(page_id == leaf_page_id);
+        };
+        context.read_set.push_back(page_id);
+        let size = internal_page.header.current_size as usize;
+        for index in 0..size {
+            if index > 0 && internal_page.key_at(index) > key {
+                break;
+            }
+            if index + 1 < size && internal_page.key_at(index + 1) < key {
+                continue;
+            }
+            if self.find_leaf_path(internal_page.value_at(index), key, leaf_page_id, context)? {
+                return Ok(true);
+            }
+        }
+        context.read_set.pop_back();
+        Ok(false)
+    }
+
+    /// Writes the leaf page an entry was deleted from and rebalances the tree.
+    fn delete_from_leaf(
+        &self,
+        leaf_page: PageRef,
+        leaf_tree_page: BPlusTreeLeafPage,
+        mut context: Context,
+    ) -> BustubxResult<()> {
+        self.buffer_pool.write_page(
+            &leaf_page,
+            page_bytes_to_array(&BPlusTreeLeafPageCodec::encode(&leaf_tree_page)),
         )?;
-        leaf_tree_page.delete(key);
-        leaf_page
-            .write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&BPlusTreeLeafPageCodec::encode(
-                &leaf_tree_page,
-            )));
 
         let mut curr_tree_page = BPlusTreePage::Leaf(leaf_tree_page);
         let mut curr_page_id = leaf_page.read().unwrap().page_id;
@@ -221,12 +290,10 @@ impl BPlusTreeIndex {
         let mut leaf_page = BPlusTreeLeafPage::new(self.key_schema.clone(), self.leaf_max_size);
         leaf_page.insert(key.clone(), rid);
 
-        new_page
-            .write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&BPlusTreeLeafPageCodec::encode(
-                &leaf_page,
-            )));
+        self.buffer_pool.write_page(
+            &new_page,
+            page_bytes_to_array(&BPlusTreeLeafPageCodec::encode(&leaf_page)),
+        )?;
 
         // 更新root page id
         self.root_page_id.store(new_page_id, Ordering::SeqCst);
@@ -254,6 +321,16 @@ impl BPlusTreeIndex {
     }
 
     fn find_leaf_page(&self, key: &Tuple, context: &mut Context) -> BustubxResult<Option<PageRef>> {
+        self.find_leaf_page_by(key, context, false)
+    }
+
+    /// Finds the leaf page of `key`, or the leftmost one which may hold it if `first`.
+    fn find_leaf_page_by(
+        &self,
+        key: &Tuple,
+        context: &mut Context,
+        first: bool,
+    ) -> BustubxResult<Option<PageRef>> {
         if self.is_empty() {
             return Ok(None);
         }
@@ -270,7 +347,11 @@ impl BPlusTreeIndex {
                         .read_set
                         .push_back(curr_page.read().unwrap().page_id);
                     // 查找下一页
-                    let next_page_id = internal_page.look_up(key);
+                    let next_page_id = if first {
+                        internal_page.look_up_first(key)
+                    } else {
+                        internal_page.look_up(key)
+                    };
                     let (next_page, next_tree_page) = self
                         .buffer_pool
                         .fetch_tree_page(next_page_id, self.key_schema.clone())?;
@@ -301,9 +382,10 @@ impl BPlusTreeIndex {
                 new_leaf_page.header.next_page_id = leaf_page.header.next_page_id;
                 leaf_page.header.next_page_id = new_page.read().unwrap().page_id;
 
-                new_page.write().unwrap().set_data(page_bytes_to_array(
-                    &BPlusTreeLeafPageCodec::encode(&new_leaf_page),
-                ));
+                self.buffer_pool.write_page(
+                    &new_page,
+                    page_bytes_to_array(&BPlusTreeLeafPageCodec::encode(&new_leaf_page)),
+                )?;
 
                 Ok((new_leaf_page.key_at(0).clone(), new_page_id))
             }
@@ -315,9 +397,10 @@ impl BPlusTreeIndex {
                     internal_page.split_off(internal_page.header.current_size as usize / 2),
                 );
 
-                new_page.write().unwrap().set_data(page_bytes_to_array(
-                    &BPlusTreeInternalPageCodec::encode(&new_internal_page),
-                ));
+                self.buffer_pool.write_page(
+                    &new_page,
+                    page_bytes_to_array(&BPlusTreeInternalPageCodec::encode(&new_internal_page)),
+                )?;
 
                 let min_leafkv = self.find_subtree_min_leafkv(new_page_id)?;
                 Ok((min_leafkv.0, new_page_id))
@@ -361,7 +444,12 @@ impl BPlusTreeIndex {
             .buffer_pool
             .fetch_tree_page(page_id, self.key_schema.clone())?;
 
-        let (old_internal_key, new_internal_key) = match borrowed_tree_page {
+        let (parent_page, mut parent_internal_page) = self
+            .buffer_pool
+            .fetch_tree_internal_page(parent_page_id, self.key_schema.clone())?;
+
+        // 分隔key随子节点位置更新，重复key时不能按值查找
+        let (separator_page_id, new_separator) = match borrowed_tree_page {
             BPlusTreePage::Internal(ref mut borrowed_internal_page) => {
                 let BPlusTreePage::Internal(ref mut internal_page) = tree_page else {
                     return Err(BustubxError::Storage(
@@ -369,24 +457,27 @@ impl BPlusTreeIndex {
                     ));
                 };
                 if min_max {
+                    // 右兄弟的第一个子节点移到末尾，其分隔key来自父节点
+                    let Some(index) = parent_internal_page.value_index(borrowed_page_id) else {
+                        return Err(BustubxError::Storage(
+                            "Cannot find sibling page".to_string(),
+                        ));
+                    };
+                    let separator = parent_internal_page.key_at(index).clone();
                     let kv = borrowed_internal_page.reverse_split_off(0).remove(0);
-                    internal_page.insert(kv.0.clone(), kv.1);
-                    (
-                        kv.0,
-                        self.find_subtree_min_leafkv(borrowed_internal_page.value_at(0))?
-                            .0,
-                    )
+                    internal_page.batch_insert(vec![(separator, kv.1)]);
+                    (borrowed_page_id, borrowed_internal_page.key_at(0).clone())
                 } else {
+                    // 左兄弟的最后一个子节点移到开头
+                    let Some(index) = parent_internal_page.value_index(page_id) else {
+                        return Err(BustubxError::Storage("Cannot find page".to_string()));
+                    };
+                    let separator = parent_internal_page.key_at(index).clone();
                     let kv = borrowed_internal_page
                         .split_off(borrowed_internal_page.header.current_size as usize - 1)
                         .remove(0);
-                    let min_key = internal_page.key_at(0).clone();
-                    internal_page.insert(kv.0.clone(), kv.1);
-                    (
-                        min_key,
-                        self.find_subtree_min_leafkv(borrowed_internal_page.value_at(0))?
-                            .0,
-                    )
+                    internal_page.push_front(kv.clone(), separator);
+                    (page_id, kv.0)
                 }
             }
             BPlusTreePage::Leaf(ref mut borrowed_leaf_page) => {
@@ -397,38 +488,35 @@ impl BPlusTreeIndex {
                 };
                 if min_max {
                     let kv = borrowed_leaf_page.reverse_split_off(0).remove(0);
-                    leaf_page.insert(kv.0.clone(), kv.1);
-                    (kv.0, borrowed_leaf_page.key_at(0).clone())
+                    leaf_page.insert(kv.0, kv.1);
+                    (borrowed_page_id, borrowed_leaf_page.key_at(0).clone())
                 } else {
                     let kv = borrowed_leaf_page
                         .split_off(borrowed_leaf_page.header.current_size as usize - 1)
                         .remove(0);
                     leaf_page.insert(kv.0.clone(), kv.1);
-                    (leaf_page.key_at(1).clone(), leaf_page.key_at(0).clone())
+                    (page_id, kv.0)
                 }
             }
         };
 
-        page.write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&BPlusTreePageCodec::encode(&tree_page)));
+        self.buffer_pool.write_page(
+            &page,
+            page_bytes_to_array(&BPlusTreePageCodec::encode(&tree_page)),
+        )?;
 
-        borrowed_page
-            .write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&BPlusTreePageCodec::encode(
-                &borrowed_tree_page,
-            )));
+        self.buffer_pool.write_page(
+            &borrowed_page,
+            page_bytes_to_array(&BPlusTreePageCodec::encode(&borrowed_tree_page)),
+        )?;
 
         // 更新父节点
-        let (parent_page, mut parent_internal_page) = self
-            .buffer_pool
-            .fetch_tree_internal_page(parent_page_id, self.key_schema.clone())?;
-        parent_internal_page.replace_key(&old_internal_key, new_internal_key);
+        parent_internal_page.replace_key(separator_page_id, new_separator);
 
-        parent_page.write().unwrap().set_data(page_bytes_to_array(
-            &BPlusTreeInternalPageCodec::encode(&parent_internal_page),
-        ));
+        self.buffer_pool.write_page(
+            &parent_page,
+            page_bytes_to_array(&BPlusTreeInternalPageCodec::encode(&parent_internal_page)),
+        )?;
         Ok(true)
     }
 
@@ -485,12 +573,10 @@ impl BPlusTreeIndex {
             }
         };
 
-        left_page
-            .write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&BPlusTreePageCodec::encode(
-                &left_tree_page,
-            )));
+        self.buffer_pool.write_page(
+            &left_page,
+            page_bytes_to_array(&BPlusTreePageCodec::encode(&left_tree_page)),
+        )?;
 
         // 删除右边页
         self.buffer_pool.delete_page(right_page_id)?;
@@ -510,9 +596,10 @@ impl BPlusTreeIndex {
             self.buffer_pool.delete_page(parent_page_id)?;
             Ok(left_page_id)
         } else {
-            parent_page.write().unwrap().set_data(page_bytes_to_array(
-                &BPlusTreeInternalPageCodec::encode(&parent_internal_page),
-            ));
+            self.buffer_pool.write_page(
+                &parent_page,
+                page_bytes_to_array(&BPlusTreeInternalPageCodec::encode(&parent_internal_page)),
+            )?;
             Ok(parent_page_id)
         }
     }
@@ -557,6 +644,25 @@ impl BPlusTreeIndex {
         }
     }
 
+    /// Ids of all pages of the tree, parents before their children.
+    pub fn page_ids(&self) -> BustubxResult<Vec<PageId>> {
+        if self.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut page_ids = vec![self.root_page_id.load(Ordering::SeqCst)];
+        let mut idx = 0;
+        while idx < page_ids.len() {
+            let (_, tree_page) = self
+                .buffer_pool
+                .fetch_tree_page(page_ids[idx], self.key_schema.clone())?;
+            if let BPlusTreePage::Internal(internal_page) = tree_page {
+                page_ids.extend(internal_page.values());
+            }
+            idx += 1;
+        }
+        Ok(page_ids)
+    }
+
     pub fn get_first_leaf_page(&self) -> BustubxResult<BPlusTreeLeafPage> {
         let (_, mut curr_tree_page) = self.buffer_pool.fetch_tree_page(
             self.root_page_id.load(Ordering::SeqCst),
@@ -616,118 +722,106 @@ impl TreeIndexIterator {
     }
 
     pub fn next(&mut self) -> BustubxResult<Option<RecordId>> {
-        if self.started {
-            match self.end_bound.as_ref() {
-                Bound::Included(end_tuple) => {
-                    self.cursor += 1;
-                    let end_tuple = end_tuple.clone();
-                    let kv = if self.cursor >= self.leaf_page.header.current_size as usize {
-                        if self.load_next_leaf_page()? {
-                            self.cursor = 0;
-                            self.leaf_page.array[self.cursor].clone()
-                        } else {
-                            return Ok(None);
-                        }
-                    } else {
-                        self.leaf_page.array[self.cursor].clone()
-                    };
-                    if kv.0 <= end_tuple {
-                        Ok(Some(kv.1))
-                    } else {
-                        Ok(None)
-                    }
-                }
-                Bound::Excluded(end_tuple) => {
-                    self.cursor += 1;
-                    let end_tuple = end_tuple.clone();
-                    let kv = if self.cursor >= self.leaf_page.header.current_size as usize {
-                        if self.load_next_leaf_page()? {
-                            self.cursor = 0;
-                            self.leaf_page.array[self.cursor].clone()
-                        } else {
-                            return Ok(None);
-                        }
-                    } else {
-                        self.leaf_page.array[self.cursor].clone()
-                    };
-                    if kv.0 < end_tuple {
-                        Ok(Some(kv.1))
-                    } else {
-                        Ok(None)
-                    }
-                }
-                Bound::Unbounded => {
-                    self.cursor += 1;
-                    if self.cursor >= self.leaf_page.header.current_size as usize {
-                        if self.load_next_leaf_page()? {
-                            self.cursor = 0;
-                            Ok(Some(self.leaf_page.array[self.cursor].1))
-                        } else {
-                            Ok(None)
-                        }
-                    } else {
-                        Ok(Some(self.leaf_page.array[self.cursor].1))
-                    }
-                }
-            }
+        Ok(self.next_kv()?.map(|kv| kv.1))
+    }
+
+    /// Returns the next key together with its record id.
+    ///
+    /// Bound tuples may contain only leading columns of the key, then keys are compared
+    /// with bounds on those columns only, e.g. bound `(1)` includes keys `(1, x)`.
+    pub fn next_kv(&mut self) -> BustubxResult<Option<LeafKV>> {
+        let kv = if self.started {
+            self.cursor += 1;
+            self.current_kv()?
         } else {
             self.started = true;
-            match self.start_bound.as_ref() {
-                Bound::Included(start_tuple) => {
-                    let mut context = Context::new(self.index.root_page_id.load(Ordering::SeqCst));
-                    let Some(leaf_page) = self.index.find_leaf_page(start_tuple, &mut context)?
-                    else {
-                        return Ok(None);
-                    };
-                    self.leaf_page = BPlusTreeLeafPageCodec::decode(
-                        leaf_page.read().unwrap().data(),
-                        self.index.key_schema.clone(),
-                    )?
-                    .0;
-                    if let Some(idx) = self.leaf_page.next_closest(start_tuple, true) {
-                        self.cursor = idx;
-                        Ok(Some(self.leaf_page.array[self.cursor].1))
-                    } else if self.load_next_leaf_page()? {
-                        self.cursor = 0;
-                        Ok(Some(self.leaf_page.array[self.cursor].1))
-                    } else {
-                        Ok(None)
-                    }
-                }
-                Bound::Excluded(start_tuple) => {
-                    let mut context = Context::new(self.index.root_page_id.load(Ordering::SeqCst));
-                    let Some(leaf_page) = self.index.find_leaf_page(start_tuple, &mut context)?
-                    else {
-                        return Ok(None);
-                    };
-                    self.leaf_page = BPlusTreeLeafPageCodec::decode(
-                        leaf_page.read().unwrap().data(),
-                        self.index.key_schema.clone(),
-                    )?
-                    .0;
-                    if let Some(idx) = self.leaf_page.next_closest(start_tuple, false) {
-                        self.cursor = idx;
-                        Ok(Some(self.leaf_page.array[self.cursor].1))
-                    } else if self.load_next_leaf_page()? {
-                        self.cursor = 0;
-                        Ok(Some(self.leaf_page.array[self.cursor].1))
-                    } else {
-                        Ok(None)
-                    }
-                }
-                Bound::Unbounded => {
-                    self.leaf_page = self.index.get_first_leaf_page()?;
-                    self.cursor = 0;
-                    Ok(Some(self.leaf_page.array[self.cursor].1))
+            self.seek_start()?
+        };
+        let Some(kv) = kv else {
+            return Ok(None);
+        };
+        let in_range = match self.end_bound.as_ref() {
+            Bound::Included(end_tuple) => {
+                compare_key_prefix(&kv.0, end_tuple) != CmpOrdering::Greater
+            }
+            Bound::Excluded(end_tuple) => compare_key_prefix(&kv.0, end_tuple) == CmpOrdering::Less,
+            Bound::Unbounded => true,
+        };
+        Ok(in_range.then_some(kv))
+    }
+
+    /// Returns the kv at the cursor, moves to following leaf pages if the cursor is beyond
+    /// the current one.
+    fn current_kv(&mut self) -> BustubxResult<Option<LeafKV>> {
+        while self.cursor >= self.leaf_page.header.current_size as usize {
+            if !self.load_next_leaf_page()? {
+                return Ok(None);
+            }
+            self.cursor = 0;
+        }
+        Ok(Some(self.leaf_page.array[self.cursor].clone()))
+    }
+
+    /// Returns the first kv within the start bound. Entries of a key may span several
+    /// leaf pages, so the scan starts at the leftmost leaf page which may hold the bound.
+    fn seek_start(&mut self) -> BustubxResult<Option<LeafKV>> {
+        let (start_tuple, included) = match self.start_bound.as_ref() {
+            Bound::Included(start_tuple) => (start_tuple.clone(), true),
+            Bound::Excluded(start_tuple) => (start_tuple.clone(), false),
+            Bound::Unbounded => {
+                if self.index.is_empty() {
+                    return Ok(None);
                 }
+                self.leaf_page = self.index.get_first_leaf_page()?;
+                self.cursor = 0;
+                return self.current_kv();
+            }
+        };
+
+        // nulls are smaller than any value, the padded key is before all keys of the prefix
+        let mut seek_key = start_tuple.data.clone();
+        for col in self.index.key_schema.columns.iter().skip(seek_key.len()) {
+            seek_key.push(ScalarValue::new_empty(col.data_type));
+        }
+        let seek_key = Tuple::new(self.index.key_schema.clone(), seek_key);
+        let mut context = Context::new(self.index.root_page_id.load(Ordering::SeqCst));
+        let Some(leaf_page) = self
+            .index
+            .find_leaf_page_by(&seek_key, &mut context, true)?
+        else {
+            return Ok(None);
+        };
+        self.leaf_page = BPlusTreeLeafPageCodec::decode(
+            leaf_page.read().unwrap().data(),
+            self.index.key_schema.clone(),
+        )?
+        .0;
+        self.cursor = 0;
+        while let Some(kv) = self.current_kv()? {
+            match compare_key_prefix(&kv.0, &start_tuple) {
+                CmpOrdering::Greater => return Ok(Some(kv)),
+                CmpOrdering::Equal if included => return Ok(Some(kv)),
+                _ => self.cursor += 1,
             }
         }
+        Ok(None)
+    }
+}
+
+/// Compares a key with a bound on the columns of the bound.
+fn compare_key_prefix(key: &Tuple, bound: &Tuple) -> CmpOrdering {
+    for (key_value, bound_value) in key.data.iter().zip(bound.data.iter()) {
+        match key_value.partial_cmp(bound_value) {
+            Some(CmpOrdering::Equal) | None => continue,
+            Some(ordering) => return ordering,
+        }
     }
+    CmpOrdering::Equal
 }
 
 #[cfg(test)]
 mod tests {
-    use std::ops::Bound;
+    use std::ops::{Bound, RangeBounds};
     use std::sync::Arc;
     use tempfile::TempDir;
 
@@ -830,34 +924,34 @@ mod tests {
         println!("{display}");
         assert_eq!(display, "B+ Tree Level No.1:
 +-----------------------+
-| page_id=13, size: 2/4 |
+| page_id=16, size: 2/4 |
 +-----------------------+
 | +------------+------+ |
 | | NULL, NULL | 5, 5 | |
 | +------------+------+ |
-| | 8          | 12   | |
+| | 11         | 15   | |
 | +------------+------+ |
 +-----------------------+
 B+ Tree Level No.2:
 +-----------------------+------------------------+
-| page_id=8, size: 2/4  | page_id=12, size: 3/4  |
+| page_id=11, size: 2/4 | page_id=15, size: 3/4  |
 +-----------------------+------------------------+
 | +------------+------+ | +------+------+------+ |
 | | NULL, NULL | 3, 3 | | | 5, 5 | 7, 7 | 9, 9 | |
 | +------------+------+ | +------+------+------+ |
-| | 6          | 7    | | | 9    | 10   | 11   | |
+| | 9          | 10   | | | 12   | 13   | 14   | |
 | +------------+------+ | +------+------+------+ |
 +-----------------------+------------------------+
 B+ Tree Level No.3:
-+--------------------------------------+--------------------------------------+---------------------------------------+----------------------------------------+---------------------------------------+
-| page_id=6, size: 2/4, next_page_id=7 | page_id=7, size: 2/4, next_page_id=9 | page_id=9, size: 2/4, next_page_id=10 | page_id=10, size: 2/4, next_page_id=11 | page_id=11, size: 3/4, next_page_id=0 |
-+--------------------------------------+--------------------------------------+---------------------------------------+----------------------------------------+---------------------------------------+
-| +------+------+                      | +------+------+                      | +------+------+                       | +------+------+                        | +------+--------+--------+            |
-| | 1, 1 | 2, 2 |                      | | 3, 3 | 4, 4 |                      | | 5, 5 | 6, 6 |                       | | 7, 7 | 8, 8 |                        | | 9, 9 | 10, 10 | 11, 11 |            |
-| +------+------+                      | +------+------+                      | +------+------+                       | +------+------+                        | +------+--------+--------+            |
-| | 1-1  | 2-2  |                      | | 3-3  | 4-4  |                      | | 5-5  | 6-6  |                       | | 7-7  | 8-8  |                        | | 9-9  | 10-10  | 11-11  |            |
-| +------+------+                      | +------+------+                      | +------+------+                       | +------+------+                        | +------+--------+--------+            |
-+--------------------------------------+--------------------------------------+---------------------------------------+----------------------------------------+---------------------------------------+
++---------------------------------------+----------------------------------------+----------------------------------------+----------------------------------------+---------------------------------------+
+| page_id=9, size: 2/4, next_page_id=10 | page_id=10, size: 2/4, next_page_id=12 | page_id=12, size: 2/4, next_page_id=13 | page_id=13, size: 2/4, next_page_id=14 | page_id=14, size: 3/4, next_page_id=0 |
++---------------------------------------+----------------------------------------+----------------------------------------+----------------------------------------+---------------------------------------+
+| +------+------+                       | +------+------+                        | +------+------+                        | +------+------+                        | +------+--------+--------+            |
+| | 1, 1 | 2, 2 |                       | | 3, 3 | 4, 4 |                        | | 5, 5 | 6, 6 |                        | | 7, 7 | 8, 8 |                        | | 9, 9 | 10, 10 | 11, 11 |            |
+| +------+------+                       | +------+------+                        | +------+------+                        | +------+------+                        | +------+--------+--------+            |
+| | 1-1  | 2-2  |                       | | 3-3  | 4-4  |                        | | 5-5  | 6-6  |                        | | 7-7  | 8-8  |                        | | 9-9  | 10-10  | 11-11  |            |
+| +------+------+                       | +------+------+                        | +------+------+                        | +------+------+                        | +------+--------+--------+            |
++---------------------------------------+----------------------------------------+----------------------------------------+----------------------------------------+---------------------------------------+
 ");
     }
 
@@ -866,48 +960,48 @@ B+ Tree Level No.3:
         let (index, key_schema) = build_index();
 
         index
-            .delete(&Tuple::new(
-                key_schema.clone(),
-                vec![3i8.into(), 3i16.into()],
-            ))
+            .delete(
+                &Tuple::new(key_schema.clone(), vec![3i8.into(), 3i16.into()]),
+                RecordId::new(3, 3),
+            )
             .unwrap();
         println!("{}", pretty_format_index_tree(&index).unwrap());
         index
-            .delete(&Tuple::new(
-                key_schema.clone(),
-                vec![10i8.into(), 10i16.into()],
-            ))
+            .delete(
+                &Tuple::new(key_schema.clone(), vec![10i8.into(), 10i16.into()]),
+                RecordId::new(10, 10),
+            )
             .unwrap();
         println!("{}", pretty_format_index_tree(&index).unwrap());
         index
-            .delete(&Tuple::new(
-                key_schema.clone(),
-                vec![8i8.into(), 8i16.into()],
-            ))
+            .delete(
+                &Tuple::new(key_schema.clone(), vec![8i8.into(), 8i16.into()]),
+                RecordId::new(8, 8),
+            )
             .unwrap();
         println!("{}", pretty_format_index_tree(&index).unwrap());
 
         assert_eq!(pretty_format_index_tree(&index).unwrap(),
                    "B+ Tree Level No.1:
 +------------------------------+
-| page_id=8, size: 3/4         |
+| page_id=11, size: 3/4        |
 +------------------------------+
 | +------------+------+------+ |
 | | NULL, NULL | 5, 5 | 7, 7 | |
 | +------------+------+------+ |
-| | 6          | 9    | 10   | |
+| | 9          | 12   | 13   | |
 | +------------+------+------+ |
 +------------------------------+
 B+ Tree Level No.2:
-+--------------------------------------+---------------------------------------+---------------------------------------+
-| page_id=6, size: 3/4, next_page_id=9 | page_id=9, size: 2/4, next_page_id=10 | page_id=10, size: 3/4, next_page_id=0 |
-+--------------------------------------+---------------------------------------+---------------------------------------+
-| +------+------+------+               | +------+------+                       | +------+------+--------+              |
-| | 1, 1 | 2, 2 | 4, 4 |               | | 5, 5 | 6, 6 |                       | | 7, 7 | 9, 9 | 11, 11 |              |
-| +------+------+------+               | +------+------+                       | +------+------+--------+              |
-| | 1-1  | 2-2  | 4-4  |               | | 5-5  | 6-6  |                       | | 7-7  | 9-9  | 11-11  |              |
-| +------+------+------+               | +------+------+                       | +------+------+--------+              |
-+--------------------------------------+---------------------------------------+---------------------------------------+
++---------------------------------------+----------------------------------------+---------------------------------------+
+| page_id=9, size: 3/4, next_page_id=12 | page_id=12, size: 2/4, next_page_id=13 | page_id=13, size: 3/4, next_page_id=0 |
++---------------------------------------+----------------------------------------+---------------------------------------+
+| +------+------+------+                | +------+------+                        | +------+------+--------+              |
+| | 1, 1 | 2, 2 | 4, 4 |                | | 5, 5 | 6, 6 |                        | | 7, 7 | 9, 9 | 11, 11 |              |
+| +------+------+------+                | +------+------+                        | +------+------+--------+              |
+| | 1-1  | 2-2  | 4-4  |                | | 5-5  | 6-6  |                        | | 7-7  | 9-9  | 11-11  |              |
+| +------+------+------+                | +------+------+                        | +------+------+--------+              |
++---------------------------------------+----------------------------------------+---------------------------------------+
 ");
     }
 
@@ -968,5 +1062,113 @@ B+ Tree Level No.2:
         assert_eq!(iterator4.next().unwrap(), Some(RecordId::new(11, 11)));
         assert_eq!(iterator4.next().unwrap(), None);
         assert_eq!(iterator4.next().unwrap(), None);
+
+        // bounds on the leading column only
+        let prefix_schema = Arc::new(key_schema.project(&[0]).unwrap());
+        let start_tuple5 = Tuple::new(prefix_schema.clone(), vec![3i8.into()]);
+        let end_tuple5 = Tuple::new(prefix_schema.clone(), vec![5i8.into()]);
+        let mut iterator5 = TreeIndexIterator::new(index.clone(), start_tuple5..end_tuple5);
+        assert_eq!(iterator5.next().unwrap(), Some(RecordId::new(3, 3)));
+        assert_eq!(iterator5.next().unwrap(), Some(RecordId::new(4, 4)));
+        assert_eq!(iterator5.next().unwrap(), None);
+
+        let key_tuple6 = Tuple::new(prefix_schema.clone(), vec![7i8.into()]);
+        let mut iterator6 = TreeIndexIterator::new(index.clone(), key_tuple6.clone()..=key_tuple6);
+        assert_eq!(iterator6.next().unwrap(), Some(RecordId::new(7, 7)));
+        assert_eq!(iterator6.next().unwrap(), None);
+
+        let start_tuple7 = Tuple::new(prefix_schema.clone(), vec![20i8.into()]);
+        let mut iterator7 = TreeIndexIterator::new(index.clone(), start_tuple7..);
+        assert_eq!(iterator7.next().unwrap(), None);
+    }
+
+    fn count_entries<R: RangeBounds<Tuple>>(index: &Arc<BPlusTreeIndex>, range: R) -> usize {
+        let mut iterator = TreeIndexIterator::new(index.clone(), range);
+        let mut count = 0;
+        while iterator.next().unwrap().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    pub fn test_index_duplicate_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("test.db");
+
+        let key_schema = Arc::new(Schema::new(vec![
+            Column::new("a", DataType::Int8, false),
+            Column::new("b", DataType::Int16, false),
+        ]));
+        let disk_manager = DiskManager::try_new(temp_path).unwrap();
+        let buffer_pool = Arc::new(BufferPoolManager::new(1000, Arc::new(disk_manager)));
+        let index = Arc::new(BPlusTreeIndex::new(key_schema.clone(), buffer_pool, 4, 4));
+        let key = |a: u32| Tuple::new(key_schema.clone(), vec![(a as i8).into(), 0i16.into()]);
+
+        // entries of every key span many leaf pages
+        for i in 0..600 {
+            index.insert(&key(i % 3), RecordId::new(i, i)).unwrap();
+        }
+        for i in (0..600).step_by(2) {
+            index.delete(&key(i % 3), RecordId::new(i, i)).unwrap();
+        }
+
+        assert_eq!(count_entries(&index, ..), 300);
+        assert_eq!(count_entries(&index, key(0)..=key(0)), 100);
+        assert_eq!(count_entries(&index, key(1)..=key(1)), 100);
+        assert_eq!(count_entries(&index, key(2)..), 100);
+        assert_eq!(count_entries(&index, key(1)..), 200);
+
+        let mut iterator = TreeIndexIterator::new(index.clone(), key(1)..=key(1));
+        while let Some(rid) = iterator.next().unwrap() {
+            assert_eq!(rid.page_id % 2, 1);
+            assert_eq!(rid.page_id % 3, 1);
+        }
+
+        for i in (1..600).step_by(2) {
+            index.delete(&key(i % 3), RecordId::new(i, i)).unwrap();
+        }
+        assert_eq!(count_entries(&index, ..), 0);
+    }
+
+    #[test]
+    pub fn test_index_iterator_duplicate_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("test.db");
+
+        let key_schema = Arc::new(Schema::new(vec![
+            Column::new("a", DataType::Int8, false),
+            Column::new("b", DataType::Int16, false),
+        ]));
+        let disk_manager = DiskManager::try_new(temp_path).unwrap();
+        let buffer_pool = Arc::new(BufferPoolManager::new(1000, Arc::new(disk_manager)));
+        let index = Arc::new(BPlusTreeIndex::new(key_schema.clone(), buffer_pool, 4, 4));
+        for i in 0..600 {
+            let key = Tuple::new(
+                key_schema.clone(),
+                vec![((i % 3) as i8).into(), 0i16.into()],
+            );
+            index.insert(&key, RecordId::new(i, i)).unwrap();
+        }
+        // borrowing and merging leaf pages of the key 0 moves separators
+        for i in (0..600).step_by(6) {
+            let key = Tuple::new(key_schema.clone(), vec![0i8.into(), 0i16.into()]);
+            index.delete(&key, RecordId::new(i, i)).unwrap();
+        }
+
+        // seeks start at the leftmost leaf page holding the key
+        let prefix_schema = Arc::new(key_schema.project(&[0]).unwrap());
+        let key = |a: i8| Tuple::new(prefix_schema.clone(), vec![a.into()]);
+        assert_eq!(count_entries(&index, key(1)..=key(1)), 200);
+        assert_eq!(count_entries(&index, key(2)..), 200);
+        assert_eq!(count_entries(&index, key(1)..key(2)), 200);
+        assert_eq!(
+            count_entries(&index, (Bound::Excluded(key(0)), Bound::Unbounded)),
+            400
+        );
+        assert_eq!(
+            count_entries(&index, (Bound::Unbounded, Bound::Excluded(key(1)))),
+            100
+        );
     }
 }
@@ -1,11 +1,15 @@
 pub mod codec;
 mod disk_manager;
 pub mod index;
+mod overflow;
 mod page;
+mod spill_file;
 mod table_heap;
 mod tuple;
 
 pub use disk_manager::DiskManager;
+pub use overflow::OverflowPointer;
 pub use page::*;
+pub use spill_file::{SpillFile, SPILL_BUFFER_POOL_SIZE};
 pub use table_heap::{TableHeap, TableIterator};
 pub use tuple::*;
@@ -0,0 +1,67 @@
+use crate::buffer::{BufferPoolManager, PageId, INVALID_PAGE_ID};
+use crate::common::util::page_bytes_to_array;
+use crate::storage::codec::OverflowPageCodec;
+use crate::storage::{OverflowPage, OVERFLOW_PAGE_MAX_DATA_SIZE};
+use crate::{BustubxError, BustubxResult};
+
+/// Location of a value which is stored in a chain of overflow pages, a tuple keeps it
+/// in place of the value.
+#[derive(derive_new::new, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowPointer {
+    pub first_page_id: PageId,
+    pub len: u32,
+}
+
+impl OverflowPointer {
+    /// Stores `bytes` in a new chain of overflow pages.
+    pub fn write(buffer_pool: &BufferPoolManager, bytes: &[u8]) -> BustubxResult<Self> {
+        let len = u32::try_from(bytes.len()).map_err(|_| {
+            BustubxError::Storage(format!("value of {} bytes is too large", bytes.len()))
+        })?;
+        // written from the end so that each page knows the next one
+        let mut next_page_id = INVALID_PAGE_ID;
+        for chunk in bytes.chunks(*OVERFLOW_PAGE_MAX_DATA_SIZE).rev() {
+            let page = buffer_pool.new_page()?;
+            let page_id = page.read().unwrap().page_id;
+            let mut overflow_page = OverflowPage::new(chunk.to_vec());
+            overflow_page.header.next_page_id = next_page_id;
+            buffer_pool.write_page(
+                &page,
+                page_bytes_to_array(&OverflowPageCodec::encode(&overflow_page)),
+            )?;
+            next_page_id = page_id;
+        }
+        Ok(Self::new(next_page_id, len))
+    }
+
+    /// Reads the value back from its overflow pages.
+    pub fn read(&self, buffer_pool: &BufferPoolManager) -> BustubxResult<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.len as usize);
+        let mut page_id = self.first_page_id;
+        while page_id != INVALID_PAGE_ID {
+            let (_, overflow_page) = buffer_pool.fetch_overflow_page(page_id)?;
+            bytes.extend(overflow_page.data);
+            page_id = overflow_page.header.next_page_id;
+        }
+        if bytes.len() != self.len as usize {
+            return Err(BustubxError::Storage(format!(
+                "overflow value has {} bytes but expected {}",
+                bytes.len(),
+                self.len
+            )));
+        }
+        Ok(bytes)
+    }
+
+    /// Ids of the overflow pages in the order they are chained.
+    pub fn page_ids(&self, buffer_pool: &BufferPoolManager) -> BustubxResult<Vec<PageId>> {
+        let mut page_ids = vec![];
+        let mut page_id = self.first_page_id;
+        while page_id != INVALID_PAGE_ID {
+            page_ids.push(page_id);
+            let (_, overflow_page) = buffer_pool.fetch_overflow_page(page_id)?;
+            page_id = overflow_page.header.next_page_id;
+        }
+        Ok(page_ids)
+    }
+}
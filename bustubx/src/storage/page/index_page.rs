@@ -1,5 +1,6 @@
 use crate::buffer::{PageId, INVALID_PAGE_ID};
 use crate::catalog::{Schema, SchemaRef};
+use crate::recovery::{Lsn, INVALID_LSN};
 use crate::storage::RecordId;
 use crate::Tuple;
 use std::sync::Arc;
@@ -29,9 +30,10 @@ impl BPlusTreePage {
             Self::Leaf(page) => page.header.current_size < page.min_size(),
         }
     }
-    pub fn insert_internalkv(&mut self, internalkv: InternalKV) {
+    /// Inserts the kv of a page split off from `page_id` right after it.
+    pub fn insert_internalkv(&mut self, page_id: PageId, internalkv: InternalKV) {
         match self {
-            Self::Internal(page) => page.insert(internalkv.0, internalkv.1),
+            Self::Internal(page) => page.insert_after(page_id, internalkv.0, internalkv.1),
             Self::Leaf(_) => panic!("Leaf page cannot insert InternalKV"),
         }
     }
@@ -58,9 +60,9 @@ pub type LeafKV = (Tuple, RecordId);
  * | HEADER | KEY(1)+PAGE_ID(1) | KEY(2)+PAGE_ID(2) | ... | KEY(n)+PAGE_ID(n) |
  *  --------------------------------------------------------------------------
  *
- * Header format (size in byte, 12 bytes in total):
+ * Header format (size in byte, 20 bytes in total):
  * ----------------------------------------------------------------------------
- * | PageType (4) | CurrentSize (4) | MaxSize (4) |
+ * | LSN (8) | PageType (4) | CurrentSize (4) | MaxSize (4) |
  * ----------------------------------------------------------------------------
  */
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -73,6 +75,7 @@ pub struct BPlusTreeInternalPage {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct BPlusTreeInternalPageHeader {
+    pub lsn: Lsn,
     pub page_type: BPlusTreePageType,
     pub current_size: u32,
     // max kv size can be stored
@@ -84,6 +87,7 @@ impl BPlusTreeInternalPage {
         Self {
             schema,
             header: BPlusTreeInternalPageHeader {
+                lsn: INVALID_LSN,
                 page_type: BPlusTreePageType::InternalPage,
                 current_size: 0,
                 max_size,
@@ -132,11 +136,30 @@ impl BPlusTreeInternalPage {
         self.array.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
         self.array.insert(0, null_kv);
     }
+    /// Inserts `key` and `new_page_id` after the child `page_id`. Separators may be
+    /// duplicated, so the position follows the child rather than the key order.
+    pub fn insert_after(&mut self, page_id: PageId, key: Tuple, new_page_id: PageId) {
+        let Some(index) = self.value_index(page_id) else {
+            self.insert(key, new_page_id);
+            return;
+        };
+        self.array.insert(index + 1, (key, new_page_id));
+        self.header.current_size += 1;
+    }
+    /// Appends kvs which follow all existing ones.
     pub fn batch_insert(&mut self, kvs: Vec<InternalKV>) {
         let kvs_len = kvs.len();
         self.array.extend(kvs);
         self.header.current_size += kvs_len as u32;
-        self.array.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    }
+    /// Prepends a kv whose subtree precedes all existing ones, `key` becomes the
+    /// separator of the former first child.
+    pub fn push_front(&mut self, kv: InternalKV, key: Tuple) {
+        if let Some(first_kv) = self.array.first_mut() {
+            first_kv.0 = key;
+        }
+        self.array.insert(0, kv);
+        self.header.current_size += 1;
     }
 
     pub fn delete(&mut self, key: &Tuple) {
@@ -204,35 +227,15 @@ impl BPlusTreeInternalPage {
         new_array
     }
 
-    pub fn replace_key(&mut self, old_key: &Tuple, new_key: Tuple) {
-        let key_index = self.key_index(old_key);
-        if let Some(index) = key_index {
+    /// Replaces the separator of the child `page_id`.
+    pub fn replace_key(&mut self, page_id: PageId, new_key: Tuple) {
+        if let Some(index) = self.value_index(page_id) {
             self.array[index].0 = new_key;
         }
     }
 
-    pub fn key_index(&self, key: &Tuple) -> Option<usize> {
-        if self.header.current_size == 0 {
-            return None;
-        }
-        // 第一个key为空，所以从1开始
-        let mut start: i32 = 1;
-        let mut end: i32 = self.header.current_size as i32 - 1;
-        while start < end {
-            let mid = (start + end) / 2;
-            let compare_res = key.partial_cmp(&self.array[mid as usize].0).unwrap();
-            if compare_res == std::cmp::Ordering::Equal {
-                return Some(mid as usize);
-            } else if compare_res == std::cmp::Ordering::Less {
-                end = mid - 1;
-            } else {
-                start = mid + 1;
-            }
-        }
-        if key.partial_cmp(&self.array[start as usize].0).unwrap() == std::cmp::Ordering::Equal {
-            return Some(start as usize);
-        }
-        None
+    pub fn value_index(&self, page_id: PageId) -> Option<usize> {
+        self.array.iter().position(|kv| kv.1 == page_id)
     }
 
     // 查找key对应的page_id
@@ -261,6 +264,16 @@ impl BPlusTreeInternalPage {
             self.array[start as usize].1
         }
     }
+
+    /// Like `look_up`, but the leftmost child page which may hold `key`. Keys equal to
+    /// a separator may also be left of it, when their entries were split.
+    pub fn look_up_first(&self, key: &Tuple) -> PageId {
+        let index = self.array[1..self.header.current_size as usize]
+            .iter()
+            .take_while(|(k, _)| k < key)
+            .count();
+        self.array[index].1
+    }
 }
 
 /**
@@ -269,9 +282,9 @@ impl BPlusTreeInternalPage {
  * | HEADER | KEY(1) + RID(1) | KEY(2) + RID(2) | ... | KEY(n) + RID(n)
  *  ----------------------------------------------------------------------
  *
- *  Header format (size in byte, 16 bytes in total):
+ *  Header format (size in byte, 24 bytes in total):
  *  ---------------------------------------------------------------------
- * | PageType (4) | CurrentSize (4) | MaxSize (4) | NextPageId (4)
+ * | LSN (8) | PageType (4) | CurrentSize (4) | MaxSize (4) | NextPageId (4)
  *  ---------------------------------------------------------------------
  */
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -283,6 +296,7 @@ pub struct BPlusTreeLeafPage {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct BPlusTreeLeafPageHeader {
+    pub lsn: Lsn,
     pub page_type: BPlusTreePageType,
     pub current_size: u32,
     // max kv size can be stored
@@ -295,6 +309,7 @@ impl BPlusTreeLeafPage {
         Self {
             schema,
             header: BPlusTreeLeafPageHeader {
+                lsn: INVALID_LSN,
                 page_type: BPlusTreePageType::LeafPage,
                 current_size: 0,
                 max_size,
@@ -308,6 +323,7 @@ impl BPlusTreeLeafPage {
         Self {
             schema: Arc::new(Schema::empty()),
             header: BPlusTreeLeafPageHeader {
+                lsn: INVALID_LSN,
                 page_type: BPlusTreePageType::LeafPage,
                 current_size: 0,
                 max_size: 0,
@@ -362,12 +378,26 @@ impl BPlusTreeLeafPage {
         new_array
     }
 
-    pub fn delete(&mut self, key: &Tuple) {
-        let key_index = self.key_index(key);
-        if let Some(index) = key_index {
-            self.array.remove(index);
-            self.header.current_size -= 1;
-        }
+    /// Removes the entry of `key` pointing to `rid`, a key may have entries of several
+    /// tuples. Returns whether the entry was found.
+    pub fn delete(&mut self, key: &Tuple, rid: RecordId) -> bool {
+        let Some(index) = self.key_index(key) else {
+            return false;
+        };
+        let start = self.array[..index]
+            .iter()
+            .rposition(|(k, _)| k != key)
+            .map_or(0, |i| i + 1);
+        let Some(offset) = self.array[start..]
+            .iter()
+            .take_while(|(k, _)| k == key)
+            .position(|(_, r)| *r == rid)
+        else {
+            return false;
+        };
+        self.array.remove(start + offset);
+        self.header.current_size -= 1;
+        true
     }
 
     // 查找key对应的rid
@@ -398,18 +428,6 @@ impl BPlusTreeLeafPage {
         }
         None
     }
-
-    pub fn next_closest(&self, tuple: &Tuple, included: bool) -> Option<usize> {
-        for (idx, (key, _)) in self.array.iter().enumerate() {
-            if tuple == key && included {
-                return Some(idx);
-            }
-            if key > tuple {
-                return Some(idx);
-            }
-        }
-        None
-    }
 }
 
 #[cfg(test)]
@@ -741,10 +759,10 @@ mod tests {
             RecordId::new(4, 4),
         );
 
-        leaf_page.delete(&Tuple::new(
-            key_schema.clone(),
-            vec![2i8.into(), 2i16.into()],
-        ));
+        leaf_page.delete(
+            &Tuple::new(key_schema.clone(), vec![2i8.into(), 2i16.into()]),
+            RecordId::new(2, 2),
+        );
         assert_eq!(leaf_page.header.current_size, 4);
         assert_eq!(leaf_page.array[0].0.data, vec![1i8.into(), 1i16.into()]);
         assert_eq!(leaf_page.array[0].1, RecordId::new(1, 1));
@@ -754,32 +772,32 @@ mod tests {
         assert_eq!(leaf_page.array[2].1, RecordId::new(4, 4));
         assert_eq!(leaf_page.array[3].0.data, vec![5i8.into(), 5i16.into()]);
         assert_eq!(leaf_page.array[3].1, RecordId::new(5, 5));
-        leaf_page.delete(&Tuple::new(
-            key_schema.clone(),
-            vec![3i8.into(), 3i16.into()],
-        ));
+        leaf_page.delete(
+            &Tuple::new(key_schema.clone(), vec![3i8.into(), 3i16.into()]),
+            RecordId::new(3, 3),
+        );
         assert_eq!(leaf_page.header.current_size, 3);
-        leaf_page.delete(&Tuple::new(
-            key_schema.clone(),
-            vec![5i8.into(), 5i16.into()],
-        ));
+        leaf_page.delete(
+            &Tuple::new(key_schema.clone(), vec![5i8.into(), 5i16.into()]),
+            RecordId::new(5, 5),
+        );
         assert_eq!(leaf_page.header.current_size, 2);
-        leaf_page.delete(&Tuple::new(
-            key_schema.clone(),
-            vec![1i8.into(), 1i16.into()],
-        ));
+        leaf_page.delete(
+            &Tuple::new(key_schema.clone(), vec![1i8.into(), 1i16.into()]),
+            RecordId::new(1, 1),
+        );
         assert_eq!(leaf_page.header.current_size, 1);
         assert_eq!(leaf_page.array[0].0.data, vec![4i8.into(), 4i16.into()]);
         assert_eq!(leaf_page.array[0].1, RecordId::new(4, 4));
-        leaf_page.delete(&Tuple::new(
-            key_schema.clone(),
-            vec![4i8.into(), 4i16.into()],
-        ));
+        leaf_page.delete(
+            &Tuple::new(key_schema.clone(), vec![4i8.into(), 4i16.into()]),
+            RecordId::new(4, 4),
+        );
         assert_eq!(leaf_page.header.current_size, 0);
-        leaf_page.delete(&Tuple::new(
-            key_schema.clone(),
-            vec![4i8.into(), 4i16.into()],
-        ));
+        leaf_page.delete(
+            &Tuple::new(key_schema.clone(), vec![4i8.into(), 4i16.into()]),
+            RecordId::new(4, 4),
+        );
         assert_eq!(leaf_page.header.current_size, 0);
     }
 }
@@ -3,7 +3,11 @@ use crate::storage::codec::MetaPageCodec;
 use crate::{BustubxError, BustubxResult};
 use std::sync::LazyLock;
 
+/// Version of the on-disk page layouts, bump it whenever a page format changes.
+pub const FORMAT_VERSION: u32 = 1;
+
 pub static EMPTY_META_PAGE: MetaPage = MetaPage {
+    format_version: 0,
     major_version: 0,
     minor_version: 0,
     freelist_page_id: 0,
@@ -11,6 +15,9 @@ pub static EMPTY_META_PAGE: MetaPage = MetaPage {
     information_schema_tables_first_page_id: 0,
     information_schema_columns_first_page_id: 0,
     information_schema_indexes_first_page_id: 0,
+    information_schema_statistics_first_page_id: 0,
+    information_schema_constraints_first_page_id: 0,
+    information_schema_foreign_keys_first_page_id: 0,
 };
 
 pub static META_PAGE_SIZE: LazyLock<usize> =
@@ -18,6 +25,7 @@ pub static META_PAGE_SIZE: LazyLock<usize> =
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct MetaPage {
+    pub format_version: u32,
     pub major_version: u32,
     pub minor_version: u32,
     pub freelist_page_id: PageId,
@@ -25,6 +33,9 @@ pub struct MetaPage {
     pub information_schema_tables_first_page_id: PageId,
     pub information_schema_columns_first_page_id: PageId,
     pub information_schema_indexes_first_page_id: PageId,
+    pub information_schema_statistics_first_page_id: PageId,
+    pub information_schema_constraints_first_page_id: PageId,
+    pub information_schema_foreign_keys_first_page_id: PageId,
 }
 
 impl MetaPage {
@@ -45,6 +56,7 @@ impl MetaPage {
         })?;
 
         Ok(Self {
+            format_version: FORMAT_VERSION,
             major_version,
             minor_version,
             freelist_page_id: INVALID_PAGE_ID,
@@ -52,6 +64,9 @@ impl MetaPage {
             information_schema_tables_first_page_id: INVALID_PAGE_ID,
             information_schema_columns_first_page_id: INVALID_PAGE_ID,
             information_schema_indexes_first_page_id: INVALID_PAGE_ID,
+            information_schema_statistics_first_page_id: INVALID_PAGE_ID,
+            information_schema_constraints_first_page_id: INVALID_PAGE_ID,
+            information_schema_foreign_keys_first_page_id: INVALID_PAGE_ID,
         })
     }
 }
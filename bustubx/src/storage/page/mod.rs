@@ -1,9 +1,11 @@
 mod freelist_page;
 mod index_page;
 mod meta_page;
+mod overflow_page;
 mod table_page;
 
 pub use freelist_page::*;
 pub use index_page::*;
 pub use meta_page::*;
+pub use overflow_page::*;
 pub use table_page::*;
@@ -0,0 +1,47 @@
+use crate::buffer::{PageId, BUSTUBX_PAGE_SIZE, INVALID_PAGE_ID};
+use crate::recovery::{Lsn, INVALID_LSN};
+use crate::storage::codec::OverflowPageHeaderCodec;
+use std::sync::LazyLock;
+
+static EMPTY_OVERFLOW_PAGE_HEADER: OverflowPageHeader = OverflowPageHeader {
+    lsn: 0,
+    next_page_id: 0,
+    data_size: 0,
+};
+
+pub static OVERFLOW_PAGE_MAX_DATA_SIZE: LazyLock<usize> = LazyLock::new(|| {
+    BUSTUBX_PAGE_SIZE - OverflowPageHeaderCodec::encode(&EMPTY_OVERFLOW_PAGE_HEADER).len()
+});
+
+/**
+ * Overflow page format, a chain of them stores a value which is too large for a table page:
+ *  -----------------------------------------------------
+ *  | LSN (8) | NextPageId (4) | DataSize (2) | DATA ... |
+ *  -----------------------------------------------------
+ */
+#[derive(Debug, Eq, PartialEq)]
+pub struct OverflowPage {
+    pub header: OverflowPageHeader,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct OverflowPageHeader {
+    pub lsn: Lsn,
+    pub next_page_id: PageId,
+    pub data_size: u16,
+}
+
+impl OverflowPage {
+    pub fn new(data: Vec<u8>) -> Self {
+        debug_assert!(data.len() <= *OVERFLOW_PAGE_MAX_DATA_SIZE);
+        Self {
+            header: OverflowPageHeader {
+                lsn: INVALID_LSN,
+                next_page_id: INVALID_PAGE_ID,
+                data_size: data.len() as u16,
+            },
+            data,
+        }
+    }
+}
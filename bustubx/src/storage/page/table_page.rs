@@ -1,8 +1,9 @@
 use crate::buffer::{PageId, BUSTUBX_PAGE_SIZE, INVALID_PAGE_ID};
 use crate::catalog::SchemaRef;
-use crate::storage::codec::{TablePageHeaderCodec, TablePageHeaderTupleInfoCodec, TupleCodec};
+use crate::recovery::{Lsn, INVALID_LSN};
+use crate::storage::codec::{TablePageHeaderCodec, TablePageHeaderTupleInfoCodec};
 use crate::transaction::TransactionId;
-use crate::{BustubxError, BustubxResult, Tuple};
+use crate::{BustubxError, BustubxResult};
 use std::sync::LazyLock;
 
 pub static EMPTY_TUPLE_META: TupleMeta = TupleMeta {
@@ -27,7 +28,7 @@ pub static EMPTY_TUPLE_INFO: LazyLock<TupleInfo> = LazyLock::new(|| TupleInfo {
  *
  *  Header format (size in bytes):
  *  ----------------------------------------------------------------------------
- *  | NextPageId (4)| NumTuples(2) | NumDeletedTuples(2) |
+ *  | LSN (8) | NextPageId (4)| NumTuples(2) | NumDeletedTuples(2) |
  *  ----------------------------------------------------------------------------
  *  ----------------------------------------------------------------
  *  | Tuple_1 offset+size + TupleMeta | Tuple_2 offset+size + TupleMeta | ... |
@@ -44,6 +45,7 @@ pub struct TablePage {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct TablePageHeader {
+    pub lsn: Lsn,
     pub next_page_id: PageId,
     pub num_tuples: u16,
     pub num_deleted_tuples: u16,
@@ -69,7 +71,7 @@ pub const INVALID_RID: RecordId = RecordId {
     slot_num: 0,
 };
 
-#[derive(derive_new::new, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(derive_new::new, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RecordId {
     pub page_id: PageId,
     pub slot_num: u32,
@@ -80,6 +82,7 @@ impl TablePage {
         Self {
             schema,
             header: TablePageHeader {
+                lsn: INVALID_LSN,
                 next_page_id,
                 num_tuples: 0,
                 num_deleted_tuples: 0,
@@ -90,7 +93,7 @@ impl TablePage {
     }
 
     // Get the offset for the next tuple insertion.
-    pub fn next_tuple_offset(&self, tuple: &Tuple) -> BustubxResult<usize> {
+    pub fn next_tuple_offset(&self, tuple_bytes: &[u8]) -> BustubxResult<usize> {
         // Get the ending offset of the current slot. If there are inserted tuples,
         // get the offset of the previous inserted tuple; otherwise, set it to the size of the page.
         let slot_end_offset = if self.header.num_tuples > 0 {
@@ -100,7 +103,7 @@ impl TablePage {
         };
 
         // Check if the current slot has enough space for the new tuple. Return None if not.
-        if slot_end_offset < TupleCodec::encode(tuple).len() {
+        if slot_end_offset < tuple_bytes.len() {
             return Err(BustubxError::Storage(
                 "No enough space to store tuple".to_string(),
             ));
@@ -108,7 +111,7 @@ impl TablePage {
 
         // Calculate the insertion offset for the new tuple by subtracting its data length
         // from the ending offset of the current slot.
-        let tuple_offset = slot_end_offset - TupleCodec::encode(tuple).len();
+        let tuple_offset = slot_end_offset - tuple_bytes.len();
 
         // Calculate the minimum valid tuple insertion offset, including the table page header size,
         // the total size of each tuple info (existing tuple infos and newly added tuple info).
@@ -124,11 +127,11 @@ impl TablePage {
         Ok(tuple_offset)
     }
 
-    pub fn insert_tuple(&mut self, meta: &TupleMeta, tuple: &Tuple) -> BustubxResult<u16> {
+    /// Inserts an encoded tuple, see `TupleCodec`.
+    pub fn insert_tuple(&mut self, meta: &TupleMeta, tuple_bytes: &[u8]) -> BustubxResult<u16> {
         // Get the offset for the next tuple insertion.
-        let tuple_offset = self.next_tuple_offset(tuple)?;
+        let tuple_offset = self.next_tuple_offset(tuple_bytes)?;
         let tuple_id = self.header.num_tuples;
-        let tuple_bytes = TupleCodec::encode(tuple);
         debug_assert!(tuple_bytes.len() < u16::MAX as usize);
 
         // Store tuple information including offset, length, and metadata.
@@ -147,7 +150,7 @@ impl TablePage {
         }
 
         // Copy the tuple's data into the appropriate position within the page's data buffer.
-        self.data[tuple_offset..tuple_offset + tuple_bytes.len()].copy_from_slice(&tuple_bytes);
+        self.data[tuple_offset..tuple_offset + tuple_bytes.len()].copy_from_slice(tuple_bytes);
         Ok(tuple_id)
     }
 
@@ -166,7 +169,7 @@ impl TablePage {
         Ok(())
     }
 
-    pub fn update_tuple(&mut self, tuple: Tuple, slot_num: u16) -> BustubxResult<()> {
+    pub fn update_tuple(&mut self, tuple_bytes: &[u8], slot_num: u16) -> BustubxResult<()> {
         if slot_num >= self.header.num_tuples {
             return Err(BustubxError::Storage(format!(
                 "tuple_id {} out of range",
@@ -175,27 +178,20 @@ impl TablePage {
         }
         let offset = self.header.tuple_infos[slot_num as usize].offset as usize;
         let size = self.header.tuple_infos[slot_num as usize].size as usize;
-        let tuple_bytes = TupleCodec::encode(&tuple);
         if tuple_bytes.len() == size {
-            self.data[offset..(offset + size)].copy_from_slice(&tuple_bytes);
+            self.data[offset..(offset + size)].copy_from_slice(tuple_bytes);
         } else {
             // need move other tuples
-            let mut full_tuples = vec![];
-            for info in self.header.tuple_infos.iter() {
-                full_tuples.push((
-                    info.meta,
-                    TupleCodec::decode(
-                        &self.data[info.offset as usize..(info.offset + info.size) as usize],
-                        self.schema.clone(),
-                    )?
-                    .0,
-                ));
-            }
-            full_tuples[slot_num as usize].1 = tuple;
-
             let mut new_page = TablePage::new(self.schema.clone(), self.header.next_page_id);
-            for (meta, tuple) in full_tuples.iter() {
-                new_page.insert_tuple(meta, tuple)?;
+            for (slot, info) in self.header.tuple_infos.iter().enumerate() {
+                if slot == slot_num as usize {
+                    new_page.insert_tuple(&info.meta, tuple_bytes)?;
+                } else {
+                    new_page.insert_tuple(
+                        &info.meta,
+                        &self.data[info.offset as usize..(info.offset + info.size) as usize],
+                    )?;
+                }
             }
             self.header = new_page.header;
             self.data = new_page.data;
@@ -203,7 +199,8 @@ impl TablePage {
         Ok(())
     }
 
-    pub fn tuple(&self, slot_num: u16) -> BustubxResult<(TupleMeta, Tuple)> {
+    /// The encoded tuple, see `TupleCodec`.
+    pub fn tuple_bytes(&self, slot_num: u16) -> BustubxResult<(TupleMeta, &[u8])> {
         if slot_num >= self.header.num_tuples {
             return Err(BustubxError::Storage(format!(
                 "tuple_id {} out of range",
@@ -211,15 +208,11 @@ impl TablePage {
             )));
         }
 
-        let offset = self.header.tuple_infos[slot_num as usize].offset;
-        let size = self.header.tuple_infos[slot_num as usize].size;
-        let meta = self.header.tuple_infos[slot_num as usize].meta;
-        let (tuple, _) = TupleCodec::decode(
-            &self.data[offset as usize..(offset + size) as usize],
-            self.schema.clone(),
-        )?;
-
-        Ok((meta, tuple))
+        let info = &self.header.tuple_infos[slot_num as usize];
+        Ok((
+            info.meta,
+            &self.data[info.offset as usize..(info.offset + info.size) as usize],
+        ))
     }
 
     pub fn tuple_meta(&self, slot_num: u16) -> BustubxResult<TupleMeta> {
@@ -247,6 +240,7 @@ impl TablePage {
 #[cfg(test)]
 mod tests {
     use crate::catalog::{Column, DataType, Schema};
+    use crate::storage::codec::TupleCodec;
     use crate::storage::{Tuple, EMPTY_TUPLE_META};
     use std::sync::Arc;
 
@@ -260,31 +254,34 @@ mod tests {
         let tuple_id = table_page
             .insert_tuple(
                 &EMPTY_TUPLE_META,
-                &Tuple::new(schema.clone(), vec![1i8.into(), 1i16.into()]),
+                &TupleCodec::encode(&Tuple::new(schema.clone(), vec![1i8.into(), 1i16.into()])),
             )
             .unwrap();
         assert_eq!(tuple_id, 0);
         let tuple_id = table_page
             .insert_tuple(
                 &EMPTY_TUPLE_META,
-                &Tuple::new(schema.clone(), vec![2i8.into(), 2i16.into()]),
+                &TupleCodec::encode(&Tuple::new(schema.clone(), vec![2i8.into(), 2i16.into()])),
             )
             .unwrap();
         assert_eq!(tuple_id, 1);
         let tuple_id = table_page
             .insert_tuple(
                 &EMPTY_TUPLE_META,
-                &Tuple::new(schema.clone(), vec![3i8.into(), 3i16.into()]),
+                &TupleCodec::encode(&Tuple::new(schema.clone(), vec![3i8.into(), 3i16.into()])),
             )
             .unwrap();
         assert_eq!(tuple_id, 2);
 
-        let (tuple_meta, tuple) = table_page.tuple(0).unwrap();
+        let (tuple_meta, tuple_bytes) = table_page.tuple_bytes(0).unwrap();
         assert_eq!(tuple_meta, EMPTY_TUPLE_META);
+        let (tuple, _) = TupleCodec::decode(tuple_bytes, schema.clone()).unwrap();
         assert_eq!(tuple.data, vec![1i8.into(), 1i16.into()]);
-        let (_tuple_meta, tuple) = table_page.tuple(1).unwrap();
+        let (_tuple_meta, tuple_bytes) = table_page.tuple_bytes(1).unwrap();
+        let (tuple, _) = TupleCodec::decode(tuple_bytes, schema.clone()).unwrap();
         assert_eq!(tuple.data, vec![2i8.into(), 2i16.into()]);
-        let (_tuple_meta, tuple) = table_page.tuple(2).unwrap();
+        let (_tuple_meta, tuple_bytes) = table_page.tuple_bytes(2).unwrap();
+        let (tuple, _) = TupleCodec::decode(tuple_bytes, schema.clone()).unwrap();
         assert_eq!(tuple.data, vec![3i8.into(), 3i16.into()]);
     }
 
@@ -298,19 +295,19 @@ mod tests {
         let _tuple_id = table_page
             .insert_tuple(
                 &EMPTY_TUPLE_META,
-                &Tuple::new(schema.clone(), vec![1i8.into(), 1i16.into()]),
+                &TupleCodec::encode(&Tuple::new(schema.clone(), vec![1i8.into(), 1i16.into()])),
             )
             .unwrap();
         let _tuple_id = table_page
             .insert_tuple(
                 &EMPTY_TUPLE_META,
-                &Tuple::new(schema.clone(), vec![2i8.into(), 2i16.into()]),
+                &TupleCodec::encode(&Tuple::new(schema.clone(), vec![2i8.into(), 2i16.into()])),
             )
             .unwrap();
         let _tuple_id = table_page
             .insert_tuple(
                 &EMPTY_TUPLE_META,
-                &Tuple::new(schema.clone(), vec![3i8.into(), 3i16.into()]),
+                &TupleCodec::encode(&Tuple::new(schema.clone(), vec![3i8.into(), 3i16.into()])),
             )
             .unwrap();
 
@@ -0,0 +1,196 @@
+use crate::buffer::{BufferPoolManager, PageId, BUSTUBX_PAGE_SIZE};
+use crate::catalog::SchemaRef;
+use crate::storage::codec::{CommonCodec, TupleCodec};
+use crate::{BustubxResult, Tuple};
+use std::sync::Arc;
+
+/// Frames of the buffer pool which spill files are written through
+pub const SPILL_BUFFER_POOL_SIZE: usize = 64;
+const PAGE_HEADER_SIZE: usize = 8;
+const PAGE_DATA_SIZE: usize = BUSTUBX_PAGE_SIZE - PAGE_HEADER_SIZE;
+
+/// Tuples which an operator moves out of memory while it executes, e.g. partitions of
+/// a hash join. The pages are written through a buffer pool of a temporary file, see
+/// `DiskManager::try_new_temp`, and are deleted when the spill file is dropped.
+///
+/// Page layout: | tuple count (4 bytes) | continued bytes (4 bytes) | tuple | tuple | ... |
+///
+/// A tuple larger than a page starts on a page of its own and continues on the following
+/// pages, whose continued bytes are the size of the part of the tuple they hold.
+#[derive(Debug)]
+pub struct SpillFile {
+    schema: SchemaRef,
+    buffer_pool: Arc<BufferPoolManager>,
+    page_ids: Vec<PageId>,
+    // tuples which don't fill a page yet
+    buffer: Vec<u8>,
+    buffered_tuples: u32,
+}
+
+impl SpillFile {
+    pub fn new(schema: SchemaRef, buffer_pool: Arc<BufferPoolManager>) -> Self {
+        Self {
+            schema,
+            buffer_pool,
+            page_ids: vec![],
+            buffer: vec![],
+            buffered_tuples: 0,
+        }
+    }
+
+    pub fn append(&mut self, tuple: &Tuple) -> BustubxResult<()> {
+        let bytes = TupleCodec::encode(tuple);
+        if self.buffer.len() + bytes.len() > PAGE_DATA_SIZE {
+            self.flush()?;
+        }
+        if bytes.len() > PAGE_DATA_SIZE {
+            let mut chunks = bytes.chunks(PAGE_DATA_SIZE);
+            if let Some(first) = chunks.next() {
+                self.write_page(1, 0, first)?;
+            }
+            for chunk in chunks {
+                self.write_page(0, chunk.len() as u32, chunk)?;
+            }
+            return Ok(());
+        }
+        self.buffer.extend(bytes);
+        self.buffered_tuples += 1;
+        Ok(())
+    }
+
+    /// Writes the buffered tuples to a new page.
+    pub fn flush(&mut self) -> BustubxResult<()> {
+        if self.buffered_tuples == 0 {
+            return Ok(());
+        }
+        let buffer = std::mem::take(&mut self.buffer);
+        self.write_page(self.buffered_tuples, 0, &buffer)?;
+        self.buffered_tuples = 0;
+        Ok(())
+    }
+
+    fn write_page(&mut self, tuple_count: u32, continued: u32, bytes: &[u8]) -> BustubxResult<()> {
+        let mut data = [0; BUSTUBX_PAGE_SIZE];
+        data[0..4].copy_from_slice(&CommonCodec::encode_u32(tuple_count));
+        data[4..PAGE_HEADER_SIZE].copy_from_slice(&CommonCodec::encode_u32(continued));
+        data[PAGE_HEADER_SIZE..PAGE_HEADER_SIZE + bytes.len()].copy_from_slice(bytes);
+
+        let page = self.buffer_pool.new_page()?;
+        let mut page = page.write().unwrap();
+        page.set_data(data);
+        self.page_ids.push(page.page_id);
+        Ok(())
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.page_ids.len()
+    }
+
+    /// Reads tuples starting on the page at `index`, buffered tuples must be flushed
+    /// before.
+    pub fn read_page(&self, index: usize) -> BustubxResult<Vec<Tuple>> {
+        let (count, continued, mut data) = self.page_data(index)?;
+        if continued > 0 {
+            return Ok(vec![]);
+        }
+        for next in index + 1..self.page_count() {
+            let (_, continued, next_data) = self.page_data(next)?;
+            if continued == 0 {
+                break;
+            }
+            data.extend_from_slice(&next_data[..continued as usize]);
+        }
+
+        let mut tuples = Vec::with_capacity(count as usize);
+        let mut offset = 0;
+        for _ in 0..count {
+            let (tuple, len) = TupleCodec::decode(&data[offset..], self.schema.clone())?;
+            tuples.push(tuple);
+            offset += len;
+        }
+        Ok(tuples)
+    }
+
+    /// Tuple count, continued bytes and data of the page at `index`.
+    fn page_data(&self, index: usize) -> BustubxResult<(u32, u32, Vec<u8>)> {
+        let page = self.buffer_pool.fetch_page(self.page_ids[index])?;
+        let page = page.read().unwrap();
+        let data = page.data();
+        let (count, _) = CommonCodec::decode_u32(data)?;
+        let (continued, _) = CommonCodec::decode_u32(&data[4..])?;
+        Ok((count, continued, data[PAGE_HEADER_SIZE..].to_vec()))
+    }
+
+    pub fn read_all(&self) -> BustubxResult<Vec<Tuple>> {
+        let mut tuples = vec![];
+        for index in 0..self.page_count() {
+            tuples.extend(self.read_page(index)?);
+        }
+        Ok(tuples)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        for page_id in self.page_ids.iter() {
+            if let Err(e) = self.buffer_pool.delete_page(*page_id) {
+                log::error!("Failed to delete spilled page {}, err: {:?}", page_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::BufferPoolManager;
+    use crate::catalog::{Column, DataType, Schema};
+    use crate::common::ScalarValue;
+    use crate::storage::{DiskManager, SpillFile};
+    use crate::Tuple;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[test]
+    pub fn test_spill_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("test.db");
+        let disk_manager = Arc::new(DiskManager::try_new(temp_path).unwrap());
+        let buffer_pool = Arc::new(BufferPoolManager::new(10, disk_manager));
+
+        let schema = Arc::new(Schema::new(vec![
+            Column::new("a", DataType::Int32, false),
+            Column::new("b", DataType::Varchar(None), true),
+        ]));
+        let tuples = (0..5000)
+            .map(|i| {
+                // some tuples are larger than a page
+                let value = if i % 1000 == 1 {
+                    format!("value{i}").repeat(2000)
+                } else {
+                    format!("value{i}")
+                };
+                Tuple::new(
+                    schema.clone(),
+                    vec![
+                        ScalarValue::Int32(Some(i)),
+                        ScalarValue::Varchar((i % 3 != 0).then_some(value)),
+                    ],
+                )
+            })
+            .collect::<Vec<Tuple>>();
+
+        let mut file = SpillFile::new(schema.clone(), buffer_pool.clone());
+        for tuple in tuples.iter() {
+            file.append(tuple).unwrap();
+        }
+        file.flush().unwrap();
+        // more pages than the buffer pool holds
+        assert!(file.page_count() > 10);
+        assert_eq!(file.read_all().unwrap(), tuples);
+
+        let page_ids = file.page_ids.clone();
+        drop(file);
+        let new_page = buffer_pool.new_page().unwrap();
+        assert!(page_ids.contains(&new_page.read().unwrap().page_id));
+    }
+}
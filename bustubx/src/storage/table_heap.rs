@@ -1,16 +1,21 @@
-use crate::buffer::{AtomicPageId, INVALID_PAGE_ID};
+use crate::buffer::{AtomicPageId, PageId, BUSTUBX_PAGE_SIZE, INVALID_PAGE_ID};
 use crate::catalog::SchemaRef;
 use crate::common::util::page_bytes_to_array;
-use crate::storage::codec::TablePageCodec;
-use crate::storage::{RecordId, TablePage, TupleMeta, INVALID_RID};
-use crate::{buffer::BufferPoolManager, BustubxResult};
-use std::collections::Bound;
+use crate::storage::codec::{ScalarValueCodec, TablePageCodec, TupleCodec, OVERFLOW_POINTER_SIZE};
+use crate::storage::{OverflowPointer, RecordId, TablePage, TupleMeta, INVALID_RID};
+use crate::transaction::Snapshot;
+use crate::{buffer::BufferPoolManager, BustubxError, BustubxResult};
+use std::collections::{Bound, HashMap};
 use std::ops::RangeBounds;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use super::tuple::Tuple;
 
+/// Tuples whose encoding is larger than this store their largest variable length values
+/// in overflow pages, until the rest fits.
+const OVERFLOW_THRESHOLD: usize = BUSTUBX_PAGE_SIZE / 4;
+
 #[derive(Debug)]
 pub struct TableHeap {
     pub schema: SchemaRef,
@@ -25,10 +30,10 @@ impl TableHeap {
         let first_page = buffer_pool.new_page()?;
         let first_page_id = first_page.read().unwrap().page_id;
         let table_page = TablePage::new(schema.clone(), INVALID_PAGE_ID);
-        first_page
-            .write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&TablePageCodec::encode(&table_page)));
+        buffer_pool.write_page(
+            &first_page,
+            page_bytes_to_array(&TablePageCodec::encode(&table_page)),
+        )?;
 
         Ok(Self {
             schema,
@@ -52,13 +57,15 @@ impl TableHeap {
     /// An `Option` containing the `Rid` of the inserted tuple if successful, otherwise `None`.
     pub fn insert_tuple(&self, meta: &TupleMeta, tuple: &Tuple) -> BustubxResult<RecordId> {
         let mut last_page_id = self.last_page_id.load(Ordering::SeqCst);
-        let (last_page, mut last_table_page) = self
+        let (mut last_page, mut last_table_page) = self
             .buffer_pool
             .fetch_table_page(last_page_id, self.schema.clone())?;
 
+        let tuple_bytes = self.encode_tuple(tuple)?;
+
         // Loop until a suitable page is found for inserting the tuple
         loop {
-            if last_table_page.next_tuple_offset(tuple).is_ok() {
+            if last_table_page.next_tuple_offset(&tuple_bytes).is_ok() {
                 break;
             }
 
@@ -73,51 +80,50 @@ impl TableHeap {
             let next_page = self.buffer_pool.new_page()?;
             let next_page_id = next_page.read().unwrap().page_id;
             let next_table_page = TablePage::new(self.schema.clone(), INVALID_PAGE_ID);
-            next_page
-                .write()
-                .unwrap()
-                .set_data(page_bytes_to_array(&TablePageCodec::encode(
-                    &next_table_page,
-                )));
+            self.buffer_pool.write_page(
+                &next_page,
+                page_bytes_to_array(&TablePageCodec::encode(&next_table_page)),
+            )?;
 
             // Update and release the previous page
             last_table_page.header.next_page_id = next_page_id;
-            last_page
-                .write()
-                .unwrap()
-                .set_data(page_bytes_to_array(&TablePageCodec::encode(
-                    &last_table_page,
-                )));
+            self.buffer_pool.write_page(
+                &last_page,
+                page_bytes_to_array(&TablePageCodec::encode(&last_table_page)),
+            )?;
 
             // Update last_page_id.
             last_page_id = next_page_id;
+            last_page = next_page;
             last_table_page = next_table_page;
             self.last_page_id.store(last_page_id, Ordering::SeqCst);
         }
 
         // Insert the tuple into the chosen page
-        let slot_id = last_table_page.insert_tuple(meta, tuple)?;
+        let slot_id = last_table_page.insert_tuple(meta, &tuple_bytes)?;
 
-        last_page
-            .write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&TablePageCodec::encode(
-                &last_table_page,
-            )));
+        self.buffer_pool.write_page(
+            &last_page,
+            page_bytes_to_array(&TablePageCodec::encode(&last_table_page)),
+        )?;
 
         // Map the slot_id to a Rid and return
         Ok(RecordId::new(last_page_id, slot_id as u32))
     }
 
+    /// Replaces the tuple, the overflow pages of the old values are not freed, see
+    /// `overflow_page_ids`.
     pub fn update_tuple(&self, rid: RecordId, tuple: Tuple) -> BustubxResult<()> {
+        let tuple_bytes = self.encode_tuple(&tuple)?;
         let (page, mut table_page) = self
             .buffer_pool
             .fetch_table_page(rid.page_id, self.schema.clone())?;
-        table_page.update_tuple(tuple, rid.slot_num as u16)?;
+        table_page.update_tuple(&tuple_bytes, rid.slot_num as u16)?;
 
-        page.write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&TablePageCodec::encode(&table_page)));
+        self.buffer_pool.write_page(
+            &page,
+            page_bytes_to_array(&TablePageCodec::encode(&table_page)),
+        )?;
         Ok(())
     }
 
@@ -127,9 +133,10 @@ impl TableHeap {
             .fetch_table_page(rid.page_id, self.schema.clone())?;
         table_page.update_tuple_meta(meta, rid.slot_num as u16)?;
 
-        page.write()
-            .unwrap()
-            .set_data(page_bytes_to_array(&TablePageCodec::encode(&table_page)));
+        self.buffer_pool.write_page(
+            &page,
+            page_bytes_to_array(&TablePageCodec::encode(&table_page)),
+        )?;
         Ok(())
     }
 
@@ -137,8 +144,33 @@ impl TableHeap {
         let (_, table_page) = self
             .buffer_pool
             .fetch_table_page(rid.page_id, self.schema.clone())?;
-        let result = table_page.tuple(rid.slot_num as u16)?;
-        Ok(result)
+        let (meta, tuple_bytes) = table_page.tuple_bytes(rid.slot_num as u16)?;
+        let (tuple, _) = TupleCodec::decode_with_overflow(
+            tuple_bytes,
+            self.schema.clone(),
+            Some(&self.buffer_pool),
+        )?;
+        Ok((meta, tuple))
+    }
+
+    pub fn projected_tuple(
+        &self,
+        rid: RecordId,
+        projection: &[usize],
+        projected_schema: SchemaRef,
+    ) -> BustubxResult<(TupleMeta, Tuple)> {
+        let (_, table_page) = self
+            .buffer_pool
+            .fetch_table_page(rid.page_id, self.schema.clone())?;
+        let (meta, tuple_bytes) = table_page.tuple_bytes(rid.slot_num as u16)?;
+        let tuple = TupleCodec::decode_projected(
+            tuple_bytes,
+            &self.schema,
+            projection,
+            projected_schema,
+            Some(&self.buffer_pool),
+        )?;
+        Ok((meta, tuple))
     }
 
     pub fn tuple(&self, rid: RecordId) -> BustubxResult<Tuple> {
@@ -147,8 +179,74 @@ impl TableHeap {
     }
 
     pub fn tuple_meta(&self, rid: RecordId) -> BustubxResult<TupleMeta> {
-        let (meta, _tuple) = self.full_tuple(rid)?;
-        Ok(meta)
+        let (_, table_page) = self
+            .buffer_pool
+            .fetch_table_page(rid.page_id, self.schema.clone())?;
+        table_page.tuple_meta(rid.slot_num as u16)
+    }
+
+    /// Ids of the overflow pages of the values of the tuple, they are freed by the caller
+    /// once its values are replaced, or once no transaction can see the deleted tuple.
+    pub fn overflow_page_ids(&self, rid: RecordId) -> BustubxResult<Vec<PageId>> {
+        let (_, table_page) = self
+            .buffer_pool
+            .fetch_table_page(rid.page_id, self.schema.clone())?;
+        let (_, tuple_bytes) = table_page.tuple_bytes(rid.slot_num as u16)?;
+        let mut page_ids = vec![];
+        for pointer in TupleCodec::overflow_pointers(tuple_bytes, &self.schema)? {
+            page_ids.extend(pointer.page_ids(&self.buffer_pool)?);
+        }
+        Ok(page_ids)
+    }
+
+    /// Encodes the tuple, the largest values are written to overflow pages if the tuple is
+    /// larger than `OVERFLOW_THRESHOLD`.
+    fn encode_tuple(&self, tuple: &Tuple) -> BustubxResult<Vec<u8>> {
+        let mut candidates = tuple
+            .data
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, value)| {
+                ScalarValueCodec::overflow_bytes(value)
+                    .filter(|bytes| bytes.len() > OVERFLOW_POINTER_SIZE)
+                    .map(|bytes| (idx, bytes))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|(_, bytes)| bytes.len());
+
+        // start with all candidates out of line and bring the smallest ones back
+        let placeholder = OverflowPointer::new(INVALID_PAGE_ID, 0);
+        let mut pointers = candidates
+            .iter()
+            .map(|(idx, _)| (*idx, placeholder))
+            .collect::<HashMap<_, _>>();
+        let mut size = TupleCodec::encode_with_pointers(tuple, &pointers).len();
+        for (idx, bytes) in candidates.iter() {
+            let inline_size = size + bytes.len() - OVERFLOW_POINTER_SIZE;
+            if inline_size > OVERFLOW_THRESHOLD || bytes.len() >= u16::MAX as usize {
+                break;
+            }
+            pointers.remove(idx);
+            size = inline_size;
+        }
+
+        let empty_page = TablePage::new(self.schema.clone(), INVALID_PAGE_ID);
+        if empty_page
+            .next_tuple_offset(&TupleCodec::encode_with_pointers(tuple, &pointers))
+            .is_err()
+        {
+            return Err(BustubxError::Storage(format!(
+                "tuple is too large, {} bytes without its variable length values",
+                size
+            )));
+        }
+
+        for (idx, bytes) in candidates.iter() {
+            if let Some(pointer) = pointers.get_mut(idx) {
+                *pointer = OverflowPointer::write(&self.buffer_pool, bytes)?;
+            }
+        }
+        Ok(TupleCodec::encode_with_pointers(tuple, &pointers))
     }
 
     pub fn get_first_rid(&self) -> BustubxResult<Option<RecordId>> {
@@ -186,6 +284,39 @@ impl TableHeap {
             Ok(Some(RecordId::new(table_page.header.next_page_id, 0)))
         }
     }
+
+    /// Replaces the values of a deleted tuple by nulls and returns the ids of the overflow
+    /// pages of the old values, which the caller frees.
+    pub fn clear_deleted_tuple(&self, rid: RecordId) -> BustubxResult<Vec<PageId>> {
+        let page_ids = self.overflow_page_ids(rid)?;
+        if !page_ids.is_empty() {
+            self.update_tuple(rid, Tuple::empty(self.schema.clone()))?;
+        }
+        Ok(page_ids)
+    }
+
+    /// Ids of all pages of the table in the order they are chained, followed by the
+    /// overflow pages of tuples which are not cleared yet.
+    pub fn page_ids(&self) -> BustubxResult<Vec<PageId>> {
+        let mut page_ids = vec![];
+        let mut overflow_page_ids = vec![];
+        let mut page_id = self.first_page_id.load(Ordering::SeqCst);
+        while page_id != INVALID_PAGE_ID {
+            page_ids.push(page_id);
+            let (_, table_page) = self
+                .buffer_pool
+                .fetch_table_page(page_id, self.schema.clone())?;
+            for slot_num in 0..table_page.header.num_tuples {
+                let (_, tuple_bytes) = table_page.tuple_bytes(slot_num)?;
+                for pointer in TupleCodec::overflow_pointers(tuple_bytes, &self.schema)? {
+                    overflow_page_ids.extend(pointer.page_ids(&self.buffer_pool)?);
+                }
+            }
+            page_id = table_page.header.next_page_id;
+        }
+        page_ids.extend(overflow_page_ids);
+        Ok(page_ids)
+    }
 }
 
 #[derive(Debug)]
@@ -196,6 +327,8 @@ pub struct TableIterator {
     cursor: RecordId,
     started: bool,
     ended: bool,
+    snapshot: Option<Snapshot>,
+    projection: Option<(Vec<usize>, SchemaRef)>,
 }
 
 impl TableIterator {
@@ -207,10 +340,64 @@ impl TableIterator {
             cursor: INVALID_RID,
             started: false,
             ended: false,
+            snapshot: None,
+            projection: None,
         }
     }
 
+    /// Reads the versions visible to the snapshot instead of the newest ones.
+    pub fn with_snapshot(mut self, snapshot: Option<Snapshot>) -> Self {
+        self.snapshot = snapshot;
+        self
+    }
+
+    /// Reads only the columns at `projection` of each tuple.
+    pub fn with_projection(mut self, projection: Vec<usize>, projected_schema: SchemaRef) -> Self {
+        self.projection = Some((projection, projected_schema));
+        self
+    }
+
+    /// Returns the next tuple which is not marked deleted, or the next visible one
+    /// if reading a snapshot.
     pub fn next(&mut self) -> BustubxResult<Option<(RecordId, Tuple)>> {
+        while let Some(rid) = self.next_rid()? {
+            // overflow values of deleted tuples are freed once no transaction can see the
+            // tuple, so they are skipped before being read
+            let meta = self.heap.tuple_meta(rid)?;
+            if self
+                .snapshot
+                .as_ref()
+                .map_or(meta.is_deleted, |snapshot| snapshot.sees_deletion(&meta))
+            {
+                continue;
+            }
+            let (meta, tuple) = match &self.projection {
+                Some((projection, projected_schema)) => {
+                    self.heap
+                        .projected_tuple(rid, projection, projected_schema.clone())?
+                }
+                None => self.heap.full_tuple(rid)?,
+            };
+            if let Some(snapshot) = &self.snapshot {
+                let visible_tuple = match &self.projection {
+                    // undo logs apply to all columns of the tuple
+                    Some((_, projected_schema)) if !snapshot.sees_newest_version(&meta) => snapshot
+                        .visible_version(rid, &meta, self.heap.tuple(rid)?)?
+                        .map(|tuple| tuple.project_with_schema(projected_schema.clone()))
+                        .transpose()?,
+                    _ => snapshot.visible_version(rid, &meta, tuple)?,
+                };
+                if let Some(tuple) = visible_tuple {
+                    return Ok(Some((rid, tuple)));
+                }
+            } else if !meta.is_deleted {
+                return Ok(Some((rid, tuple)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_rid(&mut self) -> BustubxResult<Option<RecordId>> {
         if self.ended {
             return Ok(None);
         }
@@ -223,11 +410,7 @@ impl TableIterator {
                             self.ended = true;
                         }
                         self.cursor = next_rid;
-                        Ok(self
-                            .heap
-                            .tuple(self.cursor)
-                            .ok()
-                            .map(|tuple| (self.cursor, tuple)))
+                        Ok(Some(self.cursor))
                     } else {
                         Ok(None)
                     }
@@ -238,11 +421,7 @@ impl TableIterator {
                             Ok(None)
                         } else {
                             self.cursor = next_rid;
-                            Ok(self
-                                .heap
-                                .tuple(self.cursor)
-                                .ok()
-                                .map(|tuple| (self.cursor, tuple)))
+                            Ok(Some(self.cursor))
                         }
                     } else {
                         Ok(None)
@@ -251,11 +430,7 @@ impl TableIterator {
                 Bound::Unbounded => {
                     if let Some(next_rid) = self.heap.get_next_rid(self.cursor)? {
                         self.cursor = next_rid;
-                        Ok(self
-                            .heap
-                            .tuple(self.cursor)
-                            .ok()
-                            .map(|tuple| (self.cursor, tuple)))
+                        Ok(Some(self.cursor))
                     } else {
                         Ok(None)
                     }
@@ -266,20 +441,12 @@ impl TableIterator {
             match self.start_bound {
                 Bound::Included(rid) => {
                     self.cursor = rid;
-                    Ok(self
-                        .heap
-                        .tuple(self.cursor)
-                        .ok()
-                        .map(|tuple| (self.cursor, tuple)))
+                    Ok(Some(self.cursor))
                 }
                 Bound::Excluded(rid) => {
                     if let Some(next_rid) = self.heap.get_next_rid(rid)? {
                         self.cursor = next_rid;
-                        Ok(self
-                            .heap
-                            .tuple(self.cursor)
-                            .ok()
-                            .map(|tuple| (self.cursor, tuple)))
+                        Ok(Some(self.cursor))
                     } else {
                         self.ended = true;
                         Ok(None)
@@ -288,11 +455,7 @@ impl TableIterator {
                 Bound::Unbounded => {
                     if let Some(first_rid) = self.heap.get_first_rid()? {
                         self.cursor = first_rid;
-                        Ok(self
-                            .heap
-                            .tuple(self.cursor)
-                            .ok()
-                            .map(|tuple| (self.cursor, tuple)))
+                        Ok(Some(self.cursor))
                     } else {
                         self.ended = true;
                         Ok(None)
@@ -310,6 +473,7 @@ mod tests {
     use tempfile::TempDir;
 
     use crate::catalog::{Column, DataType, Schema};
+    use crate::common::ScalarValue;
     use crate::storage::{TableIterator, EMPTY_TUPLE_META};
     use crate::{
         buffer::BufferPoolManager,
@@ -484,4 +648,104 @@ mod tests {
 
         assert!(iterator.next().unwrap().is_none());
     }
+
+    #[test]
+    pub fn test_table_heap_multiple_pages() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("test.db");
+
+        let schema = Arc::new(Schema::new(vec![
+            Column::new("a", DataType::Int32, false),
+            Column::new("b", DataType::Int32, false),
+        ]));
+
+        let disk_manager = DiskManager::try_new(temp_path).unwrap();
+        let buffer_pool = Arc::new(BufferPoolManager::new(1000, Arc::new(disk_manager)));
+        let table_heap = Arc::new(TableHeap::try_new(schema.clone(), buffer_pool).unwrap());
+
+        let meta = super::TupleMeta {
+            insert_txn_id: 1,
+            delete_txn_id: 1,
+            is_deleted: false,
+        };
+        let mut rids = vec![];
+        for i in 0..1000i32 {
+            let rid = table_heap
+                .insert_tuple(&meta, &Tuple::new(schema.clone(), vec![i.into(), i.into()]))
+                .unwrap();
+            rids.push(rid);
+        }
+        assert_ne!(rids.first().unwrap().page_id, rids.last().unwrap().page_id);
+
+        let mut iterator = TableIterator::new(table_heap.clone(), ..);
+        for (i, expected_rid) in rids.into_iter().enumerate() {
+            let (rid, tuple) = iterator.next().unwrap().unwrap();
+            assert_eq!(rid, expected_rid);
+            assert_eq!(tuple.data, vec![(i as i32).into(), (i as i32).into()]);
+        }
+        assert!(iterator.next().unwrap().is_none());
+    }
+
+    #[test]
+    pub fn test_table_heap_overflow_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path().join("test.db");
+
+        let schema = Arc::new(Schema::new(vec![
+            Column::new("a", DataType::Int32, false),
+            Column::new("b", DataType::Varchar(None), true),
+            Column::new("c", DataType::Blob, true),
+        ]));
+
+        let disk_manager = DiskManager::try_new(temp_path).unwrap();
+        let buffer_pool = Arc::new(BufferPoolManager::new(1000, Arc::new(disk_manager)));
+        let table_heap = Arc::new(TableHeap::try_new(schema.clone(), buffer_pool).unwrap());
+
+        let small = Tuple::new(
+            schema.clone(),
+            vec![1i32.into(), "a".repeat(100).into(), vec![1u8; 100].into()],
+        );
+        let large = Tuple::new(
+            schema.clone(),
+            vec![
+                2i32.into(),
+                "b".repeat(10000).into(),
+                vec![2u8; 70000].into(),
+            ],
+        );
+        let rid1 = table_heap.insert_tuple(&EMPTY_TUPLE_META, &small).unwrap();
+        let rid2 = table_heap.insert_tuple(&EMPTY_TUPLE_META, &large).unwrap();
+        assert_eq!(rid1.page_id, rid2.page_id);
+        assert!(table_heap.overflow_page_ids(rid1).unwrap().is_empty());
+        let overflow_page_ids = table_heap.overflow_page_ids(rid2).unwrap();
+        assert!(overflow_page_ids.len() > 20);
+        assert_eq!(table_heap.tuple(rid1).unwrap(), small);
+        assert_eq!(table_heap.tuple(rid2).unwrap(), large);
+        assert_eq!(
+            table_heap.page_ids().unwrap().len(),
+            overflow_page_ids.len() + 1
+        );
+
+        let mut iterator = TableIterator::new(table_heap.clone(), ..)
+            .with_projection(vec![2, 0], Arc::new(schema.project(&[2, 0]).unwrap()));
+        assert_eq!(iterator.next().unwrap().unwrap().1.data[0], small.data[2]);
+        assert_eq!(iterator.next().unwrap().unwrap().1.data[0], large.data[2]);
+
+        // the old pages are freed by the caller
+        let updated = Tuple::new(
+            schema.clone(),
+            vec![
+                2i32.into(),
+                "c".repeat(5000).into(),
+                ScalarValue::Blob(None),
+            ],
+        );
+        table_heap.update_tuple(rid2, updated.clone()).unwrap();
+        assert_eq!(table_heap.tuple(rid2).unwrap(), updated);
+        let new_overflow_page_ids = table_heap.overflow_page_ids(rid2).unwrap();
+        assert_eq!(new_overflow_page_ids.len(), 2);
+        assert!(new_overflow_page_ids
+            .iter()
+            .all(|page_id| !overflow_page_ids.contains(page_id)));
+    }
 }
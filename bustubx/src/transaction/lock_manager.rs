@@ -1,9 +1,17 @@
+use crate::catalog::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use crate::common::TableReference;
 use crate::storage::RecordId;
-use crate::transaction::{Transaction, TransactionId};
-use std::collections::HashMap;
+use crate::transaction::{IsolationLevel, Transaction, TransactionId, TransactionState};
+use crate::{BustubxError, BustubxResult};
+use log::debug;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::Hash;
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::time::Duration;
 
-#[derive(Debug)]
+pub const DEADLOCK_DETECTION_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LockMode {
     Shared,
     Exclusive,
@@ -12,54 +20,681 @@ pub enum LockMode {
     SharedIntentionExclusive,
 }
 
-pub struct LockRequest {
+impl LockMode {
+    /**
+     * Compatibility matrix:
+     *        IS   IX   S    SIX  X
+     *  IS    y    y    y    y    n
+     *  IX    y    y    n    n    n
+     *  S     y    n    y    n    n
+     *  SIX   y    n    n    n    n
+     *  X     n    n    n    n    n
+     */
+    pub fn compatible_with(&self, other: LockMode) -> bool {
+        use LockMode::*;
+        match self {
+            IntentionShared => other != Exclusive,
+            IntentionExclusive => matches!(other, IntentionShared | IntentionExclusive),
+            Shared => matches!(other, IntentionShared | Shared),
+            SharedIntentionExclusive => other == IntentionShared,
+            Exclusive => false,
+        }
+    }
+
+    /// Allowed upgrades: IS -> [S, X, IX, SIX], S -> [X, SIX], IX -> [X, SIX], SIX -> [X]
+    pub fn can_upgrade_to(&self, other: LockMode) -> bool {
+        use LockMode::*;
+        match self {
+            IntentionShared => other != IntentionShared,
+            Shared | IntentionExclusive => matches!(other, Exclusive | SharedIntentionExclusive),
+            SharedIntentionExclusive => other == Exclusive,
+            Exclusive => false,
+        }
+    }
+
+    /// Whether holding this lock implies holding `other` too.
+    pub fn covers(&self, other: LockMode) -> bool {
+        use LockMode::*;
+        match self {
+            Exclusive => true,
+            SharedIntentionExclusive => other != Exclusive,
+            Shared => matches!(other, Shared | IntentionShared),
+            IntentionExclusive => matches!(other, IntentionExclusive | IntentionShared),
+            IntentionShared => other == IntentionShared,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LockRequest {
     txn_id: TransactionId,
-    lock_mod: LockMode,
-    table_ref: TableReference,
-    rid: Option<RecordId>,
+    lock_mode: LockMode,
     granted: bool,
 }
 
+#[derive(Debug, Default)]
+struct LockRequestQueue {
+    inner: Mutex<LockRequestQueueInner>,
+    cv: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct LockRequestQueueInner {
+    requests: Vec<LockRequest>,
+    /// Transaction which is upgrading its lock, at most one upgrade is allowed at a time
+    upgrading: Option<TransactionId>,
+}
+
+impl LockRequestQueueInner {
+    /// Requests are granted in FIFO order, a request waits until it's compatible with
+    /// all requests ahead of it.
+    fn grantable(&self, txn_id: TransactionId, lock_mode: LockMode) -> bool {
+        for request in self.requests.iter() {
+            if request.txn_id == txn_id {
+                return true;
+            }
+            if !request.lock_mode.compatible_with(lock_mode) {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+#[derive(Debug)]
+struct TxnLocks {
+    state: TransactionState,
+    table_locks: HashMap<TableReference, LockMode>,
+    row_locks: HashMap<RecordId, (TableReference, LockMode)>,
+}
+
+impl Default for TxnLocks {
+    fn default() -> Self {
+        Self {
+            state: TransactionState::Running,
+            table_locks: HashMap::new(),
+            row_locks: HashMap::new(),
+        }
+    }
+}
+
+/// Hierarchical strict two-phase lock manager. Tables are locked in any of the five modes,
+/// rows only in shared or exclusive mode after an appropriate table lock was acquired.
+/// All locks are held until the transaction commits or aborts.
+///
+/// Lock order: lock maps -> request queue -> txn locks.
+#[derive(Debug, Default)]
 pub struct LockManager {
-    table_lock_map: HashMap<TableReference, Vec<LockRequest>>,
-    row_lock_map: HashMap<RecordId, Vec<LockRequest>>,
+    table_lock_map: Mutex<HashMap<TableReference, Arc<LockRequestQueue>>>,
+    row_lock_map: Mutex<HashMap<RecordId, Arc<LockRequestQueue>>>,
+    txn_locks: Mutex<HashMap<TransactionId, TxnLocks>>,
 }
 
 impl LockManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn lock_table(
         &self,
-        _txn: Transaction,
-        _mode: LockMode,
-        _table_ref: TableReference,
-    ) -> bool {
-        todo!()
-    }
+        txn: &Transaction,
+        mode: LockMode,
+        table_ref: &TableReference,
+    ) -> BustubxResult<()> {
+        let table_ref = full_table_ref(table_ref);
+        if txn.isolation_level == IsolationLevel::ReadUncommitted
+            && matches!(
+                mode,
+                LockMode::Shared | LockMode::IntentionShared | LockMode::SharedIntentionExclusive
+            )
+        {
+            return self.abort(txn.id, "shared locks are not allowed in read uncommitted");
+        }
+        self.check_running(txn.id)?;
+        let held_mode = self
+            .txn_locks
+            .lock()
+            .unwrap()
+            .get(&txn.id)
+            .and_then(|locks| locks.table_locks.get(&table_ref).copied());
+
+        let queue = self
+            .table_lock_map
+            .lock()
+            .unwrap()
+            .entry(table_ref.clone())
+            .or_default()
+            .clone();
+        let result = self.acquire(txn.id, &queue, mode, held_mode);
+        drop(queue);
+        let mode = match result {
+            Ok(mode) => mode,
+            Err(e) => {
+                remove_if_unused(&mut self.table_lock_map.lock().unwrap(), &table_ref);
+                return Err(e);
+            }
+        };
 
-    pub fn unlock_table(&self, _txn: Transaction, _table_ref: TableReference) -> bool {
-        todo!()
+        self.txn_locks
+            .lock()
+            .unwrap()
+            .entry(txn.id)
+            .or_default()
+            .table_locks
+            .insert(table_ref, mode);
+        Ok(())
     }
 
     pub fn lock_row(
         &self,
-        _txn: Transaction,
-        _mode: LockMode,
-        _table_ref: TableReference,
-        _rid: RecordId,
-    ) -> bool {
-        todo!()
+        txn: &Transaction,
+        mode: LockMode,
+        table_ref: &TableReference,
+        rid: RecordId,
+    ) -> BustubxResult<()> {
+        let table_ref = full_table_ref(table_ref);
+        match mode {
+            LockMode::Shared if txn.isolation_level == IsolationLevel::ReadUncommitted => {
+                return self.abort(txn.id, "shared locks are not allowed in read uncommitted");
+            }
+            LockMode::Shared | LockMode::Exclusive => {}
+            _ => return self.abort(txn.id, "intention locks are not allowed on rows"),
+        }
+        let held_mode = {
+            let mut txn_locks = self.txn_locks.lock().unwrap();
+            let locks = txn_locks.entry(txn.id).or_default();
+            let table_mode = locks.table_locks.get(&table_ref).copied();
+            let table_lock_present = match mode {
+                LockMode::Exclusive => matches!(
+                    table_mode,
+                    Some(
+                        LockMode::Exclusive
+                            | LockMode::IntentionExclusive
+                            | LockMode::SharedIntentionExclusive
+                    )
+                ),
+                _ => table_mode.is_some(),
+            };
+            if !table_lock_present {
+                drop(txn_locks);
+                return self.abort(txn.id, "table lock not present");
+            }
+            locks.row_locks.get(&rid).map(|(_, mode)| *mode)
+        };
+        self.check_running(txn.id)?;
+
+        let queue = self
+            .row_lock_map
+            .lock()
+            .unwrap()
+            .entry(rid)
+            .or_default()
+            .clone();
+        let result = self.acquire(txn.id, &queue, mode, held_mode);
+        drop(queue);
+        let mode = match result {
+            Ok(mode) => mode,
+            Err(e) => {
+                remove_if_unused(&mut self.row_lock_map.lock().unwrap(), &rid);
+                return Err(e);
+            }
+        };
+
+        self.txn_locks
+            .lock()
+            .unwrap()
+            .entry(txn.id)
+            .or_default()
+            .row_locks
+            .insert(rid, (table_ref, mode));
+        Ok(())
     }
 
-    pub fn unlock_row(
+    /// Releases all locks of the transaction when it commits or aborts.
+    pub fn unlock_all(&self, txn_id: TransactionId) {
+        let Some(locks) = self.txn_locks.lock().unwrap().remove(&txn_id) else {
+            return;
+        };
+        let mut row_lock_map = self.row_lock_map.lock().unwrap();
+        for rid in locks.row_locks.keys() {
+            release(txn_id, &mut row_lock_map, rid);
+        }
+        drop(row_lock_map);
+        let mut table_lock_map = self.table_lock_map.lock().unwrap();
+        for table_ref in locks.table_locks.keys() {
+            release(txn_id, &mut table_lock_map, table_ref);
+        }
+    }
+
+    /// Periodically looks for cycles in the waits-for graph until the lock manager is dropped.
+    pub fn start_deadlock_detection(self: &Arc<Self>, interval: Duration) {
+        let lock_manager: Weak<LockManager> = Arc::downgrade(self);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+            let Some(lock_manager) = lock_manager.upgrade() else {
+                break;
+            };
+            lock_manager.detect_deadlocks();
+        });
+    }
+
+    /// Aborts the youngest transaction of every cycle in the waits-for graph,
+    /// returns the aborted transactions.
+    pub fn detect_deadlocks(&self) -> Vec<TransactionId> {
+        // the maps stay locked, so queues are not referenced from outside while
+        // released locks remove them
+        let table_lock_map = self.table_lock_map.lock().unwrap();
+        let row_lock_map = self.row_lock_map.lock().unwrap();
+        let queues = table_lock_map
+            .values()
+            .chain(row_lock_map.values())
+            .collect::<Vec<_>>();
+
+        let mut waits_for = self.waits_for_graph(&queues);
+        let mut victims = vec![];
+        while let Some(cycle) = find_cycle(&waits_for) {
+            let victim = *cycle.iter().max().unwrap();
+            debug!("deadlock detected {:?}, aborting txn {}", cycle, victim);
+            if let Some(locks) = self.txn_locks.lock().unwrap().get_mut(&victim) {
+                locks.state = TransactionState::Aborted;
+            }
+            waits_for.remove(&victim);
+            for edges in waits_for.values_mut() {
+                edges.remove(&victim);
+            }
+            victims.push(victim);
+        }
+
+        if !victims.is_empty() {
+            // holding the queue lock makes sure the victim is either waiting or will see
+            // the aborted flag before waiting
+            for queue in queues.iter() {
+                let _guard = queue.inner.lock().unwrap();
+                queue.cv.notify_all();
+            }
+        }
+        victims
+    }
+
+    /// Edge t1 -> t2 means t1 is waiting for a lock held by t2
+    fn waits_for_graph(
         &self,
-        _txn: Transaction,
-        _table_ref: TableReference,
-        _rid: RecordId,
-        _force: bool,
-    ) -> bool {
-        todo!()
+        queues: &[&Arc<LockRequestQueue>],
+    ) -> BTreeMap<TransactionId, BTreeSet<TransactionId>> {
+        let mut waits_for: BTreeMap<TransactionId, BTreeSet<TransactionId>> = BTreeMap::new();
+        for queue in queues.iter() {
+            let inner = queue.inner.lock().unwrap();
+            for waiting in inner.requests.iter().filter(|r| !r.granted) {
+                for granted in inner.requests.iter().filter(|r| r.granted) {
+                    if granted.txn_id != waiting.txn_id
+                        && !granted.lock_mode.compatible_with(waiting.lock_mode)
+                    {
+                        waits_for
+                            .entry(waiting.txn_id)
+                            .or_default()
+                            .insert(granted.txn_id);
+                    }
+                }
+            }
+        }
+        waits_for
+    }
+
+    /// Waits until the lock is granted, returns the mode which is held afterwards.
+    fn acquire(
+        &self,
+        txn_id: TransactionId,
+        queue: &LockRequestQueue,
+        mode: LockMode,
+        held_mode: Option<LockMode>,
+    ) -> BustubxResult<LockMode> {
+        let mode = match (held_mode, mode) {
+            (Some(held_mode), _) if held_mode.covers(mode) => return Ok(held_mode),
+            (Some(LockMode::IntentionExclusive), LockMode::Shared)
+            | (Some(LockMode::Shared), LockMode::IntentionExclusive) => {
+                LockMode::SharedIntentionExclusive
+            }
+            _ => mode,
+        };
+
+        let mut inner = queue.inner.lock().unwrap();
+        let request = LockRequest {
+            txn_id,
+            lock_mode: mode,
+            granted: false,
+        };
+        match held_mode {
+            Some(held_mode) => {
+                if inner.upgrading.is_some() {
+                    drop(inner);
+                    return self.abort(txn_id, "upgrade conflict");
+                }
+                if !held_mode.can_upgrade_to(mode) {
+                    drop(inner);
+                    return self.abort(txn_id, "incompatible upgrade");
+                }
+                // upgrading request is prior to all waiting requests
+                inner.requests.retain(|r| r.txn_id != txn_id);
+                let pos = inner
+                    .requests
+                    .iter()
+                    .position(|r| !r.granted)
+                    .unwrap_or(inner.requests.len());
+                inner.requests.insert(pos, request);
+                inner.upgrading = Some(txn_id);
+            }
+            None => inner.requests.push(request),
+        }
+
+        loop {
+            if self.is_aborted(txn_id) {
+                inner.requests.retain(|r| r.txn_id != txn_id);
+                if inner.upgrading == Some(txn_id) {
+                    inner.upgrading = None;
+                }
+                queue.cv.notify_all();
+                drop(inner);
+                return Err(BustubxError::Transaction(format!(
+                    "Transaction {} aborted by deadlock detection",
+                    txn_id
+                )));
+            }
+            if inner.grantable(txn_id, mode) {
+                break;
+            }
+            inner = queue.cv.wait(inner).unwrap();
+        }
+
+        for request in inner.requests.iter_mut() {
+            if request.txn_id == txn_id {
+                request.granted = true;
+            }
+        }
+        if inner.upgrading == Some(txn_id) {
+            inner.upgrading = None;
+        }
+        Ok(mode)
+    }
+
+    fn check_running(&self, txn_id: TransactionId) -> BustubxResult<()> {
+        if self.is_aborted(txn_id) {
+            return self.abort(txn_id, "lock after abort");
+        }
+        Ok(())
+    }
+
+    fn is_aborted(&self, txn_id: TransactionId) -> bool {
+        self.txn_locks
+            .lock()
+            .unwrap()
+            .get(&txn_id)
+            .map(|locks| locks.state == TransactionState::Aborted)
+            .unwrap_or(false)
+    }
+
+    fn abort<T>(&self, txn_id: TransactionId, reason: &str) -> BustubxResult<T> {
+        self.txn_locks
+            .lock()
+            .unwrap()
+            .entry(txn_id)
+            .or_default()
+            .state = TransactionState::Aborted;
+        Err(BustubxError::Transaction(format!(
+            "Transaction {} aborted: {}",
+            txn_id, reason
+        )))
+    }
+}
+
+fn release<K: Eq + Hash>(
+    txn_id: TransactionId,
+    lock_map: &mut HashMap<K, Arc<LockRequestQueue>>,
+    key: &K,
+) {
+    if let Some(queue) = lock_map.get(key) {
+        let mut inner = queue.inner.lock().unwrap();
+        inner.requests.retain(|r| r.txn_id != txn_id);
+        queue.cv.notify_all();
+    }
+    remove_if_unused(lock_map, key);
+}
+
+/// Removes the queue of `key` once it has no requests. Requests are only added through
+/// a queue cloned from the map, so a queue only the map references has no waiters either.
+fn remove_if_unused<K: Eq + Hash>(lock_map: &mut HashMap<K, Arc<LockRequestQueue>>, key: &K) {
+    let unused = lock_map.get(key).is_some_and(|queue| {
+        Arc::strong_count(queue) == 1 && queue.inner.lock().unwrap().requests.is_empty()
+    });
+    if unused {
+        lock_map.remove(key);
+    }
+}
+
+fn full_table_ref(table_ref: &TableReference) -> TableReference {
+    TableReference::full(
+        table_ref.catalog().unwrap_or(DEFAULT_CATALOG_NAME),
+        table_ref.schema().unwrap_or(DEFAULT_SCHEMA_NAME),
+        table_ref.table(),
+    )
+}
+
+/// Depth first search from the lowest transaction id, neighbors are explored in order too,
+/// so the search is deterministic.
+fn find_cycle(
+    waits_for: &BTreeMap<TransactionId, BTreeSet<TransactionId>>,
+) -> Option<Vec<TransactionId>> {
+    fn dfs(
+        txn_id: TransactionId,
+        waits_for: &BTreeMap<TransactionId, BTreeSet<TransactionId>>,
+        path: &mut Vec<TransactionId>,
+        visited: &mut BTreeSet<TransactionId>,
+    ) -> Option<Vec<TransactionId>> {
+        if let Some(pos) = path.iter().position(|id| *id == txn_id) {
+            return Some(path[pos..].to_vec());
+        }
+        if !visited.insert(txn_id) {
+            return None;
+        }
+        path.push(txn_id);
+        for next in waits_for.get(&txn_id).into_iter().flatten() {
+            if let Some(cycle) = dfs(*next, waits_for, path, visited) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        None
+    }
+
+    let mut visited = BTreeSet::new();
+    for txn_id in waits_for.keys() {
+        if let Some(cycle) = dfs(*txn_id, waits_for, &mut vec![], &mut visited) {
+            return Some(cycle);
+        }
     }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::TableReference;
+    use crate::storage::RecordId;
+    use crate::transaction::{IsolationLevel, LockManager, LockMode, Transaction};
+    use crate::BustubxError;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn txn(id: u64, isolation_level: IsolationLevel) -> Transaction {
+        Transaction {
+            id,
+            isolation_level,
+            read_ts: 0,
+        }
+    }
+
+    #[test]
+    pub fn test_lock_mode_compatibility() {
+        use LockMode::*;
+        let modes = [
+            IntentionShared,
+            IntentionExclusive,
+            Shared,
+            SharedIntentionExclusive,
+            Exclusive,
+        ];
+        let matrix = [
+            [true, true, true, true, false],
+            [true, true, false, false, false],
+            [true, false, true, false, false],
+            [true, false, false, false, false],
+            [false, false, false, false, false],
+        ];
+        for (i, a) in modes.iter().enumerate() {
+            for (j, b) in modes.iter().enumerate() {
+                assert_eq!(a.compatible_with(*b), matrix[i][j], "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_lock_blocking() {
+        let lock_manager = Arc::new(LockManager::new());
+        let table_ref = TableReference::bare("t1");
+        let rid = RecordId::new(1, 0);
+        let txn1 = txn(1, IsolationLevel::Serializable);
+        lock_manager
+            .lock_table(&txn1, LockMode::IntentionExclusive, &table_ref)
+            .unwrap();
+        lock_manager
+            .lock_row(&txn1, LockMode::Exclusive, &table_ref, rid)
+            .unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let handle = {
+            let lock_manager = lock_manager.clone();
+            let table_ref = table_ref.clone();
+            std::thread::spawn(move || {
+                let txn2 = txn(2, IsolationLevel::Serializable);
+                lock_manager
+                    .lock_table(&txn2, LockMode::IntentionShared, &table_ref)
+                    .unwrap();
+                lock_manager
+                    .lock_row(&txn2, LockMode::Shared, &table_ref, rid)
+                    .unwrap();
+                sender.send(()).unwrap();
+                lock_manager.unlock_all(txn2.id);
+            })
+        };
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+        lock_manager.unlock_all(txn1.id);
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        handle.join().unwrap();
+        assert!(lock_manager.table_lock_map.lock().unwrap().is_empty());
+        assert!(lock_manager.row_lock_map.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    pub fn test_lock_upgrade() {
+        let lock_manager = LockManager::new();
+        let table_ref = TableReference::bare("t1");
+        let txn1 = txn(1, IsolationLevel::Serializable);
+        lock_manager
+            .lock_table(&txn1, LockMode::IntentionShared, &table_ref)
+            .unwrap();
+        lock_manager
+            .lock_table(&txn1, LockMode::IntentionExclusive, &table_ref)
+            .unwrap();
+        // IX + S is SIX
+        lock_manager
+            .lock_table(&txn1, LockMode::Shared, &table_ref)
+            .unwrap();
+        // already covered by SIX
+        lock_manager
+            .lock_table(&txn1, LockMode::IntentionShared, &table_ref)
+            .unwrap();
+
+        let txn2 = txn(2, IsolationLevel::Serializable);
+        lock_manager
+            .lock_table(&txn2, LockMode::IntentionShared, &table_ref)
+            .unwrap();
+        lock_manager.unlock_all(txn1.id);
+        lock_manager.unlock_all(txn2.id);
+    }
+
+    #[test]
+    pub fn test_lock_rules() {
+        let lock_manager = LockManager::new();
+        let table_ref = TableReference::bare("t1");
+        let rid = RecordId::new(1, 0);
+
+        // shared locks in read uncommitted
+        let txn1 = txn(1, IsolationLevel::ReadUncommitted);
+        assert!(matches!(
+            lock_manager.lock_table(&txn1, LockMode::Shared, &table_ref),
+            Err(BustubxError::Transaction(_))
+        ));
+
+        // row lock without table lock
+        let txn2 = txn(2, IsolationLevel::Serializable);
+        assert!(lock_manager
+            .lock_row(&txn2, LockMode::Exclusive, &table_ref, rid)
+            .is_err());
+
+        // lock after abort
+        assert!(lock_manager
+            .lock_table(&txn2, LockMode::IntentionExclusive, &table_ref)
+            .is_err());
+
+        for id in 1..=2 {
+            lock_manager.unlock_all(id);
+        }
+    }
+
+    #[test]
+    pub fn test_deadlock_detection() {
+        let lock_manager = Arc::new(LockManager::new());
+        lock_manager.start_deadlock_detection(Duration::from_millis(10));
+        let table_ref = TableReference::bare("t1");
+        let rid1 = RecordId::new(1, 0);
+        let rid2 = RecordId::new(1, 1);
+
+        let txn1 = txn(1, IsolationLevel::Serializable);
+        lock_manager
+            .lock_table(&txn1, LockMode::IntentionExclusive, &table_ref)
+            .unwrap();
+        lock_manager
+            .lock_row(&txn1, LockMode::Exclusive, &table_ref, rid1)
+            .unwrap();
+
+        let handle = {
+            let lock_manager = lock_manager.clone();
+            let table_ref = table_ref.clone();
+            std::thread::spawn(move || {
+                let txn2 = txn(2, IsolationLevel::Serializable);
+                lock_manager
+                    .lock_table(&txn2, LockMode::IntentionExclusive, &table_ref)
+                    .unwrap();
+                lock_manager
+                    .lock_row(&txn2, LockMode::Exclusive, &table_ref, rid2)
+                    .unwrap();
+                std::thread::sleep(Duration::from_millis(50));
+                let result = lock_manager.lock_row(&txn2, LockMode::Exclusive, &table_ref, rid1);
+                lock_manager.unlock_all(txn2.id);
+                result
+            })
+        };
 
-    pub fn unlock_all(&self) {
-        todo!()
+        std::thread::sleep(Duration::from_millis(20));
+        // txn2 is younger, so it's the victim
+        lock_manager
+            .lock_row(&txn1, LockMode::Exclusive, &table_ref, rid2)
+            .unwrap();
+        assert!(matches!(
+            handle.join().unwrap(),
+            Err(BustubxError::Transaction(_))
+        ));
+        lock_manager.unlock_all(txn1.id);
+        assert!(lock_manager.table_lock_map.lock().unwrap().is_empty());
+        assert!(lock_manager.row_lock_map.lock().unwrap().is_empty());
     }
 }
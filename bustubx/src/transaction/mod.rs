@@ -1,5 +1,9 @@
 mod lock_manager;
 mod transaction;
 mod transaction_manager;
+mod version_store;
 
+pub use lock_manager::*;
 pub use transaction::*;
+pub use transaction_manager::*;
+pub use version_store::*;
@@ -1,33 +1,51 @@
+use crate::transaction::IsolationLevel;
 use crate::Tuple;
 
 pub type TransactionId = u64;
 pub const INVALID_TRANSACTION_ID: TransactionId = 0;
 
+/// Commit timestamps are assigned in commit order, a transaction reads the versions
+/// committed at or before its read timestamp.
+pub type Timestamp = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransactionState {
     Running,
-    Tainted,
-    Committed,
     Aborted,
 }
 
-pub struct Transaction {}
+#[derive(Debug)]
+pub struct Transaction {
+    pub id: TransactionId,
+    pub isolation_level: IsolationLevel,
+    pub read_ts: Timestamp,
+}
 
 /// Represents a link to a previous version of this tuple
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct UndoLink {
-    prev_txn: TransactionId,
-    prev_log_idx: u32,
+    pub prev_txn: TransactionId,
+    pub prev_log_idx: u32,
 }
 
 impl UndoLink {
+    pub const INVALID: UndoLink = UndoLink {
+        prev_txn: INVALID_TRANSACTION_ID,
+        prev_log_idx: 0,
+    };
+
     pub fn is_valid(&self) -> bool {
         self.prev_txn != INVALID_TRANSACTION_ID
     }
 }
 
+/// Previous version of a tuple, only the fields which were modified are stored in `tuple`.
+#[derive(Debug, Clone)]
 pub struct UndoLog {
-    is_deleted: bool,
-    modified_fields: Vec<bool>,
-    tuple: Tuple,
-    timestamp: u64,
-    prev_version: UndoLink,
+    pub is_deleted: bool,
+    pub modified_fields: Vec<bool>,
+    pub tuple: Tuple,
+    /// Commit timestamp of the version stored in this log
+    pub timestamp: Timestamp,
+    pub prev_version: UndoLink,
 }
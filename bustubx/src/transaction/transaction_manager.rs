@@ -1,23 +1,182 @@
-use crate::transaction::Transaction;
+use crate::buffer::{BufferPoolManager, PageId, BUSTUBX_PAGE_SIZE};
+use crate::recovery::{LogManager, Lsn};
+use crate::storage::{RecordId, Tuple, TupleMeta};
+use crate::transaction::{
+    DeadVersion, LockManager, Snapshot, Timestamp, Transaction, TransactionId, VersionStore,
+};
+use crate::BustubxResult;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IsolationLevel {
     ReadUncommitted,
     SnapshotIsolation,
     Serializable,
 }
 
-pub struct TransactionManager {}
+pub struct TransactionManager {
+    next_txn_id: AtomicU64,
+    last_commit_ts: AtomicU64,
+    /// Commit timestamps must be published in order
+    commit_lock: Mutex<()>,
+    version_store: Arc<VersionStore>,
+    /// Pages of dropped tables and indexes and overflow pages of replaced values, which are
+    /// given back when the transaction commits as a rollback still needs them. Each page is
+    /// tagged with the last lsn of the transaction when it was dropped.
+    dropped_pages: Mutex<HashMap<TransactionId, Vec<(Lsn, PageId)>>>,
+    log_manager: Arc<LogManager>,
+    buffer_pool: Arc<BufferPoolManager>,
+    lock_manager: Arc<LockManager>,
+}
 
 impl TransactionManager {
-    pub fn begin(&self, _isolation_level: IsolationLevel) -> Transaction {
-        todo!()
+    pub fn new(
+        log_manager: Arc<LogManager>,
+        buffer_pool: Arc<BufferPoolManager>,
+        lock_manager: Arc<LockManager>,
+    ) -> Self {
+        // the log is empty after recovery, so transaction ids only need to be unique
+        // within this process
+        Self {
+            next_txn_id: AtomicU64::new(1),
+            last_commit_ts: AtomicU64::new(0),
+            commit_lock: Mutex::new(()),
+            version_store: Arc::new(VersionStore::default()),
+            dropped_pages: Mutex::new(HashMap::new()),
+            log_manager,
+            buffer_pool,
+            lock_manager,
+        }
+    }
+
+    pub fn begin(&self, isolation_level: IsolationLevel) -> Transaction {
+        let id: TransactionId = self.next_txn_id.fetch_add(1, Ordering::SeqCst);
+        self.log_manager.begin(id);
+        let _guard = self.commit_lock.lock().unwrap();
+        let read_ts: Timestamp = self.last_commit_ts.load(Ordering::SeqCst);
+        self.version_store.begin(id, read_ts);
+        Transaction {
+            id,
+            isolation_level,
+            read_ts,
+        }
+    }
+
+    pub fn savepoint(&self, txn: &Transaction) -> Lsn {
+        self.log_manager.savepoint(txn.id)
+    }
+
+    /// Rolls back changes of the transaction made after `savepoint`, pages dropped since
+    /// are kept. Returns whether there was any change.
+    pub fn rollback_to_savepoint(&self, txn: &Transaction, savepoint: Lsn) -> BustubxResult<bool> {
+        if let Some(dropped_pages) = self.dropped_pages.lock().unwrap().get_mut(&txn.id) {
+            dropped_pages.retain(|(lsn, _)| *lsn <= savepoint);
+        }
+        self.log_manager
+            .rollback_to(txn.id, savepoint, |page_id, offset, data, lsn| {
+                self.buffer_pool
+                    .restore_page_data(page_id, offset, data, lsn)
+            })
+    }
+
+    pub fn commit(&self, txn: Transaction) -> BustubxResult<()> {
+        let dropped_pages = self
+            .dropped_pages
+            .lock()
+            .unwrap()
+            .remove(&txn.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, page_id)| page_id)
+            .collect::<Vec<_>>();
+        // clearing dropped pages is logged, otherwise recovery would redo older changes
+        // on top of a page which was freed and reused afterwards
+        self.log_manager.begin(txn.id);
+        for page_id in dropped_pages.iter() {
+            let page = self.buffer_pool.fetch_page(*page_id)?;
+            self.buffer_pool.write_page(&page, [0; BUSTUBX_PAGE_SIZE])?;
+        }
+        self.log_manager.commit(txn.id)?;
+        {
+            let _guard = self.commit_lock.lock().unwrap();
+            let commit_ts = self.last_commit_ts.load(Ordering::SeqCst) + 1;
+            self.version_store.commit(txn.id, commit_ts);
+            self.last_commit_ts.store(commit_ts, Ordering::SeqCst);
+        }
+        self.lock_manager.unlock_all(txn.id);
+        for page_id in dropped_pages {
+            self.buffer_pool.delete_page(page_id)?;
+        }
+        self.garbage_collection();
+        Ok(())
+    }
+
+    /// Rolls back all changes of the transaction, returns whether it had any change.
+    pub fn abort(&self, txn: Transaction) -> BustubxResult<bool> {
+        // locks are held until all changes are undone
+        let changed = self
+            .log_manager
+            .rollback(txn.id, |page_id, offset, data, lsn| {
+                self.buffer_pool
+                    .restore_page_data(page_id, offset, data, lsn)
+            })?;
+        self.version_store.abort(txn.id);
+        self.dropped_pages.lock().unwrap().remove(&txn.id);
+        self.lock_manager.unlock_all(txn.id);
+        self.garbage_collection();
+        Ok(changed)
+    }
+
+    /// Frees the pages once the transaction commits, unless the changes which dropped them
+    /// are rolled back to a savepoint.
+    pub fn drop_pages_on_commit(&self, txn: &Transaction, page_ids: Vec<PageId>) {
+        let lsn = self.log_manager.savepoint(txn.id);
+        self.dropped_pages
+            .lock()
+            .unwrap()
+            .entry(txn.id)
+            .or_default()
+            .extend(page_ids.into_iter().map(|page_id| (lsn, page_id)));
+    }
+
+    /// Versions visible to the transaction, only snapshot isolation reads old versions.
+    pub fn snapshot(&self, txn: &Transaction) -> Option<Snapshot> {
+        (txn.isolation_level == IsolationLevel::SnapshotIsolation)
+            .then(|| Snapshot::new(txn, self.version_store.clone()))
+    }
+
+    pub fn check_write_conflict(&self, txn: &Transaction, meta: &TupleMeta) -> BustubxResult<()> {
+        self.version_store.check_write_conflict(txn, meta)
+    }
+
+    pub fn record_update(
+        &self,
+        txn: &Transaction,
+        rid: RecordId,
+        old_meta: &TupleMeta,
+        old_tuple: &Tuple,
+        new_tuple: &Tuple,
+    ) -> BustubxResult<()> {
+        self.version_store
+            .record_update(txn, rid, old_meta, old_tuple, new_tuple)
+    }
+
+    pub fn record_dead_version(&self, txn_id: TransactionId, dead_version: DeadVersion) {
+        self.version_store.record_dead_version(txn_id, dead_version)
+    }
+
+    pub fn take_unreachable_versions(&self) -> Vec<DeadVersion> {
+        self.version_store.take_unreachable_versions()
     }
 
-    pub fn commit(&self, _txn: Transaction) -> bool {
-        todo!()
+    pub fn visible_to_all(&self, txn_id: TransactionId) -> bool {
+        self.version_store.visible_to_all(txn_id)
     }
 
-    pub fn abort(&self, _txn: Transaction) {
-        todo!()
+    pub fn garbage_collection(&self) {
+        self.version_store
+            .garbage_collection(self.last_commit_ts.load(Ordering::SeqCst));
     }
 }
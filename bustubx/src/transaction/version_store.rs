@@ -0,0 +1,375 @@
+use crate::common::TableReference;
+use crate::storage::index::BPlusTreeIndex;
+use crate::storage::{RecordId, TableHeap, Tuple, TupleMeta};
+use crate::transaction::{
+    IsolationLevel, Timestamp, Transaction, TransactionId, UndoLink, UndoLog,
+};
+use crate::{BustubxError, BustubxResult};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Index entries of a tuple version which was replaced or deleted. They are kept for
+/// transactions reading old versions and removed once no transaction can see it.
+#[derive(Debug)]
+pub struct DeadVersion {
+    pub rid: RecordId,
+    pub table_ref: TableReference,
+    /// Entries of a dropped or rebuilt table are not removed
+    pub table_heap: Weak<TableHeap>,
+    pub index_entries: Vec<(Weak<BPlusTreeIndex>, Tuple)>,
+}
+
+/// Keeps transaction states and old versions of tuples.
+///
+/// The table heap only stores the newest version of a tuple. `insert_txn_id` of its meta is
+/// the transaction which wrote that version, `delete_txn_id` the one which deleted it.
+/// Older versions are kept in undo logs of the transactions which overwrote them, linked
+/// from the newest to the oldest one.
+#[derive(Debug, Default)]
+pub struct VersionStore {
+    inner: Mutex<VersionStoreInner>,
+}
+
+#[derive(Debug, Default)]
+struct VersionStoreInner {
+    /// Read timestamps of running transactions
+    running_txns: HashMap<TransactionId, Timestamp>,
+    /// Commit timestamps of transactions which are not visible to all running ones yet,
+    /// transactions missing here are either running or committed long ago
+    committed_txns: HashMap<TransactionId, Timestamp>,
+    undo_logs: HashMap<TransactionId, Vec<UndoLog>>,
+    /// Link to the newest undo log of a tuple
+    version_info: HashMap<RecordId, UndoLink>,
+    /// Versions replaced or deleted by a transaction
+    dead_versions: HashMap<TransactionId, HashMap<RecordId, DeadVersion>>,
+    /// Dead versions no transaction can see anymore
+    unreachable_versions: Vec<DeadVersion>,
+}
+
+impl VersionStoreInner {
+    fn is_visible(&self, writer: TransactionId, txn_id: TransactionId, read_ts: Timestamp) -> bool {
+        if writer == txn_id {
+            return true;
+        }
+        if self.running_txns.contains_key(&writer) {
+            return false;
+        }
+        self.committed_txns
+            .get(&writer)
+            .map(|commit_ts| *commit_ts <= read_ts)
+            .unwrap_or(true)
+    }
+
+    /// Whether the changes of `writer` are visible to all running transactions.
+    fn visible_to_all(&self, writer: TransactionId) -> bool {
+        let watermark = self.running_txns.values().min().copied();
+        !self.running_txns.contains_key(&writer)
+            && self
+                .committed_txns
+                .get(&writer)
+                .zip(watermark)
+                .map(|(commit_ts, watermark)| *commit_ts <= watermark)
+                .unwrap_or(true)
+    }
+
+    fn undo_log(&self, link: UndoLink) -> Option<&UndoLog> {
+        self.undo_logs
+            .get(&link.prev_txn)
+            .and_then(|logs| logs.get(link.prev_log_idx as usize))
+    }
+}
+
+impl VersionStore {
+    pub fn begin(&self, txn_id: TransactionId, read_ts: Timestamp) {
+        self.inner
+            .lock()
+            .unwrap()
+            .running_txns
+            .insert(txn_id, read_ts);
+    }
+
+    pub fn commit(&self, txn_id: TransactionId, commit_ts: Timestamp) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.running_txns.remove(&txn_id);
+        inner.committed_txns.insert(txn_id, commit_ts);
+    }
+
+    /// Unlinks undo logs of an aborted transaction, its changes to the table heap must have
+    /// been rolled back already.
+    pub fn abort(&self, txn_id: TransactionId) {
+        let mut inner = self.inner.lock().unwrap();
+        let VersionStoreInner {
+            running_txns,
+            undo_logs,
+            version_info,
+            dead_versions,
+            ..
+        } = &mut *inner;
+        dead_versions.remove(&txn_id);
+        if let Some(logs) = undo_logs.remove(&txn_id) {
+            version_info.retain(|_, link| {
+                while link.prev_txn == txn_id {
+                    *link = logs[link.prev_log_idx as usize].prev_version;
+                }
+                link.is_valid()
+            });
+        }
+        running_txns.remove(&txn_id);
+    }
+
+    /// Snapshot isolation aborts a transaction which modifies a tuple whose newest version
+    /// is not visible to it.
+    pub fn check_write_conflict(&self, txn: &Transaction, meta: &TupleMeta) -> BustubxResult<()> {
+        if txn.isolation_level != IsolationLevel::SnapshotIsolation {
+            return Ok(());
+        }
+        let inner = self.inner.lock().unwrap();
+        if (meta.is_deleted && meta.delete_txn_id != txn.id)
+            || !inner.is_visible(meta.insert_txn_id, txn.id, txn.read_ts)
+        {
+            return Err(BustubxError::Transaction(format!(
+                "Transaction {} aborted: write-write conflict",
+                txn.id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Keeps the version of a tuple which is overwritten by the transaction.
+    pub fn record_update(
+        &self,
+        txn: &Transaction,
+        rid: RecordId,
+        old_meta: &TupleMeta,
+        old_tuple: &Tuple,
+        new_tuple: &Tuple,
+    ) -> BustubxResult<()> {
+        if old_meta.insert_txn_id == txn.id {
+            // the version before this transaction is in the undo log already
+            return Ok(());
+        }
+        let modified_fields = old_tuple
+            .data
+            .iter()
+            .zip(new_tuple.data.iter())
+            .map(|(old, new)| old != new)
+            .collect::<Vec<bool>>();
+        let modified_indices = modified_fields
+            .iter()
+            .enumerate()
+            .filter(|(_, modified)| **modified)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<usize>>();
+        let partial_tuple = Tuple::new(
+            Arc::new(old_tuple.schema.project(&modified_indices)?),
+            modified_indices
+                .iter()
+                .map(|idx| old_tuple.data[*idx].clone())
+                .collect(),
+        );
+
+        let mut inner = self.inner.lock().unwrap();
+        let undo_log = UndoLog {
+            is_deleted: false,
+            modified_fields,
+            tuple: partial_tuple,
+            timestamp: inner
+                .committed_txns
+                .get(&old_meta.insert_txn_id)
+                .copied()
+                .unwrap_or(0),
+            prev_version: inner
+                .version_info
+                .get(&rid)
+                .copied()
+                .unwrap_or(UndoLink::INVALID),
+        };
+        let logs = inner.undo_logs.entry(txn.id).or_default();
+        logs.push(undo_log);
+        let link = UndoLink {
+            prev_txn: txn.id,
+            prev_log_idx: (logs.len() - 1) as u32,
+        };
+        inner.version_info.insert(rid, link);
+        Ok(())
+    }
+
+    /// Keeps the index entries of a version the transaction replaced or deleted, until
+    /// no transaction can see that version.
+    pub fn record_dead_version(&self, txn_id: TransactionId, dead_version: DeadVersion) {
+        let mut inner = self.inner.lock().unwrap();
+        let versions = inner.dead_versions.entry(txn_id).or_default();
+        match versions.get_mut(&dead_version.rid) {
+            Some(version) => version.index_entries.extend(dead_version.index_entries),
+            None => {
+                versions.insert(dead_version.rid, dead_version);
+            }
+        }
+    }
+
+    /// Takes the dead versions which became unreachable by garbage collection.
+    pub fn take_unreachable_versions(&self) -> Vec<DeadVersion> {
+        std::mem::take(&mut self.inner.lock().unwrap().unreachable_versions)
+    }
+
+    /// Whether the changes of the transaction are visible to all running transactions.
+    pub fn visible_to_all(&self, txn_id: TransactionId) -> bool {
+        self.inner.lock().unwrap().visible_to_all(txn_id)
+    }
+
+    /// Reclaims undo logs which no running transaction can see. `last_commit_ts` is
+    /// the read timestamp of transactions starting from now on.
+    pub fn garbage_collection(&self, last_commit_ts: Timestamp) {
+        let mut inner = self.inner.lock().unwrap();
+        let VersionStoreInner {
+            running_txns,
+            committed_txns,
+            undo_logs,
+            version_info,
+            dead_versions,
+            unreachable_versions,
+        } = &mut *inner;
+        let watermark = running_txns
+            .values()
+            .min()
+            .copied()
+            .unwrap_or(last_commit_ts);
+        let visible_to_all = |writer: TransactionId| {
+            !running_txns.contains_key(&writer)
+                && committed_txns
+                    .get(&writer)
+                    .map(|commit_ts| *commit_ts <= watermark)
+                    .unwrap_or(true)
+        };
+
+        let mut reachable_txns = HashSet::new();
+        version_info.retain(|_, head| {
+            // the owner of the newest undo log wrote the version in the table heap
+            if visible_to_all(head.prev_txn) {
+                return false;
+            }
+            let mut link = *head;
+            loop {
+                reachable_txns.insert(link.prev_txn);
+                let Some(log) = undo_logs
+                    .get_mut(&link.prev_txn)
+                    .and_then(|logs| logs.get_mut(link.prev_log_idx as usize))
+                else {
+                    break;
+                };
+                if log.timestamp <= watermark {
+                    // all running transactions can see this version, older ones are not needed
+                    log.prev_version = UndoLink::INVALID;
+                }
+                if !log.prev_version.is_valid() {
+                    break;
+                }
+                link = log.prev_version;
+            }
+            true
+        });
+        undo_logs.retain(|txn_id, _| {
+            reachable_txns.contains(txn_id) || running_txns.contains_key(txn_id)
+        });
+        // a version is unreachable once its writer is visible to all and the tuple has
+        // no older version left
+        dead_versions.retain(|writer, versions| {
+            if !visible_to_all(*writer) {
+                return true;
+            }
+            for (rid, version) in std::mem::take(versions) {
+                if version_info.contains_key(&rid) {
+                    versions.insert(rid, version);
+                } else {
+                    unreachable_versions.push(version);
+                }
+            }
+            !versions.is_empty()
+        });
+        committed_txns.retain(|_, commit_ts| *commit_ts > watermark);
+    }
+}
+
+/// Versions of tuples visible to a snapshot isolation transaction.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    txn_id: TransactionId,
+    read_ts: Timestamp,
+    versions: Arc<VersionStore>,
+}
+
+impl Snapshot {
+    pub fn new(txn: &Transaction, versions: Arc<VersionStore>) -> Self {
+        Self {
+            txn_id: txn.id,
+            read_ts: txn.read_ts,
+            versions,
+        }
+    }
+
+    /// Whether the visible version of the tuple is decided by the newest one in the table
+    /// heap alone, without applying undo logs.
+    pub fn sees_newest_version(&self, meta: &TupleMeta) -> bool {
+        let inner = self.versions.inner.lock().unwrap();
+        (meta.is_deleted && inner.is_visible(meta.delete_txn_id, self.txn_id, self.read_ts))
+            || inner.is_visible(meta.insert_txn_id, self.txn_id, self.read_ts)
+    }
+
+    /// Whether the tuple is deleted by a transaction visible to this snapshot.
+    pub fn sees_deletion(&self, meta: &TupleMeta) -> bool {
+        let inner = self.versions.inner.lock().unwrap();
+        meta.is_deleted && inner.is_visible(meta.delete_txn_id, self.txn_id, self.read_ts)
+    }
+
+    /// Reconstructs the version of the tuple visible to this snapshot from the newest one
+    /// in the table heap, returns `None` if the tuple didn't exist at that time.
+    pub fn visible_version(
+        &self,
+        rid: RecordId,
+        meta: &TupleMeta,
+        tuple: Tuple,
+    ) -> BustubxResult<Option<Tuple>> {
+        let inner = self.versions.inner.lock().unwrap();
+        if meta.is_deleted && inner.is_visible(meta.delete_txn_id, self.txn_id, self.read_ts) {
+            return Ok(None);
+        }
+        if inner.is_visible(meta.insert_txn_id, self.txn_id, self.read_ts) {
+            return Ok(Some(tuple));
+        }
+
+        let mut tuple = tuple;
+        let mut link = inner
+            .version_info
+            .get(&rid)
+            .copied()
+            .unwrap_or(UndoLink::INVALID);
+        while link.is_valid() {
+            let Some(undo_log) = inner.undo_log(link) else {
+                break;
+            };
+            tuple = apply_undo_log(tuple, undo_log);
+            if undo_log.timestamp <= self.read_ts {
+                return Ok((!undo_log.is_deleted).then_some(tuple));
+            }
+            link = undo_log.prev_version;
+        }
+        Ok(None)
+    }
+}
+
+fn apply_undo_log(tuple: Tuple, undo_log: &UndoLog) -> Tuple {
+    let mut old_values = undo_log.tuple.data.iter();
+    let data = tuple
+        .data
+        .into_iter()
+        .zip(undo_log.modified_fields.iter())
+        .map(|(value, modified)| {
+            if *modified {
+                old_values.next().cloned().unwrap_or(value)
+            } else {
+                value
+            }
+        })
+        .collect();
+    Tuple::new(tuple.schema, data)
+}